@@ -36,6 +36,7 @@ async fn main() {
     let mut update_count = 0;
     let mut submit_actions = 0u64;
     let mut cancel_actions = 0u64;
+    let mut replace_actions = 0u64;
 
     loop {
         let event = match tokio::time::timeout(Duration::from_secs(15), event_rx.recv()).await
@@ -92,6 +93,7 @@ async fn main() {
                 nyquestro::feed::FeedAction::Action { action, .. } => match action {
                     SimAction::Submit(_) => submit_actions += 1,
                     SimAction::Cancel { .. } => cancel_actions += 1,
+                    SimAction::Replace { .. } => replace_actions += 1,
                     SimAction::CancelHint => {}
                 },
                 nyquestro::feed::FeedAction::Status(_) => {}
@@ -111,6 +113,7 @@ async fn main() {
     println!("updates (first 10 shown above; total {update_count})");
     println!("bridge submits     {submit_actions}");
     println!("bridge cancels     {cancel_actions}");
+    println!("bridge replaces    {replace_actions}");
     println!();
     if submit_actions > 0 {
         println!("✅ live feed and bridge are working");
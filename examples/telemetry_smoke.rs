@@ -37,6 +37,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         qty: 12_500,
         buyer: 1_000_000_000_001,
         seller: 1_000_000_000_002,
+        trade: 1,
+        seq: 2,
     });
     handle.record(TelemetryEvent::Frame {
         step_us: 2300,
@@ -0,0 +1,188 @@
+//! Sharing an incoming quantity among the orders resting at one price.
+//!
+//! [`MatchingAlgorithm::allocate`] is a pure function of the incoming
+//! quantity and the level's queue, so every rule is deterministic and can
+//! be tested without a book. Allocations come back in queue order, which
+//! is also the order the book executes them in.
+//!
+//! Rounding and tie-breaking:
+//!
+//! - **`Fifo`** — walk the queue, each order taking what it can.
+//! - **`ProRata`** — order *i* gets `⌊incoming × qᵢ / total⌋`. The units
+//!   rounding leaves over (always fewer than the number of orders) go one
+//!   each to the largest orders; equal sizes break on queue position.
+//! - **`ProRataTopOrder`** — the front order first takes as much as it can.
+//!   The remainder is shared pro-rata across the other orders, rounded
+//!   down, and whatever rounding leaves over goes to them in FIFO order.
+//!
+//! When the incoming quantity covers the whole level every rule fills
+//! every order in full.
+
+use crate::book::config::MatchingAlgorithm;
+use crate::types::{OrderID, Qty};
+
+/// Working space for sharing one level out: each order's id, displayed
+/// quantity and share, front to back. The book keeps one and reloads it
+/// per level, so once it has grown to the deepest level the book trades
+/// against, pro-rata matching allocates nothing.
+#[derive(Debug, Clone, Default)]
+pub struct Allocation {
+    entries: Vec<Entry>,
+    /// Entry indices in the order pro-rata hands out rounding leftovers.
+    by_size: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    id: OrderID,
+    size: u64,
+    share: u64,
+}
+
+impl Allocation {
+    /// Start over with `queue` — `(id, displayed quantity)` pairs front to
+    /// back — and no shares.
+    pub fn load(&mut self, queue: impl IntoIterator<Item = (OrderID, Qty)>) {
+        self.entries.clear();
+        self.entries.extend(queue.into_iter().map(|(id, size)| Entry {
+            id,
+            size: size.value(),
+            share: 0,
+        }));
+    }
+
+    /// The non-zero shares, in queue order.
+    pub fn shares(&self) -> impl Iterator<Item = (OrderID, Qty)> + '_ {
+        self.entries
+            .iter()
+            .filter(|e| e.share > 0)
+            .map(|e| (e.id, Qty::new(e.share)))
+    }
+}
+
+impl MatchingAlgorithm {
+    /// Split `incoming` across `queue` — `(id, displayed quantity)` pairs
+    /// front to back. Returns the non-zero allocations in queue order.
+    pub fn allocate(self, incoming: Qty, queue: &[(OrderID, Qty)]) -> Vec<(OrderID, Qty)> {
+        let mut work = Allocation::default();
+        work.load(queue.iter().copied());
+        self.allocate_into(incoming, &mut work);
+        work.shares().collect()
+    }
+
+    /// [`allocate`](Self::allocate) over the queue `work` was loaded
+    /// with, leaving the result in [`Allocation::shares`]. Allocates only
+    /// while `work` is still growing.
+    pub fn allocate_into(self, incoming: Qty, work: &mut Allocation) {
+        let entries = &mut work.entries;
+        let total: u64 = entries.iter().map(|e| e.size).sum();
+        let want = incoming.value();
+
+        if want >= total {
+            for e in entries.iter_mut() {
+                e.share = e.size;
+            }
+            return;
+        }
+        match self {
+            MatchingAlgorithm::Fifo => fifo(want, entries),
+            MatchingAlgorithm::ProRata => pro_rata(want, entries, &mut work.by_size),
+            MatchingAlgorithm::ProRataTopOrder => top_order(want, entries),
+        }
+    }
+}
+
+/// Hand `want` out in queue order on top of the shares already held.
+fn fifo(mut want: u64, entries: &mut [Entry]) {
+    for e in entries {
+        let more = (e.size - e.share).min(want);
+        e.share += more;
+        want -= more;
+    }
+}
+
+/// Proportional shares rounded down. `want` must be below the sizes' sum.
+fn proportional(want: u64, entries: &mut [Entry]) {
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    if total == 0 {
+        return;
+    }
+    for e in entries {
+        e.share = (u128::from(want) * u128::from(e.size) / u128::from(total)) as u64;
+    }
+}
+
+fn pro_rata(want: u64, entries: &mut [Entry], by_size: &mut Vec<usize>) {
+    proportional(want, entries);
+    let leftover = want - entries.iter().map(|e| e.share).sum::<u64>();
+    // Largest first, then earliest in the queue. Each order can take one
+    // more unit: rounding down left it strictly short of its size. The
+    // index breaks every tie, so the unstable sort is deterministic.
+    by_size.clear();
+    by_size.extend(0..entries.len());
+    by_size.sort_unstable_by(|&a, &b| entries[b].size.cmp(&entries[a].size).then(a.cmp(&b)));
+    for &i in by_size.iter().take(leftover as usize) {
+        entries[i].share += 1;
+    }
+}
+
+fn top_order(want: u64, entries: &mut [Entry]) {
+    let Some((top, rest)) = entries.split_first_mut() else {
+        return;
+    };
+    top.share = top.size.min(want);
+    proportional(want - top.share, rest);
+    let leftover = want - entries.iter().map(|e| e.share).sum::<u64>();
+    fifo(leftover, entries);
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn queue(sizes: &[u64]) -> Vec<(OrderID, Qty)> {
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &q)| (OrderID::new(i as u64 + 1).unwrap(), Qty::new(q)))
+            .collect()
+    }
+
+    fn shares(algo: MatchingAlgorithm, incoming: u64, sizes: &[u64]) -> Vec<(u64, u64)> {
+        algo.allocate(Qty::new(incoming), &queue(sizes))
+            .into_iter()
+            .map(|(id, q)| (id.value(), q.value()))
+            .collect()
+    }
+
+    #[test]
+    fn every_rule_fills_everyone_when_the_level_is_covered() {
+        for algo in [
+            MatchingAlgorithm::Fifo,
+            MatchingAlgorithm::ProRata,
+            MatchingAlgorithm::ProRataTopOrder,
+        ] {
+            assert_eq!(shares(algo, 20, &[3, 5, 2]), vec![(1, 3), (2, 5), (3, 2)]);
+        }
+    }
+
+    #[test]
+    fn allocations_always_sum_to_the_incoming_quantity() {
+        let sizes = [7, 1, 13, 4, 4, 9];
+        for algo in [
+            MatchingAlgorithm::Fifo,
+            MatchingAlgorithm::ProRata,
+            MatchingAlgorithm::ProRataTopOrder,
+        ] {
+            for incoming in 1..38 {
+                let got = shares(algo, incoming, &sizes);
+                assert_eq!(got.iter().map(|(_, q)| q).sum::<u64>(), incoming, "{algo:?}");
+                for (id, q) in got {
+                    assert!(q <= sizes[id as usize - 1]);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Where a call auction uncrosses.
+//!
+//! [`clearing`] is a pure function of the two sides' per-price quantities
+//! and a reference price, so the price-determination rules can be tested
+//! without a book. The steps are the standard ones, applied in order, each
+//! narrowing the candidates left by the one before:
+//!
+//! 1. **Maximise volume.** At a price `p` the executable volume is the
+//!    smaller of the demand (bids at `p` or higher) and the supply (asks at
+//!    `p` or lower). Candidates are every limit price on either side plus
+//!    the reference price itself.
+//! 2. **Minimise imbalance.** Keep the prices whose unmatched surplus
+//!    `|demand − supply|` is smallest.
+//! 3. **Reference price.** Keep the price closest to the reference,
+//!    normally the last trade; the lower price wins an exact tie. With no
+//!    reference the middle of the remaining range stands in for it.
+//!
+//! Quantities are full open quantities: hidden iceberg reserve takes part
+//! in an auction like any other quantity.
+
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::types::{Px, Qty};
+
+/// Uncross price and volume for resting `bids` and `asks` — `(price,
+/// open quantity)` pairs in any order — or `None` when nothing would
+/// trade.
+pub fn clearing(
+    bids: &[(Px, Qty)],
+    asks: &[(Px, Qty)],
+    reference: Option<Px>,
+) -> NyquestroResult<Option<(Px, Qty)>> {
+    let demand = |p: Px| -> u128 {
+        bids.iter()
+            .filter(|(px, _)| *px >= p)
+            .map(|(_, q)| u128::from(q.value()))
+            .sum()
+    };
+    let supply = |p: Px| -> u128 {
+        asks.iter()
+            .filter(|(px, _)| *px <= p)
+            .map(|(_, q)| u128::from(q.value()))
+            .sum()
+    };
+
+    let mut prices: Vec<Px> = bids
+        .iter()
+        .chain(asks)
+        .map(|(px, _)| *px)
+        .chain(reference)
+        .collect();
+    prices.sort_unstable();
+    prices.dedup();
+
+    // (price, volume, imbalance) for every price where something trades.
+    let scored: Vec<(Px, u128, u128)> = prices
+        .into_iter()
+        .map(|p| {
+            let (d, s) = (demand(p), supply(p));
+            (p, d.min(s), d.abs_diff(s))
+        })
+        .filter(|&(_, volume, _)| volume > 0)
+        .collect();
+    let Some(volume) = scored.iter().map(|&(_, v, _)| v).max() else {
+        return Ok(None);
+    };
+    let imbalance = scored
+        .iter()
+        .filter(|&&(_, v, _)| v == volume)
+        .map(|&(_, _, i)| i)
+        .min()
+        .unwrap_or(0);
+    let tied: Vec<Px> = scored
+        .iter()
+        .filter(|&&(_, v, i)| v == volume && i == imbalance)
+        .map(|&(p, _, _)| p)
+        .collect();
+
+    let (lo, hi) = (tied[0].raw(), tied[tied.len() - 1].raw());
+    let anchor = reference.map_or(lo + (hi - lo) / 2, Px::raw);
+    let price = tied
+        .into_iter()
+        .min_by_key(|p| (p.raw().abs_diff(anchor), p.raw()))
+        .ok_or_else(|| {
+            NyquestroError::InvariantViolation("auction with no clearing candidate".into())
+        })?;
+    let volume = u64::try_from(volume).map_err(|_| NyquestroError::QuantityOverflow)?;
+    Ok(Some((price, Qty::new(volume))))
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn side(levels: &[(u64, u64)]) -> Vec<(Px, Qty)> {
+        levels
+            .iter()
+            .map(|&(p, q)| (Px::from_raw(p).unwrap(), Qty::new(q)))
+            .collect()
+    }
+
+    fn uncross(
+        bids: &[(u64, u64)],
+        asks: &[(u64, u64)],
+        reference: Option<u64>,
+    ) -> Option<(u64, u64)> {
+        clearing(&side(bids), &side(asks), reference.map(|r| Px::from_raw(r).unwrap()))
+            .unwrap()
+            .map(|(p, q)| (p.raw(), q.value()))
+    }
+
+    #[test]
+    fn nothing_crosses() {
+        assert_eq!(uncross(&[(99, 5)], &[(100, 5)], Some(100)), None);
+        assert_eq!(uncross(&[], &[(100, 5)], None), None);
+    }
+
+    #[test]
+    fn maximises_volume() {
+        // 100 and 101 trade 3; 102 and 103 trade 5.
+        let bids = [(103, 5), (101, 5)];
+        let asks = [(100, 3), (102, 4)];
+        assert_eq!(uncross(&bids, &asks, None).unwrap().1, 5);
+    }
+
+    #[test]
+    fn minimises_imbalance_before_reference() {
+        // Both prices trade 4, but 102 leaves nothing over.
+        let bids = [(102, 4), (100, 2)];
+        let asks = [(100, 4)];
+        assert_eq!(uncross(&bids, &asks, Some(100)), Some((102, 4)));
+    }
+
+    #[test]
+    fn reference_breaks_remaining_ties() {
+        let bids = [(103, 5), (101, 5)];
+        let asks = [(100, 3), (102, 4)];
+        assert_eq!(uncross(&bids, &asks, Some(110)), Some((103, 5)));
+        assert_eq!(uncross(&bids, &asks, Some(90)), Some((102, 5)));
+        // A reference inside the tied range is itself a candidate.
+        let bids = [(105, 5)];
+        let asks = [(100, 5)];
+        assert_eq!(uncross(&bids, &asks, Some(103)), Some((103, 5)));
+        // Without one, the lower middle of the range.
+        assert_eq!(uncross(&bids, &asks, None), Some((100, 5)));
+        let bids = [(103, 5), (101, 5)];
+        let asks = [(100, 3), (102, 4)];
+        assert_eq!(uncross(&bids, &asks, None), Some((102, 5)));
+    }
+}
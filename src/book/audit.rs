@@ -0,0 +1,337 @@
+//! The book's invariants and the auditor that checks them.
+//!
+//! [`crate::book::OrderBook::audit`] walks the whole book and reports
+//! every [`Violation`] it finds; a sound book reports none. With
+//! [`BookConfig::checked`](crate::book::BookConfig::checked) set, the book
+//! audits itself after every operation and fails the operation with a
+//! fatal `InvariantViolation` naming the symbol and the operation.
+//!
+//! The checks are a pure function over the book's parts, so they are
+//! tested here on hand-built ladders that no operation would produce.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::book::ladder::Ladder;
+use crate::book::price_level::Slot;
+use crate::book::stops::StopBook;
+use crate::errors::NyquestroError;
+use crate::types::{OrderID, Px, Qty, Side, Status, Symbol};
+
+/// One broken invariant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Violation {
+    /// A level is filed under a price other than its own.
+    LevelKey { side: Side, key: Px, price: Px },
+    /// A level's running total is not what its orders display.
+    LevelTotal {
+        side: Side,
+        price: Px,
+        total: Qty,
+        displayed: Qty,
+    },
+    /// A level with no orders left on the ladder.
+    EmptyLevel { side: Side, price: Px },
+    /// Best bid at or through best ask while the book is trading
+    /// continuously.
+    Crossed { bid: Px, ask: Px },
+    /// A filled, cancelled or expired order still on the ladder.
+    TerminalOrder { id: OrderID, status: Status },
+    /// A resting order that displays nothing.
+    NothingDisplayed { id: OrderID },
+    /// An order queued at a level other than its own price.
+    PriceMismatch { id: OrderID, level: Px, order: Px },
+    /// An order queued on the other side's ladder.
+    WrongSide { id: OrderID, side: Side },
+    /// The same id twice among resting orders and waiting stops.
+    DuplicateId { id: OrderID },
+    /// The id index and the ladders disagree about where an order rests.
+    IndexMismatch { id: OrderID },
+    /// A waiting stop without a trigger, or one no longer active.
+    BadStop { id: OrderID },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Violation::LevelKey { side, key, price } => write!(
+                f,
+                "{side} level filed at {} holds price {}",
+                key.raw(),
+                price.raw()
+            ),
+            Violation::LevelTotal {
+                side,
+                price,
+                total,
+                displayed,
+            } => write!(
+                f,
+                "{side} level {} totals {} but its orders display {}",
+                price.raw(),
+                total.value(),
+                displayed.value()
+            ),
+            Violation::EmptyLevel { side, price } => {
+                write!(f, "{side} level {} is empty", price.raw())
+            }
+            Violation::Crossed { bid, ask } => {
+                write!(f, "book crossed at rest: bid {} >= ask {}", bid.raw(), ask.raw())
+            }
+            Violation::TerminalOrder { id, status } => {
+                write!(f, "order {} rests while {status}", id.value())
+            }
+            Violation::NothingDisplayed { id } => {
+                write!(f, "order {} rests displaying nothing", id.value())
+            }
+            Violation::PriceMismatch { id, level, order } => write!(
+                f,
+                "order {} priced {} queued at level {}",
+                id.value(),
+                order.raw(),
+                level.raw()
+            ),
+            Violation::WrongSide { id, side } => {
+                write!(f, "order {} queued on the {side} side", id.value())
+            }
+            Violation::DuplicateId { id } => write!(f, "order {} appears twice", id.value()),
+            Violation::IndexMismatch { id } => {
+                write!(f, "index and ladders disagree on order {}", id.value())
+            }
+            Violation::BadStop { id } => {
+                write!(f, "stop {} cannot wait for a trigger", id.value())
+            }
+        }
+    }
+}
+
+/// Every violation in a book made of these parts: bids then asks, best
+/// price first and each queue front to back, then index entries no order
+/// backs (by id), then stops in firing order, then a crossed touch.
+/// `crossable` lets the ladders overlap, as they may outside continuous
+/// trading.
+pub(crate) fn audit(
+    bids: &Ladder,
+    asks: &Ladder,
+    index: &HashMap<OrderID, (Side, Px, Slot)>,
+    stops: &StopBook,
+    crossable: bool,
+) -> Vec<Violation> {
+    let mut found = Vec::new();
+    let mut seen = HashSet::new();
+    for (side, ladder) in [(Side::Buy, bids), (Side::Sell, asks)] {
+        let best_first: Vec<_> = match side {
+            Side::Buy => ladder.iter().rev().collect(),
+            Side::Sell => ladder.iter().collect(),
+        };
+        for (&key, level) in best_first {
+            if level.price() != key {
+                found.push(Violation::LevelKey {
+                    side,
+                    key,
+                    price: level.price(),
+                });
+            }
+            if level.is_empty() {
+                found.push(Violation::EmptyLevel { side, price: key });
+            }
+            let mut displayed: u128 = 0;
+            for (at, order) in level.entries() {
+                let id = order.id();
+                if !seen.insert(id) {
+                    found.push(Violation::DuplicateId { id });
+                }
+                if order.side() != side {
+                    found.push(Violation::WrongSide { id, side });
+                }
+                if order.price() != key {
+                    found.push(Violation::PriceMismatch {
+                        id,
+                        level: key,
+                        order: order.price(),
+                    });
+                }
+                if !order.is_active() {
+                    found.push(Violation::TerminalOrder {
+                        id,
+                        status: order.status(),
+                    });
+                } else if order.displayed().is_zero() {
+                    found.push(Violation::NothingDisplayed { id });
+                }
+                if index.get(&id) != Some(&(side, key, at)) {
+                    found.push(Violation::IndexMismatch { id });
+                }
+                displayed += u128::from(order.displayed().value());
+            }
+            if displayed != u128::from(level.total_quantity().value()) {
+                found.push(Violation::LevelTotal {
+                    side,
+                    price: key,
+                    total: level.total_quantity(),
+                    displayed: Qty::new(u64::try_from(displayed).unwrap_or(u64::MAX)),
+                });
+            }
+        }
+    }
+    let mut unbacked: Vec<OrderID> =
+        index.keys().filter(|id| !seen.contains(id)).copied().collect();
+    unbacked.sort_unstable();
+    found.extend(unbacked.into_iter().map(|id| Violation::IndexMismatch { id }));
+    for stop in stops.iter() {
+        let id = stop.id();
+        if !seen.insert(id) {
+            found.push(Violation::DuplicateId { id });
+        }
+        if stop.stop_price().is_none() || !stop.is_active() {
+            found.push(Violation::BadStop { id });
+        }
+    }
+    if !crossable
+        && let (Some((&bid, _)), Some((&ask, _))) = (bids.last(), asks.first())
+        && bid >= ask
+    {
+        found.push(Violation::Crossed { bid, ask });
+    }
+    found
+}
+
+/// The fatal error checked mode fails an operation with.
+pub(crate) fn report(symbol: Symbol, operation: &str, violations: &[Violation]) -> NyquestroError {
+    let found: Vec<String> = violations.iter().map(Violation::to_string).collect();
+    NyquestroError::InvariantViolation(format!("{symbol} after {operation}: {}", found.join("; ")))
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::ladder::LadderKind;
+    use crate::book::price_level::PriceLevel;
+    use crate::order::Order;
+    use crate::types::Ts;
+
+    fn px(c: u64) -> Px {
+        Px::from_raw(c).unwrap()
+    }
+    fn id(n: u64) -> OrderID {
+        OrderID::new(n).unwrap()
+    }
+    fn order(n: u64, side: Side, price: u64, qty: u64) -> Order {
+        let sym = Symbol::from_const("TEST");
+        Order::new(id(n), sym, side, px(price), Qty::new(qty), Ts::from_nanos(n)).unwrap()
+    }
+
+    type Parts = (Ladder, Ladder, HashMap<OrderID, (Side, Px, Slot)>);
+
+    /// Ladders holding `orders` at their own prices, fully indexed.
+    fn ladders(orders: &[Order]) -> Parts {
+        let mut bids = Ladder::new(LadderKind::Tree, 1, Side::Buy);
+        let mut asks = Ladder::new(LadderKind::Tree, 1, Side::Sell);
+        let mut index = HashMap::new();
+        for o in orders {
+            let ladder = match o.side() {
+                Side::Buy => &mut bids,
+                Side::Sell => &mut asks,
+            };
+            let at = ladder.entry(o.price()).insert(*o).unwrap();
+            index.insert(o.id(), (o.side(), o.price(), at));
+        }
+        (bids, asks, index)
+    }
+
+    #[test]
+    fn sound_book_reports_nothing() {
+        let (bids, asks, index) = ladders(&[
+            order(1, Side::Buy, 100, 10),
+            order(2, Side::Buy, 100, 5),
+            order(3, Side::Sell, 101, 7),
+        ]);
+        assert!(audit(&bids, &asks, &index, &StopBook::new(), false).is_empty());
+    }
+
+    #[test]
+    fn reports_each_kind_of_drift() {
+        let (mut bids, mut asks, mut index) =
+            ladders(&[order(1, Side::Buy, 100, 10), order(2, Side::Sell, 99, 7)]);
+        // A total that no longer matches its queue.
+        bids.get_mut(&px(100)).unwrap().record_execution(Qty::new(4)).unwrap();
+        // An empty level, an order at the wrong price and one on the
+        // wrong side, a dead order, and an index entry for nothing.
+        bids.insert(px(98), PriceLevel::new(px(98)));
+        let mut stray = PriceLevel::new(px(97));
+        stray.push_back(order(3, Side::Sell, 97, 1)).unwrap();
+        bids.insert(px(96), stray);
+        let mut filled = order(4, Side::Sell, 105, 2);
+        filled.fill(Qty::new(2)).unwrap();
+        let at = asks.entry(px(105)).insert(filled).unwrap();
+        index.insert(id(4), (Side::Sell, px(105), at));
+        index.insert(id(9), (Side::Buy, px(100), at));
+
+        let found = audit(&bids, &asks, &index, &StopBook::new(), false);
+        assert_eq!(
+            found,
+            vec![
+                Violation::LevelTotal {
+                    side: Side::Buy,
+                    price: px(100),
+                    total: Qty::new(6),
+                    displayed: Qty::new(10),
+                },
+                Violation::EmptyLevel {
+                    side: Side::Buy,
+                    price: px(98),
+                },
+                Violation::LevelKey {
+                    side: Side::Buy,
+                    key: px(96),
+                    price: px(97),
+                },
+                Violation::WrongSide {
+                    id: id(3),
+                    side: Side::Buy,
+                },
+                Violation::PriceMismatch {
+                    id: id(3),
+                    level: px(96),
+                    order: px(97),
+                },
+                Violation::IndexMismatch { id: id(3) },
+                Violation::TerminalOrder {
+                    id: id(4),
+                    status: Status::FullyFilled,
+                },
+                Violation::IndexMismatch { id: id(9) },
+                Violation::Crossed {
+                    bid: px(100),
+                    ask: px(99),
+                },
+            ]
+        );
+        // Outside continuous trading the ladders may overlap.
+        let found = audit(&bids, &asks, &index, &StopBook::new(), true);
+        assert!(!found.iter().any(|v| matches!(v, Violation::Crossed { .. })));
+    }
+
+    #[test]
+    fn report_names_symbol_operation_and_every_violation() {
+        let err = report(
+            Symbol::from_const("TEST"),
+            "cancel",
+            &[
+                Violation::DuplicateId { id: id(7) },
+                Violation::EmptyLevel {
+                    side: Side::Sell,
+                    price: px(101),
+                },
+            ],
+        );
+        assert!(err.is_fatal());
+        assert_eq!(
+            err.to_string(),
+            "Internal invariant violated: TEST after cancel: order 7 appears twice; \
+             SELL level 101 is empty"
+        );
+    }
+}
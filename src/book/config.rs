@@ -0,0 +1,201 @@
+//! Per-book policy knobs.
+//!
+//! A [`BookConfig`] is fixed when the book is created (see
+//! [`crate::book::Market::register_with`]) and never changes while the
+//! book holds orders, so a given input sequence always meets the same
+//! rules.
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::ladder::LadderKind;
+use crate::instrument::InstrumentSpec;
+use crate::types::{Px, Side};
+
+/// What the book does when an incoming order would trade against a
+/// resting order from the same owner. Only orders that both carry an
+/// owner (see [`crate::order::Order::with_owner`]) are compared.
+///
+/// Continuous matching only: a call auction's uncross trades same-owner
+/// orders against each other like any others; see
+/// [`crate::book::OrderBook::uncross`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum SelfTradePrevention {
+    /// Cancel the incoming order's residual; the resting order stays. An
+    /// incoming order that has not traded yet is rejected outright with
+    /// `OrderRejectionReason::SelfMatch`.
+    #[default]
+    CancelNewest,
+    /// Cancel the resting order and keep matching the incoming one.
+    CancelOldest,
+    /// Cancel the resting order and the incoming order's residual.
+    CancelBoth,
+    /// Reduce both orders by the smaller open quantity without trading.
+    /// Whichever side reaches zero is cancelled; a resting order left with
+    /// quantity keeps its place in the queue.
+    DecrementAndCancel,
+}
+
+/// How an incoming order's quantity is shared among the resting orders at
+/// the best opposite price. See [`crate::book::allocation`] for the exact
+/// rounding and tie-breaking of each rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum MatchingAlgorithm {
+    /// Strict price-time priority: the oldest order at a price fills first.
+    #[default]
+    Fifo,
+    /// Every order at the price gets a share proportional to its displayed
+    /// quantity, rounded down; leftover units go one each to the largest
+    /// orders, earliest first on equal size.
+    ProRata,
+    /// The front order of the queue fills first, the rest of the quantity
+    /// is shared pro-rata among the others, and what rounding leaves over
+    /// is handed out in FIFO order.
+    ProRataTopOrder,
+}
+
+/// How far a market order may sweep, measured from the opposite side's
+/// best price when it arrives. Liquidity beyond the band is left alone and
+/// the market order's residual is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ProtectionBand {
+    /// A fixed number of ticks through the best.
+    Ticks(u64),
+    /// Basis points of the best price, rounded down to whole ticks.
+    Bps(u64),
+}
+
+impl ProtectionBand {
+    /// Worst price a market order on `side` may trade at, given the
+    /// opposite side's best price `best` and the instrument's tick size
+    /// in raw price units.
+    pub fn limit(self, side: Side, best: Px, tick: u64) -> Px {
+        let tick = tick.max(1);
+        let offset = match self {
+            ProtectionBand::Ticks(n) => n.saturating_mul(tick),
+            ProtectionBand::Bps(bps) => {
+                let raw = u128::from(best.raw()) * u128::from(bps) / 10_000;
+                let raw = u64::try_from(raw).unwrap_or(u64::MAX);
+                raw - raw % tick
+            }
+        };
+        let raw = match side {
+            Side::Buy => best.raw().saturating_add(offset),
+            Side::Sell => best.raw().saturating_sub(offset),
+        };
+        Px::from_raw(raw).unwrap_or(Px::MIN)
+    }
+}
+
+impl Default for ProtectionBand {
+    /// 5% either side of the touch.
+    fn default() -> Self {
+        ProtectionBand::Bps(500)
+    }
+}
+
+/// Limit-up / limit-down style guard. A trade may only print within
+/// `band` either side of the reference price — the mean price of the
+/// trades in the trailing `window`, or the last trade when the window is
+/// empty. A trade that would print outside halts the book instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VolatilityGuard {
+    pub band: ProtectionBand,
+    /// Length of the trailing window, in nanoseconds of engine time.
+    pub window: u64,
+}
+
+impl VolatilityGuard {
+    /// Lowest and highest price a trade may print at around `reference`,
+    /// for an instrument with the given tick size.
+    pub fn limits(self, reference: Px, tick: u64) -> (Px, Px) {
+        (
+            self.band.limit(Side::Sell, reference, tick),
+            self.band.limit(Side::Buy, reference, tick),
+        )
+    }
+}
+
+/// Which full-depth feeds the book publishes next to its top-of-book
+/// quotes (see [`crate::events::depth`]). Both are off by default: they
+/// cost an event per resting-order change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct MarketData {
+    /// Market-by-price: a `LevelEvent` whenever a level's displayed
+    /// quantity changes.
+    pub by_price: bool,
+    /// Market-by-order: an `MboEvent` for every resting order added,
+    /// modified or removed.
+    pub by_order: bool,
+}
+
+impl MarketData {
+    pub const FULL: MarketData = MarketData {
+        by_price: true,
+        by_order: true,
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BookConfig {
+    pub matching: MatchingAlgorithm,
+    pub self_trade_prevention: SelfTradePrevention,
+    pub market_protection: ProtectionBand,
+    /// Off by default: nothing halts the book on its own.
+    pub volatility_guard: Option<VolatilityGuard>,
+    /// Tick, lot and range rules orders must meet. `None` accepts any
+    /// valid `Px` and non-zero `Qty` on a tick of one raw unit.
+    pub instrument: Option<InstrumentSpec>,
+    pub market_data: MarketData,
+    /// Audit the whole book after every operation and fail the operation
+    /// with a fatal `InvariantViolation` if it is broken (see
+    /// [`crate::book::audit`]). Off by default: each audit walks the book.
+    pub checked: bool,
+    /// How each side stores its price levels. Behaviour is the same for
+    /// every kind; pick one by benchmark.
+    pub ladder: LadderKind,
+}
+
+impl BookConfig {
+    /// Price increment in raw price units: the instrument's tick, or one.
+    pub fn tick_size(&self) -> u64 {
+        self.instrument.map_or(1, |spec| spec.tick_size)
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(c: u64) -> Px {
+        Px::from_raw(c).unwrap()
+    }
+
+    #[test]
+    fn ticks_band_offsets_from_best() {
+        let band = ProtectionBand::Ticks(5);
+        assert_eq!(band.limit(Side::Buy, px(10_000), 1), px(10_005));
+        assert_eq!(band.limit(Side::Sell, px(10_000), 1), px(9_995));
+        assert_eq!(band.limit(Side::Buy, px(10_000), 5), px(10_025));
+    }
+
+    #[test]
+    fn bps_band_rounds_down_and_floors_at_min_price() {
+        let band = ProtectionBand::Bps(25);
+        // 0.25% of 10_001 = 25.0025 → 25 ticks.
+        assert_eq!(band.limit(Side::Buy, px(10_001), 1), px(10_026));
+        // 0.23% of 10_000 = 23 → 20 on a five-cent tick.
+        assert_eq!(ProtectionBand::Bps(23).limit(Side::Buy, px(10_000), 5), px(10_020));
+        assert_eq!(ProtectionBand::Bps(20_000).limit(Side::Sell, px(100), 1), Px::MIN);
+    }
+
+    #[test]
+    fn volatility_limits_straddle_the_reference() {
+        let guard = VolatilityGuard {
+            band: ProtectionBand::Bps(500),
+            window: 1_000,
+        };
+        assert_eq!(guard.limits(px(10_000), 1), (px(9_500), px(10_500)));
+    }
+}
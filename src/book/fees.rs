@@ -0,0 +1,269 @@
+//! Maker / taker fee schedules.
+//!
+//! A [`FeeSchedule`] belongs to the [`crate::book::Market`]: books match
+//! without knowing about fees, and the market prices every fill it routes
+//! back before handing the result on (see [`crate::book::Market::set_fee_schedule`]).
+//!
+//! Amounts are signed integers in the instrument's raw price units — its
+//! quote currency at price scale. Positive is paid to the venue, negative
+//! is a rebate. Every amount is rounded up, toward the venue: a charge
+//! never comes out below the exact figure and a rebate never above it.
+//!
+//! Rates are tiered on the account's traded volume: the tier in force for
+//! a fill is the last one whose `from_volume` the account's quantity
+//! already traded in that symbol has reached. Orders without an owner are
+//! charged at the first tier and no volume is kept for them.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::types::{OwnerID, Px, Qty, Scale, Symbol};
+
+/// Price of one fill to one side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum FeeRate {
+    /// Whole basis points of the traded notional; negative for a rebate.
+    Bps(i64),
+    /// Raw price units per whole unit of quantity; negative for a rebate.
+    PerUnit(i64),
+}
+
+impl FeeRate {
+    pub const FREE: FeeRate = FeeRate::Bps(0);
+
+    /// Fee on `qty` traded at `px`, for an instrument whose quantities
+    /// carry `qty_scale` decimals. Saturates at the ends of `i64`.
+    pub fn charge(self, px: Px, qty: Qty, qty_scale: Scale) -> i64 {
+        let qty = i128::from(qty.value());
+        let unit = i128::from(qty_scale.factor());
+        let (num, den) = match self {
+            FeeRate::Bps(bps) => (
+                i128::from(px.raw())
+                    .saturating_mul(qty)
+                    .saturating_mul(i128::from(bps)),
+                unit * 10_000,
+            ),
+            FeeRate::PerUnit(rate) => (i128::from(rate).saturating_mul(qty), unit),
+        };
+        let fee = num.div_euclid(den) + i128::from(num.rem_euclid(den) != 0);
+        i64::try_from(fee).unwrap_or(if fee < 0 { i64::MIN } else { i64::MAX })
+    }
+}
+
+/// Which side of a trade an order was on: resting (maker) or taking
+/// (taker). Both orders in an auction uncross count as takers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Liquidity {
+    Maker,
+    Taker,
+}
+
+/// Rates from `from_volume` traded quantity (raw units) upward.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FeeTier {
+    pub from_volume: u64,
+    pub maker: FeeRate,
+    pub taker: FeeRate,
+}
+
+impl FeeTier {
+    /// A tier that applies from the first trade.
+    pub const fn flat(maker: FeeRate, taker: FeeRate) -> Self {
+        FeeTier {
+            from_volume: 0,
+            maker,
+            taker,
+        }
+    }
+
+    #[inline]
+    pub fn rate(&self, liquidity: Liquidity) -> FeeRate {
+        match liquidity {
+            Liquidity::Maker => self.maker,
+            Liquidity::Taker => self.taker,
+        }
+    }
+}
+
+/// Tiered maker / taker rates: a default table, optionally overridden per
+/// symbol, per account, or per account in one symbol. The most specific
+/// table wins. An empty schedule charges nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    pub(crate) default: Vec<FeeTier>,
+    pub(crate) by_symbol: BTreeMap<Symbol, Vec<FeeTier>>,
+    pub(crate) by_owner: BTreeMap<OwnerID, Vec<FeeTier>>,
+    pub(crate) by_owner_symbol: BTreeMap<(OwnerID, Symbol), Vec<FeeTier>>,
+}
+
+impl FeeSchedule {
+    /// Schedule with `tiers` as the default table.
+    pub fn new(tiers: Vec<FeeTier>) -> NyquestroResult<Self> {
+        Ok(FeeSchedule {
+            default: checked(tiers)?,
+            ..FeeSchedule::default()
+        })
+    }
+
+    /// One maker and one taker rate for everybody.
+    pub fn flat(maker: FeeRate, taker: FeeRate) -> Self {
+        FeeSchedule {
+            default: vec![FeeTier::flat(maker, taker)],
+            ..FeeSchedule::default()
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: Symbol, tiers: Vec<FeeTier>) -> NyquestroResult<Self> {
+        self.by_symbol.insert(symbol, checked(tiers)?);
+        Ok(self)
+    }
+
+    pub fn with_owner(mut self, owner: OwnerID, tiers: Vec<FeeTier>) -> NyquestroResult<Self> {
+        self.by_owner.insert(owner, checked(tiers)?);
+        Ok(self)
+    }
+
+    pub fn with_owner_symbol(
+        mut self,
+        owner: OwnerID,
+        symbol: Symbol,
+        tiers: Vec<FeeTier>,
+    ) -> NyquestroResult<Self> {
+        self.by_owner_symbol.insert((owner, symbol), checked(tiers)?);
+        Ok(self)
+    }
+
+    /// The table `owner` trades `symbol` on: account-and-symbol, then
+    /// account, then symbol, then the default.
+    pub fn tiers(&self, owner: Option<OwnerID>, symbol: Symbol) -> &[FeeTier] {
+        owner
+            .and_then(|o| {
+                self.by_owner_symbol
+                    .get(&(o, symbol))
+                    .or_else(|| self.by_owner.get(&o))
+            })
+            .or_else(|| self.by_symbol.get(&symbol))
+            .unwrap_or(&self.default)
+    }
+
+    /// Rate for a `liquidity` fill by `owner` in `symbol`, with `volume`
+    /// already traded there.
+    pub fn rate(
+        &self,
+        owner: Option<OwnerID>,
+        symbol: Symbol,
+        volume: u64,
+        liquidity: Liquidity,
+    ) -> FeeRate {
+        self.tiers(owner, symbol)
+            .iter()
+            .rev()
+            .find(|t| t.from_volume <= volume)
+            .map_or(FeeRate::FREE, |t| t.rate(liquidity))
+    }
+}
+
+/// One account's fees in one symbol so far.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct FeeTotals {
+    /// Net of fees and rebates on fills where the account's order rested.
+    pub maker: i64,
+    /// Fees on fills where the account's order took liquidity.
+    pub taker: i64,
+    /// Quantity traded, in raw units; what the tiers are measured on.
+    pub volume: u64,
+}
+
+impl FeeTotals {
+    /// Everything the account owes (negative: is owed) in this symbol.
+    #[inline]
+    pub fn net(&self) -> i64 {
+        self.maker.saturating_add(self.taker)
+    }
+
+    pub(crate) fn record(&mut self, liquidity: Liquidity, fee: i64, qty: Qty) {
+        match liquidity {
+            Liquidity::Maker => self.maker = self.maker.saturating_add(fee),
+            Liquidity::Taker => self.taker = self.taker.saturating_add(fee),
+        }
+        self.volume = self.volume.saturating_add(qty.value());
+    }
+}
+
+/// A tier table must start at zero volume and climb strictly.
+fn checked(tiers: Vec<FeeTier>) -> NyquestroResult<Vec<FeeTier>> {
+    match tiers.first() {
+        None => return Err(NyquestroError::InvalidFeeSchedule("no tiers")),
+        Some(t) if t.from_volume != 0 => {
+            return Err(NyquestroError::InvalidFeeSchedule("first tier must start at zero volume"));
+        }
+        Some(_) => {}
+    }
+    if tiers.windows(2).any(|w| w[0].from_volume >= w[1].from_volume) {
+        return Err(NyquestroError::InvalidFeeSchedule("tier volumes must increase"));
+    }
+    Ok(tiers)
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(raw: u64) -> Px {
+        Px::from_raw(raw).unwrap()
+    }
+
+    #[test]
+    fn charges_round_toward_the_venue() {
+        // 3 units at $100.01 = 30003 cents; 1 bp = 3.0003 cents.
+        assert_eq!(FeeRate::Bps(1).charge(px(10001), Qty::new(3), Scale::UNITS), 4);
+        assert_eq!(FeeRate::Bps(-1).charge(px(10001), Qty::new(3), Scale::UNITS), -3);
+        assert_eq!(FeeRate::PerUnit(2).charge(px(10001), Qty::new(3), Scale::UNITS), 6);
+        // 0.5 of a unit at eight decimals, 3 cents per whole unit.
+        let half = Qty::new(50_000_000);
+        assert_eq!(FeeRate::PerUnit(3).charge(px(1), half, Scale::new(8).unwrap()), 2);
+        assert_eq!(FeeRate::PerUnit(-3).charge(px(1), half, Scale::new(8).unwrap()), -1);
+    }
+
+    #[test]
+    fn most_specific_table_and_reached_tier_win() {
+        let sym = Symbol::from_const("AAPL");
+        let other = Symbol::from_const("MSFT");
+        let vip = OwnerID::new(1).unwrap();
+        let schedule = FeeSchedule::new(vec![
+            FeeTier::flat(FeeRate::Bps(-1), FeeRate::Bps(3)),
+            FeeTier {
+                from_volume: 100,
+                maker: FeeRate::Bps(-2),
+                taker: FeeRate::Bps(2),
+            },
+        ])
+        .unwrap()
+        .with_symbol(other, vec![FeeTier::flat(FeeRate::FREE, FeeRate::Bps(5))])
+        .unwrap()
+        .with_owner(vip, vec![FeeTier::flat(FeeRate::Bps(-4), FeeRate::Bps(1))])
+        .unwrap();
+
+        assert_eq!(schedule.rate(None, sym, 0, Liquidity::Taker), FeeRate::Bps(3));
+        assert_eq!(schedule.rate(None, sym, 100, Liquidity::Maker), FeeRate::Bps(-2));
+        assert_eq!(schedule.rate(None, other, 500, Liquidity::Taker), FeeRate::Bps(5));
+        assert_eq!(schedule.rate(Some(vip), other, 0, Liquidity::Maker), FeeRate::Bps(-4));
+        assert_eq!(FeeSchedule::default().rate(None, sym, 0, Liquidity::Taker), FeeRate::FREE);
+    }
+
+    #[test]
+    fn rejects_malformed_tiers() {
+        let tier = |from| FeeTier {
+            from_volume: from,
+            ..FeeTier::flat(FeeRate::FREE, FeeRate::FREE)
+        };
+        assert!(FeeSchedule::new(vec![]).is_err());
+        assert!(FeeSchedule::new(vec![tier(5)]).is_err());
+        assert!(FeeSchedule::new(vec![tier(0), tier(10), tier(10)]).is_err());
+        assert!(FeeSchedule::new(vec![tier(0), tier(10)]).is_ok());
+    }
+}
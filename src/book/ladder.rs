@@ -0,0 +1,675 @@
+//! Price ladders: where each side of a book keeps its levels.
+//!
+//! A book holds one `Ladder` per side, of the [`LadderKind`] its config
+//! names. Both kinds file levels by price and walk them lowest price first
+//! (or, reversed, highest first), so matching cannot tell them apart; they
+//! differ only in what finding a level costs.
+//!
+//! - **`Tree`** is a `BTreeMap<Px, PriceLevel>`: O(log n) to any level,
+//!   whatever the spread of prices.
+//! - **`Ticks`** is a `TickLadder`: a fixed array of slots one tick
+//!   apart, so a price inside the band is a subtraction and a division
+//!   away from its level. Cursors to the lowest and highest occupied slot
+//!   make the best price O(1); when the level under a cursor empties the
+//!   cursor steps inward to the next occupied slot. Prices outside the
+//!   band, or off the tick grid (a peg can land between ticks), go to a
+//!   `BTreeMap` beside the array. The band follows the touch: an on-grid
+//!   price outside it that would be the side's best level (or any on-grid
+//!   price while the array is empty) re-centres the band on itself. Levels
+//!   the new band no longer covers move out to the overflow and overflow
+//!   levels it now covers move in, so a stale level deep in the book never
+//!   holds the band in place.
+//!
+//! Either way a ladder keeps a few emptied levels as spares and opens new
+//! levels on them, so a level coming and going at the touch reuses its
+//! node slab (see [`crate::book::price_level`]) rather than allocating.
+
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::price_level::PriceLevel;
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::types::{Px, Side};
+
+/// Which structure each side of a book keeps its price levels in. Matching
+/// is the same either way; see [`crate::book::ladder`] for the costs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum LadderKind {
+    /// A `BTreeMap` keyed by price.
+    #[default]
+    Tree,
+    /// An array of `slots` levels one tick apart around the touch, with a
+    /// `BTreeMap` for prices outside it. The array is allocated up front,
+    /// so build this through [`LadderKind::ticks`]; a count outside
+    /// `1..=MAX_TICK_SLOTS` written out by hand is clamped into it.
+    Ticks { slots: u32 },
+}
+
+impl LadderKind {
+    /// The most slots a tick ladder may have: 65,536 levels per side, a
+    /// few megabytes of array before a single order rests.
+    pub const MAX_TICK_SLOTS: u32 = 1 << 16;
+
+    /// A tick ladder of `slots` levels, refusing a count of zero or one
+    /// over [`MAX_TICK_SLOTS`](Self::MAX_TICK_SLOTS).
+    pub fn ticks(slots: u32) -> NyquestroResult<LadderKind> {
+        if slots == 0 || slots > Self::MAX_TICK_SLOTS {
+            return Err(NyquestroError::InvalidLadder {
+                slots,
+                max: Self::MAX_TICK_SLOTS,
+            });
+        }
+        Ok(LadderKind::Ticks { slots })
+    }
+}
+
+/// Emptied levels a ladder keeps for reuse, per side.
+const SPARE_LEVELS: usize = 16;
+
+/// One side's price levels, keyed by price.
+#[derive(Debug, Clone)]
+pub(crate) struct Ladder {
+    levels: Levels,
+    /// Levels that emptied, kept for their node slabs: a new level takes
+    /// one instead of allocating.
+    spare: Vec<PriceLevel>,
+}
+
+#[derive(Debug, Clone)]
+enum Levels {
+    Tree(BTreeMap<Px, PriceLevel>),
+    Ticks(TickLadder),
+}
+
+impl Ladder {
+    /// An empty ladder of `kind` for `side`'s prices on a grid of `tick`
+    /// raw units.
+    pub(crate) fn new(kind: LadderKind, tick: u64, side: Side) -> Self {
+        let levels = match kind {
+            LadderKind::Tree => Levels::Tree(BTreeMap::new()),
+            LadderKind::Ticks { slots } => Levels::Ticks(TickLadder::new(slots, tick, side)),
+        };
+        Ladder {
+            levels,
+            spare: Vec::with_capacity(SPARE_LEVELS),
+        }
+    }
+
+    /// Number of levels.
+    pub(crate) fn len(&self) -> usize {
+        match &self.levels {
+            Levels::Tree(map) => map.len(),
+            Levels::Ticks(ticks) => ticks.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn get(&self, price: &Px) -> Option<&PriceLevel> {
+        match &self.levels {
+            Levels::Tree(map) => map.get(price),
+            Levels::Ticks(ticks) => ticks.get(*price),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, price: &Px) -> Option<&mut PriceLevel> {
+        match &mut self.levels {
+            Levels::Tree(map) => map.get_mut(price),
+            Levels::Ticks(ticks) => ticks.get_mut(*price),
+        }
+    }
+
+    /// The level at `price`, opened empty (on a spare if there is one) if
+    /// there is none.
+    pub(crate) fn entry(&mut self, price: Px) -> &mut PriceLevel {
+        let Ladder { levels, spare } = self;
+        let open = || match spare.pop() {
+            Some(mut level) => {
+                level.reopen(price);
+                level
+            }
+            None => PriceLevel::new(price),
+        };
+        match levels {
+            Levels::Tree(map) => map.entry(price).or_insert_with(open),
+            Levels::Ticks(ticks) => ticks.entry(price, open),
+        }
+    }
+
+    /// File `level` at `price`, replacing whatever was there.
+    pub(crate) fn insert(&mut self, price: Px, level: PriceLevel) {
+        match &mut self.levels {
+            Levels::Tree(map) => {
+                map.insert(price, level);
+            }
+            Levels::Ticks(ticks) => ticks.insert(price, level),
+        }
+    }
+
+    /// Drop the level at `price`, keeping it as a spare. Whether there was
+    /// one.
+    pub(crate) fn remove(&mut self, price: &Px) -> bool {
+        let removed = match &mut self.levels {
+            Levels::Tree(map) => map.remove(price),
+            Levels::Ticks(ticks) => ticks.remove(*price),
+        };
+        match removed {
+            Some(level) if self.spare.len() < SPARE_LEVELS => self.spare.push(level),
+            Some(_) => {}
+            None => return false,
+        }
+        true
+    }
+
+    /// The lowest-priced level: the best ask.
+    pub(crate) fn first(&self) -> Option<(&Px, &PriceLevel)> {
+        match &self.levels {
+            Levels::Tree(map) => map.first_key_value(),
+            Levels::Ticks(ticks) => ticks.first(),
+        }
+    }
+
+    /// The highest-priced level: the best bid.
+    pub(crate) fn last(&self) -> Option<(&Px, &PriceLevel)> {
+        match &self.levels {
+            Levels::Tree(map) => map.last_key_value(),
+            Levels::Ticks(ticks) => ticks.last(),
+        }
+    }
+
+    /// Every level, lowest price first.
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = (&Px, &PriceLevel)> {
+        match &self.levels {
+            Levels::Tree(map) => Either::Tree(map.iter()),
+            Levels::Ticks(ticks) => Either::Ticks(ticks.iter()),
+        }
+    }
+
+    /// Every level priced from `lo` to `hi` inclusive, lowest price first.
+    /// `lo` must not be above `hi`.
+    pub(crate) fn range_mut(
+        &mut self,
+        lo: Px,
+        hi: Px,
+    ) -> impl DoubleEndedIterator<Item = (&Px, &mut PriceLevel)> {
+        match &mut self.levels {
+            Levels::Tree(map) => Either::Tree(map.range_mut(lo..=hi)),
+            Levels::Ticks(ticks) => Either::Ticks(ticks.range_mut(lo, hi)),
+        }
+    }
+}
+
+/// Levels in an array of slots one tick apart, with a `BTreeMap` for the
+/// prices the array does not cover.
+#[derive(Debug, Clone)]
+pub(crate) struct TickLadder {
+    /// Whose levels these are, which says where the touch is: the highest
+    /// price for bids, the lowest for asks.
+    side: Side,
+    tick: u64,
+    /// Raw price of slot 0; always on the tick grid.
+    base: u64,
+    slots: Vec<Option<(Px, PriceLevel)>>,
+    /// Number of `Some` slots.
+    occupied: usize,
+    /// Lowest and highest occupied slot; meaningless while `occupied` is 0.
+    low: usize,
+    high: usize,
+    /// Levels outside the band or off the tick grid.
+    overflow: BTreeMap<Px, PriceLevel>,
+}
+
+impl TickLadder {
+    fn new(slots: u32, tick: u64, side: Side) -> Self {
+        let slots = usize::try_from(slots.clamp(1, LadderKind::MAX_TICK_SLOTS)).unwrap_or(1);
+        TickLadder {
+            side,
+            tick: tick.max(1),
+            base: 0,
+            slots: std::iter::repeat_with(|| None).take(slots).collect(),
+            occupied: 0,
+            low: 0,
+            high: 0,
+            overflow: BTreeMap::new(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.occupied + self.overflow.len()
+    }
+
+    /// The slot `price` lives in, if the band covers it.
+    fn slot(&self, price: Px) -> Option<usize> {
+        let offset = price.raw().checked_sub(self.base)?;
+        if !offset.is_multiple_of(self.tick) {
+            return None;
+        }
+        usize::try_from(offset / self.tick).ok().filter(|&i| i < self.slots.len())
+    }
+
+    /// The slot a level at `price` should be filed in, re-centring the
+    /// band on `price` first if the band does not cover it and it is the
+    /// touch: at or beyond this side's best level, or the array is empty.
+    fn home(&mut self, price: Px) -> Option<usize> {
+        if price.raw().is_multiple_of(self.tick)
+            && self.slot(price).is_none()
+            && (self.occupied == 0 || self.is_touch(price))
+        {
+            self.rebase(price);
+        }
+        self.slot(price)
+    }
+
+    /// Whether a level at `price` would be the best on this side.
+    fn is_touch(&self, price: Px) -> bool {
+        match self.side {
+            Side::Buy => self.last().is_none_or(|(best, _)| price >= *best),
+            Side::Sell => self.first().is_none_or(|(best, _)| price <= *best),
+        }
+    }
+
+    /// Centre the band on `price`: the levels it no longer covers move to
+    /// the overflow, and the overflow levels it now covers move in.
+    fn rebase(&mut self, price: Px) {
+        for i in self.low..=self.high {
+            if let Some((p, level)) = self.slots[i].take() {
+                self.overflow.insert(p, level);
+            }
+        }
+        self.occupied = 0;
+        let half = u64::try_from(self.slots.len() / 2).unwrap_or(u64::MAX);
+        self.base = price.raw().saturating_sub(half.saturating_mul(self.tick));
+        self.base -= self.base % self.tick;
+        let covered: Vec<Px> =
+            self.overflow.keys().copied().filter(|&p| self.slot(p).is_some()).collect();
+        for price in covered {
+            if let (Some(i), Some(level)) = (self.slot(price), self.overflow.remove(&price)) {
+                self.occupy(i);
+                self.slots[i] = Some((price, level));
+            }
+        }
+    }
+
+    /// Count the empty slot `i` as occupied, widening the cursors to it.
+    fn occupy(&mut self, i: usize) {
+        if self.occupied == 0 {
+            (self.low, self.high) = (i, i);
+        } else {
+            self.low = self.low.min(i);
+            self.high = self.high.max(i);
+        }
+        self.occupied += 1;
+    }
+
+    fn get(&self, price: Px) -> Option<&PriceLevel> {
+        match self.slot(price) {
+            Some(i) => self.slots[i].as_ref().map(|(_, level)| level),
+            None => self.overflow.get(&price),
+        }
+    }
+
+    fn get_mut(&mut self, price: Px) -> Option<&mut PriceLevel> {
+        match self.slot(price) {
+            Some(i) => self.slots[i].as_mut().map(|(_, level)| level),
+            None => self.overflow.get_mut(&price),
+        }
+    }
+
+    fn entry(&mut self, price: Px, open: impl FnOnce() -> PriceLevel) -> &mut PriceLevel {
+        match self.home(price) {
+            Some(i) => {
+                if self.slots[i].is_none() {
+                    self.occupy(i);
+                }
+                let (_, level) = self.slots[i].get_or_insert_with(|| (price, open()));
+                level
+            }
+            None => self.overflow.entry(price).or_insert_with(open),
+        }
+    }
+
+    fn insert(&mut self, price: Px, level: PriceLevel) {
+        match self.home(price) {
+            Some(i) => {
+                if self.slots[i].is_none() {
+                    self.occupy(i);
+                }
+                self.slots[i] = Some((price, level));
+            }
+            None => {
+                self.overflow.insert(price, level);
+            }
+        }
+    }
+
+    fn remove(&mut self, price: Px) -> Option<PriceLevel> {
+        let Some(i) = self.slot(price) else {
+            return self.overflow.remove(&price);
+        };
+        let (_, level) = self.slots[i].take()?;
+        self.occupied -= 1;
+        if self.occupied > 0 {
+            // Step the cursor that pointed here inward to the next level.
+            if i == self.low {
+                self.low = (i + 1..=self.high).find(|&j| self.slots[j].is_some()).unwrap_or(i);
+            } else if i == self.high {
+                self.high = (self.low..i).rev().find(|&j| self.slots[j].is_some()).unwrap_or(i);
+            }
+        }
+        Some(level)
+    }
+
+    /// The occupied part of the array.
+    fn band(&self) -> &[Option<(Px, PriceLevel)>] {
+        if self.occupied == 0 {
+            &[]
+        } else {
+            &self.slots[self.low..=self.high]
+        }
+    }
+
+    fn first(&self) -> Option<(&Px, &PriceLevel)> {
+        let slot = self.band().first().and_then(|s| s.as_ref()).map(|(p, l)| (p, l));
+        match (slot, self.overflow.first_key_value()) {
+            (Some(s), Some(o)) => Some(if s.0 < o.0 { s } else { o }),
+            (s, o) => s.or(o),
+        }
+    }
+
+    fn last(&self) -> Option<(&Px, &PriceLevel)> {
+        let slot = self.band().last().and_then(|s| s.as_ref()).map(|(p, l)| (p, l));
+        match (slot, self.overflow.last_key_value()) {
+            (Some(s), Some(o)) => Some(if s.0 > o.0 { s } else { o }),
+            (s, o) => s.or(o),
+        }
+    }
+
+    fn iter(
+        &self,
+    ) -> Merge<
+        impl DoubleEndedIterator<Item = (&Px, &PriceLevel)>,
+        btree_map::Iter<'_, Px, PriceLevel>,
+    > {
+        let slots = self.band().iter().flatten().map(|(p, l)| (p, l));
+        Merge::new(slots, self.overflow.iter())
+    }
+
+    fn range_mut(
+        &mut self,
+        lo: Px,
+        hi: Px,
+    ) -> Merge<
+        impl DoubleEndedIterator<Item = (&Px, &mut PriceLevel)>,
+        btree_map::RangeMut<'_, Px, PriceLevel>,
+    > {
+        // Slots from the first at or above `lo` to the last at or below
+        // `hi`, clipped to the occupied part of the array.
+        let index = |offset: u64| usize::try_from(offset).unwrap_or(usize::MAX);
+        let from = index(lo.raw().saturating_sub(self.base).div_ceil(self.tick)).max(self.low);
+        let to = hi.raw().checked_sub(self.base).map(|o| index(o / self.tick).min(self.high));
+        let slots: &mut [Option<(Px, PriceLevel)>] = match to {
+            Some(to) if self.occupied > 0 && from <= to => &mut self.slots[from..=to],
+            _ => &mut [],
+        };
+        let slots = slots.iter_mut().flatten().map(|(p, l)| (&*p, l));
+        Merge::new(slots, self.overflow.range_mut(lo..=hi))
+    }
+}
+
+/// Either ladder's iterator, so [`Ladder`] can return one type.
+enum Either<T, K> {
+    Tree(T),
+    Ticks(K),
+}
+
+impl<T: Iterator, K: Iterator<Item = T::Item>> Iterator for Either<T, K> {
+    type Item = T::Item;
+
+    fn next(&mut self) -> Option<T::Item> {
+        match self {
+            Either::Tree(it) => it.next(),
+            Either::Ticks(it) => it.next(),
+        }
+    }
+}
+
+impl<T: DoubleEndedIterator, K: DoubleEndedIterator<Item = T::Item>> DoubleEndedIterator
+    for Either<T, K>
+{
+    fn next_back(&mut self) -> Option<T::Item> {
+        match self {
+            Either::Tree(it) => it.next_back(),
+            Either::Ticks(it) => it.next_back(),
+        }
+    }
+}
+
+/// Two price-ordered walks over disjoint prices merged into one, from
+/// either end. Each side keeps the item it last looked at and lost.
+pub(crate) struct Merge<A: Iterator, B: Iterator> {
+    a: A,
+    b: B,
+    a_front: Option<A::Item>,
+    b_front: Option<A::Item>,
+    a_back: Option<A::Item>,
+    b_back: Option<A::Item>,
+}
+
+impl<'p, V, A, B> Merge<A, B>
+where
+    A: DoubleEndedIterator<Item = (&'p Px, V)>,
+    B: DoubleEndedIterator<Item = (&'p Px, V)>,
+{
+    fn new(a: A, b: B) -> Self {
+        Merge {
+            a,
+            b,
+            a_front: None,
+            b_front: None,
+            a_back: None,
+            b_back: None,
+        }
+    }
+}
+
+impl<'p, V, A, B> Iterator for Merge<A, B>
+where
+    A: DoubleEndedIterator<Item = (&'p Px, V)>,
+    B: DoubleEndedIterator<Item = (&'p Px, V)>,
+{
+    type Item = (&'p Px, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Once a side runs dry from the front, what it handed the back is
+        // next from the front too.
+        let a = self.a_front.take().or_else(|| self.a.next()).or_else(|| self.a_back.take());
+        let b = self.b_front.take().or_else(|| self.b.next()).or_else(|| self.b_back.take());
+        match (a, b) {
+            (Some(a), Some(b)) if a.0 < b.0 => {
+                self.b_front = Some(b);
+                Some(a)
+            }
+            (Some(a), Some(b)) => {
+                self.a_front = Some(a);
+                Some(b)
+            }
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+impl<'p, V, A, B> DoubleEndedIterator for Merge<A, B>
+where
+    A: DoubleEndedIterator<Item = (&'p Px, V)>,
+    B: DoubleEndedIterator<Item = (&'p Px, V)>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let a = self.a_back.take().or_else(|| self.a.next_back()).or_else(|| self.a_front.take());
+        let b = self.b_back.take().or_else(|| self.b.next_back()).or_else(|| self.b_front.take());
+        match (a, b) {
+            (Some(a), Some(b)) if a.0 > b.0 => {
+                self.b_back = Some(b);
+                Some(a)
+            }
+            (Some(a), Some(b)) => {
+                self.a_back = Some(a);
+                Some(b)
+            }
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::order::Order;
+    use crate::types::{OrderID, Qty, Side, Symbol, Ts};
+
+    fn px(c: u64) -> Px {
+        Px::from_raw(c).unwrap()
+    }
+
+    fn prices(ladder: &Ladder) -> Vec<u64> {
+        ladder.iter().map(|(p, _)| p.raw()).collect()
+    }
+
+    /// A level at `price` holding one order of `qty`.
+    fn level(price: u64, qty: u64) -> PriceLevel {
+        let sym = Symbol::from_const("TEST");
+        let id = OrderID::new(price).unwrap();
+        let order =
+            Order::new(id, sym, Side::Buy, px(price), Qty::new(qty), Ts::from_nanos(1)).unwrap();
+        let mut level = PriceLevel::new(px(price));
+        level.push_back(order).unwrap();
+        level
+    }
+
+    #[test]
+    fn tick_slot_counts_are_bounded() {
+        assert_eq!(LadderKind::ticks(16), Ok(LadderKind::Ticks { slots: 16 }));
+        let max = LadderKind::MAX_TICK_SLOTS;
+        assert!(LadderKind::ticks(max).is_ok());
+        for slots in [0, max + 1, u32::MAX] {
+            assert_eq!(
+                LadderKind::ticks(slots),
+                Err(NyquestroError::InvalidLadder { slots, max })
+            );
+        }
+        // Written out by hand, an oversized count is clamped, not allocated.
+        let ladder = Ladder::new(LadderKind::Ticks { slots: u32::MAX }, 1, Side::Buy);
+        let Levels::Ticks(ticks) = ladder.levels else { unreachable!() };
+        assert_eq!(ticks.slots.len(), max as usize);
+    }
+
+    #[test]
+    fn band_is_centred_on_the_first_price_and_spills_to_overflow() {
+        let mut ladder = Ladder::new(LadderKind::Ticks { slots: 8 }, 5, Side::Sell);
+        ladder.entry(px(1000));
+        let Levels::Ticks(ticks) = &ladder.levels else { unreachable!() };
+        assert_eq!(ticks.base, 980);
+        // In band, twice beyond it away from the touch, and between ticks.
+        for price in [1015, 1020, 1030, 1002] {
+            ladder.entry(px(price));
+        }
+        let Levels::Ticks(ticks) = &ladder.levels else { unreachable!() };
+        assert_eq!((ticks.base, ticks.occupied, ticks.overflow.len()), (980, 2, 3));
+        assert_eq!(prices(&ladder), [1000, 1002, 1015, 1020, 1030]);
+        assert_eq!(ladder.first().map(|(p, _)| p.raw()), Some(1000));
+        assert_eq!(ladder.last().map(|(p, _)| p.raw()), Some(1030));
+    }
+
+    #[test]
+    fn cursors_step_inward_and_an_empty_band_recentres() {
+        let mut ladder = Ladder::new(LadderKind::Ticks { slots: 8 }, 1, Side::Sell);
+        for price in [100, 101, 103, 110] {
+            ladder.insert(px(price), level(price, 1));
+        }
+        ladder.remove(&px(100));
+        assert_eq!(ladder.first().map(|(p, _)| p.raw()), Some(101));
+        ladder.remove(&px(103));
+        assert_eq!(ladder.last().map(|(p, _)| p.raw()), Some(110));
+        ladder.remove(&px(101));
+        // The band is empty; the next price re-centres it on 110's
+        // neighbourhood and moves 110 out of the overflow.
+        ladder.entry(px(112));
+        let Levels::Ticks(ticks) = &ladder.levels else { unreachable!() };
+        assert_eq!((ticks.base, ticks.occupied, ticks.overflow.len()), (108, 2, 0));
+        assert_eq!(prices(&ladder), [110, 112]);
+        assert_eq!(ladder.get(&px(110)).map(|l| l.total_quantity()), Some(Qty::new(1)));
+    }
+
+    #[test]
+    fn the_band_follows_the_touch_past_a_stale_level() {
+        let mut ladder = Ladder::new(LadderKind::Ticks { slots: 8 }, 1, Side::Sell);
+        // A far ask that never trades, then a market that walks down from
+        // 100 to 60, leaving a level behind every ten ticks.
+        ladder.insert(px(200), level(200, 1));
+        for price in (60..=100).rev() {
+            ladder.entry(px(price));
+            if price < 100 && (price + 1) % 10 != 0 {
+                assert!(ladder.remove(&px(price + 1)));
+            }
+            let Levels::Ticks(ticks) = &ladder.levels else { unreachable!() };
+            assert!(ticks.slot(px(price)).is_some(), "touch {price} outside the band");
+            assert_eq!(ladder.first().map(|(p, _)| p.raw()), Some(price));
+        }
+        let Levels::Ticks(ticks) = &ladder.levels else { unreachable!() };
+        assert_eq!((ticks.base, ticks.occupied), (56, 1));
+        let overflow: Vec<u64> = ticks.overflow.keys().map(|p| p.raw()).collect();
+        assert_eq!(overflow, [70, 80, 90, 100, 200]);
+        assert_eq!(prices(&ladder), [60, 70, 80, 90, 100, 200]);
+        assert_eq!(ladder.get(&px(200)).map(|l| l.total_quantity()), Some(Qty::new(1)));
+    }
+
+    #[test]
+    fn range_walks_slots_and_overflow_together_from_both_ends() {
+        let mut ladder = Ladder::new(LadderKind::Ticks { slots: 4 }, 1, Side::Sell);
+        for price in [100, 101, 102, 90, 95, 120, 130] {
+            ladder.entry(px(price));
+        }
+        let walk = |ladder: &mut Ladder, lo, hi| -> Vec<u64> {
+            ladder.range_mut(px(lo), px(hi)).map(|(p, _)| p.raw()).collect()
+        };
+        assert_eq!(walk(&mut ladder, 95, 120), [95, 100, 101, 102, 120]);
+        assert_eq!(walk(&mut ladder, 101, 101), [101]);
+        assert_eq!(walk(&mut ladder, 103, 119), Vec::<u64>::new());
+        let down: Vec<u64> = ladder.range_mut(px(1), px(200)).rev().map(|(p, _)| p.raw()).collect();
+        assert_eq!(down, [130, 120, 102, 101, 100, 95, 90]);
+        // Meeting in the middle hands out every level exactly once.
+        let mut it = ladder.iter();
+        let mut met = vec![it.next().unwrap().0.raw(), it.next_back().unwrap().0.raw()];
+        met.extend(it.map(|(p, _)| p.raw()));
+        assert_eq!(met, [90, 130, 95, 100, 101, 102, 120]);
+    }
+
+    #[test]
+    fn matches_a_tree_over_random_edits() {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |n: u64| {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (state >> 33) % n
+        };
+        for side in [Side::Buy, Side::Sell] {
+            let mut tree = Ladder::new(LadderKind::Tree, 2, side);
+            let mut ticks = Ladder::new(LadderKind::Ticks { slots: 16 }, 2, side);
+            for step in 0..20_000 {
+                let price = px(1 + next(3) * 100 + next(60));
+                match next(3) {
+                    0 => assert_eq!(tree.remove(&price), ticks.remove(&price), "step {step}"),
+                    _ => assert_eq!(tree.entry(price).price(), ticks.entry(price).price()),
+                }
+                assert_eq!(prices(&tree), prices(&ticks), "{side:?} step {step}");
+                assert_eq!(tree.first().map(|(p, _)| *p), ticks.first().map(|(p, _)| *p));
+                assert_eq!(tree.last().map(|(p, _)| *p), ticks.last().map(|(p, _)| *p));
+                assert_eq!(tree.len(), ticks.len());
+            }
+        }
+    }
+}
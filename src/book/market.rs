@@ -2,18 +2,39 @@
 //!
 //! Holds one [`OrderBook`] per [`Symbol`]. New symbols are auto-registered
 //! on first submit; existing symbols route to their existing book.
+//! Symbols registered from [`ReferenceData`] get a book that enforces
+//! their [`InstrumentSpec`]; auto-registered ones accept any valid order.
+//!
+//! The market also prices fills: every fill it hands out, in a result or
+//! to an [`EventSink`], has been charged against the [`FeeSchedule`], in
+//! fill order, with each account's running totals updated as it goes (see
+//! [`crate::book::fees`]).
 
 use std::collections::BTreeMap;
 
+use crate::book::config::BookConfig;
+use crate::book::fees::{FeeSchedule, FeeTotals, Liquidity};
+use crate::book::mass_cancel::MassCancel;
 use crate::book::order_book::{OrderBook, SubmitResult};
-use crate::errors::NyquestroResult;
-use crate::events::OrderEvent;
+use crate::book::sink::EventSink;
+use crate::book::snapshot::{
+    FeeScheduleRecord, FeeTotalsRecord, MarketSnapshot, SNAPSHOT_VERSION,
+};
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboEvent, OrderEvent, QuoteEvent, StateEvent,
+};
+use crate::instrument::{InstrumentSpec, ReferenceData};
 use crate::order::Order;
-use crate::types::{OrderID, Px, Qty, Symbol, Ts};
+use crate::types::{AuctionKind, OrderID, OwnerID, Px, Qty, Scale, Side, Symbol, Ts};
 
 #[derive(Debug, Clone, Default)]
 pub struct Market {
     books: BTreeMap<Symbol, OrderBook>,
+    fees: FeeSchedule,
+    /// Per account, per symbol: amounts in different symbols can be in
+    /// different currencies and scales, so they are never summed here.
+    fee_totals: BTreeMap<(OwnerID, Symbol), FeeTotals>,
 }
 
 impl Market {
@@ -27,6 +48,38 @@ impl Market {
         self.books.entry(symbol).or_insert_with(|| OrderBook::new(symbol))
     }
 
+    /// Pre-register a symbol with a non-default [`BookConfig`]. A symbol
+    /// that is already registered keeps the config it was created with.
+    pub fn register_with(&mut self, symbol: Symbol, config: BookConfig) -> &mut OrderBook {
+        self.books
+            .entry(symbol)
+            .or_insert_with(|| OrderBook::with_config(symbol, config))
+    }
+
+    /// Pre-register a symbol whose book enforces `spec`. Fails on an
+    /// inconsistent spec. A symbol that is already registered keeps the
+    /// config it was created with.
+    pub fn register_instrument(
+        &mut self,
+        symbol: Symbol,
+        spec: InstrumentSpec,
+    ) -> NyquestroResult<&mut OrderBook> {
+        spec.validate()?;
+        let config = BookConfig {
+            instrument: Some(spec),
+            ..BookConfig::default()
+        };
+        Ok(self.register_with(symbol, config))
+    }
+
+    /// Register every symbol in `data` with its spec, in symbol order.
+    pub fn register_reference_data(&mut self, data: &ReferenceData) -> NyquestroResult<()> {
+        for (symbol, spec) in data.iter() {
+            self.register_instrument(*symbol, *spec)?;
+        }
+        Ok(())
+    }
+
     pub fn book(&self, symbol: Symbol) -> Option<&OrderBook> {
         self.books.get(&symbol)
     }
@@ -47,32 +100,341 @@ impl Market {
         self.books.len()
     }
 
+    /// Charge fills from now on against `fees`. Totals so far are kept.
+    pub fn set_fee_schedule(&mut self, fees: FeeSchedule) {
+        self.fees = fees;
+    }
+
+    pub fn fee_schedule(&self) -> &FeeSchedule {
+        &self.fees
+    }
+
+    /// What `owner` has paid and traded in `symbol` so far.
+    pub fn fee_totals(&self, owner: OwnerID, symbol: Symbol) -> FeeTotals {
+        self.fee_totals.get(&(owner, symbol)).copied().unwrap_or_default()
+    }
+
+    /// Every symbol `owner` has traded, with its totals, in symbol order.
+    pub fn account_fees(&self, owner: OwnerID) -> impl Iterator<Item = (Symbol, FeeTotals)> + '_ {
+        self.fee_totals
+            .iter()
+            .filter(move |((o, _), _)| *o == owner)
+            .map(|(&(_, symbol), totals)| (symbol, *totals))
+    }
+
     pub fn is_empty(&self) -> bool {
         self.books.is_empty()
     }
 
+    /// Every book, the fee schedule and every account's totals; see
+    /// [`crate::book::snapshot`].
+    pub fn snapshot(&self) -> MarketSnapshot {
+        MarketSnapshot {
+            version: SNAPSHOT_VERSION,
+            books: self.books.values().map(OrderBook::snapshot).collect(),
+            fees: FeeScheduleRecord::from_schedule(&self.fees),
+            fee_totals: self
+                .fee_totals
+                .iter()
+                .map(|(&(owner, symbol), &totals)| FeeTotalsRecord {
+                    owner: owner.value(),
+                    symbol: symbol.to_string(),
+                    totals,
+                })
+                .collect(),
+        }
+    }
+
+    /// Rebuild a market from `snapshot`, validating every book on the way
+    /// in. Fails on the first problem found; nothing is half-loaded.
+    pub fn from_snapshot(snapshot: MarketSnapshot) -> NyquestroResult<Market> {
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(NyquestroError::Snapshot(format!(
+                "unsupported version {}",
+                snapshot.version
+            )));
+        }
+        let mut market = Market {
+            fees: snapshot.fees.into_schedule()?,
+            ..Market::default()
+        };
+        for record in snapshot.books {
+            let book = OrderBook::from_snapshot(record)?;
+            let symbol = book.symbol();
+            if market.books.insert(symbol, book).is_some() {
+                return Err(NyquestroError::Snapshot(format!("{symbol}: book appears twice")));
+            }
+        }
+        for record in snapshot.fee_totals {
+            let key = (OwnerID::new(record.owner)?, record.symbol.parse()?);
+            if market.fee_totals.insert(key, record.totals).is_some() {
+                return Err(NyquestroError::Snapshot(format!(
+                    "fee totals for owner {} in {} appear twice",
+                    record.owner, record.symbol
+                )));
+            }
+        }
+        Ok(market)
+    }
+
     /// Submit an order. The book for the order's symbol is auto-registered
     /// if it doesn't already exist.
     pub fn submit_limit(&mut self, order: Order) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.submit_limit_into(order, out))
+    }
+
+    /// Like [`submit_limit`](Self::submit_limit), but hands each event to
+    /// `out` as it is stamped, fills already charged.
+    pub fn submit_limit_into<S: EventSink + ?Sized>(
+        &mut self,
+        order: Order,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
         let symbol = order.symbol();
-        let book = self
-            .books
-            .entry(symbol)
-            .or_insert_with(|| OrderBook::new(symbol));
-        book.submit_limit(order)
+        self.register(symbol);
+        let (book, mut out) = self.route(symbol, out)?;
+        book.submit_limit_into(order, &mut out)
     }
 
     /// Cancel an order. The caller must specify the symbol because order
     /// ids are not globally unique across symbols (different books may
-    /// reuse them).
+    /// reuse them). See [`OrderBook::cancel`].
     pub fn cancel(&mut self, symbol: Symbol, id: OrderID, ts: Ts) -> NyquestroResult<OrderEvent> {
-        let book = self.books.get_mut(&symbol).ok_or_else(|| {
-            crate::errors::NyquestroError::SymbolMismatch {
-                expected: symbol.as_u64(),
-                actual: 0,
-            }
+        self.registered(symbol)?.cancel(id, ts)
+    }
+
+    /// Like [`cancel`](Self::cancel), but the result holds the cancel's
+    /// depth events too; see [`OrderBook::cancel_collect`].
+    pub fn cancel_collect(
+        &mut self,
+        symbol: Symbol,
+        id: OrderID,
+        ts: Ts,
+    ) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.cancel_into(symbol, id, ts, out))
+    }
+
+    /// Like [`cancel`](Self::cancel), but hands each event to `out` as it
+    /// is stamped; see [`OrderBook::cancel_into`].
+    pub fn cancel_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        id: OrderID,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.cancel_into(id, ts, &mut out)
+    }
+
+    /// Cancel/replace a resting order. See [`OrderBook::replace`] for the
+    /// priority rules.
+    pub fn replace(
+        &mut self,
+        symbol: Symbol,
+        id: OrderID,
+        new_px: Px,
+        new_qty: Qty,
+        ts: Ts,
+    ) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.replace_into(symbol, id, new_px, new_qty, ts, out))
+    }
+
+    /// Like [`replace`](Self::replace), but hands each event to `out` as it
+    /// is stamped, fills already charged.
+    pub fn replace_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        id: OrderID,
+        new_px: Px,
+        new_qty: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.replace_into(id, new_px, new_qty, ts, &mut out)
+    }
+
+    /// Reprice the pegged orders in `symbol`'s book. See
+    /// [`OrderBook::reprice`].
+    pub fn reprice(&mut self, symbol: Symbol, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.reprice_into(symbol, ts, out))
+    }
+
+    /// Like [`reprice`](Self::reprice), but hands each event to `out` as it
+    /// is stamped, fills already charged.
+    pub fn reprice_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.reprice_into(ts, &mut out)
+    }
+
+    /// Put `symbol`'s book into a `kind` call auction. See
+    /// [`OrderBook::start_auction`].
+    pub fn start_auction(
+        &mut self,
+        symbol: Symbol,
+        kind: AuctionKind,
+        ts: Ts,
+    ) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.start_auction_into(symbol, kind, ts, out))
+    }
+
+    /// Like [`start_auction`](Self::start_auction), but hands each event to
+    /// `out` as it is stamped.
+    pub fn start_auction_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        kind: AuctionKind,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.start_auction_into(kind, ts, &mut out)
+    }
+
+    /// Uncross `symbol`'s auction. See [`OrderBook::uncross`].
+    pub fn uncross(&mut self, symbol: Symbol, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.uncross_into(symbol, ts, out))
+    }
+
+    /// Like [`uncross`](Self::uncross), but hands each event to `out` as it
+    /// is stamped, fills already charged.
+    pub fn uncross_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.uncross_into(ts, &mut out)
+    }
+
+    /// Halt `symbol`'s book. See [`OrderBook::halt`].
+    pub fn halt(&mut self, symbol: Symbol, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.halt_into(symbol, ts, out))
+    }
+
+    /// Like [`halt`](Self::halt), but hands each event to `out` as it is
+    /// stamped.
+    pub fn halt_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.halt_into(ts, &mut out)
+    }
+
+    /// Return `symbol`'s book to continuous trading. See [`OrderBook::resume`].
+    pub fn resume(&mut self, symbol: Symbol, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.resume_into(symbol, ts, out))
+    }
+
+    /// Like [`resume`](Self::resume), but hands each event to `out` as it
+    /// is stamped, fills already charged.
+    pub fn resume_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.resume_into(ts, &mut out)
+    }
+
+    /// Close `symbol`'s book. See [`OrderBook::close`].
+    pub fn close(&mut self, symbol: Symbol, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.close_into(symbol, ts, out))
+    }
+
+    /// Like [`close`](Self::close), but hands each event to `out` as it is
+    /// stamped.
+    pub fn close_into<S: EventSink + ?Sized>(
+        &mut self,
+        symbol: Symbol,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (book, mut out) = self.route(symbol, out)?;
+        book.close_into(ts, &mut out)
+    }
+
+    /// Run the DAY / GTD expiry sweep on every book at engine time `ts`.
+    /// Books are swept in symbol order, so the merged result is
+    /// deterministic; each event carries its own symbol.
+    pub fn expire(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.expire_into(ts, out))
+    }
+
+    /// Like [`expire`](Self::expire), but hands each event to `out` as it
+    /// is stamped, book after book, fills already charged.
+    pub fn expire_into<S: EventSink + ?Sized>(&mut self, ts: Ts, out: &mut S) -> NyquestroResult<()> {
+        for book in self.books.values_mut() {
+            let mut out = Charging::new(book, &self.fees, &mut self.fee_totals, out);
+            book.expire_into(ts, &mut out)?;
+        }
+        Ok(())
+    }
+
+    /// Cancel every order `filter` selects. With a symbol in the filter
+    /// only that book is touched (an unknown symbol is an error);
+    /// without one every book is swept in symbol order, the same way
+    /// [`expire`](Self::expire) merges. See [`OrderBook::mass_cancel`].
+    pub fn mass_cancel(&mut self, filter: &MassCancel, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.mass_cancel_into(filter, ts, out))
+    }
+
+    /// Like [`mass_cancel`](Self::mass_cancel), but hands each event to
+    /// `out` as it is stamped, book after book, fills already charged.
+    pub fn mass_cancel_into<S: EventSink + ?Sized>(
+        &mut self,
+        filter: &MassCancel,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if let Some(symbol) = filter.symbol {
+            let (book, mut out) = self.route(symbol, out)?;
+            return book.mass_cancel_into(filter, ts, &mut out);
+        }
+        for book in self.books.values_mut() {
+            let mut out = Charging::new(book, &self.fees, &mut self.fee_totals, out);
+            book.mass_cancel_into(filter, ts, &mut out)?;
+        }
+        Ok(())
+    }
+
+    /// Take every resting order and waiting stop off every book.
+    pub fn cancel_all(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        self.mass_cancel(&MassCancel::all(), ts)
+    }
+
+    /// `symbol`'s book; an unregistered symbol is a `SymbolMismatch`.
+    fn registered(&mut self, symbol: Symbol) -> NyquestroResult<&mut OrderBook> {
+        self.books.get_mut(&symbol).ok_or(NyquestroError::SymbolMismatch {
+            expected: symbol.as_u64(),
+            actual: 0,
+        })
+    }
+
+    /// `symbol`'s book, with a sink that charges its fills on their way to
+    /// `out`; an unregistered symbol is a `SymbolMismatch`.
+    fn route<'a, S: EventSink + ?Sized>(
+        &'a mut self,
+        symbol: Symbol,
+        out: &'a mut S,
+    ) -> NyquestroResult<(&'a mut OrderBook, Charging<'a, S>)> {
+        let book = self.books.get_mut(&symbol).ok_or(NyquestroError::SymbolMismatch {
+            expected: symbol.as_u64(),
+            actual: 0,
         })?;
-        book.cancel(id, ts)
+        let out = Charging::new(book, &self.fees, &mut self.fee_totals, out);
+        Ok((book, out))
     }
 
     /// Aggregate top-of-book best bid across all symbols. Returns the
@@ -81,14 +443,95 @@ impl Market {
         self.books
             .iter()
             .filter_map(|(s, b)| b.best_bid().map(|(p, q)| (*s, p, q)))
-            .max_by_key(|(_, p, _)| p.cents())
+            .max_by_key(|(_, p, _)| p.raw())
     }
 
     pub fn aggregate_best_ask(&self) -> Option<(Symbol, Px, Qty)> {
         self.books
             .iter()
             .filter_map(|(s, b)| b.best_ask().map(|(p, q)| (*s, p, q)))
-            .min_by_key(|(_, p, _)| p.cents())
+            .min_by_key(|(_, p, _)| p.raw())
+    }
+}
+
+/// Prices every fill for both sides on its way to `out` and adds it to the
+/// owners' totals. The side that rested is the maker; with no aggressor
+/// (an uncross) both sides are takers.
+struct Charging<'a, S: ?Sized> {
+    fees: &'a FeeSchedule,
+    totals: &'a mut BTreeMap<(OwnerID, Symbol), FeeTotals>,
+    qty_scale: Scale,
+    out: &'a mut S,
+}
+
+impl<'a, S: ?Sized> Charging<'a, S> {
+    fn new(
+        book: &OrderBook,
+        fees: &'a FeeSchedule,
+        totals: &'a mut BTreeMap<(OwnerID, Symbol), FeeTotals>,
+        out: &'a mut S,
+    ) -> Self {
+        let qty_scale = book.config().instrument.map_or(Scale::UNITS, |spec| spec.qty_scale);
+        Charging {
+            fees,
+            totals,
+            qty_scale,
+            out,
+        }
+    }
+}
+
+impl<S: EventSink + ?Sized> EventSink for Charging<'_, S> {
+    fn fill(&mut self, mut fill: FillEvent) {
+        let role = |side: Side| match fill.aggressor {
+            Some(aggressor) if aggressor != side => Liquidity::Maker,
+            _ => Liquidity::Taker,
+        };
+        for (side, owner) in [(Side::Buy, fill.buyer_owner), (Side::Sell, fill.seller_owner)] {
+            let liquidity = role(side);
+            let volume = owner
+                .and_then(|o| self.totals.get(&(o, fill.symbol)))
+                .map_or(0, |totals| totals.volume);
+            let fee = self
+                .fees
+                .rate(owner, fill.symbol, volume, liquidity)
+                .charge(fill.price, fill.quantity, self.qty_scale);
+            match side {
+                Side::Buy => fill.buyer_fee = fee,
+                Side::Sell => fill.seller_fee = fee,
+            }
+            if let Some(owner) = owner {
+                self.totals
+                    .entry((owner, fill.symbol))
+                    .or_default()
+                    .record(liquidity, fee, fill.quantity);
+            }
+        }
+        self.out.fill(fill);
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.out.quote(event);
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.out.level(event);
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.out.order(event);
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.out.lifecycle(event);
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.out.auction(event);
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.out.state(event);
     }
 }
 
@@ -97,12 +540,12 @@ mod tests {
     use super::*;
     use crate::types::{OrderID, Px, Qty, Side, Ts};
 
-    fn buy(symbol: Symbol, id: u64, price: u64, qty: u32, ts: u64) -> Order {
+    fn buy(symbol: Symbol, id: u64, price: u64, qty: u64, ts: u64) -> Order {
         Order::new(
             OrderID::new(id).unwrap(),
             symbol,
             Side::Buy,
-            Px::from_cents(price).unwrap(),
+            Px::from_raw(price).unwrap(),
             Qty::new(qty),
             Ts::from_nanos(ts),
         )
@@ -133,7 +576,99 @@ mod tests {
         m.submit_limit(buy(aapl, 1, 15000, 5, 1)).unwrap();
         m.submit_limit(buy(msft, 2, 30000, 3, 2)).unwrap();
         assert_eq!(m.len(), 2);
-        assert_eq!(m.book(aapl).unwrap().best_bid().unwrap().0.cents(), 15000);
-        assert_eq!(m.book(msft).unwrap().best_bid().unwrap().0.cents(), 30000);
+        assert_eq!(m.book(aapl).unwrap().best_bid().unwrap().0.raw(), 15000);
+        assert_eq!(m.book(msft).unwrap().best_bid().unwrap().0.raw(), 30000);
+    }
+
+    #[test]
+    fn register_with_keeps_first_config() {
+        use crate::book::config::SelfTradePrevention;
+        let mut m = Market::new();
+        let aapl = Symbol::from_const("AAPL");
+        let cfg = BookConfig {
+            self_trade_prevention: SelfTradePrevention::CancelBoth,
+            ..BookConfig::default()
+        };
+        m.register_with(aapl, cfg);
+        m.register_with(aapl, BookConfig::default());
+        assert_eq!(*m.book(aapl).unwrap().config(), cfg);
+    }
+
+    #[test]
+    fn expire_sweeps_every_book_in_symbol_order() {
+        use crate::types::TimeInForce;
+        let mut m = Market::new();
+        let aapl = Symbol::from_const("AAPL");
+        let msft = Symbol::from_const("MSFT");
+        let gtd = |at| TimeInForce::Gtd(Ts::from_nanos(at));
+        m.submit_limit(buy(msft, 1, 30000, 5, 1).with_time_in_force(gtd(10)))
+            .unwrap();
+        m.submit_limit(buy(aapl, 2, 15000, 5, 1).with_time_in_force(gtd(10)))
+            .unwrap();
+        m.submit_limit(buy(aapl, 3, 14900, 5, 1).with_time_in_force(gtd(20)))
+            .unwrap();
+
+        let res = m.expire(Ts::from_nanos(10)).unwrap();
+        let ids: Vec<_> = res.lifecycle.iter().map(|e| e.order_id().value()).collect();
+        assert_eq!(ids, vec![2, 1]);
+        assert_eq!(res.quotes.len(), 2);
+        assert_eq!(m.book(aapl).unwrap().len(), 1);
+        assert!(m.book(msft).unwrap().is_empty());
+    }
+
+    #[test]
+    fn mass_cancel_scopes_to_the_named_symbol() {
+        let mut m = Market::new();
+        let aapl = Symbol::from_const("AAPL");
+        let msft = Symbol::from_const("MSFT");
+        m.submit_limit(buy(aapl, 1, 15000, 5, 1)).unwrap();
+        m.submit_limit(buy(msft, 2, 30000, 3, 2)).unwrap();
+
+        let only_msft = MassCancel {
+            symbol: Some(msft),
+            ..MassCancel::default()
+        };
+        let res = m.mass_cancel(&only_msft, Ts::from_nanos(3)).unwrap();
+        assert_eq!(res.lifecycle.len(), 1);
+        assert!(m.book(msft).unwrap().is_empty());
+        assert_eq!(m.book(aapl).unwrap().len(), 1);
+
+        let unknown = MassCancel {
+            symbol: Some(Symbol::from_const("TSLA")),
+            ..MassCancel::default()
+        };
+        assert!(m.mass_cancel(&unknown, Ts::from_nanos(4)).is_err());
+
+        let res = m.cancel_all(Ts::from_nanos(5)).unwrap();
+        assert_eq!(res.lifecycle.len(), 1);
+        assert!(m.book(aapl).unwrap().is_empty());
+    }
+
+    #[test]
+    fn replace_routes_to_symbol_book() {
+        let mut m = Market::new();
+        let aapl = Symbol::from_const("AAPL");
+        m.submit_limit(buy(aapl, 1, 15000, 5, 1)).unwrap();
+        m.replace(
+            aapl,
+            OrderID::new(1).unwrap(),
+            Px::from_raw(15010).unwrap(),
+            Qty::new(7),
+            Ts::from_nanos(2),
+        )
+        .unwrap();
+        assert_eq!(
+            m.book(aapl).unwrap().best_bid(),
+            Some((Px::from_raw(15010).unwrap(), Qty::new(7)))
+        );
+        assert!(m
+            .replace(
+                Symbol::from_const("MSFT"),
+                OrderID::new(1).unwrap(),
+                Px::from_raw(15010).unwrap(),
+                Qty::new(7),
+                Ts::from_nanos(3),
+            )
+            .is_err());
     }
 }
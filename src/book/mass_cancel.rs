@@ -0,0 +1,111 @@
+//! `MassCancel` — which orders a mass cancel takes off the book.
+//!
+//! Every field left `None` matches anything, so `MassCancel::default()`
+//! is the cancel-all. The filter is applied by
+//! [`crate::book::OrderBook::mass_cancel`], which removes every match in
+//! one pass over the ladders, and by [`crate::book::Market::mass_cancel`]
+//! across books.
+
+use crate::order::Order;
+use crate::types::{OwnerID, Px, Side, Symbol};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MassCancel {
+    pub symbol: Option<Symbol>,
+    pub side: Option<Side>,
+    /// Only orders carrying this owner. Anonymous orders never match an
+    /// owner filter.
+    pub owner: Option<OwnerID>,
+    /// Lowest price cancelled, inclusive. A waiting stop is compared on
+    /// its trigger price, a resting order on its working price.
+    pub min_price: Option<Px>,
+    /// Highest price cancelled, inclusive.
+    pub max_price: Option<Px>,
+}
+
+impl MassCancel {
+    /// Every order in every book.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Inclusive price bounds with the open ends filled in.
+    #[inline]
+    pub fn price_range(&self) -> (Px, Px) {
+        (
+            self.min_price.unwrap_or(Px::MIN),
+            self.max_price.unwrap_or(Px::MAX),
+        )
+    }
+
+    pub fn matches(&self, order: &Order) -> bool {
+        let (lo, hi) = self.price_range();
+        let price = order.stop_price().unwrap_or(order.price());
+        self.symbol.is_none_or(|s| s == order.symbol())
+            && self.side.is_none_or(|s| s == order.side())
+            && self.owner.is_none_or(|o| order.owner() == Some(o))
+            && (lo..=hi).contains(&price)
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{OrderID, Qty, Ts};
+
+    fn order(side: Side, price: u64, owner: Option<u64>) -> Order {
+        let o = Order::new(
+            OrderID::new(1).unwrap(),
+            Symbol::from_const("AAPL"),
+            side,
+            Px::from_raw(price).unwrap(),
+            Qty::new(10),
+            Ts::from_nanos(1),
+        )
+        .unwrap();
+        match owner {
+            Some(id) => o.with_owner(OwnerID::new(id).unwrap()),
+            None => o,
+        }
+    }
+
+    #[test]
+    fn default_matches_everything() {
+        assert!(MassCancel::all().matches(&order(Side::Buy, 100, None)));
+        assert!(MassCancel::all().matches(&order(Side::Sell, 1, Some(3))));
+    }
+
+    #[test]
+    fn every_field_narrows() {
+        let filter = MassCancel {
+            side: Some(Side::Buy),
+            owner: Some(OwnerID::new(7).unwrap()),
+            min_price: Some(Px::from_raw(100).unwrap()),
+            max_price: Some(Px::from_raw(200).unwrap()),
+            ..MassCancel::default()
+        };
+        assert!(filter.matches(&order(Side::Buy, 100, Some(7))));
+        assert!(filter.matches(&order(Side::Buy, 200, Some(7))));
+        assert!(!filter.matches(&order(Side::Sell, 150, Some(7))));
+        assert!(!filter.matches(&order(Side::Buy, 150, Some(8))));
+        assert!(!filter.matches(&order(Side::Buy, 150, None)));
+        assert!(!filter.matches(&order(Side::Buy, 201, Some(7))));
+        let other = MassCancel {
+            symbol: Some(Symbol::from_const("MSFT")),
+            ..MassCancel::default()
+        };
+        assert!(!other.matches(&order(Side::Buy, 150, None)));
+    }
+
+    #[test]
+    fn waiting_stops_match_on_their_trigger() {
+        let stop = order(Side::Sell, 90, None).with_stop(Px::from_raw(150).unwrap());
+        let filter = MassCancel {
+            min_price: Some(Px::from_raw(100).unwrap()),
+            ..MassCancel::default()
+        };
+        assert!(filter.matches(&stop));
+    }
+}
@@ -1,15 +1,52 @@
 //! Order book and its building blocks.
 //!
+//! - [`BookConfig`] — per-book policy (matching algorithm, self-trade prevention,
+//!   market-order protection, volatility guard, full-depth market data, checked mode,
+//!   ladder kind).
+//! - [`allocation`] — how each [`MatchingAlgorithm`] shares a fill across a level.
+//! - [`FeeSchedule`] — tiered maker / taker rates the [`Market`] charges on fills.
+//! - [`audit`] — the book's invariants and the auditor behind checked mode.
+//! - [`auction`] — the price a call auction uncrosses at.
+//! - [`ladder`] — the price-keyed ladders each side stores its levels in.
+//! - [`MassCancel`] — which orders a mass cancel removes.
 //! - [`PriceLevel`] — FIFO queue of resting orders at a single price.
+//! - [`ReferencePrice`] — rolling reference the volatility guard bands around.
+//! - [`EventSink`] — receives a book's events as they are produced.
+//! - [`StopBook`] — stop and stop-limit orders waiting for their trigger.
+//! - [`snapshot`] — versioned JSON and binary snapshots of market state.
 //! - [`OrderBook`] — single-symbol bid/ask book with deterministic
 //!   price-time matching.
 //! - [`Market`] — multi-symbol wrapper holding one [`OrderBook`] per
 //!   [`crate::types::Symbol`].
 
+pub mod allocation;
+pub mod audit;
+pub mod auction;
+pub mod config;
+pub mod fees;
+pub mod ladder;
 pub mod market;
+pub mod mass_cancel;
 pub mod order_book;
 pub mod price_level;
+pub mod reference;
+pub mod sink;
+pub mod snapshot;
+pub mod stops;
 
+pub use allocation::Allocation;
+pub use audit::Violation;
+pub use config::{
+    BookConfig, MarketData, MatchingAlgorithm, ProtectionBand, SelfTradePrevention,
+    VolatilityGuard,
+};
+pub use fees::{FeeRate, FeeSchedule, FeeTier, FeeTotals, Liquidity};
+pub use ladder::LadderKind;
 pub use market::Market;
+pub use mass_cancel::MassCancel;
 pub use order_book::{OrderBook, SubmitResult};
 pub use price_level::PriceLevel;
+pub use reference::ReferencePrice;
+pub use sink::EventSink;
+pub use snapshot::{BookSnapshot, MarketSnapshot, SNAPSHOT_VERSION};
+pub use stops::StopBook;
@@ -5,49 +5,221 @@
 //! - One `OrderBook` is **single-symbol**. The book stores its own `Symbol`
 //!   and rejects orders for any other symbol with `SymbolMismatch`. The
 //!   `Market` wrapper holds one `OrderBook` per `Symbol`.
-//! - Two `BTreeMap<Px, PriceLevel>` ladders, one per side. Bids are walked
-//!   from highest price (`iter().next_back()`); asks from lowest
-//!   (`iter().next()`).
+//! - Two price-keyed ladders, one per side: a `BTreeMap` by default, or
+//!   the tick-indexed array of [`crate::book::ladder`] when the config's
+//!   [`LadderKind`](crate::book::LadderKind) asks for it. Bids are walked from highest price
+//!   (`last()`); asks from lowest (`first()`).
 //! - Within a price level, FIFO ordering is maintained by [`PriceLevel`]
-//!   ([`VecDeque`] under the hood).
+//!   (a slab of linked order nodes under the hood). How an aggressor's quantity is shared
+//!   across that queue is the book's [`MatchingAlgorithm`]: FIFO by
+//!   default, or one of the pro-rata rules in [`crate::book::allocation`].
+//! - An `OrderID → (Side, Px, Slot)` index covers every resting order, so
+//!   cancel and replace find their level and their node in its slab
+//!   ([`crate::book::price_level`]) in O(1) and a duplicate id is rejected
+//!   at submission. Every path that adds or removes a resting order goes
+//!   through `rest` / `unrest` / the matching loop, which keep the index
+//!   in step with the ladders.
 //! - Match price is the **resting** order's price: the incoming side gets
 //!   price improvement when it crosses.
 //! - **Quote events** are emitted only when the top-of-book price OR
 //!   displayed quantity changes on the affected side. No spam on every
 //!   fill.
-//! - **Self-match policy:** match-time rejection. The aggressing order is
-//!   wholly rejected; the resting order is untouched.
+//! - **Cancel/replace** keeps time priority only for a size decrease at
+//!   the same price; anything else re-enters the matching loop with a new
+//!   priority timestamp.
+//! - **Iceberg orders** rest with only their peak displayed; level sizes,
+//!   `best_bid` / `best_ask`, `top_n_*` and quotes count displayed
+//!   quantity alone. Priority rule: an aggressor trades against the
+//!   displayed peak in normal time priority. When a peak is used up and
+//!   the order still has hidden quantity, a new peak is carved from the
+//!   reserve and the order goes to the back of its level, re-stamped with
+//!   the aggressor's timestamp (`OrderEvent::Refreshed`). Hidden quantity
+//!   therefore always trades after every displayed order already queued at
+//!   that price, and matching keeps cycling through refreshed peaks while
+//!   the aggressor still crosses.
+//! - **Pegged orders** are priced from a reference read off *non-pegged*
+//!   liquidity only, so a peg moving can never move its own reference.
+//!   After every operation that changes the book, pegs are repriced in
+//!   id order against the reference as it stood at the start of the pass.
+//!   A peg whose price changes loses time priority: it is re-stamped with
+//!   the operation's timestamp, goes back through the matching loop and
+//!   rests at the back of its new level (reported as
+//!   `OrderEvent::Replaced`). A peg whose price is unchanged keeps its
+//!   place. Only trades by repriced pegs can move the reference again;
+//!   each further pass therefore consumes quantity, so the cascade ends.
+//! - **Stop / stop-limit orders** wait in a [`StopBook`] that is invisible
+//!   to the depth. After every operation the book fires, one at a time,
+//!   each stop the last trade price has reached (`OrderEvent::Triggered`),
+//!   and the stop enters as the market or limit order underneath. Its own
+//!   trades may reach further stops; the cascade resolves inside the same
+//!   `SubmitResult`, in the stop book's firing order.
+//! - **Call auctions** ([`OrderBook::start_auction`] →
+//!   [`OrderBook::uncross`]) suspend matching: orders that can wait rest
+//!   where they are, even through the opposite side, and the book
+//!   publishes `AuctionEvent::Indicative` whenever the price it would
+//!   uncross at changes. Pegs hold their price and stops do not fire until
+//!   the uncross, which trades every crossing order at one clearing price
+//!   (see [`crate::book::auction`]) in price-time priority. An opening
+//!   auction hands over to continuous trading, a closing one closes the
+//!   book.
+//! - **Trading states** ([`TradingState`]): `PreOpen` while the opening
+//!   auction collects, `Continuous`, `Halted` and `Closed`. Halted and
+//!   closed books refuse new orders and amendments but still cancel. With
+//!   a [`VolatilityGuard`](crate::book::VolatilityGuard) configured, a
+//!   trade that would print outside the band around the rolling reference
+//!   price does not print: the book halts and the order that would have
+//!   traded loses its residual. Every change of state is reported as a
+//!   [`StateEvent`].
+//! - **Self-trade prevention:** when the incoming order meets a resting
+//!   order from the same owner, the book's [`SelfTradePrevention`] mode
+//!   decides which side gives way. The default, `CancelNewest`, keeps the
+//!   original match-time rejection: an aggressor that has not traded yet
+//!   is wholly rejected and the resting order is untouched. An auction's
+//!   uncross is exempt (see [`OrderBook::uncross`]).
+//! - **Sequencing:** every event the book emits is stamped, as it is
+//!   produced, with the next number from one per-book counter
+//!   ([`Sequenced`]); fills also take the next per-book trade id and
+//!   record the aggressor's side. Sorting a result's vectors together by
+//!   `seq` recovers the order the book produced them in.
+//! - **Full-depth market data** ([`MarketData`](crate::book::MarketData)),
+//!   when switched on: every resting-order mutation is published as a
+//!   market-by-order delta and every change to a level's displayed size as
+//!   a market-by-price delta, stamped in line with everything else. See
+//!   [`crate::events::depth`] for how a consumer applies them.
+//! - **Checked mode** ([`BookConfig::checked`]): every public operation
+//!   is followed by [`OrderBook::audit`], and a broken invariant fails the
+//!   operation with a fatal `InvariantViolation` naming the symbol and the
+//!   operation. See [`crate::book::audit`].
 //! - **Determinism:** matching never consults the wall clock. Identical
 //!   input sequences therefore produce byte-identical outputs.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeSet, HashMap};
 
-use crate::book::price_level::PriceLevel;
+use crate::book::allocation::Allocation;
+use crate::book::audit::{self, Violation};
+use crate::book::auction;
+use crate::book::config::{BookConfig, MatchingAlgorithm, SelfTradePrevention};
+use crate::book::ladder::Ladder;
+use crate::book::mass_cancel::MassCancel;
+use crate::book::price_level::{PriceLevel, Slot};
+use crate::book::reference::ReferencePrice;
+use crate::book::sink::EventSink;
+use crate::book::snapshot::{
+    AuctionRecord, BookSnapshot, ConfigRecord, LevelRecord, OrderRecord,
+};
+use crate::book::stops::StopBook;
 use crate::errors::{NyquestroError, NyquestroResult};
-use crate::events::{FillEvent, OrderEvent, OrderRejectionReason, QuoteEvent, QuoteSide};
+use crate::events::{
+    AuctionEvent, CancelReason, FillEvent, LevelAction, LevelEvent, MboAction, MboEvent,
+    OrderEvent, OrderRejectionReason, QuoteEvent, QuoteSide, Sequenced, StateEvent, StateReason,
+};
 use crate::order::Order;
-use crate::types::{OrderID, Px, Qty, Side, Symbol, Ts};
+use crate::types::{
+    AuctionKind, OrderID, OwnerID, PostOnly, Px, Qty, Side, Symbol, TimeInForce, TradingState, Ts,
+};
 
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct SubmitResult {
     pub fills: Vec<FillEvent>,
     pub quotes: Vec<QuoteEvent>,
+    /// Market-by-price deltas; empty unless the book publishes them.
+    pub levels: Vec<LevelEvent>,
+    /// Market-by-order deltas; empty unless the book publishes them.
+    pub orders: Vec<MboEvent>,
     pub lifecycle: Vec<OrderEvent>,
+    pub auction: Vec<AuctionEvent>,
+    pub state: Vec<StateEvent>,
+}
+
+/// The book's per-symbol counters: the `seq` of the last event emitted
+/// and the id of the last trade printed. Both start from zero, so the
+/// first event is 1 and so is the first trade.
+#[derive(Debug, Clone, Copy, Default)]
+struct Sequence {
+    event: u64,
+    trade: u64,
+}
+
+impl Sequence {
+    /// Number `event` as the next thing the book emits.
+    fn stamp<E: Sequenced>(&mut self, event: E) -> E {
+        self.event += 1;
+        event.with_seq(self.event)
+    }
+
+    fn next_trade(&mut self) -> u64 {
+        self.trade += 1;
+        self.trade
+    }
+}
+
+/// A call auction in progress.
+#[derive(Debug, Clone, Copy)]
+struct Auction {
+    kind: AuctionKind,
+    /// Uncross last published as `AuctionEvent::Indicative`.
+    indicative: Option<(Px, Qty)>,
+}
+
+/// Sink behind [`OrderBook::cancel`]: keeps the `Cancelled` event and
+/// drops the depth events ahead of it.
+#[derive(Default)]
+struct CancelEvent(Option<OrderEvent>);
+
+impl EventSink for CancelEvent {
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.0 = Some(event);
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct OrderBook {
     symbol: Symbol,
-    bids: BTreeMap<Px, PriceLevel>,
-    asks: BTreeMap<Px, PriceLevel>,
+    config: BookConfig,
+    bids: Ladder,
+    asks: Ladder,
+    index: HashMap<OrderID, (Side, Px, Slot)>,
+    /// DAY / GTD orders on the book or waiting in `stops`, soonest first.
+    /// Ties break on id so an expiry sweep is deterministic.
+    expiries: BTreeSet<(Ts, OrderID)>,
+    /// Resting pegged orders, in the order they are repriced.
+    pegs: BTreeSet<OrderID>,
+    stops: StopBook,
+    /// Price of the most recent trade; what stops trigger on.
+    last_trade: Option<Px>,
+    auction: Option<Auction>,
+    state: TradingState,
+    /// Recent trades the volatility guard bands around. Only fed when a
+    /// guard is configured.
+    reference: ReferencePrice,
+    seq: Sequence,
+    /// Scratch space the pro-rata rules share a level out in, kept so a
+    /// warm book matches without allocating.
+    allocation: Allocation,
 }
 
 impl OrderBook {
     pub fn new(symbol: Symbol) -> Self {
+        Self::with_config(symbol, BookConfig::default())
+    }
+
+    pub fn with_config(symbol: Symbol, config: BookConfig) -> Self {
+        let tick = config.tick_size();
         OrderBook {
             symbol,
-            bids: BTreeMap::new(),
-            asks: BTreeMap::new(),
+            config,
+            bids: Ladder::new(config.ladder, tick, Side::Buy),
+            asks: Ladder::new(config.ladder, tick, Side::Sell),
+            index: HashMap::new(),
+            expiries: BTreeSet::new(),
+            pegs: BTreeSet::new(),
+            stops: StopBook::new(),
+            last_trade: None,
+            auction: None,
+            state: TradingState::Continuous,
+            reference: ReferencePrice::new(),
+            seq: Sequence::default(),
+            allocation: Allocation::default(),
         }
     }
 
@@ -55,31 +227,83 @@ impl OrderBook {
         self.symbol
     }
 
+    pub fn config(&self) -> &BookConfig {
+        &self.config
+    }
+
     // ─── Inspection ────────────────────────────────────────────────────────
 
     pub fn best_bid(&self) -> Option<(Px, Qty)> {
-        self.bids
-            .iter()
-            .next_back()
-            .map(|(p, lvl)| (*p, lvl.total_quantity()))
+        self.bids.last().map(|(p, lvl)| (*p, lvl.total_quantity()))
     }
 
     pub fn best_ask(&self) -> Option<(Px, Qty)> {
-        self.asks
-            .iter()
-            .next()
-            .map(|(p, lvl)| (*p, lvl.total_quantity()))
+        self.asks.first().map(|(p, lvl)| (*p, lvl.total_quantity()))
     }
 
+    /// Number of resting orders across both sides. O(1) via the id index.
     pub fn len(&self) -> usize {
-        self.bids.values().map(|l| l.len()).sum::<usize>()
-            + self.asks.values().map(|l| l.len()).sum::<usize>()
+        self.index.len()
     }
 
     pub fn is_empty(&self) -> bool {
         self.bids.is_empty() && self.asks.is_empty()
     }
 
+    /// Whether an order with `id` is resting in this book.
+    pub fn contains(&self, id: OrderID) -> bool {
+        self.index.contains_key(&id)
+    }
+
+    /// Borrow the resting order with `id`, if any.
+    pub fn order(&self, id: OrderID) -> Option<&Order> {
+        let &(side, price, at) = self.index.get(&id)?;
+        self.book(side).get(&price)?.get(at)
+    }
+
+    /// Stop orders waiting for their trigger. Not part of the depth.
+    pub fn stops(&self) -> &StopBook {
+        &self.stops
+    }
+
+    /// Price of the most recent trade in this book.
+    pub fn last_trade(&self) -> Option<Px> {
+        self.last_trade
+    }
+
+    /// The call auction the book is running, if any.
+    pub fn auction(&self) -> Option<AuctionKind> {
+        self.auction.map(|a| a.kind)
+    }
+
+    /// Price and volume the running auction would uncross at, as last
+    /// published. `None` outside an auction or when nothing would match.
+    pub fn indicative(&self) -> Option<(Px, Qty)> {
+        self.auction.and_then(|a| a.indicative)
+    }
+
+    pub fn state(&self) -> TradingState {
+        self.state
+    }
+
+    /// `seq` of the last event this book emitted; 0 before the first.
+    pub fn last_seq(&self) -> u64 {
+        self.seq.event
+    }
+
+    /// Id of the last trade this book printed; 0 before the first.
+    pub fn last_trade_id(&self) -> u64 {
+        self.seq.trade
+    }
+
+    /// Lowest and highest price a trade may print at at engine time `now`.
+    /// `None` without a volatility guard or before the first trade.
+    pub fn price_limits(&self, now: Ts) -> Option<(Px, Px)> {
+        let guard = self.config.volatility_guard?;
+        let reference = self.reference.price(now, guard.window)?;
+        Some(guard.limits(reference, self.config.tick_size()))
+    }
+
     pub fn bid_levels(&self) -> impl DoubleEndedIterator<Item = (&Px, &PriceLevel)> {
         self.bids.iter().rev()
     }
@@ -105,20 +329,17 @@ impl OrderBook {
 
     /// Sum of displayed quantity across the top-N levels per side.
     pub fn depth(&self, n: usize) -> (Qty, Qty) {
-        let bid_sum: u64 = self
+        let bid_sum = self
             .bid_levels()
             .take(n)
-            .map(|(_, l)| l.total_quantity().value() as u64)
-            .sum();
-        let ask_sum: u64 = self
+            .map(|(_, l)| l.total_quantity().value())
+            .fold(0, u64::saturating_add);
+        let ask_sum = self
             .ask_levels()
             .take(n)
-            .map(|(_, l)| l.total_quantity().value() as u64)
-            .sum();
-        (
-            Qty::new(bid_sum.min(u32::MAX as u64) as u32),
-            Qty::new(ask_sum.min(u32::MAX as u64) as u32),
-        )
+            .map(|(_, l)| l.total_quantity().value())
+            .fold(0, u64::saturating_add);
+        (Qty::new(bid_sum), Qty::new(ask_sum))
     }
 
     /// Order Flow Imbalance over the top-N levels:
@@ -137,8 +358,8 @@ impl OrderBook {
     }
 
     /// Microprice: volume-weighted mid using the *best* bid and ask.
-    /// `(bid_qty * ask_px + ask_qty * bid_px) / (bid_qty + ask_qty)` in cents.
-    /// Returns `None` when either side is empty.
+    /// `(bid_qty * ask_px + ask_qty * bid_px) / (bid_qty + ask_qty)` in raw
+    /// price units. Returns `None` when either side is empty.
     pub fn microprice(&self) -> Option<f64> {
         let (bp, bq) = self.best_bid()?;
         let (ap, aq) = self.best_ask()?;
@@ -148,15 +369,15 @@ impl OrderBook {
         if total == 0.0 {
             return None;
         }
-        Some((bq * ap.cents() as f64 + aq * bp.cents() as f64) / total)
+        Some((bq * ap.raw() as f64 + aq * bp.raw() as f64) / total)
     }
 
-    /// Spread in cents (best ask - best bid). Returns `None` when either
-    /// side is empty.
-    pub fn spread_cents(&self) -> Option<u64> {
+    /// Spread in raw price units (best ask - best bid). Returns `None` when
+    /// either side is empty.
+    pub fn spread(&self) -> Option<u64> {
         let (bp, _) = self.best_bid()?;
         let (ap, _) = self.best_ask()?;
-        Some(ap.cents().saturating_sub(bp.cents()))
+        Some(ap.raw().saturating_sub(bp.raw()))
     }
 
     /// Number of distinct price levels per side.
@@ -164,9 +385,185 @@ impl OrderBook {
         (self.bids.len(), self.asks.len())
     }
 
+    /// Every resting order as an `MboEvent::Add` stamped with
+    /// [`last_seq`](Self::last_seq): bids best price first, then asks, each
+    /// level front to back. Applying the depth feeds from the next `seq`
+    /// on top of it keeps a copy of the book exact; see
+    /// [`crate::events::depth`].
+    pub fn depth_snapshot(&self, ts: Ts) -> Vec<MboEvent> {
+        self.bid_levels()
+            .chain(self.ask_levels())
+            .flat_map(|(_, level)| level.iter())
+            .map(|o| {
+                MboEvent::new(
+                    self.symbol,
+                    MboAction::Add,
+                    o.id(),
+                    o.side(),
+                    o.price(),
+                    o.displayed(),
+                    ts,
+                )
+                .with_seq(self.seq.event)
+            })
+            .collect()
+    }
+
+    /// Check every invariant the book relies on — level totals, no empty
+    /// levels, no crossed book while trading continuously, only live
+    /// orders resting, each at its level's price and side, unique ids, an
+    /// index in step with the ladders, stops with triggers — and report
+    /// every violation found; see [`crate::book::audit`]. Empty when the
+    /// book is sound. Walks the whole book.
+    pub fn audit(&self) -> Vec<Violation> {
+        let crossable = self.auction.is_some() || self.state != TradingState::Continuous;
+        audit::audit(&self.bids, &self.asks, &self.index, &self.stops, crossable)
+    }
+
+    // ─── Snapshots ─────────────────────────────────────────────────────────
+
+    /// Everything this book holds, for [`crate::book::snapshot`].
+    pub fn snapshot(&self) -> BookSnapshot {
+        BookSnapshot {
+            symbol: self.symbol.to_string(),
+            config: ConfigRecord::from_config(&self.config),
+            state: self.state,
+            auction: self.auction.map(|a| AuctionRecord {
+                kind: a.kind,
+                indicative: a.indicative.map(|(p, q)| (p.raw(), q.value())),
+            }),
+            last_trade: self.last_trade.map(Px::raw),
+            last_seq: self.seq.event,
+            last_trade_id: self.seq.trade,
+            reference: self.reference.trades().map(|(t, p)| (t.nanos(), p.raw())).collect(),
+            bids: self.bid_levels().map(|(_, l)| LevelRecord::from_level(l)).collect(),
+            asks: self.ask_levels().map(|(_, l)| LevelRecord::from_level(l)).collect(),
+            stops: self.stops.iter().map(OrderRecord::from_order).collect(),
+        }
+    }
+
+    /// Rebuild a book from `snapshot`, checking it could have come from
+    /// one: see [`crate::book::snapshot`] for what is validated.
+    pub fn from_snapshot(snapshot: BookSnapshot) -> NyquestroResult<Self> {
+        let symbol: Symbol = snapshot.symbol.parse()?;
+        let bad = |why: String| NyquestroError::Snapshot(format!("{symbol}: {why}"));
+        let mut book = OrderBook::with_config(symbol, snapshot.config.into_config()?);
+        for (side, records) in [(Side::Buy, snapshot.bids), (Side::Sell, snapshot.asks)] {
+            let mut prev: Option<Px> = None;
+            for record in records {
+                let level = record.into_level(symbol, side)?;
+                let price = level.price();
+                let in_order = prev.is_none_or(|p| match side {
+                    Side::Buy => price < p,
+                    Side::Sell => price > p,
+                });
+                if !in_order {
+                    return Err(bad(format!("{side:?} level {} out of order", price.raw())));
+                }
+                prev = Some(price);
+                for (at, order) in level.entries() {
+                    let id = order.id();
+                    if book.index.insert(id, (side, price, at)).is_some() {
+                        return Err(bad(format!("order {} appears twice", id.value())));
+                    }
+                    if let Some(at) = order.expires_at() {
+                        book.expiries.insert((at, id));
+                    }
+                    if order.is_pegged() {
+                        book.pegs.insert(id);
+                    }
+                }
+                book.book_mut(side).insert(price, level);
+            }
+        }
+        for record in snapshot.stops {
+            let order = record.into_order(symbol)?;
+            let id = order.id();
+            if !order.is_stop() || !order.is_active() {
+                return Err(bad(format!("order {} cannot wait as a stop", id.value())));
+            }
+            if book.index.contains_key(&id) || book.stops.iter().any(|o| o.id() == id) {
+                return Err(bad(format!("order {} appears twice", id.value())));
+            }
+            if let Some(at) = order.expires_at() {
+                book.expiries.insert((at, id));
+            }
+            book.stops.insert(order)?;
+        }
+        let expected = match snapshot.auction.map(|a| a.kind) {
+            Some(AuctionKind::Opening) => Some(TradingState::PreOpen),
+            Some(AuctionKind::Closing) => Some(TradingState::Continuous),
+            None if snapshot.state == TradingState::PreOpen => None,
+            None => Some(snapshot.state),
+        };
+        if expected != Some(snapshot.state) {
+            return Err(bad(format!("{:?} does not fit the auction", snapshot.state)));
+        }
+        if !snapshot.reference.is_sorted_by_key(|&(t, _)| t) {
+            return Err(bad("reference trades out of order".into()));
+        }
+        if snapshot.last_trade_id > snapshot.last_seq {
+            return Err(bad("more trades than events".into()));
+        }
+        book.state = snapshot.state;
+        book.auction = snapshot
+            .auction
+            .map(|a| -> NyquestroResult<Auction> {
+                let indicative = a
+                    .indicative
+                    .map(|(p, q)| Px::from_raw(p).map(|p| (p, Qty::new(q))))
+                    .transpose()?;
+                Ok(Auction { kind: a.kind, indicative })
+            })
+            .transpose()?;
+        book.last_trade = snapshot.last_trade.map(Px::from_raw).transpose()?;
+        book.reference = ReferencePrice::from_trades(
+            snapshot
+                .reference
+                .into_iter()
+                .map(|(t, p)| Px::from_raw(p).map(|p| (Ts::from_nanos(t), p)))
+                .collect::<NyquestroResult<Vec<_>>>()?,
+        );
+        book.seq = Sequence {
+            event: snapshot.last_seq,
+            trade: snapshot.last_trade_id,
+        };
+        // Catches what the per-level checks cannot see, such as a crossed
+        // touch.
+        if let Some(violation) = book.audit().first() {
+            return Err(bad(violation.to_string()));
+        }
+        Ok(book)
+    }
+
     // ─── Submission ────────────────────────────────────────────────────────
 
-    pub fn submit_limit(&mut self, mut order: Order) -> NyquestroResult<SubmitResult> {
+    /// Submit an order of any type. The name predates market orders; a
+    /// market order is priced at this book's protection band on arrival and
+    /// from then on runs through the same path as a limit order that may
+    /// not rest. A stop order is parked in the trigger book and reported
+    /// `Placed`; if the last trade already satisfies it, it fires within
+    /// this same call.
+    pub fn submit_limit(&mut self, order: Order) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.submit_limit_into(order, out))
+    }
+
+    /// Like [`submit_limit`](Self::submit_limit), but hands each event to `out`
+    /// as it is stamped.
+    pub fn submit_limit_into<S: EventSink + ?Sized>(
+        &mut self,
+        order: Order,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.submit_limit_unchecked(order, out);
+        self.checked("submit_limit", outcome)
+    }
+
+    fn submit_limit_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        order: Order,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
         if order.symbol() != self.symbol {
             return Err(NyquestroError::SymbolMismatch {
                 expected: self.symbol.as_u64(),
@@ -174,171 +571,1667 @@ impl OrderBook {
             });
         }
 
-        let mut result = SubmitResult::default();
         let pre_same_side_top = self.top_of(order.side());
         let pre_opposite_top = self.top_of(order.side().opposite());
 
-        let aggressor_id = order.id();
-        let aggressor_symbol = order.symbol();
-        let mut self_match_detected = false;
+        self.enter(order, out)?;
+        self.settle(order.timestamp(), out)?;
 
-        loop {
-            if !order.is_active() || order.remaining().is_zero() {
-                break;
+        let ts = order.timestamp();
+        self.emit_quote_if_changed(order.side(), pre_same_side_top, ts, out);
+        self.emit_quote_if_changed(order.side().opposite(), pre_opposite_top, ts, out);
+
+        Ok(())
+    }
+
+    /// Cancel a resting order or a waiting stop. Returns the cancel event
+    /// alone; pegged orders that tracked the cancelled order are not moved
+    /// here, nor is a running auction's indicative uncross republished —
+    /// follow with [`reprice`](Self::reprice) to bring them up to date.
+    /// With the depth feeds on, the cancel's depth events are numbered but
+    /// not returned; take them from [`cancel_into`](Self::cancel_into) or
+    /// [`cancel_collect`](Self::cancel_collect).
+    pub fn cancel(&mut self, id: OrderID, ts: Ts) -> NyquestroResult<OrderEvent> {
+        let mut kept = CancelEvent::default();
+        self.cancel_into(id, ts, &mut kept)?;
+        kept.0
+            .ok_or_else(|| NyquestroError::InvariantViolation("cancel without an event".into()))
+    }
+
+    /// Like [`cancel`](Self::cancel), but the result holds the cancel's
+    /// depth events, when the depth feeds are on, followed by the
+    /// `Cancelled` event itself.
+    pub fn cancel_collect(&mut self, id: OrderID, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.cancel_into(id, ts, out))
+    }
+
+    /// Like [`cancel`](Self::cancel), but hands each event to `out` as it
+    /// is stamped: the depth events, then the cancel event.
+    pub fn cancel_into<S: EventSink + ?Sized>(
+        &mut self,
+        id: OrderID,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.cancel_into_unchecked(id, ts, out);
+        self.checked("cancel", outcome)
+    }
+
+    fn cancel_into_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        id: OrderID,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let cancelled = self.take_off(id, ts, out)?;
+        out.lifecycle(cancelled);
+        Ok(())
+    }
+
+    /// Cancel/replace a resting order: move it to `new_px` with `new_qty`
+    /// open (leaves) quantity.
+    ///
+    /// - Same price, same or smaller quantity: amended in place and keeps
+    ///   its time priority.
+    /// - Price change or size increase: loses priority. The order is
+    ///   re-stamped with `ts` and re-enters the book through the matching
+    ///   loop, so a new price that crosses trades immediately and only the
+    ///   residual rests at the back of its level.
+    ///
+    /// Emits `OrderEvent::Replaced` first, followed by any fills and
+    /// quote changes. A replace that meets a resting order from the same
+    /// owner is resolved by the book's self-trade prevention mode; if that
+    /// ends the replaced order, its residual is reported as `Cancelled`.
+    ///
+    /// A pegged order's price belongs to its peg: `new_px` is ignored and
+    /// only the quantity changes. A post-only order keeps its flag: a
+    /// crossing `new_px` is slid or the replace is answered with
+    /// `Rejected { PostOnlyWouldTake }` and the order left untouched. A
+    /// waiting stop cannot be replaced; cancel and resubmit it. A halted
+    /// or closed book answers every replace with a rejection and leaves
+    /// the order as it was, and so does a book whose instrument rules the
+    /// new price or quantity breaks.
+    pub fn replace(
+        &mut self,
+        id: OrderID,
+        new_px: Px,
+        new_qty: Qty,
+        ts: Ts,
+    ) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.replace_into(id, new_px, new_qty, ts, out))
+    }
+
+    /// Like [`replace`](Self::replace), but hands each event to `out` as it is
+    /// stamped.
+    pub fn replace_into<S: EventSink + ?Sized>(
+        &mut self,
+        id: OrderID,
+        new_px: Px,
+        new_qty: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.replace_unchecked(id, new_px, new_qty, ts, out);
+        self.checked("replace", outcome)
+    }
+
+    fn replace_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        id: OrderID,
+        new_px: Px,
+        new_qty: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if new_qty.is_zero() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        let (side, price) = self
+            .locate(id)
+            .ok_or(NyquestroError::OrderNotFound(id.value()))?;
+
+        if let Some(reason) = self.refusal() {
+            let rejected = OrderEvent::rejected(id, self.symbol, reason, ts);
+            out.lifecycle(self.seq.stamp(rejected));
+            return Ok(());
+        }
+        let pre_same_side_top = self.top_of(side);
+        let pre_opposite_top = self.top_of(side.opposite());
+
+        let (current, pegged, post_only) = self
+            .order(id)
+            .map(|o| (o.remaining(), o.is_pegged(), o.post_only()))
+            .ok_or(NyquestroError::OrderNotFound(id.value()))?;
+        if let Some(reason) = self.amendment_off_spec((!pegged).then_some(new_px), new_qty) {
+            let rejected = OrderEvent::rejected(id, self.symbol, reason, ts);
+            out.lifecycle(self.seq.stamp(rejected));
+            return Ok(());
+        }
+        let new_px = if pegged { price } else { new_px };
+        let new_px = match post_only.map(|mode| self.post_only_price(side, new_px, mode)) {
+            None => new_px,
+            Some(Some(px)) => px,
+            Some(None) => {
+                // The amendment is refused; the order stays as it was.
+                out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                    id,
+                    self.symbol,
+                    OrderRejectionReason::PostOnlyWouldTake,
+                    ts,
+                )));
+                return Ok(());
             }
-            let opposite_top = match self.top_of(order.side().opposite()) {
-                Some(t) => t,
-                None => break,
-            };
-            if !crosses(order.side(), order.price(), opposite_top.0) {
-                break;
+        };
+
+        if new_px == price && new_qty <= current {
+            self.shrink(side, price, id, new_qty, ts, out)?;
+            out.lifecycle(self.seq.stamp(OrderEvent::replaced(
+                id,
+                self.symbol,
+                side,
+                new_px,
+                new_qty,
+                ts,
+            )?));
+        } else {
+            let mut order = self.unrest(side, price, id, ts, out)?;
+            order.requeue(new_px, new_qty, ts)?;
+            out.lifecycle(self.seq.stamp(OrderEvent::replaced(
+                id,
+                self.symbol,
+                side,
+                new_px,
+                new_qty,
+                ts,
+            )?));
+            if self.match_incoming(&mut order, out)? {
+                out.lifecycle(self.seq.stamp(OrderEvent::cancelled_with(
+                    id,
+                    self.symbol,
+                    order.remaining(),
+                    CancelReason::SelfTradePrevention,
+                    ts,
+                )));
+            } else if order.remaining().value() > 0
+                && order.is_active()
+                && let Some(reason) = self.residual_cancel_reason(&order)
+            {
+                let cancelled =
+                    OrderEvent::cancelled_with(id, self.symbol, order.remaining(), reason, ts);
+                out.lifecycle(self.seq.stamp(cancelled));
+            } else if order.remaining().value() > 0 && order.is_active() {
+                self.rest(order, out)?;
             }
+        }
+        self.settle(ts, out)?;
 
-            let opposite_best_px = opposite_top.0;
-            let opposite_levels = self.book_mut(order.side().opposite());
-            let level = opposite_levels
-                .get_mut(&opposite_best_px)
-                .expect("level must exist; we just probed it");
-            let resting_id = level.front().expect("non-empty").id();
-            if resting_id == aggressor_id {
-                self_match_detected = true;
+        self.emit_quote_if_changed(side, pre_same_side_top, ts, out);
+        self.emit_quote_if_changed(side.opposite(), pre_opposite_top, ts, out);
+        Ok(())
+    }
+
+    /// Expire every DAY / GTD order — resting or waiting stop — whose expiry
+    /// is at or before `ts`, in expiry order (ties by id). Emits one `OrderEvent::Expired` per order,
+    /// then quotes for any side whose top changed.
+    pub fn expire(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.expire_into(ts, out))
+    }
+
+    /// Like [`expire`](Self::expire), but hands each event to `out` as it is
+    /// stamped.
+    pub fn expire_into<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.expire_unchecked(ts, out);
+        self.checked("expire", outcome)
+    }
+
+    fn expire_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let pre_bid_top = self.top_of(Side::Buy);
+        let pre_ask_top = self.top_of(Side::Sell);
+
+        while let Some(&(at, id)) = self.expiries.first() {
+            if at > ts {
                 break;
             }
-
-            let (resting_id_for_event, resting_ts, resting_done, trade_qty) = {
-                let resting = level.front_mut().expect("non-empty");
-                let trade = Qty::new(
-                    order
-                        .remaining()
-                        .value()
-                        .min(resting.remaining().value()),
-                );
-                resting.fill(trade)?;
-                (
-                    resting.id(),
-                    resting.timestamp(),
-                    resting.status().is_terminal(),
-                    trade,
-                )
+            let mut removed = match self.locate(id) {
+                Some((side, price)) => self.unrest(side, price, id, ts, out)?,
+                None => self.unstop(id)?,
             };
-            level.record_execution(trade_qty)?;
-            order.fill(trade_qty)?;
+            removed.expire()?;
+            let expired = OrderEvent::expired(id, self.symbol, removed.remaining(), ts);
+            out.lifecycle(self.seq.stamp(expired));
+        }
+        self.settle(ts, out)?;
+
+        self.emit_quote_if_changed(Side::Buy, pre_bid_top, ts, out);
+        self.emit_quote_if_changed(Side::Sell, pre_ask_top, ts, out);
+        Ok(())
+    }
+
+    /// Cancel every resting order and waiting stop `filter` selects, in one
+    /// pass over each ladder rather than one lookup per order. Emits one
+    /// `Cancelled { reason: MassCancel }` per order — bids best price
+    /// first, then asks, then stops in firing order — followed by whatever
+    /// settling the book produces and quotes for any side whose top
+    /// changed. A filter naming another symbol cancels nothing.
+    pub fn mass_cancel(&mut self, filter: &MassCancel, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.mass_cancel_into(filter, ts, out))
+    }
+
+    /// Like [`mass_cancel`](Self::mass_cancel), but hands each event to `out`
+    /// as it is stamped.
+    pub fn mass_cancel_into<S: EventSink + ?Sized>(
+        &mut self,
+        filter: &MassCancel,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.mass_cancel_unchecked(filter, ts, out);
+        self.checked("mass_cancel", outcome)
+    }
+
+    fn mass_cancel_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        filter: &MassCancel,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if filter.symbol.is_some_and(|s| s != self.symbol) {
+            return Ok(());
+        }
+        let pre_bid_top = self.top_of(Side::Buy);
+        let pre_ask_top = self.top_of(Side::Sell);
 
-            let (buyer_id, seller_id) = match order.side() {
-                Side::Buy => (aggressor_id, resting_id_for_event),
-                Side::Sell => (resting_id_for_event, aggressor_id),
+        let (lo, hi) = filter.price_range();
+        let mut removed = Vec::new();
+        // Levels that lost orders, best price first on each side, with the
+        // quantity they showed before.
+        let mut touched = Vec::new();
+        for side in [Side::Buy, Side::Sell] {
+            if lo > hi || filter.side.is_some_and(|s| s != side) {
+                continue;
+            }
+            let levels = self.book_mut(side);
+            let mut emptied = Vec::new();
+            let mut take = |price: Px, level: &mut PriceLevel| -> NyquestroResult<()> {
+                let before = level.total_quantity();
+                let gone = level.remove_where(|o| filter.matches(o))?;
+                if !gone.is_empty() {
+                    touched.push((side, price, before));
+                }
+                removed.extend(gone);
+                if level.is_empty() {
+                    emptied.push(price);
+                }
+                Ok(())
             };
-            let fill = FillEvent::new(
-                aggressor_symbol,
-                buyer_id,
-                seller_id,
-                opposite_best_px,
-                trade_qty,
-                resting_ts,
-            )?;
-            result.fills.push(fill);
-
-            result.lifecycle.push(OrderEvent::filled(
-                aggressor_id,
-                aggressor_symbol,
-                trade_qty,
+            match side {
+                Side::Buy => levels.range_mut(lo, hi).rev().try_for_each(|(&p, l)| take(p, l))?,
+                Side::Sell => levels.range_mut(lo, hi).try_for_each(|(&p, l)| take(p, l))?,
+            }
+            for price in emptied {
+                levels.remove(&price);
+            }
+        }
+        for order in &removed {
+            self.forget(order);
+            self.publish_order(MboAction::Delete, order, ts, out);
+        }
+        for (side, price, before) in touched {
+            self.publish_level(side, price, before, ts, out);
+        }
+        let stops = self.stops.remove_where(|o| filter.matches(o));
+        for order in &stops {
+            if let Some(at) = order.expires_at() {
+                self.expiries.remove(&(at, order.id()));
+            }
+        }
+        for order in removed.iter().chain(&stops) {
+            out.lifecycle(self.seq.stamp(OrderEvent::cancelled_with(
+                order.id(),
+                self.symbol,
                 order.remaining(),
-                resting_ts,
-            )?);
+                CancelReason::MassCancel,
+                ts,
+            )));
+        }
+        self.settle(ts, out)?;
 
-            if resting_done {
-                let popped = level.pop_front().expect("front must exist");
-                result.lifecycle.push(OrderEvent::filled(
-                    popped.id(),
-                    aggressor_symbol,
-                    trade_qty,
-                    Qty::ZERO,
-                    resting_ts,
-                )?);
-            }
+        self.emit_quote_if_changed(Side::Buy, pre_bid_top, ts, out);
+        self.emit_quote_if_changed(Side::Sell, pre_ask_top, ts, out);
+        Ok(())
+    }
+
+    /// Bring every pegged order up to date with its reference, e.g. after
+    /// a [`cancel`](Self::cancel), and fire any stop that results.
+    /// Submissions, replaces and expiry sweeps already do this themselves.
+    pub fn reprice(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.reprice_into(ts, out))
+    }
+
+    /// Like [`reprice`](Self::reprice), but hands each event to `out` as it is
+    /// stamped.
+    pub fn reprice_into<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.reprice_unchecked(ts, out);
+        self.checked("reprice", outcome)
+    }
 
-            if level.is_empty() {
-                opposite_levels.remove(&opposite_best_px);
+    fn reprice_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let pre_bid_top = self.top_of(Side::Buy);
+        let pre_ask_top = self.top_of(Side::Sell);
+        self.settle(ts, out)?;
+        self.emit_quote_if_changed(Side::Buy, pre_bid_top, ts, out);
+        self.emit_quote_if_changed(Side::Sell, pre_ask_top, ts, out);
+        Ok(())
+    }
+
+    // ─── Call auctions ─────────────────────────────────────────────────────
+
+    /// Start collecting orders for a `kind` auction. Emits
+    /// `AuctionEvent::Started`.
+    ///
+    /// An opening auction moves a continuous, halted or closed book to
+    /// `PreOpen` — the way to reopen after a halt through an auction. A
+    /// closing auction runs inside `Continuous`. Fails if an auction is
+    /// already running or the state does not allow it.
+    pub fn start_auction(&mut self, kind: AuctionKind, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.start_auction_into(kind, ts, out))
+    }
+
+    /// Like [`start_auction`](Self::start_auction), but hands each event to
+    /// `out` as it is stamped.
+    pub fn start_auction_into<S: EventSink + ?Sized>(
+        &mut self,
+        kind: AuctionKind,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.start_auction_unchecked(kind, ts, out);
+        self.checked("start_auction", outcome)
+    }
+
+    fn start_auction_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        kind: AuctionKind,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let to = match kind {
+            AuctionKind::Opening => TradingState::PreOpen,
+            AuctionKind::Closing => TradingState::Continuous,
+        };
+        let allowed = self.auction.is_none()
+            && (kind == AuctionKind::Opening || self.state == TradingState::Continuous);
+        if !allowed {
+            return Err(self.transition_error(to));
+        }
+        self.auction = Some(Auction {
+            kind,
+            indicative: None,
+        });
+        if self.state != to {
+            self.set_state(to, StateReason::Requested, ts, out)?;
+        }
+        out.auction(self.seq.stamp(AuctionEvent::started(self.symbol, kind, ts)));
+        self.publish_indicative(ts, out)?;
+        Ok(())
+    }
+
+    /// Uncross the running auction. An opening auction hands over to
+    /// `Continuous`, a closing auction leaves the book `Closed`.
+    ///
+    /// Every order that crosses the clearing price trades at that price,
+    /// bids highest first and asks lowest first, time priority within a
+    /// price; an iceberg trades a peak at a time like in continuous
+    /// trading. Fills carry `ts`, each fill reports `Filled` for both
+    /// orders, and `AuctionEvent::Uncrossed` follows the last of them.
+    /// After an opening auction the book settles as after any other
+    /// operation: pegs reprice and stops reached by the clearing price
+    /// fire.
+    ///
+    /// Self-trade prevention does not apply here, whatever the book's
+    /// mode: same-owner orders that cross trade with each other. The
+    /// clearing price and volume are fixed for the whole book before the
+    /// first fill, and were already published as the indicative; cancelling
+    /// orders part-way through would leave fills at a price the rest of the
+    /// book no longer clears at. Venues that run call auctions exempt them
+    /// from self-match prevention for the same reason.
+    pub fn uncross(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.uncross_into(ts, out))
+    }
+
+    /// Like [`uncross`](Self::uncross), but hands each event to `out` as it is
+    /// stamped.
+    pub fn uncross_into<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.uncross_unchecked(ts, out);
+        self.checked("uncross", outcome)
+    }
+
+    fn uncross_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let Some(running) = self.auction else {
+            return Err(self.transition_error(TradingState::Continuous));
+        };
+        let pre_bid_top = self.top_of(Side::Buy);
+        let pre_ask_top = self.top_of(Side::Sell);
+
+        let clearing = self.clearing()?;
+        if let Some((px, volume)) = clearing {
+            let mut left = volume;
+            while !left.is_zero() {
+                let (bid_px, bid_id, bid_owner, bid_qty) = front(self.bids.last())?;
+                let (ask_px, ask_id, ask_owner, ask_qty) = front(self.asks.first())?;
+                let qty = left.min(bid_qty).min(ask_qty);
+                let fill = FillEvent::new(self.symbol, bid_id, ask_id, px, qty, ts)?
+                    .with_owners(bid_owner, ask_owner)
+                    .with_trade_id(self.seq.next_trade());
+                out.fill(self.seq.stamp(fill));
+                self.fill_resting(Side::Buy, bid_px, bid_id, qty, ts, out)?;
+                self.fill_resting(Side::Sell, ask_px, ask_id, qty, ts, out)?;
+                left = left
+                    .checked_sub(qty)
+                    .ok_or_else(|| NyquestroError::InvariantViolation("auction over-fill".into()))?;
+            }
+            self.last_trade = Some(px);
+            if let Some(guard) = self.config.volatility_guard {
+                self.reference.record(ts, px, guard.window);
             }
         }
+        self.auction = None;
+        let to = match running.kind {
+            AuctionKind::Opening => TradingState::Continuous,
+            AuctionKind::Closing => TradingState::Closed,
+        };
+        if self.state != to {
+            self.set_state(to, StateReason::Requested, ts, out)?;
+        }
+        out.auction(self.seq.stamp(AuctionEvent::uncrossed(
+            self.symbol,
+            running.kind,
+            clearing.map(|(px, _)| px),
+            clearing.map_or(Qty::ZERO, |(_, volume)| volume),
+            ts,
+        )?));
+        self.settle(ts, out)?;
+
+        self.emit_quote_if_changed(Side::Buy, pre_bid_top, ts, out);
+        self.emit_quote_if_changed(Side::Sell, pre_ask_top, ts, out);
+        Ok(())
+    }
+
+    // ─── Trading state ─────────────────────────────────────────────────────
+
+    /// Halt a pre-open or continuous book. A running auction is abandoned
+    /// without uncrossing; resting orders stay where they are.
+    pub fn halt(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.halt_into(ts, out))
+    }
+
+    /// Like [`halt`](Self::halt), but hands each event to `out` as it is
+    /// stamped.
+    pub fn halt_into<S: EventSink + ?Sized>(&mut self, ts: Ts, out: &mut S) -> NyquestroResult<()> {
+        let outcome = self.halt_unchecked(ts, out);
+        self.checked("halt", outcome)
+    }
+
+    fn halt_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if !matches!(self.state, TradingState::PreOpen | TradingState::Continuous) {
+            return Err(self.transition_error(TradingState::Halted));
+        }
+        self.auction = None;
+        self.set_state(TradingState::Halted, StateReason::Requested, ts, out)?;
+        Ok(())
+    }
+
+    /// Go straight back to continuous trading from a halt or a close.
+    /// The book then settles: pegs reprice and stops the last trade has
+    /// reached fire. To reopen through an auction instead, call
+    /// [`start_auction`](Self::start_auction) with `AuctionKind::Opening`.
+    ///
+    /// Fails while the book is crossed, which only an abandoned auction
+    /// leaves behind: the overlap has no single price to trade at outside
+    /// an auction, so reopen through one.
+    pub fn resume(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.resume_into(ts, out))
+    }
+
+    /// Like [`resume`](Self::resume), but hands each event to `out` as it is
+    /// stamped.
+    pub fn resume_into<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.resume_unchecked(ts, out);
+        self.checked("resume", outcome)
+    }
+
+    fn resume_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if !matches!(self.state, TradingState::Halted | TradingState::Closed) {
+            return Err(self.transition_error(TradingState::Continuous));
+        }
+        if let (Some((bid, _)), Some((ask, _))) = (self.best_bid(), self.best_ask())
+            && bid >= ask
+        {
+            return Err(self.transition_error(TradingState::Continuous));
+        }
+        let pre_bid_top = self.top_of(Side::Buy);
+        let pre_ask_top = self.top_of(Side::Sell);
+        self.set_state(TradingState::Continuous, StateReason::Requested, ts, out)?;
+        self.settle(ts, out)?;
+        self.emit_quote_if_changed(Side::Buy, pre_bid_top, ts, out);
+        self.emit_quote_if_changed(Side::Sell, pre_ask_top, ts, out);
+        Ok(())
+    }
+
+    /// Close the book without an auction. A running auction is abandoned;
+    /// resting orders stay for the next session.
+    pub fn close(&mut self, ts: Ts) -> NyquestroResult<SubmitResult> {
+        SubmitResult::collect(|out| self.close_into(ts, out))
+    }
+
+    /// Like [`close`](Self::close), but hands each event to `out` as it is
+    /// stamped.
+    pub fn close_into<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let outcome = self.close_unchecked(ts, out);
+        self.checked("close", outcome)
+    }
+
+    fn close_unchecked<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if self.state == TradingState::Closed {
+            return Err(self.transition_error(TradingState::Closed));
+        }
+        self.auction = None;
+        self.set_state(TradingState::Closed, StateReason::Requested, ts, out)?;
+        Ok(())
+    }
+
+    // ─── Internals ─────────────────────────────────────────────────────────
 
-        if self_match_detected {
-            result.lifecycle.push(OrderEvent::rejected(
-                aggressor_id,
-                aggressor_symbol,
-                OrderRejectionReason::SelfMatch,
+    /// Take one order into the book: validate it, park it if it is a stop,
+    /// otherwise price it, match it and rest or cancel what is left. Quotes
+    /// are the caller's job.
+    fn enter<S: EventSink + ?Sized>(
+        &mut self,
+        mut order: Order,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if let Some(reason) = self.refusal() {
+            out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                order.id(),
+                order.symbol(),
+                reason,
                 order.timestamp(),
-            ));
-            self.emit_quote_if_changed(
-                order.side().opposite(),
-                pre_opposite_top,
+            )));
+            return Ok(());
+        }
+        if self.index.contains_key(&order.id()) || self.stops.contains(order.id()) {
+            out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                order.id(),
+                order.symbol(),
+                OrderRejectionReason::DuplicateOrderId,
+                order.timestamp(),
+            )));
+            return Ok(());
+        }
+        if let Some(reason) = self.off_spec(&order) {
+            out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                order.id(),
+                order.symbol(),
+                reason,
                 order.timestamp(),
-                &mut result.quotes,
+            )));
+            return Ok(());
+        }
+        if order.is_stop() {
+            if order.expires_at().is_some_and(|at| at <= order.timestamp()) {
+                out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                    order.id(),
+                    order.symbol(),
+                    OrderRejectionReason::AlreadyExpired,
+                    order.timestamp(),
+                )));
+                return Ok(());
+            }
+            self.stops.insert(order)?;
+            if let Some(at) = order.expires_at() {
+                self.expiries.insert((at, order.id()));
+            }
+            out.lifecycle(self.seq.stamp(OrderEvent::placed(
+                order.id(),
+                order.symbol(),
+                order.side(),
+                order.price(),
+                order.quantity(),
+                order.timestamp(),
+            )?));
+            return Ok(());
+        }
+        if self.auction.is_some() && !waits_for_uncross(&order) {
+            out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                order.id(),
+                order.symbol(),
+                OrderRejectionReason::NotAcceptedInAuction,
+                order.timestamp(),
+            )));
+            return Ok(());
+        }
+        let opposite_best = self.top_of(order.side().opposite()).map(|(px, _)| px);
+        if order.is_market()
+            && let Some(best) = opposite_best
+        {
+            let limit = self.config.market_protection.limit(
+                order.side(),
+                best,
+                self.config.tick_size(),
             );
-            return Ok(result);
+            order.amend(limit, order.remaining())?;
+        }
+        let peg_price = order.peg().map(|peg| {
+            peg.price(
+                order.side(),
+                self.reference_top(Side::Buy),
+                self.reference_top(Side::Sell),
+            )
+        });
+        if let Some(Some(px)) = peg_price {
+            order.amend(px, order.remaining())?;
+        }
+        let post_only_price = order
+            .post_only()
+            .map(|mode| self.post_only_price(order.side(), order.price(), mode));
+        if let Some(Some(px)) = post_only_price {
+            order.amend(px, order.remaining())?;
+        }
+        let reject = if order.is_market() && opposite_best.is_none() {
+            Some(OrderRejectionReason::NoLiquidity)
+        } else if peg_price == Some(None) {
+            Some(OrderRejectionReason::NoReferencePrice)
+        } else if post_only_price == Some(None) {
+            Some(OrderRejectionReason::PostOnlyWouldTake)
+        } else if order.expires_at().is_some_and(|at| at <= order.timestamp()) {
+            Some(OrderRejectionReason::AlreadyExpired)
+        } else if order.time_in_force() == TimeInForce::Fok
+            && self.fillable(&order) < order.remaining()
+        {
+            Some(OrderRejectionReason::FillOrKillUnfilled)
+        } else if order.min_quantity().is_some_and(|min| self.fillable(&order) < min) {
+            Some(OrderRejectionReason::MinQuantityUnfilled)
+        } else {
+            None
+        };
+        if let Some(reason) = reject {
+            out.lifecycle(self.seq.stamp(OrderEvent::rejected(
+                order.id(),
+                order.symbol(),
+                reason,
+                order.timestamp(),
+            )));
+            return Ok(());
         }
 
-        if order.remaining().value() > 0 && order.is_active() {
-            let same_side = self.book_mut(order.side());
-            let level = same_side
-                .entry(order.price())
-                .or_insert_with(|| PriceLevel::new(order.price()));
-            level.push_back(order)?;
+        let self_trade = self.match_incoming(&mut order, out)?;
 
-            result.lifecycle.push(OrderEvent::placed(
-                aggressor_id,
-                aggressor_symbol,
+        if self_trade {
+            // An aggressor that never traded was never really accepted, so
+            // the plain cancel-newest case stays a rejection.
+            let untouched = order.filled().is_zero()
+                && self.config.self_trade_prevention == SelfTradePrevention::CancelNewest;
+            out.lifecycle(self.seq.stamp(if untouched {
+                OrderEvent::rejected(
+                    order.id(),
+                    order.symbol(),
+                    OrderRejectionReason::SelfMatch,
+                    order.timestamp(),
+                )
+            } else {
+                OrderEvent::cancelled_with(
+                    order.id(),
+                    order.symbol(),
+                    order.remaining(),
+                    CancelReason::SelfTradePrevention,
+                    order.timestamp(),
+                )
+            }));
+        } else if order.remaining().value() > 0
+            && order.is_active()
+            && let Some(reason) = self.residual_cancel_reason(&order)
+        {
+            out.lifecycle(self.seq.stamp(OrderEvent::cancelled_with(
+                order.id(),
+                order.symbol(),
+                order.remaining(),
+                reason,
+                order.timestamp(),
+            )));
+        } else if order.remaining().value() > 0 && order.is_active() {
+            self.rest(order, out)?;
+            out.lifecycle(self.seq.stamp(OrderEvent::placed(
+                order.id(),
+                order.symbol(),
                 order.side(),
                 order.price(),
                 order.quantity(),
                 order.timestamp(),
-            )?);
+            )?));
         }
+        Ok(())
+    }
 
-        self.emit_quote_if_changed(
-            order.side(),
-            pre_same_side_top,
-            order.timestamp(),
-            &mut result.quotes,
-        );
-        self.emit_quote_if_changed(
-            order.side().opposite(),
-            pre_opposite_top,
-            order.timestamp(),
-            &mut result.quotes,
-        );
+    /// Let the book come to rest after an operation: reprice pegs, then
+    /// fire any stop the last trade has reached, one at a time, repricing
+    /// again after each. A fired stop's own trades can reach the next stop;
+    /// the whole cascade goes to `out`. Each stop fires at most once,
+    /// so this ends. During an auction nothing trades, so the only thing
+    /// to bring up to date is the indicative uncross.
+    fn settle<S: EventSink + ?Sized>(&mut self, ts: Ts, out: &mut S) -> NyquestroResult<()> {
+        if self.auction.is_some() {
+            return self.publish_indicative(ts, out);
+        }
+        loop {
+            if self.state != TradingState::Continuous {
+                return Ok(());
+            }
+            self.reprice_pegs(ts, out)?;
+            let Some(last) = self.last_trade else {
+                return Ok(());
+            };
+            let Some(mut order) = self.stops.pop_triggered(last) else {
+                return Ok(());
+            };
+            if let Some(at) = order.expires_at() {
+                self.expiries.remove(&(at, order.id()));
+            }
+            let stop_price = order
+                .stop_price()
+                .ok_or_else(|| {
+                    NyquestroError::InvariantViolation("triggered order without a stop".into())
+                })?;
+            order.trigger(ts);
+            out.lifecycle(self.seq.stamp(OrderEvent::triggered(
+                order.id(),
+                self.symbol,
+                order.side(),
+                stop_price,
+                ts,
+            )));
+            self.enter(order, out)?;
+        }
+    }
 
-        Ok(result)
+    /// Best price on `side` among levels holding at least one non-pegged
+    /// order — the reference pegs are priced from.
+    fn reference_top(&self, side: Side) -> Option<Px> {
+        let has_anchor = |level: &PriceLevel| level.iter().any(|o| !o.is_pegged());
+        match side {
+            Side::Buy => self.bid_levels().find(|(_, l)| has_anchor(l)).map(|(p, _)| *p),
+            Side::Sell => self.ask_levels().find(|(_, l)| has_anchor(l)).map(|(p, _)| *p),
+        }
     }
 
-    pub fn cancel(&mut self, id: OrderID, ts: Ts) -> NyquestroResult<OrderEvent> {
-        let symbol = self.symbol;
-        for side in [Side::Buy, Side::Sell] {
-            let book = self.book_mut(side);
-            let prices: Vec<Px> = book.keys().copied().collect();
-            for price in prices {
-                let level = book
-                    .get_mut(&price)
-                    .expect("collected from existing keys");
-                if let Some(removed) = level.remove_by_id(id) {
-                    let remaining = removed.remaining();
-                    if level.is_empty() {
-                        book.remove(&price);
+    /// Move every resting peg whose price no longer matches its reference.
+    /// See the module docs for the priority rule and why this terminates.
+    fn reprice_pegs<S: EventSink + ?Sized>(&mut self, ts: Ts, out: &mut S) -> NyquestroResult<()> {
+        loop {
+            let bid = self.reference_top(Side::Buy);
+            let ask = self.reference_top(Side::Sell);
+            let ids: Vec<OrderID> = self.pegs.iter().copied().collect();
+            for id in ids {
+                // An earlier peg in this pass may have traded this one away.
+                let Some((side, price)) = self.locate(id) else {
+                    continue;
+                };
+                let Some(target) = self
+                    .order(id)
+                    .and_then(|o| o.peg())
+                    .and_then(|peg| peg.price(side, bid, ask))
+                else {
+                    continue;
+                };
+                if target == price {
+                    continue;
+                }
+                let mut order = self.unrest(side, price, id, ts, out)?;
+                order.requeue(target, order.remaining(), ts)?;
+                out.lifecycle(self.seq.stamp(OrderEvent::replaced(
+                    id,
+                    self.symbol,
+                    side,
+                    target,
+                    order.remaining(),
+                    ts,
+                )?));
+                if self.match_incoming(&mut order, out)? {
+                    out.lifecycle(self.seq.stamp(OrderEvent::cancelled_with(
+                        id,
+                        self.symbol,
+                        order.remaining(),
+                        CancelReason::SelfTradePrevention,
+                        ts,
+                    )));
+                } else if order.remaining().value() > 0
+                    && order.is_active()
+                    && let Some(reason) = self.residual_cancel_reason(&order)
+                {
+                    out.lifecycle(self.seq.stamp(OrderEvent::cancelled_with(
+                        id,
+                        self.symbol,
+                        order.remaining(),
+                        reason,
+                        ts,
+                    )));
+                } else if order.remaining().value() > 0 && order.is_active() {
+                    self.rest(order, out)?;
+                }
+                if self.state != TradingState::Continuous {
+                    // A volatility halt stops repricing where it is.
+                    return Ok(());
+                }
+            }
+            if (self.reference_top(Side::Buy), self.reference_top(Side::Sell)) == (bid, ask) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Where a post-only order at `price` may sit: its own price if that
+    /// does not cross the opposite best, one tick short of the opposite
+    /// best under `Slide`, `None` when it must be rejected (including a
+    /// slide that would leave the price range).
+    fn post_only_price(&self, side: Side, price: Px, mode: PostOnly) -> Option<Px> {
+        let Some((best, _)) = self.top_of(side.opposite()) else {
+            return Some(price);
+        };
+        if !crosses(side, price, best) {
+            return Some(price);
+        }
+        match mode {
+            PostOnly::Reject => None,
+            PostOnly::Slide => {
+                let slid = match side {
+                    Side::Buy => best.raw().checked_sub(self.config.tick_size())?,
+                    Side::Sell => best.raw().checked_add(self.config.tick_size())?,
+                };
+                Px::from_raw(slid).ok()
+            }
+        }
+    }
+
+    /// Why an aggressor's unfilled remainder may not rest, if it may not.
+    fn residual_cancel_reason(&self, order: &Order) -> Option<CancelReason> {
+        if self.state == TradingState::Halted {
+            // Matching stopped because the next trade would have broken
+            // the volatility band.
+            Some(CancelReason::TradingHalted)
+        } else if order.is_market() {
+            // Matching stopped with quantity left: either the next price is
+            // beyond the band, or there is nothing left to hit.
+            Some(match self.top_of(order.side().opposite()) {
+                Some(_) => CancelReason::ProtectionBand,
+                None => CancelReason::LiquidityExhausted,
+            })
+        } else if !order.time_in_force().can_rest() {
+            Some(CancelReason::ImmediateOrCancel)
+        } else {
+            None
+        }
+    }
+
+    /// How much of `order` would execute right now, without touching the
+    /// book. Walks the opposite side in priority order under the same rules
+    /// as the matching loop: stops at the first non-crossing level and at
+    /// the first level outside the volatility band (where matching would
+    /// halt the book), skips same-owner orders that `CancelOldest` would
+    /// remove, and stops at any other self-trade. Hidden iceberg quantity at a level only counts
+    /// once every order there has been passed: a refreshed peak joins the
+    /// back of the queue, behind whatever stopped the walk.
+    fn fillable(&self, order: &Order) -> Qty {
+        match order.side() {
+            Side::Buy => self.fillable_from(order, self.ask_levels()),
+            Side::Sell => self.fillable_from(order, self.bid_levels()),
+        }
+    }
+
+    fn fillable_from<'a>(
+        &self,
+        order: &Order,
+        levels: impl Iterator<Item = (&'a Px, &'a PriceLevel)>,
+    ) -> Qty {
+        let want = order.remaining().value();
+        let limits = self.price_limits(order.timestamp());
+        let mut found: u64 = 0;
+        for (px, level) in levels {
+            if !crosses(order.side(), order.price(), *px) {
+                break;
+            }
+            if let Some((lo, hi)) = limits
+                && (*px < lo || *px > hi)
+            {
+                break;
+            }
+            // A pro-rata level meets every order at once, so a same-owner
+            // order anywhere in it decides before anything trades there.
+            if self.config.matching != MatchingAlgorithm::Fifo
+                && self.config.self_trade_prevention != SelfTradePrevention::CancelOldest
+                && level
+                    .iter()
+                    .any(|o| o.id() == order.id() || same_owner(order, o))
+            {
+                return Qty::new(found);
+            }
+            let mut hidden: u64 = 0;
+            for resting in level.iter() {
+                if resting.id() == order.id() || same_owner(order, resting) {
+                    if self.config.self_trade_prevention == SelfTradePrevention::CancelOldest {
+                        continue;
+                    }
+                    return Qty::new(found);
+                }
+                found = found.saturating_add(resting.displayed().value());
+                hidden = hidden.saturating_add(resting.hidden().value());
+                if found >= want {
+                    return Qty::new(found);
+                }
+            }
+            found = found.saturating_add(hidden);
+            if found >= want {
+                return Qty::new(found);
+            }
+        }
+        Qty::new(found)
+    }
+
+    /// Run `order` against the opposite side until it is filled, stops
+    /// crossing, or self-trade prevention ends it. Sends fills, fill
+    /// lifecycle events and any STP events for resting orders to
+    /// `out`. Returns `true` when STP ended the aggressor; the caller
+    /// reports what happens to its residual.
+    fn match_incoming<S: EventSink + ?Sized>(
+        &mut self,
+        order: &mut Order,
+        out: &mut S,
+    ) -> NyquestroResult<bool> {
+        let aggressor_id = order.id();
+        let aggressor_symbol = order.symbol();
+        let stp = self.config.self_trade_prevention;
+        if self.auction.is_some() {
+            // Orders wait for the uncross, crossing or not.
+            return Ok(false);
+        }
+
+        loop {
+            if !order.is_active() || order.remaining().is_zero() {
+                return Ok(false);
+            }
+            let opposite_top = match self.top_of(order.side().opposite()) {
+                Some(t) => t,
+                None => return Ok(false),
+            };
+            if !crosses(order.side(), order.price(), opposite_top.0) {
+                return Ok(false);
+            }
+
+            let opposite_best_px = opposite_top.0;
+            let opposite = order.side().opposite();
+            // Borrowed field by field rather than through `book`, so the
+            // pro-rata arm below can take `allocation` while `level` lives.
+            let ladder = match opposite {
+                Side::Buy => &self.bids,
+                Side::Sell => &self.asks,
+            };
+            let level = ladder
+                .get(&opposite_best_px)
+                .ok_or(NyquestroError::PriceLevelMissing {
+                    price: opposite_best_px.raw(),
+                })?;
+            // FIFO only ever meets the front order; the pro-rata rules trade
+            // with the whole level at once, so any same-owner order there
+            // counts.
+            let is_self = |o: &&Order| o.id() == aggressor_id || same_owner(order, o);
+            let conflict = match self.config.matching {
+                MatchingAlgorithm::Fifo => level.front().filter(is_self),
+                _ => level.iter().find(is_self),
+            };
+            if let Some(front) = conflict {
+                let (resting_id, resting_remaining) = (front.id(), front.remaining());
+                let px = opposite_best_px;
+                let ts = order.timestamp();
+                match stp {
+                    SelfTradePrevention::CancelNewest => return Ok(true),
+                    SelfTradePrevention::CancelOldest => {
+                        self.cancel_for_stp(opposite, px, resting_id, ts, out)?;
+                    }
+                    SelfTradePrevention::CancelBoth => {
+                        self.cancel_for_stp(opposite, px, resting_id, ts, out)?;
+                        return Ok(true);
+                    }
+                    SelfTradePrevention::DecrementAndCancel => {
+                        let aggressor_remaining = order.remaining();
+                        if let Some(left) = aggressor_remaining.checked_sub(resting_remaining)
+                            && !left.is_zero()
+                        {
+                            self.cancel_for_stp(opposite, px, resting_id, ts, out)?;
+                            order.amend(order.price(), left)?;
+                            out.lifecycle(self.seq.stamp(OrderEvent::decremented(
+                                aggressor_id,
+                                aggressor_symbol,
+                                order.side(),
+                                order.price(),
+                                resting_remaining,
+                                left,
+                                ts,
+                            )?));
+                        } else if aggressor_remaining == resting_remaining {
+                            self.cancel_for_stp(opposite, px, resting_id, ts, out)?;
+                            return Ok(true);
+                        } else {
+                            let left = resting_remaining
+                                .checked_sub(aggressor_remaining)
+                                .ok_or_else(|| {
+                                    NyquestroError::InvariantViolation(
+                                        "STP decrement underflow".into(),
+                                    )
+                                })?;
+                            self.shrink(opposite, px, resting_id, left, ts, out)?;
+                            out.lifecycle(self.seq.stamp(OrderEvent::decremented(
+                                resting_id,
+                                aggressor_symbol,
+                                opposite,
+                                px,
+                                aggressor_remaining,
+                                left,
+                                ts,
+                            )?));
+                            return Ok(true);
+                        }
                     }
-                    return Ok(OrderEvent::cancelled(id, symbol, remaining, ts));
                 }
+                continue;
             }
+
+            if let Some((lo, hi)) = self.price_limits(order.timestamp())
+                && (opposite_best_px < lo || opposite_best_px > hi)
+            {
+                let reason = StateReason::VolatilityHalt;
+                self.auction = None;
+                self.set_state(TradingState::Halted, reason, order.timestamp(), out)?;
+                return Ok(false);
+            }
+
+            match self.config.matching {
+                MatchingAlgorithm::Fifo => {
+                    let front = level.front().expect("non-empty");
+                    let trade = order.remaining().min(front.displayed());
+                    let resting_id = front.id();
+                    self.execute(order, opposite_best_px, resting_id, trade, out)?;
+                }
+                algo => {
+                    // Out of the book while the trades below need `self`;
+                    // put back afterwards with its capacity.
+                    let mut work = std::mem::take(&mut self.allocation);
+                    work.load(level.iter().map(|o| (o.id(), o.displayed())));
+                    algo.allocate_into(order.remaining(), &mut work);
+                    for (resting_id, trade) in work.shares() {
+                        self.execute(order, opposite_best_px, resting_id, trade, out)?;
+                    }
+                    self.allocation = work;
+                }
+            }
+        }
+    }
+
+    /// Trade `qty` between the aggressor and the resting order
+    /// `resting_id` at `px` on the opposite side, at the resting price.
+    /// Emits the fill and both sides' lifecycle events, removes a resting
+    /// order that is done, and sends an iceberg whose peak ran out to the
+    /// back of its level. Everything is stamped with the aggressor's time:
+    /// the trade happens when the command that caused it is applied, not
+    /// when the resting order arrived.
+    fn execute<S: EventSink + ?Sized>(
+        &mut self,
+        order: &mut Order,
+        px: Px,
+        resting_id: OrderID,
+        qty: Qty,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let symbol = self.symbol;
+        let at = self.slot(resting_id)?;
+        // Borrow the ladder by field, not through `book_mut`, so events can
+        // be stamped while the level is held.
+        let levels = match order.side().opposite() {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.get_mut(&px).ok_or(NyquestroError::PriceLevelMissing {
+            price: px.raw(),
+        })?;
+        let before = level.total_quantity();
+        let resting = level.fill_at(at, qty)?;
+        let ts = order.timestamp();
+        order.fill(qty)?;
+
+        let ((buyer_id, buyer_owner), (seller_id, seller_owner)) = match order.side() {
+            Side::Buy => ((order.id(), order.owner()), (resting_id, resting.owner())),
+            Side::Sell => ((resting_id, resting.owner()), (order.id(), order.owner())),
+        };
+        let fill = FillEvent::new(symbol, buyer_id, seller_id, px, qty, ts)?
+            .with_aggressor(order.side())
+            .with_owners(buyer_owner, seller_owner)
+            .with_trade_id(self.seq.next_trade());
+        out.fill(self.seq.stamp(fill));
+        out.lifecycle(self.seq.stamp(OrderEvent::filled(
+            order.id(),
+            symbol,
+            qty,
+            order.remaining(),
+            ts,
+        )?));
+
+        let done = resting.status().is_terminal();
+        let mut requeued = None;
+        if done {
+            level.remove_at(at)?;
+            let filled = OrderEvent::filled(resting_id, symbol, qty, Qty::ZERO, ts)?;
+            out.lifecycle(self.seq.stamp(filled));
+        } else if resting.displayed().is_zero() {
+            let refreshed = level.refresh_at(at, ts)?;
+            requeued = Some(refreshed);
+            out.lifecycle(self.seq.stamp(OrderEvent::refreshed(
+                resting_id,
+                symbol,
+                refreshed.side(),
+                px,
+                refreshed.displayed(),
+                refreshed.hidden(),
+                refreshed.timestamp(),
+            )?));
+        }
+        if level.is_empty() {
+            levels.remove(&px);
+        }
+        if done {
+            self.forget(&resting);
+        }
+        self.publish_trade(&resting, requeued, before, ts, out);
+        self.last_trade = Some(px);
+        if let Some(guard) = self.config.volatility_guard {
+            self.reference.record(ts, px, guard.window);
+        }
+        Ok(())
+    }
+
+    /// Which instrument rule `order` breaks, if any. Market and pegged
+    /// orders have no price of their own to check; a stop's trigger is
+    /// checked like a limit price. Iceberg peaks are held to the lot size.
+    fn off_spec(&self, order: &Order) -> Option<OrderRejectionReason> {
+        let spec = self.config.instrument?;
+        let priced = !order.is_market() && !order.is_pegged();
+        spec.check_quantity(order.quantity())
+            .or_else(|| {
+                order
+                    .peak()
+                    .filter(|peak| !peak.value().is_multiple_of(spec.lot_size))
+                    .map(|_| OrderRejectionReason::QuantityOffLot)
+            })
+            .or_else(|| order.stop_price().and_then(|px| spec.check_price(px)))
+            .or_else(|| priced.then(|| spec.check_price(order.price())).flatten())
+    }
+
+    /// Which instrument rule a replace to `new_px` (`None` for a peg) and
+    /// `new_qty` breaks. The new quantity is what is left to trade, so it
+    /// may fall below the minimum order size; the other rules apply as on
+    /// entry.
+    fn amendment_off_spec(
+        &self,
+        new_px: Option<Px>,
+        new_qty: Qty,
+    ) -> Option<OrderRejectionReason> {
+        let spec = self.config.instrument?;
+        spec.check_quantity(new_qty)
+            .filter(|r| *r != OrderRejectionReason::QuantityBelowMinimum)
+            .or_else(|| new_px.and_then(|px| spec.check_price(px)))
+    }
+
+    /// Why an order or amendment is refused outright in the current state.
+    fn refusal(&self) -> Option<OrderRejectionReason> {
+        match self.state {
+            TradingState::Halted => Some(OrderRejectionReason::TradingHalted),
+            TradingState::Closed => Some(OrderRejectionReason::MarketClosed),
+            TradingState::PreOpen | TradingState::Continuous => None,
         }
-        Err(NyquestroError::OrderNotFound(id.value()))
     }
 
-    fn book_mut(&mut self, side: Side) -> &mut BTreeMap<Px, PriceLevel> {
+    /// Move to `to` and report the change.
+    fn set_state<S: EventSink + ?Sized>(
+        &mut self,
+        to: TradingState,
+        reason: StateReason,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        out.state(self.seq.stamp(StateEvent::new(self.symbol, self.state, to, reason, ts)?));
+        self.state = to;
+        Ok(())
+    }
+
+    fn transition_error(&self, to: TradingState) -> NyquestroError {
+        NyquestroError::InvalidPhaseTransition {
+            symbol: self.symbol.as_u64(),
+            from: self.state.as_str(),
+            to: to.as_str(),
+        }
+    }
+
+    /// Trade `qty` off the resting order `id` at `(side, px)` during an
+    /// uncross: report it `Filled`, remove it when it is done and send a
+    /// spent iceberg peak to the back of its level.
+    fn fill_resting<S: EventSink + ?Sized>(
+        &mut self,
+        side: Side,
+        px: Px,
+        id: OrderID,
+        qty: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let symbol = self.symbol;
+        let at = self.slot(id)?;
+        let levels = match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        let level = levels.get_mut(&px).ok_or(NyquestroError::PriceLevelMissing {
+            price: px.raw(),
+        })?;
+        let before = level.total_quantity();
+        let filled = level.fill_at(at, qty)?;
+        let event = OrderEvent::filled(id, symbol, qty, filled.remaining(), ts)?;
+        out.lifecycle(self.seq.stamp(event));
+
+        let done = filled.status().is_terminal();
+        let mut requeued = None;
+        if done {
+            level.remove_at(at)?;
+        } else if filled.displayed().is_zero() {
+            let refreshed = level.refresh_at(at, ts)?;
+            requeued = Some(refreshed);
+            out.lifecycle(self.seq.stamp(OrderEvent::refreshed(
+                id,
+                symbol,
+                side,
+                px,
+                refreshed.displayed(),
+                refreshed.hidden(),
+                refreshed.timestamp(),
+            )?));
+        }
+        if level.is_empty() {
+            levels.remove(&px);
+        }
+        if done {
+            self.forget(&filled);
+        }
+        self.publish_trade(&filled, requeued, before, ts, out);
+        Ok(())
+    }
+
+    /// Where the book would uncross right now, against the last trade as
+    /// the reference price.
+    fn clearing(&self) -> NyquestroResult<Option<(Px, Qty)>> {
+        let open = |levels: &Ladder| -> NyquestroResult<Vec<(Px, Qty)>> {
+            levels
+                .iter()
+                .map(|(px, level)| {
+                    let qty = level
+                        .iter()
+                        .try_fold(Qty::ZERO, |acc, o| acc.checked_add(o.remaining()))
+                        .ok_or(NyquestroError::QuantityOverflow)?;
+                    Ok((*px, qty))
+                })
+                .collect()
+        };
+        auction::clearing(&open(&self.bids)?, &open(&self.asks)?, self.last_trade)
+    }
+
+    /// Emit `AuctionEvent::Indicative` if the running auction's uncross has
+    /// moved since it was last published.
+    fn publish_indicative<S: EventSink + ?Sized>(
+        &mut self,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let Some(running) = self.auction else {
+            return Ok(());
+        };
+        let now = self.clearing()?;
+        if now == running.indicative {
+            return Ok(());
+        }
+        self.auction = Some(Auction {
+            indicative: now,
+            ..running
+        });
+        out.auction(self.seq.stamp(AuctionEvent::indicative(
+            self.symbol,
+            now.map(|(px, _)| px),
+            now.map_or(Qty::ZERO, |(_, volume)| volume),
+            ts,
+        )?));
+        Ok(())
+    }
+
+    /// Append `order` to the back of its price level on its own side and
+    /// index it. An iceberg enters with a full peak showing, whatever it
+    /// traded on the way in.
+    fn rest<S: EventSink + ?Sized>(
+        &mut self,
+        mut order: Order,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let (id, side, price) = (order.id(), order.side(), order.price());
+        let expires_at = order.expires_at();
+        if self.index.contains_key(&id) {
+            return Err(NyquestroError::OrderAlreadyExists(id.value()));
+        }
+        let before = self.shown_at(side, price);
+        order.refresh(order.timestamp());
+        let at = self.book_mut(side).entry(price).insert(order)?;
+        self.index.insert(id, (side, price, at));
+        if let Some(at) = expires_at {
+            self.expiries.insert((at, id));
+        }
+        if order.is_pegged() {
+            self.pegs.insert(id);
+        }
+        self.publish_order(MboAction::Add, &order, order.timestamp(), out);
+        self.publish_level(side, price, before, order.timestamp(), out);
+        Ok(())
+    }
+
+    /// Remove the resting order `id` from the level at `(side, price)`,
+    /// dropping the level if it empties.
+    fn unrest<S: EventSink + ?Sized>(
+        &mut self,
+        side: Side,
+        price: Px,
+        id: OrderID,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<Order> {
+        let at = self.slot(id)?;
+        let levels = self.book_mut(side);
+        let level = levels.get_mut(&price).ok_or(NyquestroError::PriceLevelMissing {
+            price: price.raw(),
+        })?;
+        let before = level.total_quantity();
+        let removed = level
+            .remove_at(at)?
+            .ok_or(NyquestroError::OrderNotFound(id.value()))?;
+        if level.is_empty() {
+            levels.remove(&price);
+        }
+        self.forget(&removed);
+        self.publish_order(MboAction::Delete, &removed, ts, out);
+        self.publish_level(side, price, before, ts, out);
+        Ok(removed)
+    }
+
+    /// Amend the resting order `id` at `(side, price)` down to `remaining`
+    /// open quantity where it stands in the queue.
+    fn shrink<S: EventSink + ?Sized>(
+        &mut self,
+        side: Side,
+        price: Px,
+        id: OrderID,
+        remaining: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let at = self.slot(id)?;
+        let level = self
+            .book_mut(side)
+            .get_mut(&price)
+            .ok_or(NyquestroError::PriceLevelMissing {
+                price: price.raw(),
+            })?;
+        let before = level.total_quantity();
+        let amended = level.shrink_at(at, remaining)?;
+        self.publish_order(MboAction::Modify, &amended, ts, out);
+        self.publish_level(side, price, before, ts, out);
+        Ok(())
+    }
+
+    /// Drop a order that has left the ladders from the id index, the
+    /// expiry schedule and the peg set.
+    fn forget(&mut self, order: &Order) {
+        self.index.remove(&order.id());
+        self.pegs.remove(&order.id());
+        if let Some(at) = order.expires_at() {
+            self.expiries.remove(&(at, order.id()));
+        }
+    }
+
+    /// Take a waiting stop out of the trigger book and the expiry schedule.
+    fn unstop(&mut self, id: OrderID) -> NyquestroResult<Order> {
+        let removed = self
+            .stops
+            .remove(id)
+            .ok_or(NyquestroError::OrderNotFound(id.value()))?;
+        if let Some(at) = removed.expires_at() {
+            self.expiries.remove(&(at, id));
+        }
+        Ok(removed)
+    }
+
+    /// Take a resting order or a waiting stop off the book for a plain
+    /// cancel and number its `Cancelled` event, which is left to the caller
+    /// to deliver.
+    fn take_off<S: EventSink + ?Sized>(
+        &mut self,
+        id: OrderID,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<OrderEvent> {
+        let removed = match self.locate(id) {
+            Some((side, price)) => self.unrest(side, price, id, ts, out)?,
+            None => self.unstop(id)?,
+        };
+        Ok(self.seq.stamp(OrderEvent::cancelled(id, self.symbol, removed.remaining(), ts)))
+    }
+
+    /// Remove a resting order on behalf of self-trade prevention and report
+    /// it as cancelled.
+    fn cancel_for_stp<S: EventSink + ?Sized>(
+        &mut self,
+        side: Side,
+        price: Px,
+        id: OrderID,
+        ts: Ts,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        let removed = self.unrest(side, price, id, ts, out)?;
+        out.lifecycle(self.seq.stamp(OrderEvent::cancelled_with(
+            id,
+            self.symbol,
+            removed.remaining(),
+            CancelReason::SelfTradePrevention,
+            ts,
+        )));
+        Ok(())
+    }
+
+    /// Find the side and price a resting order lives at.
+    #[inline]
+    fn locate(&self, id: OrderID) -> Option<(Side, Px)> {
+        self.index.get(&id).map(|&(side, price, _)| (side, price))
+    }
+
+    /// The slot a resting order holds in its level.
+    #[inline]
+    fn slot(&self, id: OrderID) -> NyquestroResult<Slot> {
+        self.index
+            .get(&id)
+            .map(|&(_, _, at)| at)
+            .ok_or(NyquestroError::OrderNotFound(id.value()))
+    }
+
+    fn book(&self, side: Side) -> &Ladder {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn book_mut(&mut self, side: Side) -> &mut Ladder {
         match side {
             Side::Buy => &mut self.bids,
             Side::Sell => &mut self.asks,
         }
     }
 
+    /// Displayed quantity at `(side, price)`; zero where there is no level.
+    fn shown_at(&self, side: Side, price: Px) -> Qty {
+        self.book(side).get(&price).map_or(Qty::ZERO, PriceLevel::total_quantity)
+    }
+
+    /// Pass `outcome` through, unless the book runs checked and an audit
+    /// after `operation` finds it broken: that fails the operation with a
+    /// fatal `InvariantViolation`, whatever it returned.
+    fn checked<T>(&self, operation: &str, outcome: NyquestroResult<T>) -> NyquestroResult<T> {
+        if self.config.checked {
+            let found = self.audit();
+            if !found.is_empty() {
+                return Err(audit::report(self.symbol, operation, &found));
+            }
+        }
+        outcome
+    }
+
+    /// Report a change to `order` on the market-by-order feed, if the book
+    /// publishes one.
+    fn publish_order<S: EventSink + ?Sized>(
+        &mut self,
+        action: MboAction,
+        order: &Order,
+        ts: Ts,
+        out: &mut S,
+    ) {
+        if !self.config.market_data.by_order {
+            return;
+        }
+        let shown = match action {
+            MboAction::Delete => Qty::ZERO,
+            MboAction::Add | MboAction::Modify => order.displayed(),
+        };
+        let event = MboEvent::new(
+            self.symbol,
+            action,
+            order.id(),
+            order.side(),
+            order.price(),
+            shown,
+            ts,
+        );
+        out.order(self.seq.stamp(event));
+    }
+
+    /// Report the level at `(side, price)` on the market-by-price feed, if
+    /// the book publishes one and its displayed quantity is no longer
+    /// `before`. Call once the ladder is back in order: the index is read
+    /// off the ladder as it stands.
+    fn publish_level<S: EventSink + ?Sized>(
+        &mut self,
+        side: Side,
+        price: Px,
+        before: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) {
+        if !self.config.market_data.by_price {
+            return;
+        }
+        let after = self.shown_at(side, price);
+        if after == before {
+            return;
+        }
+        let action = if before.is_zero() {
+            LevelAction::New
+        } else if after.is_zero() {
+            LevelAction::Delete
+        } else {
+            LevelAction::Change
+        };
+        let index = match side {
+            Side::Buy => self.bids.iter().rev().take_while(|(p, _)| **p > price).count(),
+            Side::Sell => self.asks.iter().take_while(|(p, _)| **p < price).count(),
+        };
+        let event =
+            LevelEvent::new(self.symbol, QuoteSide::from(side), action, index, price, after, ts);
+        out.level(self.seq.stamp(event));
+    }
+
+    /// Report a trade against the resting order `resting` on the depth
+    /// feeds: `Delete` once it is done, `Delete` then `Add` when its spent
+    /// peak went to the back of the level as `requeued`, otherwise
+    /// `Modify`; then its level, which showed `before`.
+    fn publish_trade<S: EventSink + ?Sized>(
+        &mut self,
+        resting: &Order,
+        requeued: Option<Order>,
+        before: Qty,
+        ts: Ts,
+        out: &mut S,
+    ) {
+        if resting.status().is_terminal() {
+            self.publish_order(MboAction::Delete, resting, ts, out);
+        } else if let Some(refreshed) = requeued {
+            self.publish_order(MboAction::Delete, resting, ts, out);
+            self.publish_order(MboAction::Add, &refreshed, ts, out);
+        } else {
+            self.publish_order(MboAction::Modify, resting, ts, out);
+        }
+        self.publish_level(resting.side(), resting.price(), before, ts, out);
+    }
+
     fn top_of(&self, side: Side) -> Option<(Px, Qty)> {
         match side {
             Side::Buy => self.best_bid(),
@@ -346,12 +2239,12 @@ impl OrderBook {
         }
     }
 
-    fn emit_quote_if_changed(
-        &self,
+    fn emit_quote_if_changed<S: EventSink + ?Sized>(
+        &mut self,
         side: Side,
         before: Option<(Px, Qty)>,
         ts: Ts,
-        out: &mut Vec<QuoteEvent>,
+        out: &mut S,
     ) {
         let after = self.top_of(side);
         if before == after {
@@ -361,18 +2254,46 @@ impl OrderBook {
         match after {
             Some((px, qty)) => {
                 if let Ok(q) = QuoteEvent::live(self.symbol, qside, px, qty, ts) {
-                    out.push(q);
+                    out.quote(self.seq.stamp(q));
                 }
             }
             None => {
                 if let Some((px, _)) = before {
-                    out.push(QuoteEvent::cleared(self.symbol, qside, px, ts));
+                    out.quote(self.seq.stamp(QuoteEvent::cleared(self.symbol, qside, px, ts)));
                 }
             }
         }
     }
 }
 
+/// Both orders carry an owner and it is the same one. Anonymous orders
+/// never self-trade.
+#[inline]
+fn same_owner(a: &Order, b: &Order) -> bool {
+    matches!((a.owner(), b.owner()), (Some(x), Some(y)) if x == y)
+}
+
+/// Whether `order` can rest through a call auction until the uncross.
+#[inline]
+fn waits_for_uncross(order: &Order) -> bool {
+    !order.is_market()
+        && !order.is_pegged()
+        && order.time_in_force().can_rest()
+        && order.post_only().is_none()
+        && order.min_quantity().is_none()
+}
+
+/// Price, id, owner and displayed quantity of the front order at a
+/// ladder's best level, for the uncross walk.
+fn front(
+    best: Option<(&Px, &PriceLevel)>,
+) -> NyquestroResult<(Px, OrderID, Option<OwnerID>, Qty)> {
+    best.and_then(|(px, level)| level.front().map(|o| (*px, o.id(), o.owner(), o.displayed())))
+        .ok_or_else(|| {
+            NyquestroError::InvariantViolation("uncross volume beyond the crossing orders".into())
+        })
+}
+
 #[inline]
 fn crosses(side: Side, price: Px, opposite_best: Px) -> bool {
     match side {
@@ -380,3 +2301,60 @@ fn crosses(side: Side, price: Px, opposite_best: Px) -> bool {
         Side::Sell => price <= opposite_best,
     }
 }
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYM: Symbol = Symbol::from_const("TEST");
+
+    fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+        let px = Px::from_raw(price).unwrap();
+        Order::new(OrderID::new(id).unwrap(), SYM, side, px, Qty::new(qty), Ts::from_nanos(ts))
+            .unwrap()
+    }
+
+    #[test]
+    fn a_drifted_level_total_fails_the_removal_even_unchecked() {
+        let mut book = OrderBook::new(SYM);
+        assert!(!book.config().checked);
+        book.submit_limit(order(1, Side::Buy, 100, 5, 1)).unwrap();
+        book.submit_limit(order(2, Side::Buy, 100, 3, 2)).unwrap();
+        let px = Px::from_raw(100).unwrap();
+        book.bids.get_mut(&px).unwrap().record_execution(Qty::new(6)).unwrap();
+
+        let id = OrderID::new(1).unwrap();
+        let Err(e @ NyquestroError::InvariantViolation(_)) = book.cancel(id, Ts::from_nanos(3))
+        else {
+            panic!("the cancel hid the drift");
+        };
+        assert!(e.is_fatal());
+        assert!(e.to_string().contains("#1"), "{e}");
+        assert!(book.contains(id));
+        assert!(matches!(
+            book.mass_cancel(&MassCancel::all(), Ts::from_nanos(4)),
+            Err(NyquestroError::InvariantViolation(_))
+        ));
+    }
+
+    #[test]
+    fn a_mass_cancel_into_drift_leaves_the_level_whole() {
+        let mut book = OrderBook::new(SYM);
+        book.submit_limit(order(1, Side::Buy, 100, 5, 1)).unwrap();
+        book.submit_limit(order(2, Side::Buy, 100, 3, 2)).unwrap();
+        let px = Px::from_raw(100).unwrap();
+        // The total covers order 1 but not both.
+        book.bids.get_mut(&px).unwrap().record_execution(Qty::new(2)).unwrap();
+
+        let Err(e) = book.mass_cancel(&MassCancel::all(), Ts::from_nanos(3)) else {
+            panic!("the mass cancel hid the drift");
+        };
+        assert!(e.to_string().contains("#2"), "{e}");
+        // Neither order came off, so the index still matches the level.
+        assert_eq!(book.len(), 2);
+        assert_eq!(book.bids.get(&px).unwrap().len(), 2);
+        assert_eq!(book.best_bid(), Some((px, Qty::new(6))));
+    }
+}
@@ -2,18 +2,48 @@
 //!
 //! `total_quantity` is maintained as a running sum across every mutation so
 //! the order book can read top-of-book size in O(1) without iterating the
-//! queue.
-
-use std::collections::VecDeque;
+//! queue. It counts *displayed* quantity only: the hidden reserve of an
+//! iceberg order is not part of the level's visible size.
+//!
+//! Orders live in a slab of nodes owned by the level, linked into the
+//! queue through `prev` / `next` indices. A node that empties goes on a
+//! free list and the next order to arrive takes it, so once the slab has
+//! grown to the level's busiest depth, queueing, cancelling, filling and
+//! refreshing allocate nothing. Each order's node is its [`Slot`]: the
+//! book keeps it in its id index, which makes removing an order from the
+//! middle of the queue O(1). The id-based methods find the slot by walking
+//! the queue first.
 
 use crate::errors::{NyquestroError, NyquestroResult};
 use crate::order::Order;
-use crate::types::{OrderID, Px, Qty};
+use crate::types::{OrderID, Px, Qty, Ts};
+
+/// End of a chain: no previous, next or free node.
+const NIL: u32 = u32::MAX;
+
+/// Where an order sits in its level's slab. Stable while the order rests
+/// at the level, across fills, amendments and refreshes; reused by a later
+/// order once it leaves.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Slot(u32);
+
+#[derive(Debug, Clone)]
+struct Node {
+    /// `None` while the node is on the free list.
+    order: Option<Order>,
+    prev: u32,
+    /// The next node in the queue, or on the free list.
+    next: u32,
+}
 
 #[derive(Debug, Clone)]
 pub struct PriceLevel {
     price: Px,
-    orders: VecDeque<Order>,
+    nodes: Vec<Node>,
+    free: u32,
+    head: u32,
+    tail: u32,
+    len: usize,
     total_quantity: Qty,
 }
 
@@ -21,11 +51,25 @@ impl PriceLevel {
     pub fn new(price: Px) -> Self {
         PriceLevel {
             price,
-            orders: VecDeque::new(),
+            nodes: Vec::new(),
+            free: NIL,
+            head: NIL,
+            tail: NIL,
+            len: 0,
             total_quantity: Qty::ZERO,
         }
     }
 
+    /// Empty this level and move it to `price`, keeping the slab's
+    /// capacity for the orders to come.
+    pub(crate) fn reopen(&mut self, price: Px) {
+        self.price = price;
+        self.nodes.clear();
+        (self.free, self.head, self.tail) = (NIL, NIL, NIL);
+        self.len = 0;
+        self.total_quantity = Qty::ZERO;
+    }
+
     #[inline]
     pub fn price(&self) -> Px {
         self.price
@@ -38,82 +82,300 @@ impl PriceLevel {
 
     #[inline]
     pub fn len(&self) -> usize {
-        self.orders.len()
+        self.len
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.orders.is_empty()
+        self.len == 0
     }
 
     /// Append an order to the back of the FIFO queue. The order's price
     /// must match the level.
     pub fn push_back(&mut self, order: Order) -> NyquestroResult<()> {
+        self.insert(order).map(|_| ())
+    }
+
+    /// [`push_back`](Self::push_back), returning the slot the order took.
+    pub fn insert(&mut self, order: Order) -> NyquestroResult<Slot> {
         if order.price() != self.price {
             return Err(NyquestroError::PriceLevelMismatch {
-                expected_cents: self.price.cents(),
-                actual_cents: order.price().cents(),
+                expected: self.price.raw(),
+                actual: order.price().raw(),
             });
         }
-        self.total_quantity = self
+        let total = self
             .total_quantity
-            .checked_add(order.remaining())
+            .checked_add(order.displayed())
             .ok_or(NyquestroError::QuantityOverflow)?;
-        self.orders.push_back(order);
-        Ok(())
+        let i = if self.free != NIL {
+            let i = self.free;
+            self.free = self.nodes[i as usize].next;
+            self.nodes[i as usize].order = Some(order);
+            i
+        } else {
+            let i = u32::try_from(self.nodes.len())
+                .ok()
+                .filter(|&i| i != NIL)
+                .ok_or_else(|| {
+                    NyquestroError::InvariantViolation("PriceLevel slab full".into())
+                })?;
+            self.nodes.push(Node {
+                order: Some(order),
+                prev: NIL,
+                next: NIL,
+            });
+            i
+        };
+        self.total_quantity = total;
+        self.link_back(i);
+        self.len += 1;
+        Ok(Slot(i))
     }
 
     /// Borrow the front order (oldest at this price).
     #[inline]
     pub fn front(&self) -> Option<&Order> {
-        self.orders.front()
+        self.get(Slot(self.head))
     }
 
     /// Borrow the front order mutably.
     #[inline]
     pub fn front_mut(&mut self) -> Option<&mut Order> {
-        self.orders.front_mut()
+        self.nodes.get_mut(self.head as usize)?.order.as_mut()
+    }
+
+    /// Borrow the order in `at`, if one is there.
+    #[inline]
+    pub fn get(&self, at: Slot) -> Option<&Order> {
+        self.nodes.get(at.0 as usize)?.order.as_ref()
+    }
+
+    /// The slot of the order with `id`. O(n): walks the queue.
+    pub fn find(&self, id: OrderID) -> Option<Slot> {
+        self.entries().find(|(_, o)| o.id() == id).map(|(at, _)| at)
     }
 
     /// Decrement the level's running total by `executed`. Called by the
     /// matching engine after applying a fill to the front order so size
     /// queries reflect post-fill state.
     pub fn record_execution(&mut self, executed: Qty) -> NyquestroResult<()> {
-        self.total_quantity = self
-            .total_quantity
-            .checked_sub(executed)
-            .ok_or(NyquestroError::InvariantViolation(
-                "PriceLevel total_quantity underflow",
-            ))?;
+        self.total_quantity = self.total_less(executed)?;
         Ok(())
     }
 
     /// Remove and return the front order. Decrements `total_quantity` by
-    /// the front order's remaining quantity at the time of removal.
-    pub fn pop_front(&mut self) -> Option<Order> {
-        let order = self.orders.pop_front()?;
-        // Cannot fail: total_quantity ≥ remaining of front by invariant.
-        if let Some(new_total) = self.total_quantity.checked_sub(order.remaining()) {
-            self.total_quantity = new_total;
-        }
-        Some(order)
+    /// the front order's displayed quantity at the time of removal.
+    pub fn pop_front(&mut self) -> NyquestroResult<Option<Order>> {
+        self.remove_at(Slot(self.head))
     }
 
     /// Remove the order with `id` from anywhere in the queue, returning it
-    /// if present. O(n) — acceptable for cancellations which are rare
-    /// relative to fills in the matching hot path.
-    pub fn remove_by_id(&mut self, id: OrderID) -> Option<Order> {
-        let pos = self.orders.iter().position(|o| o.id() == id)?;
-        let order = self.orders.remove(pos)?;
-        if let Some(new_total) = self.total_quantity.checked_sub(order.remaining()) {
-            self.total_quantity = new_total;
+    /// if present. O(n) to find it; see [`remove_at`](Self::remove_at).
+    pub fn remove_by_id(&mut self, id: OrderID) -> NyquestroResult<Option<Order>> {
+        match self.find(id) {
+            Some(at) => self.remove_at(at),
+            None => Ok(None),
         }
-        Some(order)
+    }
+
+    /// Remove the order in `at`, returning it if one was there. O(1).
+    ///
+    /// The order's displayed quantity comes off `total_quantity`. A total
+    /// smaller than that means the two have drifted apart, and the level
+    /// is left untouched with an `InvariantViolation` naming the level and
+    /// the order, whether or not the book runs checked.
+    pub fn remove_at(&mut self, at: Slot) -> NyquestroResult<Option<Order>> {
+        let Some(order) = self.nodes.get(at.0 as usize).and_then(|node| node.order) else {
+            return Ok(None);
+        };
+        let total = self.after_removing(self.total_quantity, &order)?;
+        self.release(at);
+        self.total_quantity = total;
+        Ok(Some(order))
+    }
+
+    /// Remove every order `pred` selects in one pass, returning them in
+    /// queue order. The survivors keep their relative priority.
+    ///
+    /// All or nothing: the total is checked against every chosen order
+    /// before the first one comes off, so drift anywhere in the selection
+    /// leaves the whole level as it was, like [`remove_at`](Self::remove_at).
+    pub fn remove_where(
+        &mut self,
+        mut pred: impl FnMut(&Order) -> bool,
+    ) -> NyquestroResult<Vec<Order>> {
+        let chosen: Vec<(Slot, Order)> =
+            self.entries().filter(|(_, o)| pred(o)).map(|(at, o)| (at, *o)).collect();
+        let mut total = self.total_quantity;
+        for (_, order) in &chosen {
+            total = self.after_removing(total, order)?;
+        }
+        for &(at, _) in &chosen {
+            self.release(at);
+        }
+        self.total_quantity = total;
+        Ok(chosen.into_iter().map(|(_, order)| order).collect())
+    }
+
+    /// Reduce the open quantity of the order with `id` to `remaining`
+    /// without moving it in the queue. Time priority is kept, which is
+    /// only fair when the order gets smaller — growing an order must go
+    /// through remove + `push_back` instead.
+    pub fn shrink(&mut self, id: OrderID, remaining: Qty) -> NyquestroResult<()> {
+        let at = self.find(id).ok_or(NyquestroError::OrderNotFound(id.value()))?;
+        self.shrink_at(at, remaining).map(|_| ())
+    }
+
+    /// [`shrink`](Self::shrink) the order in `at`, returning it amended.
+    /// The amendment and the new total are both worked out before either
+    /// is applied, so a failure leaves the order and the level as they
+    /// were.
+    pub fn shrink_at(&mut self, at: Slot, remaining: Qty) -> NyquestroResult<Order> {
+        let current = *self.order_mut(at)?;
+        if remaining > current.remaining() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        let mut amended = current;
+        amended.amend(self.price, remaining)?;
+        let released = current
+            .displayed()
+            .checked_sub(amended.displayed())
+            .ok_or_else(|| {
+                NyquestroError::InvariantViolation("shrink grew displayed quantity".into())
+            })?;
+        let total = self.total_less(released)?;
+        *self.order_mut(at)? = amended;
+        self.total_quantity = total;
+        Ok(amended)
+    }
+
+    /// Execute `qty` against the order with `id`, wherever it sits in the
+    /// queue, and return its updated state. The order stays in place even
+    /// when fully filled; the caller removes it.
+    pub fn fill(&mut self, id: OrderID, qty: Qty) -> NyquestroResult<Order> {
+        let at = self.find(id).ok_or(NyquestroError::OrderNotFound(id.value()))?;
+        self.fill_at(at, qty)
+    }
+
+    /// [`fill`](Self::fill) the order in `at`. Like
+    /// [`shrink_at`](Self::shrink_at), it fails without touching the order
+    /// or the level.
+    pub fn fill_at(&mut self, at: Slot, qty: Qty) -> NyquestroResult<Order> {
+        let mut filled = *self.order_mut(at)?;
+        if qty > filled.displayed() {
+            return Err(NyquestroError::InvariantViolation("fill beyond displayed quantity".into()));
+        }
+        filled.fill(qty)?;
+        let total = self.total_less(qty)?;
+        *self.order_mut(at)? = filled;
+        self.total_quantity = total;
+        Ok(filled)
+    }
+
+    /// Move the order with `id` to the back of the queue with a fresh
+    /// displayed peak and `ts` as its new priority time. Used when an
+    /// iceberg's peak has been consumed and it still has hidden quantity.
+    pub fn refresh(&mut self, id: OrderID, ts: Ts) -> NyquestroResult<Order> {
+        let at = self.find(id).ok_or(NyquestroError::OrderNotFound(id.value()))?;
+        self.refresh_at(at, ts)
+    }
+
+    /// [`refresh`](Self::refresh) the order in `at`. It keeps its slot.
+    pub fn refresh_at(&mut self, at: Slot, ts: Ts) -> NyquestroResult<Order> {
+        let mut refreshed = *self.order_mut(at)?;
+        let shown_before = refreshed.displayed();
+        refreshed.refresh(ts);
+        let total = self
+            .total_quantity
+            .checked_sub(shown_before)
+            .and_then(|total| total.checked_add(refreshed.displayed()))
+            .ok_or(NyquestroError::QuantityOverflow)?;
+        *self.order_mut(at)? = refreshed;
+        self.total_quantity = total;
+        self.unlink(at.0);
+        self.link_back(at.0);
+        Ok(refreshed)
     }
 
     /// Iterate orders in time-priority order (front to back).
     pub fn iter(&self) -> impl Iterator<Item = &Order> {
-        self.orders.iter()
+        self.entries().map(|(_, order)| order)
+    }
+
+    /// Each order with its slot, front to back.
+    pub fn entries(&self) -> impl Iterator<Item = (Slot, &Order)> {
+        let mut at = self.head;
+        std::iter::from_fn(move || {
+            let node = self.nodes.get(at as usize)?;
+            let slot = Slot(at);
+            at = node.next;
+            node.order.as_ref().map(|order| (slot, order))
+        })
+    }
+
+    /// The running total less `executed`, or an `InvariantViolation` when
+    /// it would go below zero.
+    fn total_less(&self, executed: Qty) -> NyquestroResult<Qty> {
+        self.total_quantity.checked_sub(executed).ok_or_else(|| {
+            NyquestroError::InvariantViolation("PriceLevel total_quantity underflow".into())
+        })
+    }
+
+    /// `total` once `order` has left it, or the drift error when `order`
+    /// shows more than `total` holds.
+    fn after_removing(&self, total: Qty, order: &Order) -> NyquestroResult<Qty> {
+        total.checked_sub(order.displayed()).ok_or_else(|| {
+            NyquestroError::InvariantViolation(format!(
+                "level {} total {} is below the {} displayed by order {}",
+                self.price,
+                total,
+                order.displayed(),
+                order.id()
+            ))
+        })
+    }
+
+    /// Unlink the order in `at` and put its node on the free list. The
+    /// total is the caller's to settle.
+    fn release(&mut self, at: Slot) {
+        self.unlink(at.0);
+        let node = &mut self.nodes[at.0 as usize];
+        node.order = None;
+        node.next = self.free;
+        self.free = at.0;
+        self.len -= 1;
+    }
+
+    fn order_mut(&mut self, at: Slot) -> NyquestroResult<&mut Order> {
+        self.nodes
+            .get_mut(at.0 as usize)
+            .and_then(|node| node.order.as_mut())
+            .ok_or_else(|| NyquestroError::InvariantViolation("no order in PriceLevel slot".into()))
+    }
+
+    /// Chain node `i` onto the back of the queue.
+    fn link_back(&mut self, i: u32) {
+        self.nodes[i as usize].prev = self.tail;
+        self.nodes[i as usize].next = NIL;
+        match self.tail {
+            NIL => self.head = i,
+            tail => self.nodes[tail as usize].next = i,
+        }
+        self.tail = i;
+    }
+
+    /// Take node `i` out of the queue, joining its neighbours.
+    fn unlink(&mut self, i: u32) {
+        let Node { prev, next, .. } = self.nodes[i as usize];
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev as usize].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next as usize].prev = prev,
+        }
     }
 }
 
@@ -122,16 +384,16 @@ impl PriceLevel {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::{Side, Symbol, Ts};
+    use crate::types::{Side, Status, Symbol};
 
     const SYM: Symbol = Symbol::from_const("TEST");
 
-    fn order(id: u64, price: u64, qty: u32, ts: u64) -> Order {
+    fn order(id: u64, price: u64, qty: u64, ts: u64) -> Order {
         Order::new(
             OrderID::new(id).unwrap(),
             SYM,
             Side::Buy,
-            Px::from_cents(price).unwrap(),
+            Px::from_raw(price).unwrap(),
             Qty::new(qty),
             Ts::from_nanos(ts),
         )
@@ -140,14 +402,14 @@ mod tests {
 
     #[test]
     fn empty_level_has_zero_total() {
-        let lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         assert!(lvl.is_empty());
         assert_eq!(lvl.total_quantity(), Qty::ZERO);
     }
 
     #[test]
     fn push_back_maintains_total() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         lvl.push_back(order(1, 100, 5, 1)).unwrap();
         lvl.push_back(order(2, 100, 3, 2)).unwrap();
         assert_eq!(lvl.total_quantity(), Qty::new(8));
@@ -156,7 +418,7 @@ mod tests {
 
     #[test]
     fn push_back_rejects_wrong_price() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         let err = lvl.push_back(order(1, 200, 5, 1));
         assert!(matches!(
             err,
@@ -166,17 +428,17 @@ mod tests {
 
     #[test]
     fn pop_front_returns_oldest_first() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         lvl.push_back(order(1, 100, 5, 1)).unwrap();
         lvl.push_back(order(2, 100, 3, 2)).unwrap();
-        let first = lvl.pop_front().unwrap();
+        let first = lvl.pop_front().unwrap().unwrap();
         assert_eq!(first.id().value(), 1);
         assert_eq!(lvl.total_quantity(), Qty::new(3));
     }
 
     #[test]
     fn record_execution_updates_total() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         lvl.push_back(order(1, 100, 5, 1)).unwrap();
         lvl.record_execution(Qty::new(2)).unwrap();
         assert_eq!(lvl.total_quantity(), Qty::new(3));
@@ -184,11 +446,11 @@ mod tests {
 
     #[test]
     fn remove_by_id_returns_and_decrements() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         lvl.push_back(order(1, 100, 5, 1)).unwrap();
         lvl.push_back(order(2, 100, 3, 2)).unwrap();
         lvl.push_back(order(3, 100, 7, 3)).unwrap();
-        let removed = lvl.remove_by_id(OrderID::new(2).unwrap()).unwrap();
+        let removed = lvl.remove_by_id(OrderID::new(2).unwrap()).unwrap().unwrap();
         assert_eq!(removed.id().value(), 2);
         assert_eq!(lvl.total_quantity(), Qty::new(12));
         assert_eq!(lvl.len(), 2);
@@ -199,14 +461,132 @@ mod tests {
 
     #[test]
     fn remove_by_id_missing_returns_none() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
         lvl.push_back(order(1, 100, 5, 1)).unwrap();
-        assert!(lvl.remove_by_id(OrderID::new(99).unwrap()).is_none());
+        assert!(lvl.remove_by_id(OrderID::new(99).unwrap()).unwrap().is_none());
+    }
+
+    #[test]
+    fn removing_from_a_drifted_total_is_an_invariant_violation() {
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        lvl.push_back(order(1, 100, 5, 1)).unwrap();
+        lvl.push_back(order(2, 100, 3, 2)).unwrap();
+        // The total forgets 6 of the 8 the queue shows.
+        lvl.record_execution(Qty::new(6)).unwrap();
+        let Err(NyquestroError::InvariantViolation(msg)) = lvl.pop_front() else {
+            panic!("drift went unnoticed");
+        };
+        assert!(msg.contains("$1.00") && msg.contains("#1"), "{msg}");
+        // Nothing was removed on the way out.
+        assert_eq!(lvl.len(), 2);
+        assert_eq!(lvl.total_quantity(), Qty::new(2));
+        assert!(lvl.remove_where(|_| true).is_err());
+        assert_eq!(lvl.front().unwrap().id().value(), 1);
+    }
+
+    #[test]
+    fn a_bulk_removal_checks_the_whole_selection_before_removing_any() {
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        lvl.push_back(order(1, 100, 5, 1)).unwrap();
+        lvl.push_back(order(2, 100, 3, 2)).unwrap();
+        // Enough total for the first order, not for both.
+        lvl.record_execution(Qty::new(2)).unwrap();
+        let Err(NyquestroError::InvariantViolation(msg)) = lvl.remove_where(|_| true) else {
+            panic!("drift went unnoticed");
+        };
+        assert!(msg.contains("#2"), "{msg}");
+        assert_eq!(lvl.len(), 2);
+        assert_eq!(lvl.total_quantity(), Qty::new(6));
+        let ids: Vec<u64> = lvl.iter().map(|o| o.id().value()).collect();
+        assert_eq!(ids, [1, 2]);
+    }
+
+    #[test]
+    fn a_fill_or_shrink_the_total_cannot_cover_changes_nothing() {
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        lvl.push_back(order(1, 100, 5, 1)).unwrap();
+        lvl.push_back(order(2, 100, 3, 2)).unwrap();
+        lvl.record_execution(Qty::new(7)).unwrap();
+        let at = lvl.find(OrderID::new(1).unwrap()).unwrap();
+
+        let drift =
+            |r: NyquestroResult<Order>| matches!(r, Err(NyquestroError::InvariantViolation(_)));
+        assert!(drift(lvl.fill_at(at, Qty::new(4))));
+        assert!(drift(lvl.shrink_at(at, Qty::new(2))));
+        let front = lvl.front().unwrap();
+        assert_eq!((front.remaining(), front.displayed()), (Qty::new(5), Qty::new(5)));
+        assert_eq!(front.status(), Status::Open);
+        assert_eq!(lvl.total_quantity(), Qty::new(1));
+    }
+
+    #[test]
+    fn shrink_keeps_position_and_updates_total() {
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        lvl.push_back(order(1, 100, 5, 1)).unwrap();
+        lvl.push_back(order(2, 100, 3, 2)).unwrap();
+        lvl.shrink(OrderID::new(1).unwrap(), Qty::new(2)).unwrap();
+        assert_eq!(lvl.front().unwrap().id().value(), 1);
+        assert_eq!(lvl.front().unwrap().remaining(), Qty::new(2));
+        assert_eq!(lvl.total_quantity(), Qty::new(5));
+        // Growing in place is refused.
+        assert!(lvl.shrink(OrderID::new(2).unwrap(), Qty::new(4)).is_err());
+        assert_eq!(lvl.total_quantity(), Qty::new(5));
+    }
+
+    #[test]
+    fn iceberg_counts_displayed_and_refreshes_to_back() {
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        lvl.push_back(order(1, 100, 25, 1).with_peak(Qty::new(10)).unwrap())
+            .unwrap();
+        lvl.push_back(order(2, 100, 3, 2)).unwrap();
+        assert_eq!(lvl.total_quantity(), Qty::new(13));
+
+        let filled = lvl.fill(OrderID::new(1).unwrap(), Qty::new(10)).unwrap();
+        assert_eq!(filled.displayed(), Qty::ZERO);
+        assert_eq!(lvl.total_quantity(), Qty::new(3));
+        // Only the displayed peak can be executed against.
+        assert!(lvl.fill(OrderID::new(1).unwrap(), Qty::new(1)).is_err());
+
+        let refreshed = lvl.refresh(OrderID::new(1).unwrap(), Ts::from_nanos(7)).unwrap();
+        assert_eq!(refreshed.displayed(), Qty::new(10));
+        assert_eq!(refreshed.hidden(), Qty::new(5));
+        assert_eq!(lvl.total_quantity(), Qty::new(13));
+        let ids: Vec<_> = lvl.iter().map(|o| o.id().value()).collect();
+        assert_eq!(ids, vec![2, 1]);
+    }
+
+    #[test]
+    fn slots_stay_put_and_free_nodes_are_reused() {
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        let a = lvl.insert(order(1, 100, 5, 1)).unwrap();
+        let b = lvl.insert(order(2, 100, 3, 2)).unwrap();
+        let c = lvl.insert(order(3, 100, 7, 3)).unwrap();
+        assert_eq!(lvl.find(OrderID::new(2).unwrap()), Some(b));
+
+        // Out of the middle, and the next order takes its node at the back.
+        assert_eq!(lvl.remove_at(b).unwrap().unwrap().id().value(), 2);
+        assert!(lvl.remove_at(b).unwrap().is_none());
+        let d = lvl.insert(order(4, 100, 1, 4)).unwrap();
+        assert_eq!(d, b);
+        let ids: Vec<_> = lvl.iter().map(|o| o.id().value()).collect();
+        assert_eq!(ids, vec![1, 3, 4]);
+        assert_eq!(lvl.total_quantity(), Qty::new(13));
+
+        // Amending and filling in place keep the slot.
+        assert_eq!(lvl.shrink_at(c, Qty::new(2)).unwrap().remaining(), Qty::new(2));
+        assert_eq!(lvl.fill_at(a, Qty::new(5)).unwrap().remaining(), Qty::ZERO);
+        assert_eq!(lvl.get(c).unwrap().id().value(), 3);
+        assert_eq!(lvl.total_quantity(), Qty::new(3));
+
+        lvl.reopen(Px::from_raw(101).unwrap());
+        assert!(lvl.is_empty() && lvl.front().is_none());
+        assert_eq!(lvl.total_quantity(), Qty::ZERO);
+        assert!(lvl.push_back(order(5, 100, 1, 5)).is_err());
     }
 
     #[test]
     fn pop_front_empty_returns_none() {
-        let mut lvl = PriceLevel::new(Px::from_cents(100).unwrap());
-        assert!(lvl.pop_front().is_none());
+        let mut lvl = PriceLevel::new(Px::from_raw(100).unwrap());
+        assert!(lvl.pop_front().unwrap().is_none());
     }
 }
@@ -0,0 +1,88 @@
+//! `ReferencePrice` — the rolling trade price the volatility guard bands
+//! around.
+//!
+//! Trades are recorded with the engine time of the operation that printed
+//! them, so the reference is as deterministic as the input sequence.
+
+use std::collections::VecDeque;
+
+use crate::types::{Px, Ts};
+
+#[derive(Debug, Clone, Default)]
+pub struct ReferencePrice {
+    /// Trades inside the window as of the latest one, oldest first.
+    trades: VecDeque<(Ts, Px)>,
+}
+
+impl ReferencePrice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a trade at `px` at engine time `ts`, forgetting trades more
+    /// than `window` nanoseconds older.
+    pub fn record(&mut self, ts: Ts, px: Px, window: u64) {
+        self.trades.push_back((ts, px));
+        let cutoff = ts.nanos().saturating_sub(window);
+        while self.trades.len() > 1
+            && self.trades.front().is_some_and(|(t, _)| t.nanos() < cutoff)
+        {
+            self.trades.pop_front();
+        }
+    }
+
+    /// Trades still held, oldest first.
+    pub fn trades(&self) -> impl Iterator<Item = (Ts, Px)> + '_ {
+        self.trades.iter().copied()
+    }
+
+    /// A reference holding `trades`, oldest first, as read back from a
+    /// snapshot.
+    pub(crate) fn from_trades(trades: impl IntoIterator<Item = (Ts, Px)>) -> Self {
+        ReferencePrice {
+            trades: trades.into_iter().collect(),
+        }
+    }
+
+    /// Mean price of the trades within `window` nanoseconds of `now`,
+    /// rounded down; the most recent trade when none are that recent.
+    /// `None` before the first trade.
+    pub fn price(&self, now: Ts, window: u64) -> Option<Px> {
+        let cutoff = now.nanos().saturating_sub(window);
+        let (sum, count) = self
+            .trades
+            .iter()
+            .filter(|(t, _)| t.nanos() >= cutoff)
+            .fold((0u128, 0u128), |(sum, n), (_, px)| (sum + u128::from(px.raw()), n + 1));
+        if count == 0 {
+            return self.trades.back().map(|&(_, px)| px);
+        }
+        Px::from_raw((sum / count) as u64).ok()
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(c: u64) -> Px {
+        Px::from_raw(c).unwrap()
+    }
+
+    #[test]
+    fn averages_the_window_and_falls_back_to_the_last_trade() {
+        let mut r = ReferencePrice::new();
+        assert_eq!(r.price(Ts::from_nanos(0), 100), None);
+        r.record(Ts::from_nanos(10), px(100), 100);
+        r.record(Ts::from_nanos(50), px(103), 100);
+        assert_eq!(r.price(Ts::from_nanos(60), 100), Some(px(101)));
+        // Only the second trade is still inside the window.
+        assert_eq!(r.price(Ts::from_nanos(120), 100), Some(px(103)));
+        // Neither is; the last trade stands in.
+        assert_eq!(r.price(Ts::from_nanos(1_000), 100), Some(px(103)));
+        r.record(Ts::from_nanos(1_000), px(90), 100);
+        assert_eq!(r.trades.len(), 1);
+    }
+}
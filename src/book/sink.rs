@@ -0,0 +1,265 @@
+//! `EventSink` — where a book's output goes as it is produced.
+//!
+//! Every [`OrderBook`](crate::book::OrderBook) and
+//! [`Market`](crate::book::Market) operation has an `_into` form that
+//! hands each event to a sink the moment it is stamped, instead of
+//! collecting them into a [`SubmitResult`]. A consumer that only folds the
+//! stream into its own state (a dashboard, a counter, a journal writer)
+//! therefore costs the engine nothing beyond the calls themselves.
+//!
+//! Events reach a sink in `seq` order across all seven kinds. The
+//! `SubmitResult`-returning operations are adapters over the same path:
+//! [`SubmitResult`] is itself a sink that files each event into its
+//! vector, and [`SubmitResult::replay_into`] goes the other way.
+//!
+//! A sink sees events as they happen, so when an operation fails part way
+//! through (only ever a fatal error) the events before the failure have
+//! already been delivered. A `SubmitResult` built by the adapters is
+//! dropped with the error, as it always was.
+
+use crate::book::order_book::SubmitResult;
+use crate::errors::NyquestroResult;
+use crate::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboEvent, OrderEvent, QuoteEvent, Sequenced, StateEvent,
+};
+
+/// Receives a book's events one at a time, in the order they are
+/// stamped. Every method defaults to dropping the event, so a sink only
+/// implements the kinds it cares about.
+pub trait EventSink {
+    fn fill(&mut self, event: FillEvent) {
+        let _ = event;
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        let _ = event;
+    }
+
+    /// Market-by-price delta; only sent when the book publishes them.
+    fn level(&mut self, event: LevelEvent) {
+        let _ = event;
+    }
+
+    /// Market-by-order delta; only sent when the book publishes them.
+    fn order(&mut self, event: MboEvent) {
+        let _ = event;
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        let _ = event;
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        let _ = event;
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        let _ = event;
+    }
+}
+
+/// Drops everything.
+impl EventSink for () {}
+
+impl<S: EventSink + ?Sized> EventSink for &mut S {
+    fn fill(&mut self, event: FillEvent) {
+        (**self).fill(event);
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        (**self).quote(event);
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        (**self).level(event);
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        (**self).order(event);
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        (**self).lifecycle(event);
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        (**self).auction(event);
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        (**self).state(event);
+    }
+}
+
+/// Fan-out: every event goes to `A`, then to `B`. Nest pairs for more.
+impl<A: EventSink, B: EventSink> EventSink for (A, B) {
+    fn fill(&mut self, event: FillEvent) {
+        self.0.fill(event);
+        self.1.fill(event);
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.0.quote(event);
+        self.1.quote(event);
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.0.level(event);
+        self.1.level(event);
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.0.order(event);
+        self.1.order(event);
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.0.lifecycle(event);
+        self.1.lifecycle(event);
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.0.auction(event);
+        self.1.auction(event);
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.0.state(event);
+        self.1.state(event);
+    }
+}
+
+/// The collecting adapter: each event is appended to its vector.
+impl EventSink for SubmitResult {
+    fn fill(&mut self, event: FillEvent) {
+        self.fills.push(event);
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.quotes.push(event);
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.levels.push(event);
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.orders.push(event);
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.lifecycle.push(event);
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.auction.push(event);
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.state.push(event);
+    }
+}
+
+impl SubmitResult {
+    /// Collect what `run` sends to its sink. The `SubmitResult`-returning
+    /// operations are all this wrapped around their `_into` forms.
+    pub fn collect(
+        run: impl FnOnce(&mut SubmitResult) -> NyquestroResult<()>,
+    ) -> NyquestroResult<SubmitResult> {
+        let mut result = SubmitResult::default();
+        run(&mut result)?;
+        Ok(result)
+    }
+
+    /// Send every event to `out` in `seq` order, interleaving the vectors
+    /// back into the stream the book produced. Only meaningful for one
+    /// book's result: numbers from different books overlap.
+    pub fn replay_into<S: EventSink + ?Sized>(self, out: &mut S) {
+        let mut fills = self.fills.into_iter().peekable();
+        let mut quotes = self.quotes.into_iter().peekable();
+        let mut levels = self.levels.into_iter().peekable();
+        let mut orders = self.orders.into_iter().peekable();
+        let mut lifecycle = self.lifecycle.into_iter().peekable();
+        let mut auction = self.auction.into_iter().peekable();
+        let mut state = self.state.into_iter().peekable();
+        loop {
+            let heads = [
+                fills.peek().map(Sequenced::seq),
+                quotes.peek().map(Sequenced::seq),
+                levels.peek().map(Sequenced::seq),
+                orders.peek().map(Sequenced::seq),
+                lifecycle.peek().map(Sequenced::seq),
+                auction.peek().map(Sequenced::seq),
+                state.peek().map(Sequenced::seq),
+            ];
+            // Earliest head wins; ties go to the kind listed first.
+            let Some((next, _)) = heads
+                .iter()
+                .enumerate()
+                .filter_map(|(kind, seq)| seq.map(|seq| (kind, seq)))
+                .min_by_key(|&(kind, seq)| (seq, kind))
+            else {
+                return;
+            };
+            match next {
+                0 => fills.next().into_iter().for_each(|e| out.fill(e)),
+                1 => quotes.next().into_iter().for_each(|e| out.quote(e)),
+                2 => levels.next().into_iter().for_each(|e| out.level(e)),
+                3 => orders.next().into_iter().for_each(|e| out.order(e)),
+                4 => lifecycle.next().into_iter().for_each(|e| out.lifecycle(e)),
+                5 => auction.next().into_iter().for_each(|e| out.auction(e)),
+                _ => state.next().into_iter().for_each(|e| out.state(e)),
+            }
+        }
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::QuoteSide;
+    use crate::types::{OrderID, Px, Qty, Side, Symbol, Ts};
+
+    const SYM: Symbol = Symbol::from_const("TEST");
+
+    fn placed(id: u64, seq: u64) -> OrderEvent {
+        let id = OrderID::new(id).unwrap();
+        let px = Px::from_raw(100).unwrap();
+        OrderEvent::placed(id, SYM, Side::Buy, px, Qty::new(1), Ts::from_nanos(1))
+            .unwrap()
+            .with_seq(seq)
+    }
+
+    fn quote(seq: u64) -> QuoteEvent {
+        let px = Px::from_raw(100).unwrap();
+        QuoteEvent::live(SYM, QuoteSide::Bid, px, Qty::new(1), Ts::from_nanos(1))
+            .unwrap()
+            .with_seq(seq)
+    }
+
+    #[test]
+    fn replay_interleaves_by_seq_and_collect_rebuilds_the_result() {
+        let result = SubmitResult {
+            quotes: vec![quote(2), quote(5)],
+            lifecycle: vec![placed(1, 1), placed(2, 3), placed(3, 4)],
+            ..SubmitResult::default()
+        };
+
+        #[derive(Default)]
+        struct Seqs(Vec<u64>);
+        impl EventSink for Seqs {
+            fn quote(&mut self, event: QuoteEvent) {
+                self.0.push(event.seq());
+            }
+            fn lifecycle(&mut self, event: OrderEvent) {
+                self.0.push(event.seq());
+            }
+        }
+        let mut both = (Seqs::default(), SubmitResult::default());
+        result.clone().replay_into(&mut both);
+        assert_eq!(both.0.0, [1, 2, 3, 4, 5]);
+        assert_eq!(both.1, result);
+    }
+}
@@ -0,0 +1,443 @@
+//! Snapshots of market state, for dumping a book while debugging and
+//! restoring one in a test or after a restart.
+//!
+//! [`Market::snapshot`](crate::book::Market::snapshot) captures every book
+//! — config, trading state, running auction, counters, the volatility
+//! guard's reference trades, both ladders in priority order and the
+//! waiting stops — plus the fee schedule and per-account fee totals.
+//! [`Market::from_snapshot`](crate::book::Market::from_snapshot) rebuilds
+//! a market that behaves identically from there on.
+//!
+//! Two encodings carry the same [`MarketSnapshot`]: pretty-printed JSON
+//! for people and a compact binary form (`NYQS` followed by bincode with
+//! variable-length integers) for storage. Both lead with
+//! [`SNAPSHOT_VERSION`], which is checked before anything else is read.
+//!
+//! Like reference data, a snapshot stores plain integers and every value
+//! is rebuilt through its own constructor on load. Nothing in the file is
+//! trusted: loading also checks that each order's state is one the engine
+//! could have produced, that every order sits on the right side at its
+//! level's price, that level totals match their orders, that ladders are
+//! in price order and that no id appears twice in a book.
+//!
+//! A snapshot holds state, not output: depth events a `cancel` has
+//! numbered but not yet delivered are not part of it.
+
+use serde::{Deserialize, Serialize};
+
+use crate::book::config::{
+    BookConfig, MarketData, MatchingAlgorithm, ProtectionBand, SelfTradePrevention,
+    VolatilityGuard,
+};
+use crate::book::fees::{FeeSchedule, FeeTier, FeeTotals};
+use crate::book::ladder::LadderKind;
+use crate::book::price_level::PriceLevel;
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::instrument::SpecRecord;
+use crate::order::Order;
+use crate::types::{
+    AuctionKind, OrderID, OrderType, OwnerID, Peg, PegReference, PostOnly, Px, Qty, Side, Status,
+    Symbol, TimeInForce, TradingState, Ts,
+};
+
+/// Format version written into every snapshot. Loading refuses any other.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Leads the binary form.
+const MAGIC: [u8; 4] = *b"NYQS";
+
+/// Everything a [`crate::book::Market`] holds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MarketSnapshot {
+    pub(crate) version: u32,
+    pub(crate) books: Vec<BookSnapshot>,
+    pub(crate) fees: FeeScheduleRecord,
+    pub(crate) fee_totals: Vec<FeeTotalsRecord>,
+}
+
+impl MarketSnapshot {
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn books(&self) -> &[BookSnapshot] {
+        &self.books
+    }
+
+    pub fn to_json(&self) -> NyquestroResult<String> {
+        serde_json::to_string_pretty(self).map_err(|e| NyquestroError::Snapshot(e.to_string()))
+    }
+
+    /// Parse the JSON form. The version is read and checked first, so a
+    /// snapshot from another version fails on that rather than on its
+    /// layout. The content is validated by
+    /// [`Market::from_snapshot`](crate::book::Market::from_snapshot).
+    pub fn from_json(json: &str) -> NyquestroResult<Self> {
+        #[derive(Deserialize)]
+        struct Probe {
+            version: u32,
+        }
+        let probe: Probe =
+            serde_json::from_str(json).map_err(|e| NyquestroError::Snapshot(e.to_string()))?;
+        supported(probe.version)?;
+        serde_json::from_str(json).map_err(|e| NyquestroError::Snapshot(e.to_string()))
+    }
+
+    pub fn to_bytes(&self) -> NyquestroResult<Vec<u8>> {
+        use bincode::Options;
+        let body = bincode::DefaultOptions::new()
+            .serialize(self)
+            .map_err(|e| NyquestroError::Snapshot(e.to_string()))?;
+        Ok(MAGIC.iter().copied().chain(body).collect())
+    }
+
+    /// Parse the binary form; see [`from_json`](Self::from_json).
+    pub fn from_bytes(bytes: &[u8]) -> NyquestroResult<Self> {
+        use bincode::Options;
+        let body = bytes
+            .strip_prefix(&MAGIC)
+            .ok_or_else(|| NyquestroError::Snapshot("not a binary snapshot".into()))?;
+        let options = bincode::DefaultOptions::new();
+        // The version is the first field, so it can be read on its own.
+        let version: u32 = options
+            .allow_trailing_bytes()
+            .deserialize(body)
+            .map_err(|e| NyquestroError::Snapshot(e.to_string()))?;
+        supported(version)?;
+        options.deserialize(body).map_err(|e| NyquestroError::Snapshot(e.to_string()))
+    }
+}
+
+fn supported(version: u32) -> NyquestroResult<()> {
+    if version != SNAPSHOT_VERSION {
+        return Err(NyquestroError::Snapshot(format!("unsupported version {version}")));
+    }
+    Ok(())
+}
+
+/// Everything one [`crate::book::OrderBook`] holds. Levels are listed
+/// best price first, orders in each level front to back, stops in firing
+/// order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BookSnapshot {
+    pub(crate) symbol: String,
+    pub(crate) config: ConfigRecord,
+    pub(crate) state: TradingState,
+    pub(crate) auction: Option<AuctionRecord>,
+    pub(crate) last_trade: Option<u64>,
+    pub(crate) last_seq: u64,
+    pub(crate) last_trade_id: u64,
+    /// Trades the volatility guard's reference still holds, as
+    /// `(timestamp, price)`, oldest first.
+    pub(crate) reference: Vec<(u64, u64)>,
+    pub(crate) bids: Vec<LevelRecord>,
+    pub(crate) asks: Vec<LevelRecord>,
+    pub(crate) stops: Vec<OrderRecord>,
+}
+
+impl BookSnapshot {
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ConfigRecord {
+    matching: MatchingAlgorithm,
+    self_trade_prevention: SelfTradePrevention,
+    market_protection: ProtectionBand,
+    volatility_guard: Option<VolatilityGuard>,
+    instrument: Option<SpecRecord>,
+    market_data: MarketData,
+    checked: bool,
+    ladder: LadderKind,
+}
+
+impl ConfigRecord {
+    pub(crate) fn from_config(config: &BookConfig) -> Self {
+        ConfigRecord {
+            matching: config.matching,
+            self_trade_prevention: config.self_trade_prevention,
+            market_protection: config.market_protection,
+            volatility_guard: config.volatility_guard,
+            instrument: config.instrument.as_ref().map(SpecRecord::from_spec),
+            market_data: config.market_data,
+            checked: config.checked,
+            ladder: config.ladder,
+        }
+    }
+
+    pub(crate) fn into_config(self) -> NyquestroResult<BookConfig> {
+        if let LadderKind::Ticks { slots } = self.ladder {
+            LadderKind::ticks(slots).map_err(|e| NyquestroError::Snapshot(e.to_string()))?;
+        }
+        Ok(BookConfig {
+            matching: self.matching,
+            self_trade_prevention: self.self_trade_prevention,
+            market_protection: self.market_protection,
+            volatility_guard: self.volatility_guard,
+            instrument: self.instrument.map(SpecRecord::into_spec).transpose()?,
+            market_data: self.market_data,
+            checked: self.checked,
+            ladder: self.ladder,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct AuctionRecord {
+    pub(crate) kind: AuctionKind,
+    /// Last published uncross as `(price, volume)`.
+    pub(crate) indicative: Option<(u64, u64)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct LevelRecord {
+    price: u64,
+    /// Displayed quantity; must equal the sum over `orders`.
+    total: u64,
+    orders: Vec<OrderRecord>,
+}
+
+impl LevelRecord {
+    pub(crate) fn from_level(level: &PriceLevel) -> Self {
+        LevelRecord {
+            price: level.price().raw(),
+            total: level.total_quantity().value(),
+            orders: level.iter().map(OrderRecord::from_order).collect(),
+        }
+    }
+
+    /// Rebuild the level for `symbol`'s `side`. Checks every order can
+    /// rest there and that the stored total is what they display; ids are
+    /// the caller's to check across levels.
+    pub(crate) fn into_level(self, symbol: Symbol, side: Side) -> NyquestroResult<PriceLevel> {
+        let price = Px::from_raw(self.price)?;
+        let at = self.price;
+        let bad = |why: String| NyquestroError::Snapshot(format!("{symbol} level {at}: {why}"));
+        if self.orders.is_empty() {
+            return Err(bad("no orders".into()));
+        }
+        let mut level = PriceLevel::new(price);
+        for record in self.orders {
+            let order = record.into_order(symbol)?;
+            let id = order.id().value();
+            if order.side() != side {
+                return Err(bad(format!("order {id} is on the other side")));
+            }
+            if order.price() != price {
+                return Err(bad(format!("order {id} is priced at {}", order.price().raw())));
+            }
+            if !order.is_active() || order.is_stop() || order.displayed().is_zero() {
+                return Err(bad(format!("order {id} cannot rest")));
+            }
+            level.push_back(order)?;
+        }
+        if level.total_quantity().value() != self.total {
+            return Err(bad(format!(
+                "total {} but its orders show {}",
+                self.total,
+                level.total_quantity().value()
+            )));
+        }
+        Ok(level)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PegRecord {
+    reference: PegReference,
+    offset: i64,
+    cap: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum TimeInForceRecord {
+    Gtc,
+    Ioc,
+    Fok,
+    Day,
+    Gtd(u64),
+}
+
+/// One order, resting or waiting. Its symbol is the book's.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct OrderRecord {
+    id: u64,
+    owner: Option<u64>,
+    order_type: OrderType,
+    peg: Option<PegRecord>,
+    stop_price: Option<u64>,
+    side: Side,
+    price: u64,
+    quantity: u64,
+    remaining: u64,
+    peak: Option<u64>,
+    displayed: u64,
+    timestamp: u64,
+    time_in_force: TimeInForceRecord,
+    expires_at: Option<u64>,
+    post_only: Option<PostOnly>,
+    min_quantity: Option<u64>,
+    status: Status,
+}
+
+impl OrderRecord {
+    pub(crate) fn from_order(order: &Order) -> Self {
+        OrderRecord {
+            id: order.id().value(),
+            owner: order.owner().map(OwnerID::value),
+            order_type: order.order_type(),
+            peg: order.peg().map(|peg| PegRecord {
+                reference: peg.reference,
+                offset: peg.offset,
+                cap: peg.cap.map(Px::raw),
+            }),
+            stop_price: order.stop_price().map(Px::raw),
+            side: order.side(),
+            price: order.price().raw(),
+            quantity: order.quantity().value(),
+            remaining: order.remaining().value(),
+            peak: order.peak().map(Qty::value),
+            displayed: order.displayed().value(),
+            timestamp: order.timestamp().nanos(),
+            time_in_force: match order.time_in_force() {
+                TimeInForce::Gtc => TimeInForceRecord::Gtc,
+                TimeInForce::Ioc => TimeInForceRecord::Ioc,
+                TimeInForce::Fok => TimeInForceRecord::Fok,
+                TimeInForce::Day => TimeInForceRecord::Day,
+                TimeInForce::Gtd(at) => TimeInForceRecord::Gtd(at.nanos()),
+            },
+            expires_at: order.expires_at().map(Ts::nanos),
+            post_only: order.post_only(),
+            min_quantity: order.min_quantity().map(Qty::value),
+            status: order.status(),
+        }
+    }
+
+    /// Rebuild the order through the same constructors an order entering
+    /// the engine goes through, then put back the state it had reached.
+    pub(crate) fn into_order(self, symbol: Symbol) -> NyquestroResult<Order> {
+        let id = OrderID::new(self.id)?;
+        let bad = |why: &str| NyquestroError::Snapshot(format!("order {}: {why}", self.id));
+        let price = Px::from_raw(self.price)?;
+        let quantity = Qty::new(self.quantity);
+        let timestamp = Ts::from_nanos(self.timestamp);
+        let mut order = match (self.order_type, self.peg) {
+            (OrderType::Limit, None) => {
+                Order::new(id, symbol, self.side, price, quantity, timestamp)?
+            }
+            (OrderType::Market, None) => Order::market(id, symbol, self.side, quantity, timestamp)?,
+            (OrderType::Pegged, Some(peg)) => {
+                let peg = Peg {
+                    reference: peg.reference,
+                    offset: peg.offset,
+                    cap: peg.cap.map(Px::from_raw).transpose()?,
+                };
+                Order::pegged(id, symbol, self.side, peg, quantity, timestamp)?
+            }
+            _ => return Err(bad("peg does not match the order type")),
+        };
+        let tif = match self.time_in_force {
+            TimeInForceRecord::Gtc => TimeInForce::Gtc,
+            TimeInForceRecord::Ioc => TimeInForce::Ioc,
+            TimeInForceRecord::Fok => TimeInForce::Fok,
+            TimeInForceRecord::Day => TimeInForce::Day,
+            TimeInForceRecord::Gtd(at) => TimeInForce::Gtd(Ts::from_nanos(at)),
+        };
+        order = order.with_time_in_force(tif);
+        let expiry_agrees = match tif {
+            TimeInForce::Day => self.expires_at.is_some(),
+            _ => order.expires_at().map(Ts::nanos) == self.expires_at,
+        };
+        if !expiry_agrees {
+            return Err(bad("expiry does not match the time in force"));
+        }
+        if let Some(owner) = self.owner {
+            order = order.with_owner(OwnerID::new(owner)?);
+        }
+        if let Some(peak) = self.peak {
+            order = order.with_peak(Qty::new(peak))?;
+        }
+        if let Some(stop) = self.stop_price {
+            order = order.with_stop(Px::from_raw(stop)?);
+        }
+        if let Some(mode) = self.post_only {
+            order = order.with_post_only(mode);
+        }
+        if let Some(min) = self.min_quantity {
+            order = order.with_min_quantity(Qty::new(min))?;
+        }
+        order.restore(
+            price,
+            Qty::new(self.remaining),
+            Qty::new(self.displayed),
+            timestamp,
+            self.expires_at.map(Ts::from_nanos),
+            self.status,
+        )
+    }
+}
+
+/// A [`FeeSchedule`] as lists; symbols by name, accounts by id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FeeScheduleRecord {
+    default: Vec<FeeTier>,
+    by_symbol: Vec<(String, Vec<FeeTier>)>,
+    by_owner: Vec<(u64, Vec<FeeTier>)>,
+    by_owner_symbol: Vec<(u64, String, Vec<FeeTier>)>,
+}
+
+impl FeeScheduleRecord {
+    pub(crate) fn from_schedule(fees: &FeeSchedule) -> Self {
+        FeeScheduleRecord {
+            default: fees.default.clone(),
+            by_symbol: fees
+                .by_symbol
+                .iter()
+                .map(|(s, t)| (s.to_string(), t.clone()))
+                .collect(),
+            by_owner: fees.by_owner.iter().map(|(o, t)| (o.value(), t.clone())).collect(),
+            by_owner_symbol: fees
+                .by_owner_symbol
+                .iter()
+                .map(|((o, s), t)| (o.value(), s.to_string(), t.clone()))
+                .collect(),
+        }
+    }
+
+    /// Rebuild the schedule through its builder, which checks each table.
+    pub(crate) fn into_schedule(self) -> NyquestroResult<FeeSchedule> {
+        let mut fees = if self.default.is_empty() {
+            FeeSchedule::default()
+        } else {
+            FeeSchedule::new(self.default)?
+        };
+        for (symbol, tiers) in self.by_symbol {
+            fees = fees.with_symbol(symbol.parse()?, tiers)?;
+        }
+        for (owner, tiers) in self.by_owner {
+            fees = fees.with_owner(OwnerID::new(owner)?, tiers)?;
+        }
+        for (owner, symbol, tiers) in self.by_owner_symbol {
+            fees = fees.with_owner_symbol(OwnerID::new(owner)?, symbol.parse()?, tiers)?;
+        }
+        Ok(fees)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct FeeTotalsRecord {
+    pub(crate) owner: u64,
+    pub(crate) symbol: String,
+    pub(crate) totals: FeeTotals,
+}
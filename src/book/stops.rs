@@ -0,0 +1,173 @@
+//! `StopBook` — stop and stop-limit orders waiting for their trigger.
+//!
+//! Orders here are invisible: they are not on the price ladders, count
+//! toward no level size and never produce quotes. The owning
+//! [`crate::book::OrderBook`] asks for the next triggered stop after each
+//! operation and feeds it back through submission.
+//!
+//! Firing order is deterministic: buy stops lowest trigger first, sell
+//! stops highest trigger first (the order the market reached them in), and
+//! arrival order within a trigger price.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::order::Order;
+use crate::types::{OrderID, Px, Side};
+
+#[derive(Debug, Clone, Default)]
+pub struct StopBook {
+    buys: BTreeMap<(Px, u64), Order>,
+    sells: BTreeMap<(Reverse<Px>, u64), Order>,
+    /// Where each waiting stop is keyed, so cancel is O(log n).
+    keys: HashMap<OrderID, (Px, u64)>,
+    /// Arrival counter; breaks ties between stops at the same trigger.
+    seq: u64,
+}
+
+impl StopBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: OrderID) -> bool {
+        self.keys.contains_key(&id)
+    }
+
+    /// Borrow the waiting stop with `id`, if any.
+    pub fn get(&self, id: OrderID) -> Option<&Order> {
+        let &(stop, seq) = self.keys.get(&id)?;
+        self.buys
+            .get(&(stop, seq))
+            .or_else(|| self.sells.get(&(Reverse(stop), seq)))
+            .filter(|o| o.id() == id)
+    }
+
+    /// Park a stop. The order must carry a trigger price and an id not
+    /// already waiting here.
+    pub fn insert(&mut self, order: Order) -> NyquestroResult<()> {
+        let id = order.id();
+        let stop = order
+            .stop_price()
+            .ok_or_else(|| {
+                NyquestroError::InvariantViolation("stop book entry without a trigger".into())
+            })?;
+        if self.keys.contains_key(&id) {
+            return Err(NyquestroError::OrderAlreadyExists(id.value()));
+        }
+        let seq = self.seq;
+        self.seq += 1;
+        match order.side() {
+            Side::Buy => self.buys.insert((stop, seq), order),
+            Side::Sell => self.sells.insert((Reverse(stop), seq), order),
+        };
+        self.keys.insert(id, (stop, seq));
+        Ok(())
+    }
+
+    /// Take the waiting stop with `id` out of the book.
+    pub fn remove(&mut self, id: OrderID) -> Option<Order> {
+        let (stop, seq) = self.keys.remove(&id)?;
+        self.buys
+            .remove(&(stop, seq))
+            .or_else(|| self.sells.remove(&(Reverse(stop), seq)))
+    }
+
+    /// Take out every waiting stop `pred` selects, buys then sells, each in
+    /// firing order.
+    pub fn remove_where(&mut self, mut pred: impl FnMut(&Order) -> bool) -> Vec<Order> {
+        let mut removed: Vec<Order> = self.buys.extract_if(.., |_, o| pred(o)).map(|(_, o)| o).collect();
+        removed.extend(self.sells.extract_if(.., |_, o| pred(o)).map(|(_, o)| o));
+        for order in &removed {
+            self.keys.remove(&order.id());
+        }
+        removed
+    }
+
+    /// Take the next stop a last trade at `last` fires, buys before sells.
+    pub fn pop_triggered(&mut self, last: Px) -> Option<Order> {
+        let buy = self
+            .buys
+            .first_key_value()
+            .filter(|(_, o)| o.is_triggered_by(last))
+            .map(|(_, o)| o.id());
+        let sell = self
+            .sells
+            .first_key_value()
+            .filter(|(_, o)| o.is_triggered_by(last))
+            .map(|(_, o)| o.id());
+        self.remove(buy.or(sell)?)
+    }
+
+    /// Waiting stops, buys then sells, each in firing order.
+    pub fn iter(&self) -> impl Iterator<Item = &Order> {
+        self.buys.values().chain(self.sells.values())
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Qty, Symbol, Ts};
+
+    const SYM: Symbol = Symbol::from_const("TEST");
+
+    fn px(c: u64) -> Px {
+        Px::from_raw(c).unwrap()
+    }
+
+    fn stop(id: u64, side: Side, trigger: u64) -> Order {
+        Order::market(OrderID::new(id).unwrap(), SYM, side, Qty::new(1), Ts::from_nanos(id))
+            .unwrap()
+            .with_stop(px(trigger))
+    }
+
+    #[test]
+    fn fires_in_trigger_then_arrival_order() {
+        let mut book = StopBook::new();
+        book.insert(stop(1, Side::Buy, 105)).unwrap();
+        book.insert(stop(2, Side::Buy, 103)).unwrap();
+        book.insert(stop(3, Side::Buy, 103)).unwrap();
+        book.insert(stop(4, Side::Sell, 95)).unwrap();
+        book.insert(stop(5, Side::Sell, 97)).unwrap();
+
+        assert!(book.pop_triggered(px(100)).is_none());
+        let fired: Vec<_> = std::iter::from_fn(|| book.pop_triggered(px(104)))
+            .map(|o| o.id().value())
+            .collect();
+        assert_eq!(fired, vec![2, 3]);
+        let fired: Vec<_> = std::iter::from_fn(|| book.pop_triggered(px(90)))
+            .map(|o| o.id().value())
+            .collect();
+        assert_eq!(fired, vec![5, 4]);
+        assert_eq!(book.len(), 1);
+    }
+
+    #[test]
+    fn remove_and_duplicates() {
+        let mut book = StopBook::new();
+        book.insert(stop(1, Side::Sell, 95)).unwrap();
+        assert!(matches!(
+            book.insert(stop(1, Side::Buy, 99)),
+            Err(NyquestroError::OrderAlreadyExists(1))
+        ));
+        assert_eq!(book.get(OrderID::new(1).unwrap()).unwrap().side(), Side::Sell);
+        assert!(book.remove(OrderID::new(1).unwrap()).is_some());
+        assert!(book.is_empty());
+        assert!(book.remove(OrderID::new(1).unwrap()).is_none());
+    }
+}
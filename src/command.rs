@@ -0,0 +1,129 @@
+//! `Command` — one inbound instruction to the engine.
+//!
+//! Every way into the books is a command: the simulator's orders, the
+//! dashboard's cancels, a live feed's levels. The
+//! [`Sequencer`](crate::sequencer::Sequencer) stamps each one with engine
+//! time and a sequence number, then it is applied to a [`Market`]
+//! directly or routed to a [`BookShard`](crate::concurrent::BookShard).
+//! Commands are plain `Copy` data, so a log of stamped ones replays
+//! exactly.
+
+use crate::book::{BookConfig, EventSink, Market, MassCancel};
+use crate::errors::NyquestroResult;
+use crate::order::Order;
+use crate::types::{AuctionKind, OrderID, Px, Qty, Symbol, Ts};
+
+/// One inbound instruction, mirroring the [`Market`] operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Pre-register `symbol` with `config`; see [`Market::register_with`].
+    Register { symbol: Symbol, config: BookConfig },
+    /// Submit an order, registering its symbol on first use.
+    Submit(Order),
+    Cancel { symbol: Symbol, id: OrderID, ts: Ts },
+    Replace {
+        symbol: Symbol,
+        id: OrderID,
+        px: Px,
+        qty: Qty,
+        ts: Ts,
+    },
+    Reprice { symbol: Symbol, ts: Ts },
+    StartAuction {
+        symbol: Symbol,
+        kind: AuctionKind,
+        ts: Ts,
+    },
+    Uncross { symbol: Symbol, ts: Ts },
+    Halt { symbol: Symbol, ts: Ts },
+    Resume { symbol: Symbol, ts: Ts },
+    Close { symbol: Symbol, ts: Ts },
+    /// Expiry sweep over every book; sent to every shard.
+    Expire { ts: Ts },
+    /// Sent to the filter's symbol's shard, or to every shard without one.
+    MassCancel { filter: MassCancel, ts: Ts },
+}
+
+impl Command {
+    /// The symbol the command is routed by; `None` for the ones every
+    /// shard applies.
+    pub fn symbol(&self) -> Option<Symbol> {
+        match *self {
+            Command::Register { symbol, .. }
+            | Command::Cancel { symbol, .. }
+            | Command::Replace { symbol, .. }
+            | Command::Reprice { symbol, .. }
+            | Command::StartAuction { symbol, .. }
+            | Command::Uncross { symbol, .. }
+            | Command::Halt { symbol, .. }
+            | Command::Resume { symbol, .. }
+            | Command::Close { symbol, .. } => Some(symbol),
+            Command::Submit(order) => Some(order.symbol()),
+            Command::Expire { .. } => None,
+            Command::MassCancel { filter, .. } => filter.symbol,
+        }
+    }
+
+    /// The same command at `at`: every time it carries, including a
+    /// submitted order's, becomes `at`. See [`crate::sequencer`].
+    pub fn with_ts(self, at: Ts) -> Command {
+        match self {
+            Command::Register { .. } => self,
+            Command::Submit(order) => Command::Submit(order.with_timestamp(at)),
+            Command::Cancel { symbol, id, .. } => Command::Cancel { symbol, id, ts: at },
+            Command::Replace {
+                symbol, id, px, qty, ..
+            } => Command::Replace {
+                symbol,
+                id,
+                px,
+                qty,
+                ts: at,
+            },
+            Command::Reprice { symbol, .. } => Command::Reprice { symbol, ts: at },
+            Command::StartAuction { symbol, kind, .. } => {
+                Command::StartAuction { symbol, kind, ts: at }
+            }
+            Command::Uncross { symbol, .. } => Command::Uncross { symbol, ts: at },
+            Command::Halt { symbol, .. } => Command::Halt { symbol, ts: at },
+            Command::Resume { symbol, .. } => Command::Resume { symbol, ts: at },
+            Command::Close { symbol, .. } => Command::Close { symbol, ts: at },
+            Command::Expire { .. } => Command::Expire { ts: at },
+            Command::MassCancel { filter, .. } => Command::MassCancel { filter, ts: at },
+        }
+    }
+
+    /// Apply the command to `market`, handing each event to `out` as it is
+    /// stamped.
+    pub fn apply<S: EventSink + ?Sized>(
+        self,
+        market: &mut Market,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        match self {
+            Command::Register { symbol, config } => {
+                market.register_with(symbol, config);
+                Ok(())
+            }
+            Command::Submit(order) => market.submit_limit_into(order, out),
+            Command::Cancel { symbol, id, ts } => market.cancel_into(symbol, id, ts, out),
+            Command::Replace {
+                symbol,
+                id,
+                px,
+                qty,
+                ts,
+            } => market.replace_into(symbol, id, px, qty, ts, out),
+            Command::Reprice { symbol, ts } => market.reprice_into(symbol, ts, out),
+            Command::StartAuction { symbol, kind, ts } => {
+                market.start_auction_into(symbol, kind, ts, out)
+            }
+            Command::Uncross { symbol, ts } => market.uncross_into(symbol, ts, out),
+            Command::Halt { symbol, ts } => market.halt_into(symbol, ts, out),
+            Command::Resume { symbol, ts } => market.resume_into(symbol, ts, out),
+            Command::Close { symbol, ts } => market.close_into(symbol, ts, out),
+            Command::Expire { ts } => market.expire_into(ts, out),
+            Command::MassCancel { filter, ts } => market.mass_cancel_into(&filter, ts, out),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Sharded, multi-threaded engine runtime.
+//!
+//! - [`BookShard`] — a [`crate::book::Market`] for one slice of the symbol
+//!   space, applying [`Command`](crate::command::Command)s one at a time.
+//! - [`Runtime`] — one shard per worker thread, fed through bounded
+//!   ingress queues and publishing [`Output`]s on bounded outbound ones.
+//!
+//! Every book has exactly one writer, the thread that owns its shard, so
+//! the matching engine runs unchanged and unlocked inside it; threads only
+//! ever exchange messages. See `context/systems/concurrent.md` for why
+//! this was chosen over a book shared between threads.
+
+pub mod runtime;
+pub mod shard;
+
+pub use runtime::{Output, Runtime, RuntimeConfig, shard_of};
+pub use shard::{BookShard, EngineEvent};
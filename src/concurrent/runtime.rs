@@ -0,0 +1,299 @@
+//! `Runtime` — symbols sharded across worker threads.
+//!
+//! Each worker thread owns one [`BookShard`] and drains its own bounded
+//! ingress queue; nothing else ever touches its books, so there is no lock
+//! around them. Commands arrive already [`Stamped`] by a
+//! [`Sequencer`](crate::sequencer::Sequencer) in front of the runtime. A
+//! command goes to the shard its symbol is routed to, and each shard
+//! publishes on its own bounded outbound queue: the events a command
+//! produced, in `seq` order, then an [`Output::Done`] with the command's
+//! stamp and result. A command without a symbol goes to every shard and
+//! is answered once by each.
+//!
+//! Both directions are `crossbeam-channel` bounded queues, array-backed
+//! and lock-free on their fast path. A full ingress queue blocks
+//! [`Runtime::send`]; a full outbound queue blocks its shard, which then
+//! stops draining its ingress queue. A consumer that falls behind therefore
+//! holds the producer back instead of letting the queues grow.
+//!
+//! Order is kept per shard and nowhere else. A symbol's outputs on its
+//! shard's queue are exactly what a lone [`BookShard`] would produce for
+//! that symbol's commands, whatever the shard count; outputs on different
+//! queues interleave however the threads happened to run.
+
+use std::thread::{self, JoinHandle};
+
+use core_affinity::CoreId;
+use crossbeam_channel::{Receiver, Sender, bounded};
+
+use crate::book::{EventSink, FeeSchedule, Market};
+use crate::concurrent::shard::{BookShard, EngineEvent};
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboEvent, OrderEvent, QuoteEvent, StateEvent,
+};
+use crate::sequencer::{Stamp, Stamped};
+use crate::types::Symbol;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeConfig {
+    /// Worker threads, one shard each.
+    pub shards: usize,
+    /// Commands a shard's ingress queue holds before `send` blocks.
+    pub ingress_capacity: usize,
+    /// Outputs a shard's outbound queue holds before the shard blocks.
+    pub outbound_capacity: usize,
+    /// Pin shard `i` to core `i` modulo the core count. Best effort: where
+    /// the platform will not say or will not pin, shards run unpinned.
+    pub pin_cores: bool,
+    /// The schedule every shard's market charges its fills against.
+    pub fees: FeeSchedule,
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        RuntimeConfig {
+            shards: 4,
+            ingress_capacity: 4_096,
+            outbound_capacity: 65_536,
+            pin_cores: false,
+            fees: FeeSchedule::default(),
+        }
+    }
+}
+
+/// What a shard publishes.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Output {
+    Event(EngineEvent),
+    /// The command with this stamp has been applied; every event it
+    /// produced came before this on the same queue.
+    Done {
+        stamp: Stamp,
+        result: NyquestroResult<()>,
+    },
+}
+
+/// The shard `symbol` is routed to out of `shards` (at least one). Fixed
+/// for a given count: a 64-bit finaliser over the packed symbol, so
+/// symbols that share a prefix still spread.
+pub fn shard_of(symbol: Symbol, shards: usize) -> usize {
+    let mut x = symbol.as_u64();
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+    (x % shards.max(1) as u64) as usize
+}
+
+struct Worker {
+    ingress: Sender<Stamped>,
+    outbound: Receiver<Output>,
+    thread: JoinHandle<Market>,
+}
+
+/// The producer's handle on a set of running shards.
+pub struct Runtime {
+    workers: Vec<Worker>,
+}
+
+impl Runtime {
+    /// Start `config.shards` worker threads, each with an empty market.
+    pub fn spawn(config: RuntimeConfig) -> NyquestroResult<Runtime> {
+        if config.shards == 0 {
+            return Err(NyquestroError::InvalidRuntimeConfig("shards must be non-zero"));
+        }
+        if config.ingress_capacity == 0 || config.outbound_capacity == 0 {
+            return Err(NyquestroError::InvalidRuntimeConfig(
+                "queue capacities must be non-zero",
+            ));
+        }
+        let cores = if config.pin_cores {
+            core_affinity::get_core_ids().unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        let mut workers = Vec::with_capacity(config.shards);
+        for shard in 0..config.shards {
+            let (ingress, inbox) = bounded(config.ingress_capacity);
+            let (publish, outbound) = bounded(config.outbound_capacity);
+            let core = (!cores.is_empty()).then(|| cores[shard % cores.len()]);
+            let mut market = Market::new();
+            market.set_fee_schedule(config.fees.clone());
+            let thread = thread::Builder::new()
+                .name(format!("nyquestro-shard-{shard}"))
+                .spawn(move || run(BookShard::new(market), inbox, publish, core))
+                .map_err(|_| NyquestroError::ShardDown {
+                    shard,
+                    reason: "its thread could not be started",
+                })?;
+            workers.push(Worker {
+                ingress,
+                outbound,
+                thread,
+            });
+        }
+        Ok(Runtime { workers })
+    }
+
+    pub fn shards(&self) -> usize {
+        self.workers.len()
+    }
+
+    /// The shard `symbol`'s commands go to.
+    pub fn shard_for(&self, symbol: Symbol) -> usize {
+        shard_of(symbol, self.workers.len())
+    }
+
+    /// Queue a stamped command, blocking while the shard's ingress queue
+    /// is full. The shards apply commands in the order they are sent, so
+    /// send them in stamp order: straight from the sequencer, or from a
+    /// log of its output to replay one.
+    pub fn send(&mut self, stamped: Stamped) -> NyquestroResult<()> {
+        match stamped.command.symbol() {
+            Some(symbol) => self.deliver(self.shard_for(symbol), stamped),
+            None => {
+                for shard in 0..self.workers.len() {
+                    self.deliver(shard, stamped)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn deliver(&self, shard: usize, stamped: Stamped) -> NyquestroResult<()> {
+        self.workers[shard]
+            .ingress
+            .send(stamped)
+            .map_err(|_| NyquestroError::ShardDown {
+                shard,
+                reason: "its thread has exited",
+            })
+    }
+
+    /// `shard`'s outbound queue, or `None` past the last shard. The
+    /// receiver can be cloned and moved to a consumer thread; it
+    /// disconnects once the shard has shut down and been drained.
+    pub fn outbound(&self, shard: usize) -> Option<Receiver<Output>> {
+        self.workers.get(shard).map(|w| w.outbound.clone())
+    }
+
+    /// Stop taking commands, let every shard finish what it has queued and
+    /// hand back the markets, in shard order. The outbound queues must
+    /// keep being drained until this returns: a shard blocked on a full
+    /// one never gets to finish.
+    pub fn shutdown(self) -> NyquestroResult<Vec<Market>> {
+        let threads: Vec<_> = self.workers.into_iter().map(|w| w.thread).collect();
+        threads
+            .into_iter()
+            .enumerate()
+            .map(|(shard, thread)| {
+                thread.join().map_err(|_| NyquestroError::ShardDown {
+                    shard,
+                    reason: "its thread panicked",
+                })
+            })
+            .collect()
+    }
+}
+
+/// A worker thread: apply commands until the runtime lets go of the
+/// ingress queue, then give the market back.
+fn run(
+    mut shard: BookShard,
+    inbox: Receiver<Stamped>,
+    outbound: Sender<Output>,
+    core: Option<CoreId>,
+) -> Market {
+    if let Some(core) = core {
+        core_affinity::set_for_current(core);
+    }
+    let mut publish = Publish(&outbound);
+    for Stamped { stamp, command } in inbox.iter() {
+        let result = shard.apply(command, &mut publish);
+        publish.send(Output::Done { stamp, result });
+    }
+    shard.into_market()
+}
+
+/// Puts each event on the shard's outbound queue as it is stamped.
+struct Publish<'a>(&'a Sender<Output>);
+
+impl Publish<'_> {
+    /// Blocks while the queue is full. Once every receiver has been
+    /// dropped there is nobody left to read, and outputs are discarded.
+    fn send(&mut self, output: Output) {
+        let _ = self.0.send(output);
+    }
+}
+
+impl EventSink for Publish<'_> {
+    fn fill(&mut self, event: FillEvent) {
+        self.send(Output::Event(EngineEvent::Fill(event)));
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.send(Output::Event(EngineEvent::Quote(event)));
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.send(Output::Event(EngineEvent::Level(event)));
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.send(Output::Event(EngineEvent::Order(event)));
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.send(Output::Event(EngineEvent::Lifecycle(event)));
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.send(Output::Event(EngineEvent::Auction(event)));
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.send(Output::Event(EngineEvent::State(event)));
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routing_is_stable_and_spreads_symbols_sharing_a_prefix() {
+        let symbols: Vec<Symbol> =
+            (0..64).map(|n| format!("SYM{n}").parse().unwrap()).collect();
+        let mut used = [0usize; 4];
+        for &symbol in &symbols {
+            let shard = shard_of(symbol, 4);
+            assert_eq!(shard_of(symbol, 4), shard);
+            assert_eq!(shard_of(symbol, 1), 0);
+            used[shard] += 1;
+        }
+        assert!(used.iter().all(|&n| n >= 8), "{used:?}");
+    }
+
+    #[test]
+    fn a_config_without_shards_or_queue_room_is_rejected() {
+        let no_shards = RuntimeConfig {
+            shards: 0,
+            ..RuntimeConfig::default()
+        };
+        assert!(matches!(
+            Runtime::spawn(no_shards),
+            Err(NyquestroError::InvalidRuntimeConfig(_))
+        ));
+        let no_room = RuntimeConfig {
+            ingress_capacity: 0,
+            ..RuntimeConfig::default()
+        };
+        assert!(matches!(
+            Runtime::spawn(no_room),
+            Err(NyquestroError::InvalidRuntimeConfig(_))
+        ));
+    }
+}
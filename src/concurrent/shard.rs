@@ -0,0 +1,132 @@
+//! `BookShard` — the single-writer unit of the sharded runtime.
+//!
+//! A shard owns a [`Market`] holding the books for the symbols routed to
+//! it and applies [`Command`]s to it one at a time. It knows nothing about
+//! threads: the [`Runtime`](crate::concurrent::Runtime) runs one per worker
+//! thread, and a test or a replay tool can drive one directly and get the
+//! same output.
+//!
+//! A symbol's book sees its commands in the order they were applied and
+//! numbers its own events, and fees are kept per account and symbol, so a
+//! symbol's output depends only on its own commands — never on which other
+//! symbols happen to share the shard.
+
+use crate::book::{EventSink, Market};
+use crate::command::Command;
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboEvent, OrderEvent, QuoteEvent, Sequenced, StateEvent,
+};
+use crate::types::Symbol;
+
+/// Any one event a book emits, so all seven kinds can share a queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineEvent {
+    Fill(FillEvent),
+    Quote(QuoteEvent),
+    Level(LevelEvent),
+    Order(MboEvent),
+    Lifecycle(OrderEvent),
+    Auction(AuctionEvent),
+    State(StateEvent),
+}
+
+impl EngineEvent {
+    pub fn symbol(&self) -> Symbol {
+        match self {
+            EngineEvent::Fill(e) => e.symbol,
+            EngineEvent::Quote(e) => e.symbol,
+            EngineEvent::Level(e) => e.symbol,
+            EngineEvent::Order(e) => e.symbol,
+            EngineEvent::Lifecycle(e) => e.symbol(),
+            EngineEvent::Auction(e) => e.symbol(),
+            EngineEvent::State(e) => e.symbol,
+        }
+    }
+
+    /// Hand the event to the matching [`EventSink`] method, so a consumer
+    /// of the runtime's queues can reuse a sink written for a book.
+    pub fn deliver<S: EventSink + ?Sized>(self, out: &mut S) {
+        match self {
+            EngineEvent::Fill(e) => out.fill(e),
+            EngineEvent::Quote(e) => out.quote(e),
+            EngineEvent::Level(e) => out.level(e),
+            EngineEvent::Order(e) => out.order(e),
+            EngineEvent::Lifecycle(e) => out.lifecycle(e),
+            EngineEvent::Auction(e) => out.auction(e),
+            EngineEvent::State(e) => out.state(e),
+        }
+    }
+}
+
+impl Sequenced for EngineEvent {
+    fn seq(&self) -> u64 {
+        match self {
+            EngineEvent::Fill(e) => e.seq(),
+            EngineEvent::Quote(e) => e.seq(),
+            EngineEvent::Level(e) => e.seq(),
+            EngineEvent::Order(e) => e.seq(),
+            EngineEvent::Lifecycle(e) => e.seq(),
+            EngineEvent::Auction(e) => e.seq(),
+            EngineEvent::State(e) => e.seq(),
+        }
+    }
+
+    fn with_seq(self, seq: u64) -> Self {
+        match self {
+            EngineEvent::Fill(e) => EngineEvent::Fill(e.with_seq(seq)),
+            EngineEvent::Quote(e) => EngineEvent::Quote(e.with_seq(seq)),
+            EngineEvent::Level(e) => EngineEvent::Level(e.with_seq(seq)),
+            EngineEvent::Order(e) => EngineEvent::Order(e.with_seq(seq)),
+            EngineEvent::Lifecycle(e) => EngineEvent::Lifecycle(e.with_seq(seq)),
+            EngineEvent::Auction(e) => EngineEvent::Auction(e.with_seq(seq)),
+            EngineEvent::State(e) => EngineEvent::State(e.with_seq(seq)),
+        }
+    }
+}
+
+/// The books for one slice of the symbol space, and the only thing that
+/// ever writes to them.
+#[derive(Debug, Default)]
+pub struct BookShard {
+    market: Market,
+    /// The first fatal error, after which the books are not trusted.
+    failed: Option<NyquestroError>,
+}
+
+impl BookShard {
+    pub fn new(market: Market) -> Self {
+        BookShard {
+            market,
+            failed: None,
+        }
+    }
+
+    pub fn market(&self) -> &Market {
+        &self.market
+    }
+
+    pub fn into_market(self) -> Market {
+        self.market
+    }
+
+    /// Apply one command, handing each event to `out` as it is stamped.
+    /// After a fatal error every later command is refused with that same
+    /// error and the books are left as they were.
+    pub fn apply<S: EventSink + ?Sized>(
+        &mut self,
+        command: Command,
+        out: &mut S,
+    ) -> NyquestroResult<()> {
+        if let Some(e) = &self.failed {
+            return Err(e.clone());
+        }
+        let result = command.apply(&mut self.market, out);
+        if let Err(e) = &result
+            && e.is_fatal()
+        {
+            self.failed = Some(e.clone());
+        }
+        result
+    }
+}
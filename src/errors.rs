@@ -26,14 +26,17 @@ pub enum NyquestroError {
     #[error("OrderID cannot be zero")]
     InvalidOrderId,
 
+    #[error("OwnerID cannot be zero")]
+    InvalidOwnerId,
+
     #[error("Symbol must be 1..=8 bytes")]
     InvalidSymbol,
 
     #[error("Symbol mismatch: order on {actual} routed to book for {expected}")]
     SymbolMismatch { expected: u64, actual: u64 },
 
-    #[error("Price in cents must be non-zero, got {cents}")]
-    InvalidPrice { cents: u64 },
+    #[error("Price must be non-zero, got {raw}")]
+    InvalidPrice { raw: u64 },
 
     #[error("Price from float must be finite and positive, got {value}")]
     InvalidPriceFloat { value: f64 },
@@ -41,6 +44,12 @@ pub enum NyquestroError {
     #[error("Quantity must be non-zero")]
     InvalidQuantity,
 
+    #[error("Decimal scale must be 0..=18 places, got {decimals}")]
+    InvalidScale { decimals: u8 },
+
+    #[error("Cannot read {input:?} as a decimal with at most {decimals} places")]
+    InvalidDecimal { input: String, decimals: u8 },
+
     #[error("Quantity arithmetic would overflow")]
     QuantityOverflow,
 
@@ -48,8 +57,8 @@ pub enum NyquestroError {
     #[error("Fill {fill} exceeds remaining {remaining} on order {order_id}")]
     OverFill {
         order_id: u64,
-        fill: u32,
-        remaining: u32,
+        fill: u64,
+        remaining: u64,
     },
 
     #[error("Cannot transition order {order_id} from {from} to {to}")]
@@ -72,18 +81,49 @@ pub enum NyquestroError {
     #[error("Order {0} already exists in book")]
     OrderAlreadyExists(u64),
 
-    #[error("Price level {price_cents} not present in book")]
-    PriceLevelMissing { price_cents: u64 },
+    #[error("Price level {price} not present in book")]
+    PriceLevelMissing { price: u64 },
 
-    #[error("PriceLevel mismatch: expected {expected_cents}, got {actual_cents}")]
+    #[error("PriceLevel mismatch: expected {expected}, got {actual}")]
     PriceLevelMismatch {
-        expected_cents: u64,
-        actual_cents: u64,
+        expected: u64,
+        actual: u64,
     },
 
+    #[error("Book for symbol {symbol} cannot move from {from} to {to}")]
+    InvalidPhaseTransition {
+        symbol: u64,
+        from: &'static str,
+        to: &'static str,
+    },
+
+    #[error("Tick ladder must have 1..={max} slots, got {slots}")]
+    InvalidLadder { slots: u32, max: u32 },
+
+    // ── reference data (recoverable) ───────────────────────────────────────
+    #[error("Invalid instrument spec: {0}")]
+    InvalidInstrument(&'static str),
+
+    #[error("Could not read reference data: {0}")]
+    ReferenceData(String),
+
+    #[error("Invalid fee schedule: {0}")]
+    InvalidFeeSchedule(&'static str),
+
+    // ── snapshots (recoverable) ────────────────────────────────────────────
+    #[error("Invalid snapshot: {0}")]
+    Snapshot(String),
+
+    // ── sharded runtime ────────────────────────────────────────────────────
+    #[error("Invalid runtime config: {0}")]
+    InvalidRuntimeConfig(&'static str),
+
+    #[error("Shard {shard} is not running: {reason}")]
+    ShardDown { shard: usize, reason: &'static str },
+
     // ── invariant breakage (fatal) ─────────────────────────────────────────
     #[error("Internal invariant violated: {0}")]
-    InvariantViolation(&'static str),
+    InvariantViolation(String),
 }
 
 impl NyquestroError {
@@ -94,11 +134,14 @@ impl NyquestroError {
         match self {
             // Caller-facing input validation + lifecycle + matching errors.
             InvalidOrderId
+            | InvalidOwnerId
             | InvalidSymbol
             | SymbolMismatch { .. }
             | InvalidPrice { .. }
             | InvalidPriceFloat { .. }
             | InvalidQuantity
+            | InvalidScale { .. }
+            | InvalidDecimal { .. }
             | QuantityOverflow
             | OverFill { .. }
             | InvalidStatusTransition { .. }
@@ -107,10 +150,18 @@ impl NyquestroError {
             | OrderNotFound(_)
             | OrderAlreadyExists(_)
             | PriceLevelMissing { .. }
-            | PriceLevelMismatch { .. } => ErrorSeverity::Recoverable,
+            | PriceLevelMismatch { .. }
+            | InvalidPhaseTransition { .. }
+            | InvalidLadder { .. }
+            | InvalidInstrument(_)
+            | ReferenceData(_)
+            | InvalidFeeSchedule(_)
+            | Snapshot(_)
+            | InvalidRuntimeConfig(_) => ErrorSeverity::Recoverable,
 
-            // Bug in the engine itself.
-            InvariantViolation(_) => ErrorSeverity::Fatal,
+            // Bug in the engine itself, or a shard that can no longer run
+            // the symbols routed to it.
+            InvariantViolation(_) | ShardDown { .. } => ErrorSeverity::Fatal,
         }
     }
 
@@ -137,14 +188,20 @@ mod tests {
     fn recoverable_variants_classify_recoverable() {
         let cases = [
             NyquestroError::InvalidOrderId,
+            NyquestroError::InvalidOwnerId,
             NyquestroError::InvalidSymbol,
             NyquestroError::SymbolMismatch {
                 expected: 1,
                 actual: 2,
             },
-            NyquestroError::InvalidPrice { cents: 0 },
+            NyquestroError::InvalidPrice { raw: 0 },
             NyquestroError::InvalidPriceFloat { value: -1.0 },
             NyquestroError::InvalidQuantity,
+            NyquestroError::InvalidScale { decimals: 19 },
+            NyquestroError::InvalidDecimal {
+                input: "1.005".into(),
+                decimals: 2,
+            },
             NyquestroError::QuantityOverflow,
             NyquestroError::OverFill {
                 order_id: 1,
@@ -160,11 +217,25 @@ mod tests {
             NyquestroError::SelfMatch(1),
             NyquestroError::OrderNotFound(1),
             NyquestroError::OrderAlreadyExists(1),
-            NyquestroError::PriceLevelMissing { price_cents: 100 },
+            NyquestroError::PriceLevelMissing { price: 100 },
             NyquestroError::PriceLevelMismatch {
-                expected_cents: 100,
-                actual_cents: 101,
+                expected: 100,
+                actual: 101,
+            },
+            NyquestroError::InvalidPhaseTransition {
+                symbol: 1,
+                from: "CONTINUOUS",
+                to: "CONTINUOUS",
+            },
+            NyquestroError::InvalidLadder {
+                slots: 0,
+                max: 65_536,
             },
+            NyquestroError::InvalidInstrument("tick size must be non-zero"),
+            NyquestroError::ReferenceData("missing field".into()),
+            NyquestroError::InvalidFeeSchedule("no tiers"),
+            NyquestroError::Snapshot("unsupported version 9".into()),
+            NyquestroError::InvalidRuntimeConfig("shards must be non-zero"),
         ];
         for case in cases {
             assert!(case.is_recoverable(), "{case:?} should be recoverable");
@@ -174,11 +245,21 @@ mod tests {
 
     #[test]
     fn invariant_violation_is_fatal() {
-        let e = NyquestroError::InvariantViolation("test");
+        let e = NyquestroError::InvariantViolation("test".into());
         assert!(e.is_fatal());
         assert_eq!(e.severity(), ErrorSeverity::Fatal);
     }
 
+    #[test]
+    fn a_shard_that_is_down_is_fatal() {
+        let e = NyquestroError::ShardDown {
+            shard: 2,
+            reason: "its thread panicked",
+        };
+        assert!(e.is_fatal());
+        assert_eq!(e.to_string(), "Shard 2 is not running: its thread panicked");
+    }
+
     #[test]
     fn errors_format_human_readably() {
         let e = NyquestroError::OverFill {
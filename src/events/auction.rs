@@ -0,0 +1,158 @@
+//! `AuctionEvent` — call auction phase changes and indicative prices.
+
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::Sequenced;
+use crate::types::{AuctionKind, Px, Qty, Symbol, Ts};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuctionEvent {
+    /// The book left continuous trading and started collecting orders
+    /// without matching them.
+    Started {
+        symbol: Symbol,
+        kind: AuctionKind,
+        timestamp: Ts,
+        seq: u64,
+    },
+    /// The price and volume the auction would uncross at right now
+    /// changed. `price` is `None` (and `volume` zero) when nothing would
+    /// match.
+    Indicative {
+        symbol: Symbol,
+        price: Option<Px>,
+        volume: Qty,
+        timestamp: Ts,
+        seq: u64,
+    },
+    /// The auction uncrossed — every auction fill printed at `price` — and
+    /// the book went back to continuous trading. `price` is `None` when
+    /// nothing matched.
+    Uncrossed {
+        symbol: Symbol,
+        kind: AuctionKind,
+        price: Option<Px>,
+        volume: Qty,
+        timestamp: Ts,
+        seq: u64,
+    },
+}
+
+impl AuctionEvent {
+    pub fn started(symbol: Symbol, kind: AuctionKind, timestamp: Ts) -> Self {
+        AuctionEvent::Started {
+            symbol,
+            kind,
+            timestamp,
+            seq: 0,
+        }
+    }
+
+    /// Rejects a price without volume or volume without a price.
+    pub fn indicative(
+        symbol: Symbol,
+        price: Option<Px>,
+        volume: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        check_clearing(price, volume)?;
+        Ok(AuctionEvent::Indicative {
+            symbol,
+            price,
+            volume,
+            timestamp,
+            seq: 0,
+        })
+    }
+
+    /// Same validation as [`indicative`](Self::indicative).
+    pub fn uncrossed(
+        symbol: Symbol,
+        kind: AuctionKind,
+        price: Option<Px>,
+        volume: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        check_clearing(price, volume)?;
+        Ok(AuctionEvent::Uncrossed {
+            symbol,
+            kind,
+            price,
+            volume,
+            timestamp,
+            seq: 0,
+        })
+    }
+
+    pub fn symbol(&self) -> Symbol {
+        match self {
+            AuctionEvent::Started { symbol, .. }
+            | AuctionEvent::Indicative { symbol, .. }
+            | AuctionEvent::Uncrossed { symbol, .. } => *symbol,
+        }
+    }
+
+    pub fn timestamp(&self) -> Ts {
+        match self {
+            AuctionEvent::Started { timestamp, .. }
+            | AuctionEvent::Indicative { timestamp, .. }
+            | AuctionEvent::Uncrossed { timestamp, .. } => *timestamp,
+        }
+    }
+}
+
+impl Sequenced for AuctionEvent {
+    fn seq(&self) -> u64 {
+        match self {
+            AuctionEvent::Started { seq, .. }
+            | AuctionEvent::Indicative { seq, .. }
+            | AuctionEvent::Uncrossed { seq, .. } => *seq,
+        }
+    }
+
+    fn with_seq(mut self, next: u64) -> Self {
+        match &mut self {
+            AuctionEvent::Started { seq, .. }
+            | AuctionEvent::Indicative { seq, .. }
+            | AuctionEvent::Uncrossed { seq, .. } => *seq = next,
+        }
+        self
+    }
+}
+
+fn check_clearing(price: Option<Px>, volume: Qty) -> NyquestroResult<()> {
+    if price.is_some() == volume.is_zero() {
+        return Err(NyquestroError::InvalidQuantity);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYM: Symbol = Symbol::from_const("TEST");
+
+    #[test]
+    fn price_and_volume_come_together() {
+        let px = Px::from_raw(100).unwrap();
+        let ts = Ts::from_nanos(1);
+        assert!(AuctionEvent::indicative(SYM, Some(px), Qty::new(5), ts).is_ok());
+        assert!(AuctionEvent::indicative(SYM, None, Qty::ZERO, ts).is_ok());
+        assert!(matches!(
+            AuctionEvent::indicative(SYM, Some(px), Qty::ZERO, ts),
+            Err(NyquestroError::InvalidQuantity)
+        ));
+        assert!(matches!(
+            AuctionEvent::uncrossed(SYM, AuctionKind::Opening, None, Qty::new(5), ts),
+            Err(NyquestroError::InvalidQuantity)
+        ));
+    }
+
+    #[test]
+    fn accessors_cover_every_variant() {
+        let ts = Ts::from_nanos(7);
+        let e = AuctionEvent::started(SYM, AuctionKind::Closing, ts);
+        assert_eq!(e.symbol(), SYM);
+        assert_eq!(e.timestamp(), ts);
+    }
+}
@@ -0,0 +1,132 @@
+//! Full-depth market data: `LevelEvent` (market-by-price) and `MboEvent`
+//! (market-by-order).
+//!
+//! Both are off unless the book's [`crate::book::MarketData`] asks for
+//! them. Applied in `seq` order on top of
+//! [`crate::book::OrderBook::depth_snapshot`] — or an empty book — they
+//! reproduce every displayed level and every resting order's place in its
+//! queue exactly:
+//!
+//! - A `LevelEvent` carries the ladder index it applies at, counted from
+//!   the best price (0) on its side. `New` inserts at that index, pushing
+//!   worse levels down; `Delete` removes the level there; `Change` sets
+//!   its displayed quantity.
+//! - `MboEvent::Add` appends an order to the back of its level's queue.
+//!   `Modify` changes what an order shows without moving it; an order
+//!   that loses its place — a refreshed iceberg peak, a repriced peg — is
+//!   reported as `Delete` followed by `Add`.
+//!
+//! Only displayed quantity is published; an iceberg's reserve never is.
+
+use crate::events::quote::QuoteSide;
+use crate::events::Sequenced;
+use crate::types::{OrderID, Px, Qty, Side, Symbol, Ts};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LevelAction {
+    New,
+    Change,
+    Delete,
+}
+
+/// A price level appeared, changed size or went away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LevelEvent {
+    pub symbol: Symbol,
+    pub side: QuoteSide,
+    pub action: LevelAction,
+    /// Position on the ladder, best price first.
+    pub index: usize,
+    pub price: Px,
+    /// Displayed quantity left at the level; zero on `Delete`.
+    pub quantity: Qty,
+    pub timestamp: Ts,
+    pub seq: u64,
+}
+
+impl LevelEvent {
+    pub fn new(
+        symbol: Symbol,
+        side: QuoteSide,
+        action: LevelAction,
+        index: usize,
+        price: Px,
+        quantity: Qty,
+        timestamp: Ts,
+    ) -> Self {
+        LevelEvent {
+            symbol,
+            side,
+            action,
+            index,
+            price,
+            quantity,
+            timestamp,
+            seq: 0,
+        }
+    }
+}
+
+impl Sequenced for LevelEvent {
+    fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    fn with_seq(self, seq: u64) -> Self {
+        LevelEvent { seq, ..self }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MboAction {
+    Add,
+    Modify,
+    Delete,
+}
+
+/// A resting order joined a queue, changed what it shows, or left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MboEvent {
+    pub symbol: Symbol,
+    pub action: MboAction,
+    pub order_id: OrderID,
+    pub side: Side,
+    pub price: Px,
+    /// Quantity the order now displays; zero on `Delete`.
+    pub quantity: Qty,
+    pub timestamp: Ts,
+    pub seq: u64,
+}
+
+impl MboEvent {
+    pub fn new(
+        symbol: Symbol,
+        action: MboAction,
+        order_id: OrderID,
+        side: Side,
+        price: Px,
+        quantity: Qty,
+        timestamp: Ts,
+    ) -> Self {
+        MboEvent {
+            symbol,
+            action,
+            order_id,
+            side,
+            price,
+            quantity,
+            timestamp,
+            seq: 0,
+        }
+    }
+}
+
+impl Sequenced for MboEvent {
+    fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    fn with_seq(self, seq: u64) -> Self {
+        MboEvent { seq, ..self }
+    }
+}
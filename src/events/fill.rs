@@ -1,7 +1,14 @@
 //! `FillEvent` — emitted when two orders match.
+//!
+//! The book that printed the fill numbers it: `trade_id` counts that
+//! book's trades from 1, `seq` places it among everything else the book
+//! emitted (see [`Sequenced`]). Both are 0 on a fill built outside a book.
+//! Fees are filled in by the [`crate::book::Market`] that routed the
+//! fill, from its fee schedule; a bare book leaves them 0.
 
 use crate::errors::{NyquestroError, NyquestroResult};
-use crate::types::{OrderID, Px, Qty, Symbol, Ts};
+use crate::events::Sequenced;
+use crate::types::{OrderID, OwnerID, Px, Qty, Side, Symbol, Ts};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct FillEvent {
@@ -11,6 +18,17 @@ pub struct FillEvent {
     pub price: Px,
     pub quantity: Qty,
     pub timestamp: Ts,
+    /// The side that took liquidity. `None` for a call-auction uncross,
+    /// where neither side aggressed.
+    pub aggressor: Option<Side>,
+    pub buyer_owner: Option<OwnerID>,
+    pub seller_owner: Option<OwnerID>,
+    /// What each side pays, in raw price units; negative is a rebate.
+    /// See [`crate::book::fees`].
+    pub buyer_fee: i64,
+    pub seller_fee: i64,
+    pub trade_id: u64,
+    pub seq: u64,
 }
 
 impl FillEvent {
@@ -38,8 +56,59 @@ impl FillEvent {
             price,
             quantity,
             timestamp,
+            aggressor: None,
+            buyer_owner: None,
+            seller_owner: None,
+            buyer_fee: 0,
+            seller_fee: 0,
+            trade_id: 0,
+            seq: 0,
         })
     }
+
+    /// Mark which side took liquidity.
+    pub fn with_aggressor(mut self, side: Side) -> Self {
+        self.aggressor = Some(side);
+        self
+    }
+
+    /// Record who owns each side, for fee and position accounting.
+    pub fn with_owners(mut self, buyer: Option<OwnerID>, seller: Option<OwnerID>) -> Self {
+        self.buyer_owner = buyer;
+        self.seller_owner = seller;
+        self
+    }
+
+    pub fn with_trade_id(mut self, trade_id: u64) -> Self {
+        self.trade_id = trade_id;
+        self
+    }
+
+    /// The order that took liquidity, if there was an aggressor.
+    pub fn aggressor_order_id(&self) -> Option<OrderID> {
+        self.aggressor.map(|side| match side {
+            Side::Buy => self.buyer_order_id,
+            Side::Sell => self.seller_order_id,
+        })
+    }
+
+    /// The order that was resting on the book, if there was an aggressor.
+    pub fn resting_order_id(&self) -> Option<OrderID> {
+        self.aggressor.map(|side| match side {
+            Side::Buy => self.seller_order_id,
+            Side::Sell => self.buyer_order_id,
+        })
+    }
+}
+
+impl Sequenced for FillEvent {
+    fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    fn with_seq(self, seq: u64) -> Self {
+        FillEvent { seq, ..self }
+    }
 }
 
 #[cfg(test)]
@@ -58,7 +127,7 @@ mod tests {
             SYM,
             OrderID::new(1).unwrap(),
             OrderID::new(2).unwrap(),
-            Px::from_cents(100).unwrap(),
+            Px::from_raw(100).unwrap(),
             Qty::ZERO,
             ts(1),
         );
@@ -68,7 +137,7 @@ mod tests {
     #[test]
     fn rejects_self_match() {
         let same = OrderID::new(1).unwrap();
-        let err = FillEvent::new(SYM, same, same, Px::from_cents(100).unwrap(), Qty::new(5), ts(1));
+        let err = FillEvent::new(SYM, same, same, Px::from_raw(100).unwrap(), Qty::new(5), ts(1));
         assert!(matches!(err, Err(NyquestroError::SelfMatch(1))));
     }
 
@@ -78,13 +147,31 @@ mod tests {
             SYM,
             OrderID::new(1).unwrap(),
             OrderID::new(2).unwrap(),
-            Px::from_cents(150).unwrap(),
+            Px::from_raw(150).unwrap(),
             Qty::new(7),
             ts(42),
         )
         .unwrap();
         assert_eq!(f.quantity, Qty::new(7));
-        assert_eq!(f.price.cents(), 150);
+        assert_eq!(f.price.raw(), 150);
         assert_eq!(f.symbol, SYM);
+        assert_eq!((f.trade_id, f.seq, f.aggressor), (0, 0, None));
+    }
+
+    #[test]
+    fn aggressor_labels_both_orders() {
+        let f = FillEvent::new(
+            SYM,
+            OrderID::new(1).unwrap(),
+            OrderID::new(2).unwrap(),
+            Px::from_raw(150).unwrap(),
+            Qty::new(7),
+            ts(42),
+        )
+        .unwrap();
+        assert_eq!(f.aggressor_order_id(), None);
+        let sold_into = f.with_aggressor(Side::Sell);
+        assert_eq!(sold_into.aggressor_order_id(), Some(OrderID::new(2).unwrap()));
+        assert_eq!(sold_into.resting_order_id(), Some(OrderID::new(1).unwrap()));
     }
 }
@@ -1,6 +1,7 @@
 //! `OrderEvent` — lifecycle transitions for a single order.
 
 use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::Sequenced;
 use crate::types::{OrderID, Px, Qty, Side, Symbol, Ts};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -10,6 +11,56 @@ pub enum OrderRejectionReason {
     InvalidOrderId,
     SelfMatch,
     DuplicateOrderId,
+    /// Fill-or-kill order could not be filled in full on arrival.
+    FillOrKillUnfilled,
+    /// Good-till-date order whose expiry is not after its entry time.
+    AlreadyExpired,
+    /// Market order arrived while the opposite side was empty.
+    NoLiquidity,
+    /// Pegged order arrived while the price it tracks did not exist.
+    NoReferencePrice,
+    /// Post-only order would have taken liquidity.
+    PostOnlyWouldTake,
+    /// Less than the order's minimum quantity could execute on arrival.
+    MinQuantityUnfilled,
+    /// The book is in a call auction and the order cannot wait for the
+    /// uncross: market, pegged, IOC / FOK, post-only or minimum-quantity.
+    NotAcceptedInAuction,
+    /// Trading in the symbol is halted.
+    TradingHalted,
+    /// Trading in the symbol is closed for the day.
+    MarketClosed,
+    /// Price is not a multiple of the instrument's tick size.
+    PriceOffTick,
+    /// Price is outside the instrument's allowed range.
+    PriceOutOfRange,
+    /// Quantity is not a multiple of the instrument's lot size.
+    QuantityOffLot,
+    /// Quantity is below the instrument's minimum order size.
+    QuantityBelowMinimum,
+    /// Quantity is above the instrument's maximum order size.
+    QuantityAboveMaximum,
+}
+
+/// Why a live order left the book without filling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CancelReason {
+    /// Explicit cancel from the order's owner.
+    Requested,
+    /// Removed by the book's self-trade prevention policy.
+    SelfTradePrevention,
+    /// Unfilled remainder of an immediate-or-cancel order.
+    ImmediateOrCancel,
+    /// Market order stopped at the book's protection band with liquidity
+    /// still resting beyond it.
+    ProtectionBand,
+    /// Market order swept the whole opposite side and still had quantity.
+    LiquidityExhausted,
+    /// The order's next trade would have printed outside the volatility
+    /// band; the book halted and the rest of the order was cancelled.
+    TradingHalted,
+    /// Removed by a mass cancel (see [`crate::book::MassCancel`]).
+    MassCancel,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -22,6 +73,7 @@ pub enum OrderEvent {
         price: Px,
         quantity: Qty,
         timestamp: Ts,
+        seq: u64,
     },
     /// Order was either matched (`remaining > 0` if partial, `0` if full)
     /// or fully consumed by a counterparty fill.
@@ -31,13 +83,72 @@ pub enum OrderEvent {
         executed: Qty,
         remaining: Qty,
         timestamp: Ts,
+        seq: u64,
+    },
+    /// Resting order amended in place (cancel/replace). `price` and
+    /// `remaining` are the order's new working price and open quantity.
+    Replaced {
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        price: Px,
+        remaining: Qty,
+        timestamp: Ts,
+        seq: u64,
+    },
+    /// Self-trade prevention's decrement-and-cancel took `decremented` off
+    /// the order's open quantity without trading; `remaining` is what is
+    /// left. Reported for the resting order, which keeps its place in the
+    /// queue, and for the aggressor, which keeps matching.
+    Decremented {
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        price: Px,
+        decremented: Qty,
+        remaining: Qty,
+        timestamp: Ts,
+        seq: u64,
+    },
+    /// Iceberg order's displayed peak was consumed and a new one carved
+    /// from its hidden reserve. The order moved to the back of its level;
+    /// `timestamp` is its new priority time.
+    Refreshed {
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        price: Px,
+        displayed: Qty,
+        hidden: Qty,
+        timestamp: Ts,
+        seq: u64,
+    },
+    /// Stop order's trigger price was reached by the last trade. The order
+    /// enters the book from here, stamped with `timestamp`.
+    Triggered {
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        stop_price: Px,
+        timestamp: Ts,
+        seq: u64,
     },
     /// Order cancelled before fully filling.
     Cancelled {
         order_id: OrderID,
         symbol: Symbol,
         remaining: Qty,
+        reason: CancelReason,
         timestamp: Ts,
+        seq: u64,
+    },
+    /// Resting order removed by the engine when its time in force ran out.
+    Expired {
+        order_id: OrderID,
+        symbol: Symbol,
+        remaining: Qty,
+        timestamp: Ts,
+        seq: u64,
     },
     /// Order rejected before reaching the book.
     Rejected {
@@ -45,6 +156,7 @@ pub enum OrderEvent {
         symbol: Symbol,
         reason: OrderRejectionReason,
         timestamp: Ts,
+        seq: u64,
     },
 }
 
@@ -67,6 +179,7 @@ impl OrderEvent {
             price,
             quantity,
             timestamp,
+            seq: 0,
         })
     }
 
@@ -86,15 +199,127 @@ impl OrderEvent {
             executed,
             remaining,
             timestamp,
+            seq: 0,
+        })
+    }
+
+    pub fn replaced(
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        price: Px,
+        remaining: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        if remaining.is_zero() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        Ok(OrderEvent::Replaced {
+            order_id,
+            symbol,
+            side,
+            price,
+            remaining,
+            timestamp,
+            seq: 0,
+        })
+    }
+
+    pub fn decremented(
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        price: Px,
+        decremented: Qty,
+        remaining: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        if decremented.is_zero() || remaining.is_zero() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        Ok(OrderEvent::Decremented {
+            order_id,
+            symbol,
+            side,
+            price,
+            decremented,
+            remaining,
+            timestamp,
+            seq: 0,
+        })
+    }
+
+    pub fn refreshed(
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        price: Px,
+        displayed: Qty,
+        hidden: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        if displayed.is_zero() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        Ok(OrderEvent::Refreshed {
+            order_id,
+            symbol,
+            side,
+            price,
+            displayed,
+            hidden,
+            timestamp,
+            seq: 0,
         })
     }
 
+    pub fn triggered(
+        order_id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        stop_price: Px,
+        timestamp: Ts,
+    ) -> Self {
+        OrderEvent::Triggered {
+            order_id,
+            symbol,
+            side,
+            stop_price,
+            timestamp,
+            seq: 0,
+        }
+    }
+
+    /// Cancel requested by the order's owner.
     pub fn cancelled(order_id: OrderID, symbol: Symbol, remaining: Qty, timestamp: Ts) -> Self {
+        Self::cancelled_with(order_id, symbol, remaining, CancelReason::Requested, timestamp)
+    }
+
+    /// Cancel initiated by the engine, tagged with why.
+    pub fn cancelled_with(
+        order_id: OrderID,
+        symbol: Symbol,
+        remaining: Qty,
+        reason: CancelReason,
+        timestamp: Ts,
+    ) -> Self {
         OrderEvent::Cancelled {
             order_id,
             symbol,
             remaining,
+            reason,
             timestamp,
+            seq: 0,
+        }
+    }
+
+    pub fn expired(order_id: OrderID, symbol: Symbol, remaining: Qty, timestamp: Ts) -> Self {
+        OrderEvent::Expired {
+            order_id,
+            symbol,
+            remaining,
+            timestamp,
+            seq: 0,
         }
     }
 
@@ -109,6 +334,7 @@ impl OrderEvent {
             symbol,
             reason,
             timestamp,
+            seq: 0,
         }
     }
 
@@ -116,7 +342,12 @@ impl OrderEvent {
         match self {
             OrderEvent::Placed { order_id, .. }
             | OrderEvent::Filled { order_id, .. }
+            | OrderEvent::Replaced { order_id, .. }
+            | OrderEvent::Decremented { order_id, .. }
+            | OrderEvent::Refreshed { order_id, .. }
+            | OrderEvent::Triggered { order_id, .. }
             | OrderEvent::Cancelled { order_id, .. }
+            | OrderEvent::Expired { order_id, .. }
             | OrderEvent::Rejected { order_id, .. } => *order_id,
         }
     }
@@ -125,7 +356,12 @@ impl OrderEvent {
         match self {
             OrderEvent::Placed { symbol, .. }
             | OrderEvent::Filled { symbol, .. }
+            | OrderEvent::Replaced { symbol, .. }
+            | OrderEvent::Decremented { symbol, .. }
+            | OrderEvent::Refreshed { symbol, .. }
+            | OrderEvent::Triggered { symbol, .. }
             | OrderEvent::Cancelled { symbol, .. }
+            | OrderEvent::Expired { symbol, .. }
             | OrderEvent::Rejected { symbol, .. } => *symbol,
         }
     }
@@ -134,12 +370,48 @@ impl OrderEvent {
         match self {
             OrderEvent::Placed { timestamp, .. }
             | OrderEvent::Filled { timestamp, .. }
+            | OrderEvent::Replaced { timestamp, .. }
+            | OrderEvent::Decremented { timestamp, .. }
+            | OrderEvent::Refreshed { timestamp, .. }
+            | OrderEvent::Triggered { timestamp, .. }
             | OrderEvent::Cancelled { timestamp, .. }
+            | OrderEvent::Expired { timestamp, .. }
             | OrderEvent::Rejected { timestamp, .. } => *timestamp,
         }
     }
 }
 
+impl Sequenced for OrderEvent {
+    fn seq(&self) -> u64 {
+        match self {
+            OrderEvent::Placed { seq, .. }
+            | OrderEvent::Filled { seq, .. }
+            | OrderEvent::Replaced { seq, .. }
+            | OrderEvent::Decremented { seq, .. }
+            | OrderEvent::Refreshed { seq, .. }
+            | OrderEvent::Triggered { seq, .. }
+            | OrderEvent::Cancelled { seq, .. }
+            | OrderEvent::Expired { seq, .. }
+            | OrderEvent::Rejected { seq, .. } => *seq,
+        }
+    }
+
+    fn with_seq(mut self, next: u64) -> Self {
+        match &mut self {
+            OrderEvent::Placed { seq, .. }
+            | OrderEvent::Filled { seq, .. }
+            | OrderEvent::Replaced { seq, .. }
+            | OrderEvent::Decremented { seq, .. }
+            | OrderEvent::Refreshed { seq, .. }
+            | OrderEvent::Triggered { seq, .. }
+            | OrderEvent::Cancelled { seq, .. }
+            | OrderEvent::Expired { seq, .. }
+            | OrderEvent::Rejected { seq, .. } => *seq = next,
+        }
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -156,7 +428,7 @@ mod tests {
             OrderID::new(1).unwrap(),
             SYM,
             Side::Buy,
-            Px::from_cents(100).unwrap(),
+            Px::from_raw(100).unwrap(),
             Qty::ZERO,
             ts(1),
         );
@@ -169,14 +441,72 @@ mod tests {
         assert!(matches!(err, Err(NyquestroError::InvalidQuantity)));
     }
 
+    #[test]
+    fn replaced_rejects_zero_remaining() {
+        let err = OrderEvent::replaced(
+            OrderID::new(1).unwrap(),
+            SYM,
+            Side::Sell,
+            Px::from_raw(100).unwrap(),
+            Qty::ZERO,
+            ts(1),
+        );
+        assert!(matches!(err, Err(NyquestroError::InvalidQuantity)));
+    }
+
+    #[test]
+    fn decremented_rejects_zero_on_either_side() {
+        let event = |decremented: u64, remaining: u64| {
+            OrderEvent::decremented(
+                OrderID::new(1).unwrap(),
+                SYM,
+                Side::Buy,
+                Px::from_raw(100).unwrap(),
+                Qty::new(decremented),
+                Qty::new(remaining),
+                ts(1),
+            )
+        };
+        assert!(matches!(event(0, 3), Err(NyquestroError::InvalidQuantity)));
+        assert!(matches!(event(2, 0), Err(NyquestroError::InvalidQuantity)));
+        assert!(matches!(event(2, 3), Ok(OrderEvent::Decremented { .. })));
+    }
+
+    #[test]
+    fn refreshed_rejects_zero_displayed() {
+        let err = OrderEvent::refreshed(
+            OrderID::new(1).unwrap(),
+            SYM,
+            Side::Buy,
+            Px::from_raw(100).unwrap(),
+            Qty::ZERO,
+            Qty::new(5),
+            ts(1),
+        );
+        assert!(matches!(err, Err(NyquestroError::InvalidQuantity)));
+    }
+
     #[test]
     fn cancelled_is_infallible() {
         let e = OrderEvent::cancelled(OrderID::new(1).unwrap(), SYM, Qty::new(3), ts(1));
-        assert!(matches!(e, OrderEvent::Cancelled { .. }));
+        assert!(matches!(
+            e,
+            OrderEvent::Cancelled {
+                reason: CancelReason::Requested,
+                ..
+            }
+        ));
         assert_eq!(e.order_id().value(), 1);
         assert_eq!(e.symbol(), SYM);
     }
 
+    #[test]
+    fn expired_is_infallible() {
+        let e = OrderEvent::expired(OrderID::new(4).unwrap(), SYM, Qty::new(2), ts(9));
+        assert!(matches!(e, OrderEvent::Expired { .. }));
+        assert_eq!(e.timestamp(), ts(9));
+    }
+
     #[test]
     fn rejected_carries_reason() {
         let e = OrderEvent::rejected(
@@ -198,7 +528,7 @@ mod tests {
             OrderID::new(1).unwrap(),
             SYM,
             Side::Buy,
-            Px::from_cents(100).unwrap(),
+            Px::from_raw(100).unwrap(),
             Qty::new(5),
             ts(10),
         )
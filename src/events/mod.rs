@@ -6,10 +6,30 @@
 //! [`OrderRejectionReason`] enums rather than `String` fields so the frames
 //! stay `Copy`.
 
+pub mod auction;
+pub mod depth;
 pub mod fill;
 pub mod lifecycle;
 pub mod quote;
+pub mod state;
 
+pub use auction::AuctionEvent;
+pub use depth::{LevelAction, LevelEvent, MboAction, MboEvent};
 pub use fill::FillEvent;
-pub use lifecycle::{OrderEvent, OrderRejectionReason};
+pub use lifecycle::{CancelReason, OrderEvent, OrderRejectionReason};
 pub use quote::{QuoteEvent, QuoteSide};
+pub use state::{StateEvent, StateReason};
+
+/// An event's place in its book's output stream.
+///
+/// Each [`crate::book::OrderBook`] numbers everything it emits — fills,
+/// quotes, lifecycle, auction and state events — from one counter that
+/// starts at 1, in the order the events were produced. Merging a
+/// `SubmitResult`'s vectors by `seq` therefore recovers that order, and a
+/// gap in one symbol's numbers means an event went missing. `seq` is 0 on
+/// an event built outside a book.
+pub trait Sequenced: Sized {
+    fn seq(&self) -> u64;
+
+    fn with_seq(self, seq: u64) -> Self;
+}
@@ -1,6 +1,7 @@
 //! `QuoteEvent` — emitted when top-of-book changes on either side.
 
 use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::Sequenced;
 use crate::types::{Px, Qty, Side, Symbol, Ts};
 
 /// Which side of the book is being quoted, and whether the level was
@@ -31,6 +32,7 @@ pub struct QuoteEvent {
     /// level has been fully cleared.
     pub quantity: Qty,
     pub timestamp: Ts,
+    pub seq: u64,
 }
 
 impl QuoteEvent {
@@ -52,6 +54,7 @@ impl QuoteEvent {
             price,
             quantity,
             timestamp,
+            seq: 0,
         })
     }
 
@@ -65,10 +68,21 @@ impl QuoteEvent {
             price,
             quantity: Qty::ZERO,
             timestamp,
+            seq: 0,
         }
     }
 }
 
+impl Sequenced for QuoteEvent {
+    fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    fn with_seq(self, seq: u64) -> Self {
+        QuoteEvent { seq, ..self }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,7 +94,7 @@ mod tests {
         let err = QuoteEvent::live(
             SYM,
             QuoteSide::Bid,
-            Px::from_cents(100).unwrap(),
+            Px::from_raw(100).unwrap(),
             Qty::ZERO,
             Ts::from_nanos(1),
         );
@@ -89,7 +103,7 @@ mod tests {
 
     #[test]
     fn cleared_carries_zero_quantity() {
-        let q = QuoteEvent::cleared(SYM, QuoteSide::Ask, Px::from_cents(200).unwrap(), Ts::from_nanos(1));
+        let q = QuoteEvent::cleared(SYM, QuoteSide::Ask, Px::from_raw(200).unwrap(), Ts::from_nanos(1));
         assert!(q.quantity.is_zero());
         assert_eq!(q.side, QuoteSide::Ask);
         assert_eq!(q.symbol, SYM);
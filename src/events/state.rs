@@ -0,0 +1,90 @@
+//! `StateEvent` — a book moved between trading states.
+
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::Sequenced;
+use crate::types::{Symbol, TradingState, Ts};
+
+/// What moved the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StateReason {
+    /// An explicit engine call: auction start or uncross, halt, resume,
+    /// close.
+    Requested,
+    /// A trade would have printed outside the volatility band.
+    VolatilityHalt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateEvent {
+    pub symbol: Symbol,
+    pub from: TradingState,
+    pub to: TradingState,
+    pub reason: StateReason,
+    pub timestamp: Ts,
+    pub seq: u64,
+}
+
+impl StateEvent {
+    /// Rejects a "change" to the state the book is already in.
+    pub fn new(
+        symbol: Symbol,
+        from: TradingState,
+        to: TradingState,
+        reason: StateReason,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        if from == to {
+            return Err(NyquestroError::InvalidPhaseTransition {
+                symbol: symbol.as_u64(),
+                from: from.as_str(),
+                to: to.as_str(),
+            });
+        }
+        Ok(StateEvent {
+            symbol,
+            from,
+            to,
+            reason,
+            timestamp,
+            seq: 0,
+        })
+    }
+}
+
+impl Sequenced for StateEvent {
+    fn seq(&self) -> u64 {
+        self.seq
+    }
+
+    fn with_seq(self, seq: u64) -> Self {
+        StateEvent { seq, ..self }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SYM: Symbol = Symbol::from_const("TEST");
+
+    #[test]
+    fn rejects_a_non_change() {
+        let err = StateEvent::new(
+            SYM,
+            TradingState::Halted,
+            TradingState::Halted,
+            StateReason::Requested,
+            Ts::from_nanos(1),
+        );
+        assert!(matches!(err, Err(NyquestroError::InvalidPhaseTransition { .. })));
+        let ok = StateEvent::new(
+            SYM,
+            TradingState::Continuous,
+            TradingState::Halted,
+            StateReason::VolatilityHalt,
+            Ts::from_nanos(1),
+        )
+        .unwrap();
+        assert_eq!(ok.to, TradingState::Halted);
+    }
+}
@@ -5,9 +5,12 @@
 //! "the bid at $67000.50 is now 0.5 BTC", not "order ABC for 0.3 BTC was
 //! cancelled and order DEF for 0.5 BTC was added". To feed our per-order
 //! matching engine, we maintain a virtual `OrderID` per `(symbol, side,
-//! price)` cell. Updates translate to "cancel old virtual id, submit new
-//! virtual id with updated quantity"; clearing a level (qty = 0)
-//! translates to a single cancel.
+//! price)` cell. Updates to a known cell translate to a cancel/replace of
+//! its virtual id with the new quantity; updates to an unknown cell submit
+//! a fresh virtual id; clearing a level (qty = 0) translates to a single
+//! cancel. Trades can consume a virtual order while the feed still shows
+//! its level; the engine then resubmits the replace as a fresh order under
+//! the same id, so the cell keeps its key.
 //!
 //! Snapshots clear all virtual ids for the affected symbol and rebuild
 //! from scratch.
@@ -143,21 +146,30 @@ impl Bridge {
         let key = (symbol, side, price);
         let mut actions = Vec::new();
 
-        // Cancel any existing virtual order at this level.
-        if let Some(old_id) = self.level_id.remove(&key) {
+        if new_quantity.is_zero() {
+            // Level cleared: retract the virtual order, if any.
+            if let Some(old_id) = self.level_id.remove(&key) {
+                actions.push(FeedAction::Action {
+                    symbol_idx: idx,
+                    action: SimAction::Cancel {
+                        symbol,
+                        order_id: old_id,
+                    },
+                });
+            }
+        } else if let Some(&old_id) = self.level_id.get(&key) {
+            // Known level: resize the existing virtual order in place.
             actions.push(FeedAction::Action {
                 symbol_idx: idx,
-                action: SimAction::Cancel {
+                action: SimAction::Replace {
                     symbol,
                     order_id: old_id,
+                    side,
+                    price,
+                    quantity: new_quantity,
                 },
             });
-        }
-
-        // If new quantity is non-zero, submit a fresh virtual order.
-        if !new_quantity.is_zero()
-            && let Some(action) = self.submit_level(symbol, side, price, new_quantity)
-        {
+        } else if let Some(action) = self.submit_level(symbol, side, price, new_quantity) {
             actions.push(FeedAction::Action {
                 symbol_idx: idx,
                 action,
@@ -174,10 +186,9 @@ impl Bridge {
         qty: Qty,
     ) -> Option<SimAction> {
         let id = self.alloc_id();
-        // Use real wall-clock time so the dashboard's trade tape renders
-        // human-readable timestamps. Determinism doesn't apply here —
-        // live mode is non-deterministic by construction (the venue
-        // controls the message order).
+        // The time the level reached us. The dashboard's sequencer
+        // re-stamps the order from its monotonic clock on the way into the
+        // engine, so this is the source's time only.
         let ts = Ts::now();
         let order = Order::new(id, symbol, side, price, qty, ts).ok()?;
         self.level_id.insert((symbol, side, price), id);
@@ -198,9 +209,9 @@ mod tests {
     use crate::types::Symbol;
 
     fn px(c: u64) -> Px {
-        Px::from_cents(c).unwrap()
+        Px::from_raw(c).unwrap()
     }
-    fn qty(n: u32) -> Qty {
+    fn qty(n: u64) -> Qty {
         Qty::new(n)
     }
 
@@ -219,7 +230,7 @@ mod tests {
     }
 
     #[test]
-    fn update_with_nonzero_emits_cancel_then_submit() {
+    fn update_with_nonzero_emits_replace() {
         let btc = Symbol::from_const("BTC-USD");
         let mut bridge = Bridge::new(vec![btc]);
         // Seed a level via snapshot.
@@ -234,14 +245,30 @@ mod tests {
             price: px(7_000_000),
             new_quantity: qty(150_000),
         });
-        // Cancel old + submit new = 2 actions.
-        assert_eq!(actions.len(), 2);
+        // One replace of the level's existing virtual order.
+        assert_eq!(actions.len(), 1);
         assert!(matches!(
             &actions[0],
-            FeedAction::Action { action: SimAction::Cancel { .. }, .. }
+            FeedAction::Action {
+                action: SimAction::Replace { quantity, .. },
+                ..
+            } if *quantity == qty(150_000)
         ));
+    }
+
+    #[test]
+    fn update_on_unknown_level_submits() {
+        let btc = Symbol::from_const("BTC-USD");
+        let mut bridge = Bridge::new(vec![btc]);
+        let actions = bridge.translate(FeedEvent::Update {
+            symbol: btc,
+            side: Side::Sell,
+            price: px(7_000_100),
+            new_quantity: qty(20_000),
+        });
+        assert_eq!(actions.len(), 1);
         assert!(matches!(
-            &actions[1],
+            &actions[0],
             FeedAction::Action { action: SimAction::Submit(_), .. }
         ));
     }
@@ -20,6 +20,7 @@ use tokio::sync::mpsc;
 use tokio::time::sleep;
 use tokio_tungstenite::tungstenite::Message;
 
+use crate::instrument::ReferenceData;
 use crate::types::{Px, Qty, Side, Symbol};
 
 const COINBASE_WS_URL: &str = "wss://advanced-trade-ws.coinbase.com";
@@ -40,6 +41,10 @@ const SNAPSHOT_LEVEL_CAP: usize = 50;
 pub struct CoinbaseConfig {
     /// Coinbase product ids to subscribe to, e.g. ["BTC-USD", "ETH-USD"].
     pub product_ids: Vec<String>,
+    /// Instrument specs; each product's price and quantity scales turn
+    /// venue strings into `Px` and `Qty`. Products without a spec use the
+    /// default cents and whole units.
+    pub reference: ReferenceData,
 }
 
 impl Default for CoinbaseConfig {
@@ -50,6 +55,7 @@ impl Default for CoinbaseConfig {
                 "ETH-USD".to_string(),
                 "SOL-USD".to_string(),
             ],
+            reference: ReferenceData::builtin(),
         }
     }
 }
@@ -179,7 +185,7 @@ async fn connect_and_pump(
         let msg = msg?;
         match msg {
             Message::Text(text) => {
-                if let Err(e) = handle_text(text.as_str(), tx).await {
+                if let Err(e) = handle_text(text.as_str(), &cfg.reference, tx).await {
                     let _ = tx
                         .send(FeedEvent::Status(format!("parse error: {e}")))
                         .await;
@@ -199,6 +205,7 @@ async fn connect_and_pump(
 
 async fn handle_text(
     text: &str,
+    reference: &ReferenceData,
     tx: &mpsc::Sender<FeedEvent>,
 ) -> Result<(), serde_json::Error> {
     let msg: ServerMessage = serde_json::from_str(text)?;
@@ -211,18 +218,19 @@ async fn handle_text(
             Some(s) => s,
             None => continue,
         };
+        let spec = reference.spec_or_default(symbol);
         match event.event_type.as_str() {
             "snapshot" => {
                 let mut bids = Vec::new();
                 let mut asks = Vec::new();
                 for u in &event.updates {
-                    let qty = match parse_qty(&u.new_quantity) {
-                        Some(q) if !q.is_zero() => q,
+                    let qty = match spec.parse_qty(&u.new_quantity) {
+                        Ok(q) if !q.is_zero() => q,
                         _ => continue,
                     };
-                    let price = match parse_price(&u.price_level) {
-                        Some(p) => p,
-                        None => continue,
+                    let price = match spec.parse_price(&u.price_level) {
+                        Ok(p) => p,
+                        Err(_) => continue,
                     };
                     match u.side.as_str() {
                         "bid" => bids.push((price, qty)),
@@ -259,12 +267,17 @@ async fn handle_text(
                         "offer" | "ask" => Side::Sell,
                         _ => continue,
                     };
-                    let price = match parse_price(&u.price_level) {
-                        Some(p) => p,
-                        None => continue,
+                    // Prices or sizes finer than the instrument's scale
+                    // would be rounded onto another level; drop them.
+                    let price = match spec.parse_price(&u.price_level) {
+                        Ok(p) => p,
+                        Err(_) => continue,
+                    };
+                    // Quantity may be zero (level cleared).
+                    let qty = match spec.parse_qty(&u.new_quantity) {
+                        Ok(q) => q,
+                        Err(_) => continue,
                     };
-                    // Quantity may be zero (level cleared). Use Qty::ZERO.
-                    let qty = parse_qty(&u.new_quantity).unwrap_or(Qty::ZERO);
                     let _ = tx
                         .send(FeedEvent::Update {
                             symbol,
@@ -294,30 +307,3 @@ fn symbol_from_product_id(product_id: &str) -> Option<Symbol> {
     buf[..len].copy_from_slice(&bytes[..len]);
     Some(Symbol::from_const_bytes(buf))
 }
-
-/// Parse Coinbase's price string (e.g. "67234.56") into our integer-cents
-/// `Px`. Returns `None` for malformed input or zero/negative prices.
-fn parse_price(s: &str) -> Option<Px> {
-    let dollars: f64 = s.parse().ok()?;
-    if !dollars.is_finite() || dollars <= 0.0 {
-        return None;
-    }
-    let cents = (dollars * 100.0).round() as u64;
-    Px::from_cents(cents.max(1)).ok()
-}
-
-/// Parse Coinbase's quantity string (e.g. "0.5") into our scaled-integer
-/// `Qty`. Multiplies by [`QTY_SCALE`] so 0.5 BTC becomes Qty(500_000).
-/// Returns `Qty::ZERO` for valid zero (level cleared) and `None` for
-/// malformed input.
-fn parse_qty(s: &str) -> Option<Qty> {
-    let units: f64 = s.parse().ok()?;
-    if !units.is_finite() || units < 0.0 {
-        return None;
-    }
-    let scaled = (units * super::QTY_SCALE).round();
-    if scaled > u32::MAX as f64 {
-        return Some(Qty::new(u32::MAX));
-    }
-    Some(Qty::new(scaled.max(0.0) as u32))
-}
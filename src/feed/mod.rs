@@ -10,15 +10,12 @@
 //! pushes `(symbol_idx, SimAction)` pairs through a `std::sync::mpsc`
 //! channel into the main dashboard loop, which drains non-blockingly per
 //! render frame.
+//!
+//! How a venue price or size becomes a `Px` or `Qty` is per instrument:
+//! see `InstrumentSpec::parse_price` / `parse_qty` in [`crate::instrument`].
 
 pub mod bridge;
 pub mod coinbase;
 
 pub use bridge::{Bridge, FeedAction};
 pub use coinbase::{run_coinbase, CoinbaseConfig, FeedEvent};
-
-/// One Coinbase level-quantity unit corresponds to `1 / QTY_SCALE` of the
-/// base asset. We use 1e6 so a Qty of 500_000 represents 0.5 BTC; this
-/// keeps fractional crypto quantities in `u32` range while preserving
-/// micro-unit precision (~$0.06 at $60k BTC).
-pub const QTY_SCALE: f64 = 1_000_000.0;
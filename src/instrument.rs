@@ -0,0 +1,316 @@
+//! Instrument reference data.
+//!
+//! An [`InstrumentSpec`] says what a well-formed order in one symbol looks
+//! like: the price grid (tick size and range) and the quantity grid (lot
+//! size and per-order bounds). A book created with a spec (see
+//! [`crate::book::Market::register_instrument`]) rejects orders off either
+//! grid with a reason naming the rule that failed.
+//!
+//! [`ReferenceData`] is the per-symbol table of specs, loaded from a JSON
+//! file so the synthetic simulators, the live feed and the books all read
+//! the same numbers. The shipped table is `reference/instruments.json`;
+//! [`ReferenceData::builtin`] embeds it at compile time. Fields left out of
+//! a record take the [`InstrumentSpec::default`] value. Scales are decimal
+//! places; every price and quantity in a record is a raw integer at its
+//! instrument's scale:
+//!
+//! ```json
+//! {
+//!   "instruments": {
+//!     "AAPL":    { "tick_size": 1, "max_qty": 100000 },
+//!     "BTC-USD": { "tick_size": 1, "price_scale": 2, "qty_scale": 8 }
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::OrderRejectionReason;
+use crate::types::{Px, Qty, Scale, Scaled, Symbol};
+
+const BUILTIN: &str = include_str!("../reference/instruments.json");
+
+// ─── InstrumentSpec ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InstrumentSpec {
+    /// Price increment in raw price units. Every limit and stop price is
+    /// a multiple.
+    pub tick_size: u64,
+    /// Quantity increment in raw quantity units. Every order quantity is a
+    /// multiple.
+    pub lot_size: u64,
+    pub min_qty: Qty,
+    pub max_qty: Qty,
+    pub min_price: Px,
+    pub max_price: Px,
+    /// Decimal places of a raw `Px`: 2 for cents, more for assets quoted
+    /// below a cent.
+    pub price_scale: Scale,
+    /// Decimal places of a raw `Qty`: 0 for shares, 8 for a coin traded
+    /// to the satoshi.
+    pub qty_scale: Scale,
+}
+
+impl Default for InstrumentSpec {
+    /// No constraint beyond what the primitive types already enforce.
+    fn default() -> Self {
+        InstrumentSpec {
+            tick_size: 1,
+            lot_size: 1,
+            min_qty: Qty::new(1),
+            max_qty: Qty::MAX,
+            min_price: Px::MIN,
+            max_price: Px::MAX,
+            price_scale: Scale::CENTS,
+            qty_scale: Scale::UNITS,
+        }
+    }
+}
+
+impl InstrumentSpec {
+    /// Check the spec is internally consistent: non-zero increments,
+    /// non-empty ranges.
+    pub fn validate(&self) -> NyquestroResult<()> {
+        if self.tick_size == 0 {
+            return Err(NyquestroError::InvalidInstrument("tick size must be non-zero"));
+        }
+        if self.lot_size == 0 {
+            return Err(NyquestroError::InvalidInstrument("lot size must be non-zero"));
+        }
+        if self.min_qty.is_zero() || self.min_qty > self.max_qty {
+            return Err(NyquestroError::InvalidInstrument("empty quantity range"));
+        }
+        if self.min_price > self.max_price {
+            return Err(NyquestroError::InvalidInstrument("empty price range"));
+        }
+        Ok(())
+    }
+
+    /// Why `price` is not a valid order price, if it is not.
+    pub fn check_price(&self, price: Px) -> Option<OrderRejectionReason> {
+        if price < self.min_price || price > self.max_price {
+            Some(OrderRejectionReason::PriceOutOfRange)
+        } else if !price.raw().is_multiple_of(self.tick_size) {
+            Some(OrderRejectionReason::PriceOffTick)
+        } else {
+            None
+        }
+    }
+
+    /// Why `qty` is not a valid order quantity, if it is not.
+    pub fn check_quantity(&self, qty: Qty) -> Option<OrderRejectionReason> {
+        if !qty.value().is_multiple_of(self.lot_size) {
+            Some(OrderRejectionReason::QuantityOffLot)
+        } else if qty < self.min_qty {
+            Some(OrderRejectionReason::QuantityBelowMinimum)
+        } else if qty > self.max_qty {
+            Some(OrderRejectionReason::QuantityAboveMaximum)
+        } else {
+            None
+        }
+    }
+
+    /// Read a decimal price ("67234.56") at this instrument's price scale.
+    pub fn parse_price(&self, s: &str) -> NyquestroResult<Px> {
+        Px::parse(s, self.price_scale)
+    }
+
+    /// Read a decimal quantity ("0.015") at this instrument's quantity
+    /// scale. Zero is accepted.
+    pub fn parse_qty(&self, s: &str) -> NyquestroResult<Qty> {
+        Qty::parse(s, self.qty_scale)
+    }
+
+    pub fn format_price(&self, price: Px) -> Scaled {
+        price.scaled(self.price_scale)
+    }
+
+    pub fn format_qty(&self, qty: Qty) -> Scaled {
+        qty.scaled(self.qty_scale)
+    }
+}
+
+// ─── ReferenceData ──────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReferenceData {
+    specs: BTreeMap<Symbol, InstrumentSpec>,
+}
+
+/// On-disk shape of one spec. Plain integers, so the file stays readable
+/// and every validated type is built through its own constructor. Also
+/// how book snapshots store a spec (see [`crate::book::snapshot`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SpecRecord {
+    tick_size: Option<u64>,
+    lot_size: Option<u64>,
+    min_qty: Option<u64>,
+    max_qty: Option<u64>,
+    min_price: Option<u64>,
+    max_price: Option<u64>,
+    price_scale: Option<u8>,
+    qty_scale: Option<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ReferenceFile {
+    instruments: BTreeMap<String, SpecRecord>,
+}
+
+impl SpecRecord {
+    pub(crate) fn from_spec(spec: &InstrumentSpec) -> Self {
+        SpecRecord {
+            tick_size: Some(spec.tick_size),
+            lot_size: Some(spec.lot_size),
+            min_qty: Some(spec.min_qty.value()),
+            max_qty: Some(spec.max_qty.value()),
+            min_price: Some(spec.min_price.raw()),
+            max_price: Some(spec.max_price.raw()),
+            price_scale: Some(spec.price_scale.decimals()),
+            qty_scale: Some(spec.qty_scale.decimals()),
+        }
+    }
+
+    pub(crate) fn into_spec(self) -> NyquestroResult<InstrumentSpec> {
+        let d = InstrumentSpec::default();
+        let spec = InstrumentSpec {
+            tick_size: self.tick_size.unwrap_or(d.tick_size),
+            lot_size: self.lot_size.unwrap_or(d.lot_size),
+            min_qty: self.min_qty.map_or(d.min_qty, Qty::new),
+            max_qty: self.max_qty.map_or(d.max_qty, Qty::new),
+            min_price: self.min_price.map_or(Ok(d.min_price), Px::from_raw)?,
+            max_price: self.max_price.map_or(Ok(d.max_price), Px::from_raw)?,
+            price_scale: self.price_scale.map_or(Ok(d.price_scale), Scale::new)?,
+            qty_scale: self.qty_scale.map_or(Ok(d.qty_scale), Scale::new)?,
+        };
+        spec.validate()?;
+        Ok(spec)
+    }
+}
+
+impl ReferenceData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The table shipped in `reference/instruments.json`.
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN).expect("embedded reference data is valid")
+    }
+
+    /// Parse a reference-data document. Every spec is validated; the first
+    /// bad record fails the whole load.
+    pub fn from_json(json: &str) -> NyquestroResult<Self> {
+        let file: ReferenceFile = serde_json::from_str(json)
+            .map_err(|e| NyquestroError::ReferenceData(e.to_string()))?;
+        let mut data = ReferenceData::new();
+        for (name, record) in file.instruments {
+            data.insert(Symbol::from_str(&name)?, record.into_spec()?)?;
+        }
+        Ok(data)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> NyquestroResult<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| NyquestroError::ReferenceData(format!("{}: {e}", path.display())))?;
+        Self::from_json(&json)
+    }
+
+    /// Add or replace `symbol`'s spec after validating it.
+    pub fn insert(&mut self, symbol: Symbol, spec: InstrumentSpec) -> NyquestroResult<()> {
+        spec.validate()?;
+        self.specs.insert(symbol, spec);
+        Ok(())
+    }
+
+    pub fn get(&self, symbol: Symbol) -> Option<&InstrumentSpec> {
+        self.specs.get(&symbol)
+    }
+
+    /// `symbol`'s spec, or the unconstrained default when it has none.
+    pub fn spec_or_default(&self, symbol: Symbol) -> InstrumentSpec {
+        self.get(symbol).copied().unwrap_or_default()
+    }
+
+    /// Specs in symbol order.
+    pub fn iter(&self) -> impl Iterator<Item = (&Symbol, &InstrumentSpec)> {
+        self.specs.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.specs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn px(c: u64) -> Px {
+        Px::from_raw(c).unwrap()
+    }
+
+    #[test]
+    fn builtin_covers_every_shipped_symbol() {
+        let data = ReferenceData::builtin();
+        for name in ["AAPL", "MSFT", "NVDA", "BTC-USD", "ETH-USD", "SOL-USD"] {
+            assert!(data.get(Symbol::from_const(name)).is_some(), "{name}");
+        }
+    }
+
+    #[test]
+    fn checks_name_the_failed_rule() {
+        let spec = InstrumentSpec {
+            tick_size: 5,
+            lot_size: 10,
+            min_qty: Qty::new(10),
+            max_qty: Qty::new(1_000),
+            min_price: px(100),
+            max_price: px(10_000),
+            ..InstrumentSpec::default()
+        };
+        assert_eq!(spec.check_price(px(105)), None);
+        assert_eq!(spec.check_price(px(103)), Some(OrderRejectionReason::PriceOffTick));
+        assert_eq!(spec.check_price(px(95)), Some(OrderRejectionReason::PriceOutOfRange));
+        assert_eq!(spec.check_quantity(Qty::new(20)), None);
+        assert_eq!(spec.check_quantity(Qty::new(25)), Some(OrderRejectionReason::QuantityOffLot));
+        assert_eq!(
+            spec.check_quantity(Qty::new(1_010)),
+            Some(OrderRejectionReason::QuantityAboveMaximum)
+        );
+    }
+
+    #[test]
+    fn omitted_fields_default_and_bad_records_fail() {
+        let data = ReferenceData::from_json(r#"{"instruments": {"X": {"tick_size": 5}}}"#).unwrap();
+        let spec = data.spec_or_default(Symbol::from_const("X"));
+        assert_eq!(spec.tick_size, 5);
+        assert_eq!(spec.lot_size, 1);
+        assert_eq!(data.spec_or_default(Symbol::from_const("Y")), InstrumentSpec::default());
+
+        let zero_tick = r#"{"instruments": {"X": {"tick_size": 0}}}"#;
+        assert!(matches!(
+            ReferenceData::from_json(zero_tick),
+            Err(NyquestroError::InvalidInstrument(_))
+        ));
+        let typo = r#"{"instruments": {"X": {"tick": 5}}}"#;
+        assert!(matches!(ReferenceData::from_json(typo), Err(NyquestroError::ReferenceData(_))));
+        let long_name = r#"{"instruments": {"TOO-LONG-NAME": {}}}"#;
+        assert!(matches!(ReferenceData::from_json(long_name), Err(NyquestroError::InvalidSymbol)));
+    }
+}
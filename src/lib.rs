@@ -1,15 +1,19 @@
 pub mod book;
+pub mod command;
+pub mod concurrent;
 pub mod errors;
 pub mod events;
 pub mod feed;
+pub mod instrument;
 pub mod metrics;
 pub mod order;
+pub mod sequencer;
 pub mod simulator;
 pub mod telemetry;
 pub mod types;
 pub mod ui;
 
 pub use errors::{ErrorSeverity, NyquestroError, NyquestroResult};
-pub use events::{FillEvent, OrderEvent, OrderRejectionReason, QuoteEvent, QuoteSide};
+pub use events::{FillEvent, OrderEvent, OrderRejectionReason, QuoteEvent, QuoteSide, Sequenced};
 pub use order::Order;
 pub use types::{OrderID, Px, Qty, Side, Status, Ts};
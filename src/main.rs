@@ -4,6 +4,8 @@
 //!   cargo run                              → real-time multi-instrument TUI (synthetic flow)
 //!   cargo run -- --live coinbase           → live BTC-USD/ETH-USD/SOL-USD depth from Coinbase
 //!   cargo run -- --no-tui                  → headless demo (text output, synthetic)
+//!   cargo run -- --no-tui --shards 2       → headless demo on the sharded runtime
+//!   cargo run -- --no-tui --shards 2 --pin-cores → same, each shard pinned to a core
 //!   cargo run -- --seed 1234               → deterministic dashboard from a seed (synthetic)
 //! ```
 
@@ -11,18 +13,24 @@ use std::env;
 use std::sync::mpsc;
 use std::thread;
 
-use nyquestro::book::Market;
+use nyquestro::book::{BookConfig, Market, SubmitResult};
+use nyquestro::command::Command;
+use nyquestro::concurrent::{BookShard, EngineEvent, Output, Runtime, RuntimeConfig};
 use nyquestro::events::OrderEvent;
 use nyquestro::feed::{run_coinbase, Bridge, CoinbaseConfig};
+use nyquestro::instrument::ReferenceData;
+use nyquestro::sequencer::{ManualClock, Sequencer};
 use nyquestro::simulator::{MarketSimulator, SimAction, SimConfig};
 use nyquestro::telemetry::{spawn_writer, TelemetryEvent, TelemetryHandle};
-use nyquestro::types::Symbol;
+use nyquestro::types::{Px, Qty, Symbol};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
     let no_tui = args.iter().any(|a| a == "--no-tui");
     let seed = parse_seed(&args).unwrap_or(0xC0FFEE);
     let live_venue = parse_live(&args);
+    let shards = parse_shards(&args);
+    let pin_cores = args.iter().any(|a| a == "--pin-cores");
 
     if let Some(venue) = live_venue.as_deref() {
         match venue {
@@ -35,7 +43,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
 
     if no_tui {
-        run_headless(seed)
+        run_headless(seed, shards, pin_cores)
     } else {
         nyquestro::ui::run(seed)
     }
@@ -55,6 +63,20 @@ fn parse_seed(args: &[String]) -> Option<u64> {
     None
 }
 
+fn parse_shards(args: &[String]) -> Option<usize> {
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--shards"
+            && i + 1 < args.len()
+            && let Ok(n) = args[i + 1].parse::<usize>()
+        {
+            return Some(n);
+        }
+        i += 1;
+    }
+    None
+}
+
 fn parse_live(args: &[String]) -> Option<String> {
     let mut i = 0;
     while i < args.len() {
@@ -139,63 +161,161 @@ fn run_live_coinbase() -> Result<(), Box<dyn std::error::Error>> {
     nyquestro::ui::run_with_app(app)
 }
 
-fn run_headless(seed: u64) -> Result<(), Box<dyn std::error::Error>> {
+fn run_headless(
+    seed: u64,
+    shards: Option<usize>,
+    pin_cores: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
     let symbols: [(Symbol, u64); 3] = [
         (Symbol::from_const("AAPL"), 15_000),
         (Symbol::from_const("MSFT"), 30_000),
         (Symbol::from_const("NVDA"), 50_000),
     ];
 
-    let mut market = Market::new();
+    let reference = ReferenceData::builtin();
+    let configs: Vec<BookConfig> = symbols
+        .iter()
+        .map(|(sym, _)| BookConfig {
+            instrument: Some(reference.spec_or_default(*sym)),
+            ..BookConfig::default()
+        })
+        .collect();
     let mut sims: Vec<MarketSimulator> = symbols
         .iter()
         .enumerate()
         .map(|(i, (sym, fair))| {
+            let instrument = reference.spec_or_default(*sym);
             let cfg = SimConfig {
                 symbol: *sym,
-                fair_value_cents: *fair,
+                fair_value: *fair,
+                instrument,
                 ..SimConfig::default()
             };
             MarketSimulator::new(cfg, seed.wrapping_add((i as u64) * 0x100))
         })
         .collect();
 
+    // Engine time is simulated: 50ms per round, stamped on every command.
+    let clock = ManualClock::default();
+    let mut sequencer = Sequencer::new(clock.clone());
     let mut totals = [(0u64, 0u64, 0u64); 3];
     println!("nyquestro headless mode · seed={seed}");
-    println!("simulating 10 seconds across 3 symbols...");
-    for _ in 0..200 {
-        for (i, sim) in sims.iter_mut().enumerate() {
-            for action in sim.step(0.05) {
-                if let SimAction::Submit(o) = action
-                    && let Ok(res) = market.submit_limit(o)
-                {
-                    totals[i].0 += 1;
-                    totals[i].1 += res.fills.len() as u64;
-                    totals[i].2 += res
-                        .lifecycle
-                        .iter()
-                        .filter(|e| matches!(e, OrderEvent::Rejected { .. }))
-                        .count() as u64;
+    match shards {
+        Some(n) => println!("simulating 10 seconds across 3 symbols on {n} shards..."),
+        None => println!("simulating 10 seconds across 3 symbols..."),
+    }
+    let markets = match shards {
+        None => {
+            // The same stamped commands the runtime would get, applied in
+            // place on one shard.
+            let mut shard = BookShard::default();
+            let mut apply = |command| {
+                let stamped = sequencer.stamp(command);
+                SubmitResult::collect(|out| shard.apply(stamped.command, out))
+            };
+            for ((symbol, _), config) in symbols.iter().zip(&configs) {
+                apply(Command::Register {
+                    symbol: *symbol,
+                    config: *config,
+                })?;
+            }
+            for _ in 0..200 {
+                clock.advance(50_000_000);
+                for (i, sim) in sims.iter_mut().enumerate() {
+                    for action in sim.step(0.05) {
+                        let SimAction::Submit(o) = action else { continue };
+                        if let Ok(res) = apply(Command::Submit(o)) {
+                            totals[i].0 += 1;
+                            totals[i].1 += res.fills.len() as u64;
+                            totals[i].2 += res
+                                .lifecycle
+                                .iter()
+                                .filter(|e| matches!(e, OrderEvent::Rejected { .. }))
+                                .count() as u64;
+                        }
+                    }
                 }
             }
+            vec![shard.into_market()]
         }
-    }
+        Some(n) => {
+            let config = RuntimeConfig {
+                shards: n,
+                pin_cores,
+                ..RuntimeConfig::default()
+            };
+            let index = |sym: Symbol| symbols.iter().position(|(s, _)| *s == sym);
+            thread::scope(|scope| -> Result<Vec<Market>, Box<dyn std::error::Error>> {
+                // Spawned in here so an early return drops it: the shards
+                // stop, then the consumers, before the scope joins them.
+                let mut runtime = Runtime::spawn(config)?;
+                // One consumer per shard, tallying fills and rejects per symbol.
+                let mut consumers = Vec::new();
+                for shard in 0..runtime.shards() {
+                    let outbound = runtime.outbound(shard).ok_or("missing shard")?;
+                    consumers.push(scope.spawn(move || {
+                        let mut seen = [(0u64, 0u64); 3];
+                        for output in outbound.iter() {
+                            let Output::Event(event) = output else { continue };
+                            let Some(i) = index(event.symbol()) else { continue };
+                            match event {
+                                EngineEvent::Fill(_) => seen[i].0 += 1,
+                                EngineEvent::Lifecycle(OrderEvent::Rejected { .. }) => {
+                                    seen[i].1 += 1
+                                }
+                                _ => {}
+                            }
+                        }
+                        seen
+                    }));
+                }
+                for ((symbol, _), config) in symbols.iter().zip(&configs) {
+                    runtime.send(sequencer.stamp(Command::Register {
+                        symbol: *symbol,
+                        config: *config,
+                    }))?;
+                }
+                for _ in 0..200 {
+                    clock.advance(50_000_000);
+                    for (i, sim) in sims.iter_mut().enumerate() {
+                        for action in sim.step(0.05) {
+                            if let SimAction::Submit(o) = action {
+                                runtime.send(sequencer.stamp(Command::Submit(o)))?;
+                                totals[i].0 += 1;
+                            }
+                        }
+                    }
+                }
+                let markets = runtime.shutdown()?;
+                for consumer in consumers {
+                    let seen = consumer.join().map_err(|_| "consumer thread panicked")?;
+                    for (total, (fills, rejects)) in totals.iter_mut().zip(seen) {
+                        total.1 += fills;
+                        total.2 += rejects;
+                    }
+                }
+                Ok(markets)
+            })?
+        }
+    };
 
     println!("\n── result ──────────────────────");
     for (i, (sym, _)) in symbols.iter().enumerate() {
-        let book = market.book(*sym);
+        let book = markets.iter().find_map(|m| m.book(*sym));
+        let spec = reference.spec_or_default(*sym);
+        let level = |(p, q): (Px, Qty)| format!("${}×{}", spec.format_price(p), spec.format_qty(q));
         let bid = book
             .and_then(|b| b.best_bid())
-            .map(|(p, q)| format!("${:.2}×{}", p.to_dollars(), q.value()))
+            .map(level)
             .unwrap_or_else(|| "—".into());
         let ask = book
             .and_then(|b| b.best_ask())
-            .map(|(p, q)| format!("${:.2}×{}", p.to_dollars(), q.value()))
+            .map(level)
             .unwrap_or_else(|| "—".into());
         let resting = book.map(|b| b.len()).unwrap_or(0);
-        let mid = sims[i].mid_cents() as f64 / 100.0;
+        let mid = spec.price_scale.display(sims[i].mid());
         println!(
-            "{sym:<6} submitted {:>5}  filled {:>5}  rejected {}  resting {:>4}  bid {bid:<12} ask {ask:<12} mid ${mid:.2}",
+            "{sym:<6} submitted {:>5}  filled {:>5}  rejected {}  resting {:>4}  bid {bid:<12} ask {ask:<12} mid ${mid}",
             totals[i].0, totals[i].1, totals[i].2, resting,
         );
     }
@@ -4,21 +4,39 @@
 //! mutations: invalid inputs return classified errors and leave the order
 //! unchanged. Status transitions are one-way; observing state never moves
 //! the order.
+//!
+//! An iceberg order (see [`Order::with_peak`]) shows only part of its open
+//! quantity. `remaining` is always the full open quantity; `displayed` is
+//! the slice of it currently visible, and the difference is the hidden
+//! reserve.
 
 use std::fmt;
 
 use crate::errors::{NyquestroError, NyquestroResult};
-use crate::types::{OrderID, Px, Qty, Side, Status, Symbol, Ts};
+use crate::sequencer::Clock;
+use crate::types::{
+    OrderID, OrderType, OwnerID, Peg, PostOnly, Px, Qty, Side, Status, Symbol, TimeInForce, Ts,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Order {
     id: OrderID,
     symbol: Symbol,
+    owner: Option<OwnerID>,
+    order_type: OrderType,
+    peg: Option<Peg>,
+    stop_price: Option<Px>,
     side: Side,
     price: Px,
     quantity: Qty,
     remaining: Qty,
+    peak: Option<Qty>,
+    displayed: Qty,
     timestamp: Ts,
+    time_in_force: TimeInForce,
+    expires_at: Option<Ts>,
+    post_only: Option<PostOnly>,
+    min_quantity: Option<Qty>,
     status: Status,
 }
 
@@ -40,25 +58,146 @@ impl Order {
         Ok(Order {
             id,
             symbol,
+            owner: None,
+            order_type: OrderType::Limit,
+            peg: None,
+            stop_price: None,
             side,
             price,
             quantity,
             remaining: quantity,
+            peak: None,
+            displayed: quantity,
             timestamp,
+            time_in_force: TimeInForce::Gtc,
+            expires_at: None,
+            post_only: None,
+            min_quantity: None,
             status: Status::Open,
         })
     }
 
-    /// Convenience constructor for callers that don't care about
-    /// determinism — uses `Ts::now()`.
-    pub fn new_now(
+    /// Construct a market order. It carries no limit of its own: the price
+    /// is the extreme for its side until the book applies its protection
+    /// band at arrival.
+    pub fn market(
+        id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        quantity: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        let price = match side {
+            Side::Buy => Px::MAX,
+            Side::Sell => Px::MIN,
+        };
+        let mut order = Self::new(id, symbol, side, price, quantity, timestamp)?;
+        order.order_type = OrderType::Market;
+        Ok(order)
+    }
+
+    /// Construct a pegged order. Its price is set by the book from `peg`
+    /// on arrival and kept there as the reference moves; until then it
+    /// holds the cap, or the most passive price for its side.
+    pub fn pegged(
+        id: OrderID,
+        symbol: Symbol,
+        side: Side,
+        peg: Peg,
+        quantity: Qty,
+        timestamp: Ts,
+    ) -> NyquestroResult<Self> {
+        let price = peg.cap.unwrap_or(match side {
+            Side::Buy => Px::MIN,
+            Side::Sell => Px::MAX,
+        });
+        let mut order = Self::new(id, symbol, side, price, quantity, timestamp)?;
+        order.order_type = OrderType::Pegged;
+        order.peg = Some(peg);
+        Ok(order)
+    }
+
+    /// Construct an order stamped with `clock`'s current time.
+    pub fn new_now<C: Clock + ?Sized>(
         id: OrderID,
         symbol: Symbol,
         side: Side,
         price: Px,
         quantity: Qty,
+        clock: &mut C,
     ) -> NyquestroResult<Self> {
-        Self::new(id, symbol, side, price, quantity, Ts::now())
+        Self::new(id, symbol, side, price, quantity, clock.now())
+    }
+
+    /// Re-stamp an order that has not reached a book yet, as the
+    /// [`Sequencer`](crate::sequencer::Sequencer) does on entry. A `Day`
+    /// order's expiry moves to the end of its new day; a `Gtd` expiry is
+    /// the caller's own time and stays.
+    pub fn with_timestamp(mut self, timestamp: Ts) -> Self {
+        self.timestamp = timestamp;
+        if self.time_in_force == TimeInForce::Day {
+            self.expires_at = Some(timestamp.next_utc_midnight());
+        }
+        self
+    }
+
+    /// Attribute the order to a participant. Orders without an owner are
+    /// anonymous and never trip self-trade prevention.
+    pub fn with_owner(mut self, owner: OwnerID) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    /// Set the order's time in force. `Day` expires at the first UTC
+    /// midnight after the order's timestamp; `Gtd` at its given time. The
+    /// expiry is fixed here, so a later replace that re-stamps the order
+    /// does not extend its life.
+    pub fn with_time_in_force(mut self, tif: TimeInForce) -> Self {
+        self.time_in_force = tif;
+        self.expires_at = match tif {
+            TimeInForce::Day => Some(self.timestamp.next_utc_midnight()),
+            TimeInForce::Gtd(at) => Some(at),
+            TimeInForce::Gtc | TimeInForce::Ioc | TimeInForce::Fok => None,
+        };
+        self
+    }
+
+    /// Make this an iceberg order that displays at most `peak` units at a
+    /// time. A peak at or above the order's quantity displays everything.
+    pub fn with_peak(mut self, peak: Qty) -> NyquestroResult<Self> {
+        if peak.is_zero() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        self.peak = Some(peak);
+        self.displayed = self.remaining.min(peak);
+        Ok(self)
+    }
+
+    /// Hold the order back until the last trade reaches `trigger`: at or
+    /// above it for a buy, at or below it for a sell. On a limit order this
+    /// makes a stop-limit, on a market order a plain stop.
+    pub fn with_stop(mut self, trigger: Px) -> Self {
+        self.stop_price = Some(trigger);
+        self
+    }
+
+    /// Only ever add liquidity: if the order would trade on arrival, `mode`
+    /// decides whether it is rejected or slid one tick away.
+    pub fn with_post_only(mut self, mode: PostOnly) -> Self {
+        self.post_only = Some(mode);
+        self
+    }
+
+    /// Require at least `min` units to execute on arrival, or reject the
+    /// order without touching the book. Whatever is left after that first
+    /// execution rests or cancels as usual, with no minimum. Rejects a zero
+    /// minimum or one above the order's quantity.
+    pub fn with_min_quantity(mut self, min: Qty) -> NyquestroResult<Self> {
+        if min.is_zero() || min > self.quantity {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        self.min_quantity = Some(min);
+        Ok(self)
     }
 
     // ─── Accessors ─────────────────────────────────────────────────────────
@@ -73,6 +212,54 @@ impl Order {
         self.symbol
     }
 
+    #[inline]
+    pub fn owner(&self) -> Option<OwnerID> {
+        self.owner
+    }
+
+    #[inline]
+    pub fn order_type(&self) -> OrderType {
+        self.order_type
+    }
+
+    #[inline]
+    pub fn is_market(&self) -> bool {
+        self.order_type == OrderType::Market
+    }
+
+    /// Pricing rule of a pegged order; `None` for every other type.
+    #[inline]
+    pub fn peg(&self) -> Option<Peg> {
+        self.peg
+    }
+
+    #[inline]
+    pub fn is_pegged(&self) -> bool {
+        self.peg.is_some()
+    }
+
+    /// Trigger price of a stop that has not fired yet.
+    #[inline]
+    pub fn stop_price(&self) -> Option<Px> {
+        self.stop_price
+    }
+
+    #[inline]
+    pub fn is_stop(&self) -> bool {
+        self.stop_price.is_some()
+    }
+
+    /// Whether a last trade at `last` fires this stop. Always `false` for
+    /// an order without a trigger.
+    #[inline]
+    pub fn is_triggered_by(&self, last: Px) -> bool {
+        match (self.stop_price, self.side) {
+            (Some(stop), Side::Buy) => last >= stop,
+            (Some(stop), Side::Sell) => last <= stop,
+            (None, _) => false,
+        }
+    }
+
     #[inline]
     pub fn side(&self) -> Side {
         self.side
@@ -93,6 +280,31 @@ impl Order {
         self.remaining
     }
 
+    /// Peak size of an iceberg order; `None` for a fully displayed order.
+    #[inline]
+    pub fn peak(&self) -> Option<Qty> {
+        self.peak
+    }
+
+    #[inline]
+    pub fn is_iceberg(&self) -> bool {
+        self.peak.is_some()
+    }
+
+    /// Open quantity currently visible to the market. Equal to
+    /// [`remaining`](Self::remaining) unless this is an iceberg order.
+    #[inline]
+    pub fn displayed(&self) -> Qty {
+        self.displayed
+    }
+
+    /// Open quantity held back from the market.
+    #[inline]
+    pub fn hidden(&self) -> Qty {
+        // Safe: `displayed <= remaining` is maintained by every mutation.
+        Qty::new(self.remaining.value() - self.displayed.value())
+    }
+
     #[inline]
     pub fn filled(&self) -> Qty {
         // Safe: `quantity >= remaining` is an invariant the constructor and
@@ -105,6 +317,28 @@ impl Order {
         self.timestamp
     }
 
+    #[inline]
+    pub fn time_in_force(&self) -> TimeInForce {
+        self.time_in_force
+    }
+
+    /// When the engine will expire this order, if ever.
+    #[inline]
+    pub fn expires_at(&self) -> Option<Ts> {
+        self.expires_at
+    }
+
+    #[inline]
+    pub fn post_only(&self) -> Option<PostOnly> {
+        self.post_only
+    }
+
+    /// Minimum quantity that must execute on arrival.
+    #[inline]
+    pub fn min_quantity(&self) -> Option<Qty> {
+        self.min_quantity
+    }
+
     #[inline]
     pub fn status(&self) -> Status {
         self.status
@@ -121,6 +355,10 @@ impl Order {
     /// - `amount` is zero
     /// - `amount` exceeds [`remaining`](Self::remaining)
     /// - the order is already terminal
+    ///
+    /// An iceberg fill comes out of the displayed peak first. A peak that
+    /// reaches zero with quantity still hidden stays at zero until
+    /// [`refresh`](Self::refresh).
     pub fn fill(&mut self, amount: Qty) -> NyquestroResult<()> {
         if self.status.is_terminal() {
             return Err(NyquestroError::OrderTerminal(self.id.value()));
@@ -143,9 +381,21 @@ impl Order {
         };
         self.transition_to(new_status)?;
         self.remaining = new_remaining;
+        self.displayed = match self.peak {
+            None => new_remaining,
+            Some(_) => self.displayed.checked_sub(amount).unwrap_or(Qty::ZERO),
+        };
         Ok(())
     }
 
+    /// Carve a new displayed peak out of the hidden reserve and take
+    /// `timestamp` as the order's new priority time. A no-op on quantity
+    /// for a fully displayed order.
+    pub fn refresh(&mut self, timestamp: Ts) {
+        self.displayed = self.peak.map_or(self.remaining, |p| self.remaining.min(p));
+        self.timestamp = timestamp;
+    }
+
     pub fn cancel(&mut self) -> NyquestroResult<()> {
         if self.status.is_terminal() {
             return Err(NyquestroError::OrderTerminal(self.id.value()));
@@ -154,6 +404,108 @@ impl Order {
         Ok(())
     }
 
+    /// Fire a stop: drop its trigger and take `timestamp` as the time it
+    /// enters the book. From here on it is an ordinary market or limit
+    /// order.
+    pub fn trigger(&mut self, timestamp: Ts) {
+        self.stop_price = None;
+        self.timestamp = timestamp;
+    }
+
+    /// Mark the order expired. Same terminal rules as [`cancel`](Self::cancel).
+    pub fn expire(&mut self) -> NyquestroResult<()> {
+        if self.status.is_terminal() {
+            return Err(NyquestroError::OrderTerminal(self.id.value()));
+        }
+        self.transition_to(Status::Expired)?;
+        Ok(())
+    }
+
+    /// Amend price and open quantity keeping time priority. `remaining`
+    /// is the new *leaves* quantity; executed quantity is preserved, so
+    /// the total `quantity` becomes `filled + remaining`. An iceberg can
+    /// only show less than before. Whether an amendment may keep priority
+    /// is the caller's call: the book only amends in place when the order
+    /// stays at its price and does not grow.
+    ///
+    /// Rejects (without mutation) when the order is terminal or
+    /// `remaining` is zero.
+    pub fn amend(&mut self, price: Px, remaining: Qty) -> NyquestroResult<()> {
+        self.reprice(price, remaining)?;
+        self.displayed = self.displayed.min(remaining);
+        Ok(())
+    }
+
+    /// Amend price and open quantity like [`amend`](Self::amend), but give
+    /// up time priority: `timestamp` becomes the order's priority time and
+    /// an iceberg shows a fresh peak.
+    pub fn requeue(&mut self, price: Px, remaining: Qty, timestamp: Ts) -> NyquestroResult<()> {
+        self.reprice(price, remaining)?;
+        self.refresh(timestamp);
+        Ok(())
+    }
+
+    /// Set price and leaves quantity, the part [`amend`](Self::amend) and
+    /// [`requeue`](Self::requeue) share.
+    fn reprice(&mut self, price: Px, remaining: Qty) -> NyquestroResult<()> {
+        if self.status.is_terminal() {
+            return Err(NyquestroError::OrderTerminal(self.id.value()));
+        }
+        if remaining.is_zero() {
+            return Err(NyquestroError::InvalidQuantity);
+        }
+        let quantity = self
+            .filled()
+            .checked_add(remaining)
+            .ok_or(NyquestroError::QuantityOverflow)?;
+        self.price = price;
+        self.quantity = quantity;
+        self.remaining = remaining;
+        Ok(())
+    }
+
+    /// Put back the state an order reached after entry — price, open and
+    /// displayed quantity, priority time, expiry and status — on top of
+    /// `self`, built from its entry parameters. Used to load snapshots:
+    /// fails unless the state is one the mutations above could produce.
+    pub(crate) fn restore(
+        self,
+        price: Px,
+        remaining: Qty,
+        displayed: Qty,
+        timestamp: Ts,
+        expires_at: Option<Ts>,
+        status: Status,
+    ) -> NyquestroResult<Self> {
+        let fills_agree = match status {
+            Status::Open => remaining == self.quantity,
+            Status::PartiallyFilled => !remaining.is_zero() && remaining < self.quantity,
+            Status::FullyFilled => remaining.is_zero(),
+            Status::Cancelled | Status::Expired => remaining <= self.quantity,
+        };
+        let shown_agrees = displayed <= remaining
+            && match self.peak {
+                Some(peak) => displayed <= peak,
+                None => displayed == remaining,
+            };
+        let why = if !fills_agree {
+            "remaining quantity does not match the status"
+        } else if !shown_agrees {
+            "displayed quantity does not match the open quantity and peak"
+        } else {
+            return Ok(Order {
+                price,
+                remaining,
+                displayed,
+                timestamp,
+                expires_at,
+                status,
+                ..self
+            });
+        };
+        Err(NyquestroError::Snapshot(format!("order {}: {why}", self.id.value())))
+    }
+
     fn transition_to(&mut self, next: Status) -> NyquestroResult<()> {
         if !self.status.can_transition_to(next) {
             return Err(NyquestroError::InvalidStatusTransition {
@@ -173,6 +525,7 @@ fn status_str(s: Status) -> &'static str {
         Status::PartiallyFilled => "PARTIAL",
         Status::FullyFilled => "FILLED",
         Status::Cancelled => "CANCELLED",
+        Status::Expired => "EXPIRED",
     }
 }
 
@@ -195,12 +548,12 @@ mod tests {
     const SYM: Symbol = Symbol::from_const("TEST");
 
     fn px(c: u64) -> Px {
-        Px::from_cents(c).unwrap()
+        Px::from_raw(c).unwrap()
     }
     fn id(n: u64) -> OrderID {
         OrderID::new(n).unwrap()
     }
-    fn qty(n: u32) -> Qty {
+    fn qty(n: u64) -> Qty {
         Qty::new(n)
     }
 
@@ -272,11 +625,168 @@ mod tests {
         assert!(matches!(o.cancel(), Err(NyquestroError::OrderTerminal(_))));
     }
 
+    #[test]
+    fn day_and_gtd_fix_expiry_at_entry() {
+        const DAY: u64 = 86_400 * 1_000_000_000;
+        let o = Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(DAY + 5))
+            .unwrap()
+            .with_time_in_force(TimeInForce::Day);
+        assert_eq!(o.expires_at(), Some(Ts::from_nanos(2 * DAY)));
+        let mut o = o.with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(DAY + 9)));
+        assert_eq!(o.expires_at(), Some(Ts::from_nanos(DAY + 9)));
+        // Re-stamping does not move the expiry.
+        o.requeue(px(101), qty(10), Ts::from_nanos(DAY + 7)).unwrap();
+        assert_eq!(o.expires_at(), Some(Ts::from_nanos(DAY + 9)));
+        assert_eq!(o.with_time_in_force(TimeInForce::Ioc).expires_at(), None);
+    }
+
+    #[test]
+    fn market_order_has_no_limit_of_its_own() {
+        let b = Order::market(id(1), SYM, Side::Buy, qty(5), Ts::from_nanos(1)).unwrap();
+        let s = Order::market(id(2), SYM, Side::Sell, qty(5), Ts::from_nanos(1)).unwrap();
+        assert!(b.is_market());
+        assert_eq!(b.price(), Px::MAX);
+        assert_eq!(s.price(), Px::MIN);
+        assert!(Order::market(id(3), SYM, Side::Buy, Qty::ZERO, Ts::from_nanos(1)).is_err());
+    }
+
+    #[test]
+    fn iceberg_fills_from_peak_then_refreshes() {
+        let mut o = Order::new(id(1), SYM, Side::Sell, px(100), qty(25), Ts::from_nanos(1))
+            .unwrap()
+            .with_peak(qty(10))
+            .unwrap();
+        assert_eq!((o.displayed(), o.hidden()), (qty(10), qty(15)));
+        o.fill(qty(4)).unwrap();
+        assert_eq!((o.displayed(), o.hidden()), (qty(6), qty(15)));
+        o.fill(qty(6)).unwrap();
+        assert_eq!((o.displayed(), o.hidden()), (Qty::ZERO, qty(15)));
+        o.refresh(Ts::from_nanos(9));
+        assert_eq!((o.displayed(), o.hidden()), (qty(10), qty(5)));
+        assert_eq!(o.timestamp(), Ts::from_nanos(9));
+        // Keeping priority never grows the peak; re-queueing shows a new one.
+        o.fill(qty(7)).unwrap();
+        o.amend(px(100), qty(6)).unwrap();
+        assert_eq!((o.displayed(), o.timestamp()), (qty(3), Ts::from_nanos(9)));
+        // Even at the order's own time, a requeue is a fresh peak.
+        o.requeue(px(100), qty(6), Ts::from_nanos(9)).unwrap();
+        assert_eq!(o.displayed(), qty(6));
+        assert!(o.with_peak(Qty::ZERO).is_err());
+    }
+
+    #[test]
+    fn pegged_order_waits_passively_for_its_price() {
+        use crate::types::PegReference;
+        let peg = Peg { reference: PegReference::Midpoint, offset: 0, cap: None };
+        let b = Order::pegged(id(1), SYM, Side::Buy, peg, qty(5), Ts::from_nanos(1)).unwrap();
+        assert_eq!(b.order_type(), OrderType::Pegged);
+        assert_eq!(b.peg(), Some(peg));
+        assert_eq!(b.price(), Px::MIN);
+        let capped = Peg { cap: Some(px(250)), ..peg };
+        let s = Order::pegged(id(2), SYM, Side::Sell, capped, qty(5), Ts::from_nanos(1)).unwrap();
+        assert_eq!(s.price(), px(250));
+    }
+
+    #[test]
+    fn stop_triggers_through_its_price() {
+        let mut b = Order::market(id(1), SYM, Side::Buy, qty(5), Ts::from_nanos(1))
+            .unwrap()
+            .with_stop(px(105));
+        let s = Order::new(id(2), SYM, Side::Sell, px(90), qty(5), Ts::from_nanos(1))
+            .unwrap()
+            .with_stop(px(95));
+        assert!(!b.is_triggered_by(px(104)));
+        assert!(b.is_triggered_by(px(105)));
+        assert!(!s.is_triggered_by(px(96)));
+        assert!(s.is_triggered_by(px(95)));
+        b.trigger(Ts::from_nanos(7));
+        assert!(!b.is_stop());
+        assert!(b.is_market());
+        assert_eq!(b.timestamp(), Ts::from_nanos(7));
+        assert!(!b.is_triggered_by(px(200)));
+    }
+
+    #[test]
+    fn min_quantity_must_fit_the_order() {
+        let o = Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(1)).unwrap();
+        assert_eq!(o.with_min_quantity(qty(10)).unwrap().min_quantity(), Some(qty(10)));
+        assert!(o.with_min_quantity(qty(11)).is_err());
+        assert!(o.with_min_quantity(Qty::ZERO).is_err());
+    }
+
+    #[test]
+    fn expire_is_terminal() {
+        let mut o = Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(1)).unwrap();
+        o.fill(qty(4)).unwrap();
+        o.expire().unwrap();
+        assert_eq!(o.status(), Status::Expired);
+        assert!(matches!(o.expire(), Err(NyquestroError::OrderTerminal(1))));
+    }
+
+    #[test]
+    fn requeue_preserves_executed_quantity() {
+        let mut o = Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(1)).unwrap();
+        o.fill(qty(4)).unwrap();
+        o.requeue(px(101), qty(3), Ts::from_nanos(5)).unwrap();
+        assert_eq!(o.price(), px(101));
+        assert_eq!(o.remaining(), qty(3));
+        assert_eq!(o.filled(), qty(4));
+        assert_eq!(o.quantity(), qty(7));
+        assert_eq!(o.timestamp(), Ts::from_nanos(5));
+        assert_eq!(o.status(), Status::PartiallyFilled);
+    }
+
+    #[test]
+    fn amendments_reject_zero_and_terminal() {
+        let mut o = Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(1)).unwrap();
+        assert!(matches!(
+            o.requeue(px(100), Qty::ZERO, Ts::from_nanos(2)),
+            Err(NyquestroError::InvalidQuantity)
+        ));
+        assert!(matches!(o.amend(px(100), Qty::ZERO), Err(NyquestroError::InvalidQuantity)));
+        o.cancel().unwrap();
+        assert!(matches!(
+            o.requeue(px(100), qty(5), Ts::from_nanos(2)),
+            Err(NyquestroError::OrderTerminal(1))
+        ));
+        assert!(matches!(o.amend(px(100), qty(5)), Err(NyquestroError::OrderTerminal(1))));
+    }
+
+    #[test]
+    fn restore_accepts_only_reachable_state() {
+        let fresh = || {
+            Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(1))
+                .unwrap()
+                .with_peak(qty(4))
+                .unwrap()
+        };
+        let restore = |remaining, displayed, status| {
+            fresh().restore(px(105), qty(remaining), qty(displayed), Ts::from_nanos(9), None, status)
+        };
+        let o = restore(7, 3, Status::PartiallyFilled).unwrap();
+        assert_eq!((o.price(), o.remaining(), o.hidden()), (px(105), qty(7), qty(4)));
+        assert_eq!((o.timestamp(), o.filled()), (Ts::from_nanos(9), qty(3)));
+
+        for (remaining, displayed, status) in [
+            (7, 3, Status::Open),
+            (10, 4, Status::PartiallyFilled),
+            (0, 0, Status::PartiallyFilled),
+            (11, 4, Status::Cancelled),
+            (7, 5, Status::PartiallyFilled),
+            (2, 3, Status::PartiallyFilled),
+        ] {
+            let err = restore(remaining, displayed, status);
+            assert!(matches!(err, Err(NyquestroError::Snapshot(_))), "{remaining} {displayed}");
+        }
+    }
+
     #[test]
     fn observing_state_does_not_move_order() {
         let o = Order::new(id(1), SYM, Side::Buy, px(100), qty(10), Ts::from_nanos(1)).unwrap();
         // All accessors take &self — no clone needed.
         let _ = o.id();
+        let _ = o.owner();
+        let _ = o.order_type();
         let _ = o.side();
         let _ = o.price();
         let _ = o.quantity();
@@ -0,0 +1,143 @@
+//! `Clock` — where engine time comes from.
+//!
+//! The engine never reads the time itself: the [`Sequencer`] asks its
+//! clock once per inbound command and stamps the command with the answer.
+//! Swapping the clock is how the same engine runs live, in a simulation
+//! or in a test.
+//!
+//! - [`WallClock`] — the system clock; can step backwards when NTP or an
+//!   operator corrects it.
+//! - [`MonotonicClock`] — wall time read once at start, then advanced by
+//!   a monotonic timer, so it never steps backwards.
+//! - [`ManualClock`] — time only moves when told to; a simulation
+//!   advances it by its own step and a test sets it outright.
+//!
+//! [`Sequencer`]: crate::sequencer::Sequencer
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+use crate::types::Ts;
+
+/// A source of engine timestamps.
+pub trait Clock {
+    fn now(&mut self) -> Ts;
+}
+
+impl<C: Clock + ?Sized> Clock for Box<C> {
+    fn now(&mut self) -> Ts {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &mut C {
+    fn now(&mut self) -> Ts {
+        (**self).now()
+    }
+}
+
+/// The system clock, as [`Ts::now`] reads it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WallClock;
+
+impl Clock for WallClock {
+    fn now(&mut self) -> Ts {
+        Ts::now()
+    }
+}
+
+/// Wall time at construction plus the monotonic time elapsed since, so
+/// stamps read as real times but never go backwards.
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicClock {
+    origin: Ts,
+    started: Instant,
+}
+
+impl MonotonicClock {
+    pub fn new() -> Self {
+        MonotonicClock {
+            origin: Ts::now(),
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        MonotonicClock::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&mut self) -> Ts {
+        let elapsed = u64::try_from(self.started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        Ts::from_nanos(self.origin.nanos().saturating_add(elapsed))
+    }
+}
+
+/// A clock that only moves when set or advanced. Clones share one time,
+/// so a driver can keep a handle while a sequencer owns another.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock(Arc<AtomicU64>);
+
+impl ManualClock {
+    pub fn new(start: Ts) -> Self {
+        ManualClock(Arc::new(AtomicU64::new(start.nanos())))
+    }
+
+    pub fn set(&self, ts: Ts) {
+        self.0.store(ts.nanos(), Ordering::Relaxed);
+    }
+
+    /// Move time forward by `nanos`, saturating at the end of `Ts`.
+    pub fn advance(&self, nanos: u64) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |t| Some(t.saturating_add(nanos)));
+    }
+
+    pub fn get(&self) -> Ts {
+        Ts::from_nanos(self.0.load(Ordering::Relaxed))
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&mut self) -> Ts {
+        self.get()
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_manual_clock_is_shared_between_its_clones() {
+        let driver = ManualClock::new(Ts::from_nanos(100));
+        let mut held = driver.clone();
+        driver.advance(50);
+        assert_eq!(held.now(), Ts::from_nanos(150));
+        driver.set(Ts::from_nanos(7));
+        assert_eq!(held.now(), Ts::from_nanos(7));
+        driver.advance(u64::MAX);
+        assert_eq!(held.now(), Ts::from_nanos(u64::MAX));
+    }
+
+    #[test]
+    fn the_monotonic_clock_starts_at_wall_time_and_never_steps_back() {
+        let before = Ts::now();
+        let mut clock = MonotonicClock::new();
+        let mut last = clock.now();
+        assert!(last >= before);
+        for _ in 0..1_000 {
+            let now = clock.now();
+            assert!(now >= last);
+            last = now;
+        }
+        assert!(last.duration_since(Ts::now()) < 1_000_000_000);
+    }
+}
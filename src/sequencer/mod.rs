@@ -0,0 +1,151 @@
+//! The sequencer: the one place engine time and order are decided.
+//!
+//! Every inbound [`Command`] passes through a [`Sequencer`] before it
+//! reaches a [`Market`](crate::book::Market), a
+//! [`BookShard`](crate::concurrent::BookShard) or the
+//! [`Runtime`](crate::concurrent::Runtime). The sequencer reads its
+//! [`Clock`] once, gives the command the next global sequence number and
+//! overwrites whatever time the source put on it. Orders from the
+//! simulator, cancels from the dashboard and levels from a live feed
+//! therefore all carry times from one clock and can be compared.
+//!
+//! A [`Stamped`] command holds everything the engine will use, so a log
+//! of them replays exactly: feed the log back in, skip the sequencer, and
+//! every book sees the same commands at the same times in the same order.
+//!
+//! - [`clock`] — the [`Clock`] trait and the wall, monotonic and manual
+//!   clocks.
+
+pub mod clock;
+
+pub use clock::{Clock, ManualClock, MonotonicClock, WallClock};
+
+use crate::command::Command;
+use crate::types::Ts;
+
+/// A command's place in engine order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Stamp {
+    /// Global sequence number: 1 for the first command, then one more for
+    /// each after it, whatever symbol or shard it goes to.
+    pub seq: u64,
+    /// Engine time. Never earlier than the previous stamp's.
+    pub ts: Ts,
+}
+
+/// A command with its stamp; its own times have already been replaced by
+/// `stamp.ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stamped {
+    pub stamp: Stamp,
+    pub command: Command,
+}
+
+/// Stamps inbound commands from a clock it owns.
+#[derive(Debug, Clone)]
+pub struct Sequencer<C> {
+    clock: C,
+    last: Stamp,
+}
+
+impl<C: Clock> Sequencer<C> {
+    pub fn new(clock: C) -> Self {
+        Sequencer {
+            clock,
+            last: Stamp {
+                seq: 0,
+                ts: Ts::from_nanos(0),
+            },
+        }
+    }
+
+    /// The next stamp. A clock reading earlier than the last stamp (a
+    /// wall clock stepped back) is held at the last stamp's time, so
+    /// engine time never runs backwards.
+    pub fn tick(&mut self) -> Stamp {
+        self.last = Stamp {
+            seq: self.last.seq + 1,
+            ts: self.clock.now().max(self.last.ts),
+        };
+        self.last
+    }
+
+    /// Stamp `command`, replacing every time it carries with the stamp's.
+    pub fn stamp(&mut self, command: Command) -> Stamped {
+        let stamp = self.tick();
+        Stamped {
+            stamp,
+            command: command.with_ts(stamp.ts),
+        }
+    }
+
+    /// The last stamp handed out; sequence 0 before the first.
+    pub fn last(&self) -> Stamp {
+        self.last
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn clock_mut(&mut self) -> &mut C {
+        &mut self.clock
+    }
+}
+
+// ─── Tests ──────────────────────────────────────────────────────────────────
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::book::{BookConfig, MassCancel};
+    use crate::order::Order;
+    use crate::types::{OrderID, Px, Qty, Side, Symbol, TimeInForce};
+
+    const SYM: Symbol = Symbol::from_const("TEST");
+    const DAY: u64 = 86_400 * 1_000_000_000;
+
+    #[test]
+    fn stamps_count_up_and_time_never_runs_backwards() {
+        let clock = ManualClock::new(Ts::from_nanos(500));
+        let mut sequencer = Sequencer::new(clock.clone());
+        assert_eq!(sequencer.tick(), Stamp { seq: 1, ts: Ts::from_nanos(500) });
+        clock.set(Ts::from_nanos(200));
+        assert_eq!(sequencer.tick(), Stamp { seq: 2, ts: Ts::from_nanos(500) });
+        clock.advance(1_000);
+        assert_eq!(sequencer.tick(), Stamp { seq: 3, ts: Ts::from_nanos(1_200) });
+        assert_eq!(sequencer.last().seq, 3);
+    }
+
+    #[test]
+    fn stamping_replaces_the_sources_times() {
+        let clock = ManualClock::new(Ts::from_nanos(3 * DAY + 5));
+        let mut sequencer = Sequencer::new(clock);
+        let order = Order::new(
+            OrderID::new(1).unwrap(),
+            SYM,
+            Side::Buy,
+            Px::from_raw(100).unwrap(),
+            Qty::new(5),
+            Ts::from_nanos(10),
+        )
+        .unwrap()
+        .with_time_in_force(TimeInForce::Day);
+
+        let Command::Submit(stamped) = sequencer.stamp(Command::Submit(order)).command else {
+            panic!("a submit stays a submit");
+        };
+        assert_eq!(stamped.timestamp(), Ts::from_nanos(3 * DAY + 5));
+        // A day order expires at the end of the day it was stamped in.
+        assert_eq!(stamped.expires_at(), Some(Ts::from_nanos(4 * DAY)));
+
+        let ts = Ts::from_nanos(3 * DAY + 5);
+        let filter = MassCancel::all();
+        let sweep = sequencer.stamp(Command::MassCancel { filter, ts: Ts::from_nanos(1) });
+        assert_eq!(sweep.command, Command::MassCancel { filter, ts });
+        assert_eq!(sweep.stamp.seq, 2);
+        let config = BookConfig::default();
+        let register = Command::Register { symbol: SYM, config };
+        assert_eq!(sequencer.stamp(register).command, register);
+    }
+}
@@ -3,6 +3,7 @@
 use rand::{Rng, RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
+use crate::instrument::InstrumentSpec;
 use crate::order::Order;
 use crate::types::{OrderID, Px, Qty, Side, Symbol, Ts};
 
@@ -22,20 +23,33 @@ pub enum SimAction {
     /// retract virtual level-orders when a Coinbase L2 update reports a
     /// level cleared or quantity-changed.
     Cancel { symbol: Symbol, order_id: OrderID },
+    /// Explicit cancel/replace by id and symbol. Used by the feed bridge
+    /// when a Coinbase L2 update changes the size of a level it already
+    /// holds a virtual order for. `side` lets the engine resubmit the
+    /// level under the same id when trades have already consumed it.
+    Replace {
+        symbol: Symbol,
+        order_id: OrderID,
+        side: Side,
+        price: Px,
+        quantity: Qty,
+    },
 }
 
 #[derive(Debug, Clone)]
 pub struct SimConfig {
     /// Symbol this simulator emits orders for.
     pub symbol: Symbol,
-    /// Theoretical fair value the OU walk reverts to (in cents).
-    pub fair_value_cents: u64,
+    /// Theoretical fair value the OU walk reverts to, in raw units of the
+    /// instrument's price scale.
+    pub fair_value: u64,
     /// Mean reversion strength θ (per second). 0.5 = half-life ~1.4s.
     pub theta: f64,
-    /// Diffusion σ (cents per √second).
-    pub sigma_cents: f64,
-    /// Tick size (cents). Prices snap to multiples of this.
-    pub tick_cents: u64,
+    /// Diffusion σ (raw price units per √second).
+    pub sigma: f64,
+    /// Reference data for the symbol. Prices snap to its tick and stay in
+    /// its range; sizes are whole lots within its order-size bounds.
+    pub instrument: InstrumentSpec,
     /// Limit-order arrival intensity per side (events per second).
     pub limit_lambda: f64,
     /// Market-order arrival intensity per side (events per second).
@@ -46,8 +60,8 @@ pub struct SimConfig {
     pub size_log_mean: f64,
     /// Log-normal stddev of order size.
     pub size_log_sigma: f64,
-    /// Maximum order size (clipping cap).
-    pub size_max: u32,
+    /// Maximum order size (clipping cap). Zero is treated as one.
+    pub size_max: u64,
     /// Distance-decay exponent α; controls how fast intensity drops with
     /// distance from the touch.
     pub price_alpha: f64,
@@ -59,10 +73,10 @@ impl Default for SimConfig {
     fn default() -> Self {
         SimConfig {
             symbol: Symbol::from_const("DEFAULT"),
-            fair_value_cents: 10_000, // $100.00
+            fair_value: 10_000, // $100.00
             theta: 0.5,
-            sigma_cents: 2.0,
-            tick_cents: 1,
+            sigma: 2.0,
+            instrument: InstrumentSpec::default(),
             limit_lambda: 30.0,
             market_lambda: 8.0,
             cancel_lambda: 25.0,
@@ -79,18 +93,21 @@ impl Default for SimConfig {
 pub struct MarketSimulator {
     cfg: SimConfig,
     rng: ChaCha8Rng,
-    /// Mid-price (cents, real-valued so the OU process can drift between ticks).
+    /// Mid-price (raw price units, real-valued so the OU process can drift
+    /// between ticks).
     mid_real: f64,
     /// Monotonic order id supply.
     next_id: u64,
-    /// Wall-clock-ns supply for deterministic order timestamps.
+    /// Simulated nanoseconds since start (or the last reseed), stamped on
+    /// the orders this simulator builds. A sequencer in front of the
+    /// engine re-stamps them from its own clock on the way in.
     sim_clock_ns: u64,
 }
 
 impl MarketSimulator {
     pub fn new(cfg: SimConfig, seed: u64) -> Self {
         MarketSimulator {
-            mid_real: cfg.fair_value_cents as f64,
+            mid_real: cfg.fair_value as f64,
             cfg,
             rng: ChaCha8Rng::seed_from_u64(seed),
             next_id: 1,
@@ -102,13 +119,13 @@ impl MarketSimulator {
         &self.cfg
     }
 
-    pub fn mid_cents(&self) -> u64 {
+    pub fn mid(&self) -> u64 {
         // Self-defending public accessor: a non-finite mid (should be
         // impossible after the guards in `step`, but this is a public surface)
         // maps to fair value rather than saturating to u64::MAX through the
         // `as` cast.
         if !self.mid_real.is_finite() {
-            return self.cfg.fair_value_cents.max(1);
+            return self.cfg.fair_value.max(1);
         }
         self.mid_real.round().max(1.0) as u64
     }
@@ -141,14 +158,14 @@ impl MarketSimulator {
 
         // 1. Drift the OU mid by dt.
         // dX = θ(μ − X)dt + σ √dt · N(0, 1)
-        let mu = self.cfg.fair_value_cents as f64;
+        let mu = self.cfg.fair_value as f64;
         let drift = self.cfg.theta * (mu - self.mid_real) * dt;
-        let shock = self.cfg.sigma_cents * dt.sqrt() * standard_normal(&mut self.rng);
+        let shock = self.cfg.sigma * dt.sqrt() * standard_normal(&mut self.rng);
         self.mid_real += drift + shock;
         // Belt-and-braces: the mid is a price and must remain a sane, finite,
         // positive value regardless of what the integrator produced. A
         // non-finite result resets to fair value; the upper clamp bounds any
-        // finite runaway so `mid_cents()` can never emit a giant u64 downstream.
+        // finite runaway so `mid()` can never emit a giant u64 downstream.
         // With the dt cap above this never engages in normal operation (the mid
         // sits within a few hundred cents of fair value), so it changes no
         // existing behaviour.
@@ -187,8 +204,8 @@ impl MarketSimulator {
     fn gen_limit(&mut self, side: Side) -> Option<Order> {
         // Distance ticks ~ geometric weighted by 1/(k+1)^α.
         let ticks = sample_distance(&mut self.rng, self.cfg.price_alpha, self.cfg.price_max_ticks);
-        let mid = self.mid_cents() as i64;
-        let tick = self.cfg.tick_cents as i64;
+        let tick = self.cfg.instrument.tick_size.max(1) as i64;
+        let mid = self.mid() as i64 / tick * tick;
         // Limits sit on their own side: buys below mid, sells above mid.
         let raw = match side {
             Side::Buy => mid - (ticks as i64) * tick,
@@ -197,35 +214,35 @@ impl MarketSimulator {
         if raw <= 0 {
             return None;
         }
-        let price = Px::from_cents(raw as u64).ok()?;
+        let price = Px::from_raw(raw as u64).ok()?;
+        if self.cfg.instrument.check_price(price).is_some() {
+            return None;
+        }
         let qty = self.gen_qty()?;
         let id = self.next_order_id();
         Order::new(id, self.cfg.symbol, side, price, qty, Ts::from_nanos(self.sim_clock_ns)).ok()
     }
 
     fn gen_market(&mut self, side: Side) -> Option<Order> {
-        // Aggressive limit priced "through" the touch by 5 ticks.
-        let mid = self.mid_cents() as i64;
-        let tick = self.cfg.tick_cents as i64;
-        let raw = match side {
-            Side::Buy => mid + 5 * tick,
-            Side::Sell => mid - 5 * tick,
-        };
-        if raw <= 0 {
-            return None;
-        }
-        let price = Px::from_cents(raw as u64).ok()?;
+        // True market order: the book's protection band decides how far it
+        // may sweep.
         let qty = self.gen_qty()?;
         let id = self.next_order_id();
-        Order::new(id, self.cfg.symbol, side, price, qty, Ts::from_nanos(self.sim_clock_ns)).ok()
+        Order::market(id, self.cfg.symbol, side, qty, Ts::from_nanos(self.sim_clock_ns)).ok()
     }
 
     fn gen_qty(&mut self) -> Option<Qty> {
         let n = standard_normal(&mut self.rng);
         let log_size = self.cfg.size_log_mean + self.cfg.size_log_sigma * n;
-        let raw = log_size.exp().round() as u32;
-        let clipped = raw.clamp(1, self.cfg.size_max);
-        Some(Qty::new(clipped))
+        let raw = log_size.exp().round() as u64;
+        let spec = &self.cfg.instrument;
+        let lot = spec.lot_size.max(1);
+        // A zero cap (from the config or the spec) still means one unit;
+        // `clamp` panics on an upper bound below its lower one.
+        let cap = self.cfg.size_max.min(spec.max_qty.value()).max(1);
+        let clipped = raw.clamp(1, cap);
+        let lots = Qty::new(clipped / lot * lot);
+        spec.check_quantity(lots).is_none().then_some(lots)
     }
 
     fn next_order_id(&mut self) -> OrderID {
@@ -237,7 +254,7 @@ impl MarketSimulator {
     /// Re-seed the RNG. Used by `reset` keybind.
     pub fn reseed(&mut self, seed: u64) {
         self.rng = ChaCha8Rng::seed_from_u64(seed);
-        self.mid_real = self.cfg.fair_value_cents as f64;
+        self.mid_real = self.cfg.fair_value as f64;
         self.sim_clock_ns = 0;
     }
 }
@@ -330,13 +347,52 @@ mod tests {
         assert!(submits > 50, "expected >50 submits, got {submits}");
     }
 
+    #[test]
+    fn market_flow_uses_market_orders() {
+        let cfg = SimConfig {
+            limit_lambda: 0.0,
+            cancel_lambda: 0.0,
+            ..SimConfig::default()
+        };
+        let mut sim = MarketSimulator::new(cfg, 3);
+        let mut markets = 0;
+        for _ in 0..20 {
+            for action in sim.step(0.1) {
+                if let SimAction::Submit(o) = action {
+                    assert!(o.is_market());
+                    markets += 1;
+                }
+            }
+        }
+        assert!(markets > 0);
+    }
+
+    #[test]
+    fn a_zero_size_cap_submits_single_units() {
+        let cfg = SimConfig {
+            size_max: 0,
+            ..SimConfig::default()
+        };
+        let mut sim = MarketSimulator::new(cfg, 5);
+        let mut submits = 0;
+        for _ in 0..20 {
+            for action in sim.step(0.1) {
+                if let SimAction::Submit(o) = action {
+                    assert_eq!(o.quantity(), Qty::new(1));
+                    submits += 1;
+                }
+            }
+        }
+        assert!(submits > 0);
+    }
+
     #[test]
     fn mid_price_stays_in_reasonable_neighbourhood() {
         let mut sim = MarketSimulator::new(SimConfig::default(), 1);
         for _ in 0..1_000 {
             let _ = sim.step(0.01);
         }
-        let mid = sim.mid_cents();
+        let mid = sim.mid();
         // OU around 10000 with θ=0.5, σ=2 ⇒ stationary stddev ≈ σ/√(2θ) ≈ 2.
         // Well within ±200 cents.
         assert!(mid > 9_500 && mid < 10_500, "mid drifted to {mid}");
@@ -354,7 +410,7 @@ mod tests {
         for dt in [5.0, 50.0, 1_000.0, f64::INFINITY, f64::NAN, -3.0] {
             for _ in 0..20 {
                 let _ = sim.step(dt);
-                let mid = sim.mid_cents();
+                let mid = sim.mid();
                 assert!(mid >= 1, "mid underflowed to {mid} at dt={dt}");
                 // Fair value is 10_000; a divergence would shoot to millions or
                 // u64::MAX. 100_000 (10× fair) is generous enough never to flake
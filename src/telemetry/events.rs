@@ -9,6 +9,10 @@ use serde::Serialize;
 /// New variants are additive; bumping the schema version is reserved for
 /// breaking changes (renaming fields, removing variants). See
 /// `notes/telemetry-policy.md`.
+///
+/// Prices (`px_c`, `microprice_c`, `spread_c`) and quantities are raw
+/// fixed-point integers at the symbol's scales. The `_c` suffix dates from
+/// when every price was cents and is kept so the schema does not break.
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum TelemetryEvent {
@@ -36,7 +40,7 @@ pub enum TelemetryEvent {
         sym: String,
         side: &'static str,
         px_c: u64,
-        qty: u32,
+        qty: u64,
         id: u64,
     },
 
@@ -44,16 +48,27 @@ pub enum TelemetryEvent {
     Fill {
         sym: String,
         px_c: u64,
-        qty: u32,
+        qty: u64,
         buyer: u64,
         seller: u64,
+        /// Per-symbol trade id and event sequence number from the book.
+        trade: u64,
+        seq: u64,
     },
 
     /// Emitted for every successful cancel.
     Cancel {
         sym: String,
         id: u64,
-        remaining: u32,
+        remaining: u64,
+    },
+
+    /// Emitted for every replace sent to the engine.
+    Replace {
+        sym: String,
+        id: u64,
+        px_c: u64,
+        qty: u64,
     },
 
     /// Emitted for every `OrderEvent::Rejected`.
@@ -63,6 +78,15 @@ pub enum TelemetryEvent {
         reason: &'static str,
     },
 
+    /// Emitted for every `StateEvent`: a book moved between trading
+    /// states. `reason` is `"requested"` or `"volatility_halt"`.
+    TradingState {
+        sym: String,
+        from: &'static str,
+        to: &'static str,
+        reason: &'static str,
+    },
+
     /// Emitted (sampled at 1-in-10) for `QuoteEvent`s. Sampled because
     /// busy live mode produces 1000+/sec; full audit isn't required for
     /// quote events, the periodic state snapshot covers the relevant
@@ -71,7 +95,7 @@ pub enum TelemetryEvent {
         sym: String,
         side: &'static str,
         px_c: u64,
-        qty: u32,
+        qty: u64,
     },
 
     /// Per render-tick profiling.
@@ -124,8 +148,8 @@ pub enum TelemetryEvent {
         sym: String,
         levels_bid: usize,
         levels_ask: usize,
-        depth_bid: u32,
-        depth_ask: u32,
+        depth_bid: u64,
+        depth_ask: u64,
         ofi: f64,
         microprice_c: Option<u64>,
         spread_c: Option<u64>,
@@ -11,6 +11,7 @@ use std::str::FromStr;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::errors::{NyquestroError, NyquestroResult};
 
@@ -115,9 +116,37 @@ impl fmt::Display for OrderID {
     }
 }
 
+// ─── OwnerID ────────────────────────────────────────────────────────────────
+
+/// Participant (firm / account) that owns an order. Self-trade prevention
+/// compares owners, not order ids. Zero is reserved, matching [`OrderID`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OwnerID(u64);
+
+impl OwnerID {
+    pub fn new(id: u64) -> NyquestroResult<Self> {
+        if id == 0 {
+            Err(NyquestroError::InvalidOwnerId)
+        } else {
+            Ok(OwnerID(id))
+        }
+    }
+
+    #[inline]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for OwnerID {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}", self.0)
+    }
+}
+
 // ─── Side ───────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Side {
     Buy,
     Sell,
@@ -152,25 +181,320 @@ impl fmt::Display for Side {
     }
 }
 
+// ─── OrderType ──────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum OrderType {
+    /// Trades at its limit price or better; the remainder may rest.
+    #[default]
+    Limit,
+    /// Trades at whatever the opposite side offers, inside the book's
+    /// protection band. Never rests.
+    Market,
+    /// Priced by the book from a reference on the top of book (see
+    /// [`Peg`]) and repriced whenever that reference moves.
+    Pegged,
+}
+
+impl fmt::Display for OrderType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OrderType::Limit => "LIMIT",
+            OrderType::Market => "MARKET",
+            OrderType::Pegged => "PEGGED",
+        })
+    }
+}
+
+// ─── TimeInForce ────────────────────────────────────────────────────────────
+
+/// How long an order may work before the engine takes it away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TimeInForce {
+    /// Good till cancel: rests until filled or explicitly cancelled.
+    #[default]
+    Gtc,
+    /// Immediate or cancel: trades what it can on arrival, never rests.
+    Ioc,
+    /// Fill or kill: trades its full size on arrival or is rejected
+    /// without touching the book.
+    Fok,
+    /// Rests until the end of the UTC day it was entered on.
+    Day,
+    /// Good till date: rests until the given engine timestamp.
+    Gtd(Ts),
+}
+
+impl TimeInForce {
+    /// Whether any unfilled remainder may rest on the book.
+    #[inline]
+    pub const fn can_rest(self) -> bool {
+        !matches!(self, TimeInForce::Ioc | TimeInForce::Fok)
+    }
+}
+
+impl fmt::Display for TimeInForce {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeInForce::Gtc => f.write_str("GTC"),
+            TimeInForce::Ioc => f.write_str("IOC"),
+            TimeInForce::Fok => f.write_str("FOK"),
+            TimeInForce::Day => f.write_str("DAY"),
+            TimeInForce::Gtd(ts) => write!(f, "GTD({ts})"),
+        }
+    }
+}
+
+// ─── PostOnly ───────────────────────────────────────────────────────────────
+
+/// What the book does with a post-only order that would take liquidity on
+/// arrival, i.e. whose price crosses the opposite best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PostOnly {
+    /// Reject it with `OrderRejectionReason::PostOnlyWouldTake`.
+    Reject,
+    /// Reprice it one tick away from the opposite best, so it rests at
+    /// the most aggressive price that still adds liquidity.
+    Slide,
+}
+
+// ─── Peg ────────────────────────────────────────────────────────────────────
+
+/// Which top-of-book price a pegged order tracks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PegReference {
+    /// Best price on the order's own side (a buy follows the best bid).
+    Primary,
+    /// Best price on the opposite side (a buy follows the best ask).
+    Market,
+    /// Midpoint of best bid and best ask. A half tick rounds away from the
+    /// opposite side: down for buys, up for sells.
+    Midpoint,
+}
+
+/// Pricing rule for a pegged order: the reference price plus `offset`
+/// ticks, never beyond `cap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Peg {
+    pub reference: PegReference,
+    /// Signed offset in ticks added to the reference price. Positive is a
+    /// higher price whichever the side.
+    pub offset: i64,
+    /// Limit price: a buy never pegs above it, a sell never below.
+    pub cap: Option<Px>,
+}
+
+impl Peg {
+    /// Working price for an order on `side` given the reference best bid
+    /// and ask. `None` when the reference is missing — e.g. a midpoint peg
+    /// with one side empty. Clamped to the representable price range.
+    pub fn price(self, side: Side, bid: Option<Px>, ask: Option<Px>) -> Option<Px> {
+        let base = match self.reference {
+            PegReference::Primary => match side {
+                Side::Buy => bid?.raw(),
+                Side::Sell => ask?.raw(),
+            },
+            PegReference::Market => match side {
+                Side::Buy => ask?.raw(),
+                Side::Sell => bid?.raw(),
+            },
+            PegReference::Midpoint => {
+                let sum = u128::from(bid?.raw()) + u128::from(ask?.raw());
+                let mid = match side {
+                    Side::Buy => sum / 2,
+                    Side::Sell => sum.div_ceil(2),
+                };
+                u64::try_from(mid).unwrap_or(u64::MAX)
+            }
+        };
+        let raw = (i128::from(base) + i128::from(self.offset)).clamp(1, i128::from(u64::MAX));
+        let px = Px(u64::try_from(raw).unwrap_or(u64::MAX));
+        Some(match (side, self.cap) {
+            (Side::Buy, Some(cap)) => px.min(cap),
+            (Side::Sell, Some(cap)) => px.max(cap),
+            (_, None) => px,
+        })
+    }
+}
+
+// ─── AuctionKind ────────────────────────────────────────────────────────────
+
+/// Which call auction a book is running. Both collect orders without
+/// matching and uncross at a single price; the kind is carried on the
+/// auction's events for consumers that treat the open and the close
+/// differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AuctionKind {
+    Opening,
+    Closing,
+}
+
+impl fmt::Display for AuctionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AuctionKind::Opening => "OPENING",
+            AuctionKind::Closing => "CLOSING",
+        })
+    }
+}
+
+// ─── TradingState ───────────────────────────────────────────────────────────
+
+/// Where a book is in its trading day. Which orders it accepts and
+/// whether it matches them follows from the state; see
+/// [`crate::book::OrderBook`] for the transitions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum TradingState {
+    /// Collecting orders for the opening auction; nothing matches.
+    PreOpen,
+    /// Normal matching. A closing auction also collects here.
+    #[default]
+    Continuous,
+    /// Trading stopped, by request or by the volatility guard. Orders are
+    /// refused; cancels still work.
+    Halted,
+    /// Trading over. Orders are refused; cancels still work.
+    Closed,
+}
+
+impl TradingState {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TradingState::PreOpen => "PRE_OPEN",
+            TradingState::Continuous => "CONTINUOUS",
+            TradingState::Halted => "HALTED",
+            TradingState::Closed => "CLOSED",
+        }
+    }
+}
+
+impl fmt::Display for TradingState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// ─── Scale ──────────────────────────────────────────────────────────────────
+
+/// Decimal places carried by a fixed-point value: raw `n` at scale `d`
+/// reads as `n / 10^d`. Each instrument has one scale for prices and one
+/// for quantities (see [`crate::instrument::InstrumentSpec`]); `Px` and
+/// `Qty` store the raw integer and never the scale, so values of different
+/// instruments must not be mixed. Capped at 18 places so `10^d` fits in a
+/// `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Scale(u8);
+
+impl Scale {
+    /// Whole units: shares, contracts.
+    pub const UNITS: Scale = Scale(0);
+    /// Two places: the default price scale.
+    pub const CENTS: Scale = Scale(2);
+    pub const MAX_DECIMALS: u8 = 18;
+
+    pub fn new(decimals: u8) -> NyquestroResult<Self> {
+        if decimals > Self::MAX_DECIMALS {
+            Err(NyquestroError::InvalidScale { decimals })
+        } else {
+            Ok(Scale(decimals))
+        }
+    }
+
+    #[inline]
+    pub const fn decimals(self) -> u8 {
+        self.0
+    }
+
+    /// Raw units per whole unit, `10^decimals`.
+    #[inline]
+    pub const fn factor(self) -> u64 {
+        10u64.pow(self.0 as u32)
+    }
+
+    /// Parse a plain decimal string ("42", "0.5", "67234.56") into raw
+    /// units at this scale. Exact: digits past the scale are accepted only
+    /// when they are zeros, so "1.50" parses at two places but "1.505" is
+    /// an error rather than a silent rounding. Signs, exponents and digit
+    /// separators are rejected.
+    pub fn parse(self, s: &str) -> NyquestroResult<u64> {
+        let invalid = || NyquestroError::InvalidDecimal {
+            input: s.to_string(),
+            decimals: self.0,
+        };
+        let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+        let digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if int.is_empty() || !digits(int) || !digits(frac) || s.ends_with('.') {
+            return Err(invalid());
+        }
+        let places = usize::from(self.0);
+        let (kept, dropped) = frac.split_at(frac.len().min(places));
+        if dropped.bytes().any(|b| b != b'0') {
+            return Err(invalid());
+        }
+        let mut raw: u64 = 0;
+        for b in int.bytes().chain(kept.bytes()).chain(std::iter::repeat_n(b'0', places - kept.len())) {
+            raw = raw
+                .checked_mul(10)
+                .and_then(|r| r.checked_add(u64::from(b - b'0')))
+                .ok_or_else(invalid)?;
+        }
+        Ok(raw)
+    }
+
+    /// Formats `raw` as a decimal with exactly this many places.
+    #[inline]
+    pub const fn display(self, raw: u64) -> Scaled {
+        Scaled { raw, scale: self }
+    }
+}
+
+/// A raw fixed-point value paired with its scale, for display. Exact — no
+/// float round trip — and honours width and alignment flags, so
+/// `format!("{:>10}", px.scaled(scale))` lines up in a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Scaled {
+    raw: u64,
+    scale: Scale,
+}
+
+impl fmt::Display for Scaled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let factor = self.scale.factor();
+        let whole = self.raw / factor;
+        let text = match usize::from(self.scale.0) {
+            0 => whole.to_string(),
+            places => format!("{whole}.{:0places$}", self.raw % factor),
+        };
+        f.pad(&text)
+    }
+}
+
 // ─── Px ─────────────────────────────────────────────────────────────────────
 
-/// Price in integer cents. Float arithmetic is never used for price comparison
-/// — only for human-friendly display via [`Px::to_dollars`].
+/// Price as a raw integer at the instrument's price [`Scale`] — cents at
+/// the default two places. Float arithmetic is never used for price
+/// comparison; use [`Px::parse`] and [`Px::scaled`] to move between raw
+/// values and decimal text.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Px(u64);
 
 impl Px {
-    /// Construct from raw cents. Zero is rejected.
-    pub fn from_cents(cents: u64) -> NyquestroResult<Self> {
-        if cents == 0 {
-            Err(NyquestroError::InvalidPrice { cents: 0 })
+    /// Lowest representable price (one raw unit).
+    pub const MIN: Px = Px(1);
+    /// Highest representable price.
+    pub const MAX: Px = Px(u64::MAX);
+
+    /// Construct from raw units. Zero is rejected.
+    pub fn from_raw(raw: u64) -> NyquestroResult<Self> {
+        if raw == 0 {
+            Err(NyquestroError::InvalidPrice { raw: 0 })
         } else {
-            Ok(Px(cents))
+            Ok(Px(raw))
         }
     }
 
-    /// Construct from a dollar value, rounding to the nearest cent.
-    /// Rejects NaN, infinities, zero, and negative values.
+    /// Construct from a dollar value at [`Scale::CENTS`], rounding to the
+    /// nearest cent. Rejects NaN, infinities, zero, and negative values.
     pub fn from_dollars(dollars: f64) -> NyquestroResult<Self> {
         if !dollars.is_finite() || dollars <= 0.0 {
             return Err(NyquestroError::InvalidPriceFloat { value: dollars });
@@ -182,41 +506,79 @@ impl Px {
         Ok(Px(scaled as u64))
     }
 
+    /// Parse decimal text at `scale`; see [`Scale::parse`]. Zero is
+    /// rejected.
+    pub fn parse(s: &str, scale: Scale) -> NyquestroResult<Self> {
+        Px::from_raw(scale.parse(s)?)
+    }
+
     #[inline]
-    pub const fn cents(self) -> u64 {
+    pub const fn raw(self) -> u64 {
         self.0
     }
 
+    /// The price as dollars, reading the raw value as cents.
     #[inline]
     pub fn to_dollars(self) -> f64 {
-        self.0 as f64 / 100.0
+        self.to_f64(Scale::CENTS)
+    }
+
+    /// The price as a float at `scale`. For charts and ratios only.
+    #[inline]
+    pub fn to_f64(self, scale: Scale) -> f64 {
+        self.0 as f64 / scale.factor() as f64
+    }
+
+    #[inline]
+    pub const fn scaled(self, scale: Scale) -> Scaled {
+        scale.display(self.0)
+    }
+}
+
+impl FromStr for Px {
+    type Err = NyquestroError;
+    /// Parse at [`Scale::CENTS`], with an optional leading `$`, so the
+    /// output of `Display` reads back.
+    fn from_str(s: &str) -> NyquestroResult<Self> {
+        Px::parse(s.strip_prefix('$').unwrap_or(s), Scale::CENTS)
     }
 }
 
 impl fmt::Display for Px {
+    /// Dollars and cents. Prices of instruments at another scale should
+    /// be shown through [`Px::scaled`].
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "${:.2}", self.to_dollars())
+        write!(f, "${}", self.scaled(Scale::CENTS))
     }
 }
 
 // ─── Qty ────────────────────────────────────────────────────────────────────
 
-/// Quantity in whole units. Zero is representable (e.g. `remaining_quantity`
-/// after a full fill); rejection of zero is enforced at the construction
-/// boundaries that need it (`Order::new`, event constructors).
+/// Quantity as a raw integer at the instrument's quantity [`Scale`] —
+/// whole units at the default of zero places. Zero is representable (e.g.
+/// `remaining_quantity` after a full fill); rejection of zero is enforced
+/// at the construction boundaries that need it (`Order::new`, event
+/// constructors).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
-pub struct Qty(u32);
+pub struct Qty(u64);
 
 impl Qty {
     pub const ZERO: Qty = Qty(0);
+    pub const MAX: Qty = Qty(u64::MAX);
 
     #[inline]
-    pub const fn new(value: u32) -> Self {
+    pub const fn new(value: u64) -> Self {
         Qty(value)
     }
 
+    /// Parse decimal text at `scale`; see [`Scale::parse`]. Zero is
+    /// accepted.
+    pub fn parse(s: &str, scale: Scale) -> NyquestroResult<Self> {
+        scale.parse(s).map(Qty)
+    }
+
     #[inline]
-    pub const fn value(self) -> u32 {
+    pub const fn value(self) -> u64 {
         self.0
     }
 
@@ -225,6 +587,11 @@ impl Qty {
         self.0 == 0
     }
 
+    #[inline]
+    pub const fn scaled(self, scale: Scale) -> Scaled {
+        scale.display(self.0)
+    }
+
     /// Subtract `other` from `self`. Returns `None` when `other > self` —
     /// callers are expected to check explicitly rather than rely on
     /// saturating arithmetic, which has historically masked over-fill bugs.
@@ -239,6 +606,14 @@ impl Qty {
     }
 }
 
+impl FromStr for Qty {
+    type Err = NyquestroError;
+    /// Parse at [`Scale::UNITS`].
+    fn from_str(s: &str) -> NyquestroResult<Self> {
+        Qty::parse(s, Scale::UNITS)
+    }
+}
+
 impl fmt::Display for Qty {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -255,7 +630,8 @@ pub struct Ts(u64);
 impl Ts {
     /// Wall-clock now. If the system clock is before the UNIX epoch (an
     /// extreme edge case on misconfigured hardware) this returns `Ts(0)`
-    /// rather than panicking.
+    /// rather than panicking. Engine time comes from a
+    /// [`Clock`](crate::sequencer::Clock) instead.
     pub fn now() -> Self {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -290,6 +666,17 @@ impl Ts {
         self.0.max(other.0) - self.0.min(other.0)
     }
 
+    /// The first UTC midnight strictly after `self`. Saturates at the
+    /// largest representable midnight.
+    pub const fn next_utc_midnight(self) -> Ts {
+        const NANOS_PER_DAY: u64 = 86_400 * 1_000_000_000;
+        let day = self.0 / NANOS_PER_DAY;
+        match (day + 1).checked_mul(NANOS_PER_DAY) {
+            Some(n) => Ts(n),
+            None => Ts(day * NANOS_PER_DAY),
+        }
+    }
+
     pub fn to_utc_datetime(self) -> DateTime<Utc> {
         let when = UNIX_EPOCH + Duration::from_nanos(self.0);
         when.into()
@@ -311,14 +698,20 @@ impl fmt::Display for Ts {
 /// Open  ────────┤                              │
 ///                └─────────────────────────────┴──→ (terminal)
 ///                │
-///                └──────────────────────────────→ Cancelled (terminal)
+///                ├──────────────────────────────→ Cancelled (terminal)
+///                │
+///                └──────────────────────────────→ Expired (terminal)
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// `PartiallyFilled` may also move to `Cancelled` or `Expired`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Status {
     Open,
     PartiallyFilled,
     FullyFilled,
     Cancelled,
+    /// Taken off the book by the engine when its time in force ran out.
+    Expired,
 }
 
 impl Status {
@@ -329,7 +722,10 @@ impl Status {
 
     #[inline]
     pub const fn is_terminal(self) -> bool {
-        matches!(self, Status::FullyFilled | Status::Cancelled)
+        matches!(
+            self,
+            Status::FullyFilled | Status::Cancelled | Status::Expired
+        )
     }
 
     /// Whether the transition `self -> next` is valid under the one-way state
@@ -341,11 +737,14 @@ impl Status {
                 | (Status::PartiallyFilled, Status::PartiallyFilled)
                 | (Status::FullyFilled, Status::FullyFilled)
                 | (Status::Cancelled, Status::Cancelled)
+                | (Status::Expired, Status::Expired)
                 | (Status::Open, Status::PartiallyFilled)
                 | (Status::Open, Status::FullyFilled)
                 | (Status::Open, Status::Cancelled)
                 | (Status::PartiallyFilled, Status::FullyFilled)
                 | (Status::PartiallyFilled, Status::Cancelled)
+                | (Status::Open, Status::Expired)
+                | (Status::PartiallyFilled, Status::Expired)
         )
     }
 }
@@ -357,6 +756,7 @@ impl fmt::Display for Status {
             Status::PartiallyFilled => "PARTIAL",
             Status::FullyFilled => "FILLED",
             Status::Cancelled => "CANCELLED",
+            Status::Expired => "EXPIRED",
         })
     }
 }
@@ -397,6 +797,15 @@ mod tests {
         assert_eq!(OrderID::new(42).unwrap().value(), 42);
     }
 
+    #[test]
+    fn owner_id_rejects_zero() {
+        assert!(matches!(
+            OwnerID::new(0),
+            Err(NyquestroError::InvalidOwnerId)
+        ));
+        assert_eq!(format!("{}", OwnerID::new(9).unwrap()), "@9");
+    }
+
     #[test]
     fn side_opposite_is_involution() {
         assert_eq!(Side::Buy.opposite(), Side::Sell);
@@ -406,16 +815,16 @@ mod tests {
 
     #[test]
     fn px_from_cents_rejects_zero() {
-        assert!(Px::from_cents(0).is_err());
-        assert_eq!(Px::from_cents(1).unwrap().cents(), 1);
-        assert_eq!(Px::from_cents(12_345).unwrap().to_dollars(), 123.45);
+        assert!(Px::from_raw(0).is_err());
+        assert_eq!(Px::from_raw(1).unwrap().raw(), 1);
+        assert_eq!(Px::from_raw(12_345).unwrap().to_dollars(), 123.45);
     }
 
     #[test]
     fn px_from_dollars_rounds_to_nearest_cent() {
-        assert_eq!(Px::from_dollars(10.999).unwrap().cents(), 1100);
-        assert_eq!(Px::from_dollars(10.991).unwrap().cents(), 1099);
-        assert_eq!(Px::from_dollars(10.005).unwrap().cents(), 1001);
+        assert_eq!(Px::from_dollars(10.999).unwrap().raw(), 1100);
+        assert_eq!(Px::from_dollars(10.991).unwrap().raw(), 1099);
+        assert_eq!(Px::from_dollars(10.005).unwrap().raw(), 1001);
     }
 
     #[test]
@@ -429,8 +838,8 @@ mod tests {
 
     #[test]
     fn px_ordering_works_on_cents() {
-        assert!(Px::from_cents(100).unwrap() < Px::from_cents(200).unwrap());
-        assert!(Px::from_cents(200).unwrap() > Px::from_cents(100).unwrap());
+        assert!(Px::from_raw(100).unwrap() < Px::from_raw(200).unwrap());
+        assert!(Px::from_raw(200).unwrap() > Px::from_raw(100).unwrap());
     }
 
     #[test]
@@ -438,7 +847,43 @@ mod tests {
         assert!(Qty::ZERO.is_zero());
         assert_eq!(Qty::new(10).checked_sub(Qty::new(3)), Some(Qty::new(7)));
         assert_eq!(Qty::new(3).checked_sub(Qty::new(10)), None); // over-subtract
-        assert_eq!(Qty::new(u32::MAX).checked_add(Qty::new(1)), None); // overflow
+        assert_eq!(Qty::MAX.checked_add(Qty::new(1)), None); // overflow
+    }
+
+    #[test]
+    fn scale_parses_exactly() {
+        let sats = Scale::new(8).unwrap();
+        assert_eq!(sats.parse("5000").unwrap(), 500_000_000_000);
+        assert_eq!(sats.parse("0.00000001").unwrap(), 1);
+        assert_eq!(Scale::CENTS.parse("1.50000").unwrap(), 150);
+        assert_eq!(Scale::UNITS.parse("7.0").unwrap(), 7);
+        for bad in ["", ".5", "5.", "-1", "1e3", "1,000", "1.005", " 1"] {
+            assert!(Scale::CENTS.parse(bad).is_err(), "{bad:?}");
+        }
+        // 2^64 / 100 overflows at two places.
+        assert!(Scale::CENTS.parse("184467440737095517").is_err());
+        assert!(matches!(Scale::new(19), Err(NyquestroError::InvalidScale { decimals: 19 })));
+    }
+
+    #[test]
+    fn scaled_display_is_exact_and_padded() {
+        let four = Scale::new(4).unwrap();
+        assert_eq!(Px::from_raw(1_234).unwrap().scaled(four).to_string(), "0.1234");
+        assert_eq!(Qty::new(42).scaled(Scale::UNITS).to_string(), "42");
+        assert_eq!(format!("{:>8}", Qty::new(5).scaled(Scale::CENTS)), "    0.05");
+        let max = Scale::new(Scale::MAX_DECIMALS).unwrap();
+        assert_eq!(Px::MAX.scaled(max).to_string(), "18.446744073709551615");
+    }
+
+    #[test]
+    fn px_and_qty_text_round_trip() {
+        let px = Px::from_raw(12_345).unwrap();
+        assert_eq!(px.to_string(), "$123.45");
+        assert_eq!(px.to_string().parse::<Px>().unwrap(), px);
+        assert_eq!("0.07".parse::<Px>().unwrap().raw(), 7);
+        assert!("0".parse::<Px>().is_err());
+        let big = Qty::new(u64::from(u32::MAX) * 10);
+        assert_eq!(big.to_string().parse::<Qty>().unwrap(), big);
     }
 
     #[test]
@@ -456,6 +901,43 @@ mod tests {
         assert_eq!(b.duration_since(a), 4_000);
     }
 
+    #[test]
+    fn ts_next_utc_midnight() {
+        const DAY: u64 = 86_400 * 1_000_000_000;
+        assert_eq!(Ts::from_nanos(0).next_utc_midnight(), Ts::from_nanos(DAY));
+        assert_eq!(Ts::from_nanos(DAY - 1).next_utc_midnight(), Ts::from_nanos(DAY));
+        // Exactly midnight rolls to the following day.
+        assert_eq!(Ts::from_nanos(DAY).next_utc_midnight(), Ts::from_nanos(2 * DAY));
+    }
+
+    #[test]
+    fn time_in_force_resting_rules() {
+        assert_eq!(TimeInForce::default(), TimeInForce::Gtc);
+        assert!(TimeInForce::Gtc.can_rest());
+        assert!(TimeInForce::Day.can_rest());
+        assert!(TimeInForce::Gtd(Ts::from_nanos(5)).can_rest());
+        assert!(!TimeInForce::Ioc.can_rest());
+        assert!(!TimeInForce::Fok.can_rest());
+    }
+
+    #[test]
+    fn peg_prices_from_reference_offset_and_cap() {
+        let px = |c| Px::from_raw(c).unwrap();
+        let (bid, ask) = (Some(px(100)), Some(px(105)));
+        let primary = Peg { reference: PegReference::Primary, offset: -1, cap: None };
+        assert_eq!(primary.price(Side::Buy, bid, ask), Some(px(99)));
+        assert_eq!(primary.price(Side::Sell, bid, ask), Some(px(104)));
+        let market = Peg { reference: PegReference::Market, offset: 0, cap: Some(px(103)) };
+        assert_eq!(market.price(Side::Buy, bid, ask), Some(px(103)));
+        assert_eq!(market.price(Side::Sell, bid, ask), Some(px(103)));
+        let mid = Peg { reference: PegReference::Midpoint, offset: 0, cap: None };
+        assert_eq!(mid.price(Side::Buy, bid, ask), Some(px(102)));
+        assert_eq!(mid.price(Side::Sell, bid, ask), Some(px(103)));
+        assert_eq!(mid.price(Side::Buy, bid, None), None);
+        let deep = Peg { reference: PegReference::Primary, offset: -500, cap: None };
+        assert_eq!(deep.price(Side::Buy, bid, ask), Some(Px::MIN));
+    }
+
     #[test]
     fn status_one_way_transitions() {
         assert!(Status::Open.can_transition_to(Status::PartiallyFilled));
@@ -469,6 +951,10 @@ mod tests {
         assert!(!Status::FullyFilled.can_transition_to(Status::Open));
         assert!(!Status::FullyFilled.can_transition_to(Status::PartiallyFilled));
         assert!(!Status::Cancelled.can_transition_to(Status::Open));
+        assert!(Status::Open.can_transition_to(Status::Expired));
+        assert!(Status::PartiallyFilled.can_transition_to(Status::Expired));
+        assert!(!Status::Expired.can_transition_to(Status::Open));
+        assert!(!Status::FullyFilled.can_transition_to(Status::Expired));
     }
 
     #[test]
@@ -478,5 +964,7 @@ mod tests {
         assert!(!Status::FullyFilled.is_active());
         assert!(Status::FullyFilled.is_terminal());
         assert!(Status::Cancelled.is_terminal());
+        assert!(Status::Expired.is_terminal());
+        assert!(!Status::Expired.is_active());
     }
 }
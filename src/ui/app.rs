@@ -4,6 +4,10 @@
 //! on the main thread. A 50ms tick advances every simulator and applies
 //! its output to the engine; a 33ms render tick paints. Both share one
 //! `App`. The `Tab` key cycles the symbol the dashboard focuses on.
+//!
+//! Everything the dashboard sends the engine is stamped by the `App`'s
+//! [`Sequencer`]: from simulated time in synthetic mode, advanced by each
+//! tick's scaled step, and from a monotonic clock in live mode.
 
 use std::collections::VecDeque;
 use std::io::{stdout, Stdout};
@@ -19,11 +23,15 @@ use ratatui::Terminal;
 
 use std::sync::mpsc::Receiver;
 
-use crate::book::{Market, OrderBook};
-use crate::events::{FillEvent, OrderEvent, OrderRejectionReason, QuoteSide};
+use crate::book::{BookConfig, Market, OrderBook, SubmitResult};
+use crate::command::Command;
+use crate::errors::{NyquestroError, NyquestroResult};
+use crate::events::{FillEvent, OrderEvent, OrderRejectionReason, QuoteSide, StateReason};
 use crate::feed::FeedAction;
+use crate::instrument::{InstrumentSpec, ReferenceData};
 use crate::metrics::{MetricsRegistry, Op};
 use crate::order::Order;
+use crate::sequencer::{Clock, ManualClock, MonotonicClock, Sequencer};
 use crate::simulator::{MarketSimulator, SimAction, SimConfig};
 use crate::telemetry::{TelemetryEvent, TelemetryHandle};
 use crate::types::{OrderID, Px, Qty, Side, Symbol, Ts};
@@ -36,6 +44,9 @@ const POLL_TICK: Duration = Duration::from_millis(10);
 /// Default seed for the Reset key.
 const RESET_SEED: u64 = 0xC0FFEE;
 
+/// The time a dashboard command carries until the sequencer stamps it.
+const UNSTAMPED: Ts = Ts::from_nanos(0);
+
 #[derive(Debug, Clone, Copy)]
 pub enum Action {
     Quit,
@@ -58,13 +69,15 @@ pub struct TapePrint {
     pub symbol: Symbol,
     pub price: Px,
     pub quantity: Qty,
-    pub aggressor: Side,
+    /// `None` for an auction uncross print.
+    pub aggressor: Option<Side>,
     pub at: Ts,
 }
 
 /// Per-symbol state: simulator + tape ring + mid history + lifetime totals.
 pub struct SymbolState {
     pub symbol: Symbol,
+    pub instrument: InstrumentSpec,
     pub sim: MarketSimulator,
     pub tape: VecDeque<TapePrint>,
     pub mid_history: VecDeque<u64>,
@@ -77,14 +90,21 @@ pub struct SymbolState {
 }
 
 impl SymbolState {
-    pub fn new(symbol: Symbol, fair_value_cents: u64, seed: u64) -> Self {
+    pub fn new(
+        symbol: Symbol,
+        fair_value: u64,
+        instrument: InstrumentSpec,
+        seed: u64,
+    ) -> Self {
         let cfg = SimConfig {
             symbol,
-            fair_value_cents,
+            fair_value,
+            instrument,
             ..SimConfig::default()
         };
         SymbolState {
             symbol,
+            instrument,
             sim: MarketSimulator::new(cfg, seed),
             tape: VecDeque::with_capacity(200),
             mid_history: VecDeque::with_capacity(600),
@@ -96,6 +116,15 @@ impl SymbolState {
             last_resting_refresh: Instant::now(),
         }
     }
+
+    /// Register this symbol's book, enforcing its instrument spec.
+    fn register(&self, market: &mut Market) {
+        let config = BookConfig {
+            instrument: Some(self.instrument),
+            ..BookConfig::default()
+        };
+        market.register_with(self.symbol, config);
+    }
 }
 
 /// Where the dashboard's flow comes from. The two modes are mutually
@@ -108,6 +137,11 @@ pub enum Mode {
 
 pub struct App {
     pub market: Market,
+    /// Stamps every command on its way into `market`.
+    pub sequencer: Sequencer<Box<dyn Clock>>,
+    /// The simulated time the sequencer reads in synthetic mode; `None`
+    /// in live mode, where it reads a monotonic clock.
+    engine_time: Option<ManualClock>,
     pub symbols: Vec<SymbolState>,
     pub selected_idx: usize,
     pub metrics: MetricsRegistry,
@@ -162,15 +196,24 @@ struct RateBaseline {
 impl App {
     /// Construct a dashboard for three default synthetic symbols (AAPL,
     /// MSFT, NVDA) with realistic fair values. Each symbol gets a distinct
-    /// seed derived from the user-supplied master seed.
+    /// seed derived from the user-supplied master seed, and its book and
+    /// simulator share the symbol's spec from the built-in reference data.
     pub fn new(seed: u64, telemetry: TelemetryHandle) -> Self {
-        let aapl = Symbol::from_const("AAPL");
-        let msft = Symbol::from_const("MSFT");
-        let nvda = Symbol::from_const("NVDA");
+        let engine_time = ManualClock::default();
+        let reference = ReferenceData::builtin();
+        let state = |name: &'static str, fair: u64, salt: u64| {
+            let sym = Symbol::from_const(name);
+            SymbolState::new(sym, fair, reference.spec_or_default(sym), seed.wrapping_add(salt))
+        };
+        let symbols = vec![
+            state("AAPL", 15_000, 0xA1),
+            state("MSFT", 30_000, 0xB2),
+            state("NVDA", 50_000, 0xC3),
+        ];
         let mut market = Market::new();
-        market.register(aapl);
-        market.register(msft);
-        market.register(nvda);
+        for s in &symbols {
+            s.register(&mut market);
+        }
         let symbols_str: Vec<String> = vec!["AAPL".into(), "MSFT".into(), "NVDA".into()];
         telemetry.record(TelemetryEvent::Startup {
             mode: "synthetic",
@@ -180,11 +223,9 @@ impl App {
         });
         App {
             market,
-            symbols: vec![
-                SymbolState::new(aapl, 15_000, seed.wrapping_add(0xA1)),
-                SymbolState::new(msft, 30_000, seed.wrapping_add(0xB2)),
-                SymbolState::new(nvda, 50_000, seed.wrapping_add(0xC3)),
-            ],
+            sequencer: Sequencer::new(Box::new(engine_time.clone())),
+            engine_time: Some(engine_time),
+            symbols,
             selected_idx: 0,
             metrics: MetricsRegistry::new(),
             state: EngineState::Running,
@@ -211,11 +252,14 @@ impl App {
         feed_rx: Receiver<FeedAction>,
         telemetry: TelemetryHandle,
     ) -> Self {
+        let reference = ReferenceData::builtin();
         let mut market = Market::new();
         let mut states = Vec::with_capacity(symbols.len());
         for (i, (sym, fair)) in symbols.iter().enumerate() {
-            market.register(*sym);
-            states.push(SymbolState::new(*sym, *fair, (i as u64).wrapping_add(0xFEED)));
+            let spec = reference.spec_or_default(*sym);
+            let state = SymbolState::new(*sym, *fair, spec, (i as u64).wrapping_add(0xFEED));
+            state.register(&mut market);
+            states.push(state);
         }
         let symbols_str: Vec<String> = symbols.iter().map(|(s, _)| s.to_string()).collect();
         telemetry.record(TelemetryEvent::Startup {
@@ -226,6 +270,8 @@ impl App {
         });
         App {
             market,
+            sequencer: Sequencer::new(Box::new(MonotonicClock::new())),
+            engine_time: None,
             symbols: states,
             selected_idx: 0,
             metrics: MetricsRegistry::new(),
@@ -318,6 +364,9 @@ impl App {
         match &mut taken {
             Mode::Synthetic => {
                 let scaled_dt = dt_secs * self.speed;
+                if let Some(clock) = &self.engine_time {
+                    clock.advance((scaled_dt * 1_000_000_000.0) as u64);
+                }
                 for idx in 0..self.symbols.len() {
                     let actions = self.symbols[idx].sim.step(scaled_dt);
                     for a in actions {
@@ -356,9 +405,9 @@ impl App {
                     if let Some(book) = self.market.book(symbol)
                         && let Some(mp) = book.microprice()
                     {
-                        let mp_cents = mp.round() as u64;
+                        let mp_raw = mp.round() as u64;
                         let hist = &mut self.symbols[idx].mid_history;
-                        hist.push_back(mp_cents);
+                        hist.push_back(mp_raw);
                         if hist.len() > 600 {
                             hist.pop_front();
                         }
@@ -455,7 +504,7 @@ impl App {
                 let (depth_bid, depth_ask) = book.depth(10);
                 let ofi = book.ofi(10);
                 let microprice_c = book.microprice().map(|f| f.round() as u64);
-                let spread_c = book.spread_cents();
+                let spread_c = book.spread();
                 self.telemetry.record(TelemetryEvent::BookState {
                     sym: sym.to_string(),
                     levels_bid: n_bid,
@@ -502,7 +551,7 @@ impl App {
     /// simulator's current mid to the history ring and refresh the
     /// resting-id cache periodically.
     fn bookkeep_per_symbol(&mut self, idx: usize) {
-        let mid = self.symbols[idx].sim.mid_cents();
+        let mid = self.symbols[idx].sim.mid();
         let hist = &mut self.symbols[idx].mid_history;
         hist.push_back(mid);
         if hist.len() > 600 {
@@ -527,27 +576,91 @@ impl App {
             SimAction::Cancel { symbol, order_id } => {
                 self.handle_cancel(symbol, order_id, idx)
             }
+            SimAction::Replace {
+                symbol,
+                order_id,
+                side,
+                price,
+                quantity,
+            } => self.handle_replace(symbol, order_id, side, price, quantity, idx),
+        }
+    }
+
+    fn handle_replace(
+        &mut self,
+        symbol: Symbol,
+        order_id: OrderID,
+        side: Side,
+        price: Px,
+        quantity: Qty,
+        idx: usize,
+    ) {
+        let started = Instant::now();
+        self.telemetry.record(TelemetryEvent::Replace {
+            sym: symbol.to_string(),
+            id: order_id.value(),
+            px_c: price.raw(),
+            qty: quantity.value(),
+        });
+        let replace = Command::Replace {
+            symbol,
+            id: order_id,
+            px: price,
+            qty: quantity,
+            ts: UNSTAMPED,
+        };
+        match self.apply(replace) {
+            Ok(res) => {
+                let elapsed = started.elapsed();
+                self.metrics.record_latency(Op::Submit, elapsed);
+                if !res.fills.is_empty() {
+                    self.metrics.record_latency(Op::Match, elapsed);
+                }
+                self.absorb_result(idx, &res);
+            }
+            Err(NyquestroError::OrderNotFound(_)) => {
+                // Fills consumed the level's order, but the feed still
+                // shows the level: put it back under the same id, which
+                // the bridge keeps keyed to the level.
+                if let Ok(order) = Order::new(order_id, symbol, side, price, quantity, UNSTAMPED) {
+                    self.handle_submit(order, idx);
+                }
+            }
+            Err(_) => {
+                // Any other refusal is benign, as for cancel. Don't
+                // telemeter.
+            }
         }
     }
 
     fn handle_cancel(&mut self, symbol: Symbol, order_id: OrderID, idx: usize) {
         let started = Instant::now();
-        let ts = Ts::from_nanos(self.uptime().as_nanos() as u64);
-        match self.market.cancel(symbol, order_id, ts) {
-            Ok(ev) => {
+        let cancel = Command::Cancel {
+            symbol,
+            id: order_id,
+            ts: UNSTAMPED,
+        };
+        match self.apply(cancel) {
+            Ok(res) => {
                 self.metrics.record_latency(Op::Cancel, started.elapsed());
                 self.metrics.record_cancels(1);
                 self.symbols[idx].total_cancels =
                     self.symbols[idx].total_cancels.saturating_add(1);
-                let remaining = match ev {
-                    OrderEvent::Cancelled { remaining, .. } => remaining.value(),
-                    _ => 0,
-                };
+                let remaining = res
+                    .lifecycle
+                    .iter()
+                    .find_map(|ev| match ev {
+                        OrderEvent::Cancelled { remaining, .. } => Some(remaining.value()),
+                        _ => None,
+                    })
+                    .unwrap_or(0);
+                self.absorb_result(idx, &res);
                 self.telemetry.record(TelemetryEvent::Cancel {
                     sym: symbol.to_string(),
                     id: order_id.value(),
                     remaining,
                 });
+                self.reprice_after_cancel(symbol, idx);
             }
             Err(_) => {
                 // Cancel-of-unknown-id is benign in live mode (we may
@@ -561,18 +674,19 @@ impl App {
         let symbol = order.symbol();
         let order_id = order.id();
         let order_qty = order.quantity();
-        let order_px = order.price();
+        // Market orders carry no limit of their own; log them at price 0.
+        let order_px_c = if order.is_market() { 0 } else { order.price().raw() };
         let started = Instant::now();
         // Telemetry: record the submit before dispatch so the audit trail
         // captures intent even if the engine rejects.
         self.telemetry.record(TelemetryEvent::Submit {
             sym: symbol.to_string(),
             side: side_str(aggressor_side),
-            px_c: order_px.cents(),
+            px_c: order_px_c,
             qty: order_qty.value(),
             id: order_id.value(),
         });
-        match self.market.submit_limit(order) {
+        match self.apply(Command::Submit(order)) {
             Ok(res) => {
                 let elapsed = started.elapsed();
                 self.metrics.record_latency(Op::Submit, elapsed);
@@ -583,47 +697,7 @@ impl App {
                 self.symbols[idx].total_orders =
                     self.symbols[idx].total_orders.saturating_add(1);
 
-                for f in &res.fills {
-                    self.metrics.record_fills(1);
-                    self.symbols[idx].total_fills =
-                        self.symbols[idx].total_fills.saturating_add(1);
-                    self.telemetry.record(TelemetryEvent::Fill {
-                        sym: f.symbol.to_string(),
-                        px_c: f.price.cents(),
-                        qty: f.quantity.value(),
-                        buyer: f.buyer_order_id.value(),
-                        seller: f.seller_order_id.value(),
-                    });
-                    self.push_print(idx, *f, aggressor_side);
-                }
-                for ev in &res.lifecycle {
-                    if let OrderEvent::Rejected {
-                        order_id, reason, ..
-                    } = ev
-                    {
-                        self.metrics.record_rejects(1);
-                        self.symbols[idx].total_rejects =
-                            self.symbols[idx].total_rejects.saturating_add(1);
-                        self.telemetry.record(TelemetryEvent::Reject {
-                            sym: ev.symbol().to_string(),
-                            id: order_id.value(),
-                            reason: rejection_reason_str(*reason),
-                        });
-                    }
-                }
-                self.metrics.record_quotes(res.quotes.len() as u64);
-                // Sample quotes 1-in-10 — busy live mode produces 1k+/sec.
-                for q in &res.quotes {
-                    self.quote_sample_counter = self.quote_sample_counter.wrapping_add(1);
-                    if self.quote_sample_counter.is_multiple_of(10) {
-                        self.telemetry.record(TelemetryEvent::Quote {
-                            sym: q.symbol.to_string(),
-                            side: quote_side_str(q.side),
-                            px_c: q.price.cents(),
-                            qty: q.quantity.value(),
-                        });
-                    }
-                }
+                self.absorb_result(idx, &res);
             }
             Err(_) => {
                 self.metrics.record_rejects(1);
@@ -638,6 +712,62 @@ impl App {
         }
     }
 
+    /// Fold one engine result into the dashboard: fill counters, tape,
+    /// reject counters, quote sampling, and the matching telemetry.
+    fn absorb_result(&mut self, idx: usize, res: &SubmitResult) {
+        for f in &res.fills {
+            self.metrics.record_fills(1);
+            self.symbols[idx].total_fills =
+                self.symbols[idx].total_fills.saturating_add(1);
+            self.telemetry.record(TelemetryEvent::Fill {
+                sym: f.symbol.to_string(),
+                px_c: f.price.raw(),
+                qty: f.quantity.value(),
+                buyer: f.buyer_order_id.value(),
+                seller: f.seller_order_id.value(),
+                trade: f.trade_id,
+                seq: f.seq,
+            });
+            self.push_print(idx, *f);
+        }
+        for ev in &res.lifecycle {
+            if let OrderEvent::Rejected {
+                order_id, reason, ..
+            } = ev
+            {
+                self.metrics.record_rejects(1);
+                self.symbols[idx].total_rejects =
+                    self.symbols[idx].total_rejects.saturating_add(1);
+                self.telemetry.record(TelemetryEvent::Reject {
+                    sym: ev.symbol().to_string(),
+                    id: order_id.value(),
+                    reason: rejection_reason_str(*reason),
+                });
+            }
+        }
+        for s in &res.state {
+            self.telemetry.record(TelemetryEvent::TradingState {
+                sym: s.symbol.to_string(),
+                from: s.from.as_str(),
+                to: s.to.as_str(),
+                reason: state_reason_str(s.reason),
+            });
+        }
+        self.metrics.record_quotes(res.quotes.len() as u64);
+        // Sample quotes 1-in-10 — busy live mode produces 1k+/sec.
+        for q in &res.quotes {
+            self.quote_sample_counter = self.quote_sample_counter.wrapping_add(1);
+            if self.quote_sample_counter.is_multiple_of(10) {
+                self.telemetry.record(TelemetryEvent::Quote {
+                    sym: q.symbol.to_string(),
+                    side: quote_side_str(q.side),
+                    px_c: q.price.raw(),
+                    qty: q.quantity.value(),
+                });
+            }
+        }
+    }
+
     fn handle_cancel_hint(&mut self, idx: usize) {
         let resting = &self.symbols[idx].resting_ids;
         if resting.is_empty() {
@@ -646,17 +776,39 @@ impl App {
         let cancels = self.symbols[idx].total_cancels;
         let id = resting[(cancels as usize) % resting.len()];
         let started = Instant::now();
-        let ts = Ts::from_nanos(self.uptime().as_nanos() as u64);
         let symbol = self.symbols[idx].symbol;
-        if self.market.cancel(symbol, id, ts).is_ok() {
+        let cancel = Command::Cancel {
+            symbol,
+            id,
+            ts: UNSTAMPED,
+        };
+        if let Ok(res) = self.apply(cancel) {
             self.metrics.record_latency(Op::Cancel, started.elapsed());
             self.metrics.record_cancels(1);
             self.symbols[idx].total_cancels =
                 self.symbols[idx].total_cancels.saturating_add(1);
+            self.absorb_result(idx, &res);
+            self.reprice_after_cancel(symbol, idx);
         }
     }
 
-    fn push_print(&mut self, idx: usize, f: FillEvent, aggressor: Side) {
+    /// A cancel can move the price pegged orders track; let the book catch
+    /// them up and fold in whatever that produces.
+    fn reprice_after_cancel(&mut self, symbol: Symbol, idx: usize) {
+        let Ok(res) = self.apply(Command::Reprice { symbol, ts: UNSTAMPED }) else {
+            return;
+        };
+        self.absorb_result(idx, &res);
+    }
+
+    /// Stamp `command` with the next sequence number and engine time, then
+    /// apply it to the market and collect what it produces.
+    fn apply(&mut self, command: Command) -> NyquestroResult<SubmitResult> {
+        let stamped = self.sequencer.stamp(command);
+        SubmitResult::collect(|out| stamped.command.apply(&mut self.market, out))
+    }
+
+    fn push_print(&mut self, idx: usize, f: FillEvent) {
         let tape = &mut self.symbols[idx].tape;
         if tape.len() >= 200 {
             tape.pop_back();
@@ -665,7 +817,7 @@ impl App {
             symbol: f.symbol,
             price: f.price,
             quantity: f.quantity,
-            aggressor,
+            aggressor: f.aggressor,
             at: f.timestamp,
         });
     }
@@ -712,9 +864,13 @@ impl App {
             }
             Action::Reset => {
                 self.market = Market::new();
+                if let Some(clock) = &self.engine_time {
+                    clock.set(Ts::from_nanos(0));
+                    self.sequencer = Sequencer::new(Box::new(clock.clone()));
+                }
                 let count = self.symbols.len();
                 for (i, s) in self.symbols.iter_mut().enumerate() {
-                    self.market.register(s.symbol);
+                    s.register(&mut self.market);
                     s.sim.reseed(RESET_SEED.wrapping_add(i as u64));
                     s.tape.clear();
                     s.mid_history.clear();
@@ -812,6 +968,27 @@ fn rejection_reason_str(r: OrderRejectionReason) -> &'static str {
         OrderRejectionReason::InvalidOrderId => "InvalidOrderId",
         OrderRejectionReason::SelfMatch => "SelfMatch",
         OrderRejectionReason::DuplicateOrderId => "DuplicateOrderId",
+        OrderRejectionReason::FillOrKillUnfilled => "FillOrKillUnfilled",
+        OrderRejectionReason::AlreadyExpired => "AlreadyExpired",
+        OrderRejectionReason::NoLiquidity => "NoLiquidity",
+        OrderRejectionReason::NoReferencePrice => "NoReferencePrice",
+        OrderRejectionReason::PostOnlyWouldTake => "PostOnlyWouldTake",
+        OrderRejectionReason::MinQuantityUnfilled => "MinQuantityUnfilled",
+        OrderRejectionReason::NotAcceptedInAuction => "NotAcceptedInAuction",
+        OrderRejectionReason::TradingHalted => "TradingHalted",
+        OrderRejectionReason::MarketClosed => "MarketClosed",
+        OrderRejectionReason::PriceOffTick => "PriceOffTick",
+        OrderRejectionReason::PriceOutOfRange => "PriceOutOfRange",
+        OrderRejectionReason::QuantityOffLot => "QuantityOffLot",
+        OrderRejectionReason::QuantityBelowMinimum => "QuantityBelowMinimum",
+        OrderRejectionReason::QuantityAboveMaximum => "QuantityAboveMaximum",
+    }
+}
+
+fn state_reason_str(r: StateReason) -> &'static str {
+    match r {
+        StateReason::Requested => "requested",
+        StateReason::VolatilityHalt => "volatility_halt",
     }
 }
 
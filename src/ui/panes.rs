@@ -12,8 +12,9 @@ use ratatui::widgets::{
 use ratatui::Frame;
 
 use crate::book::OrderBook;
+use crate::instrument::InstrumentSpec;
 use crate::metrics::registry::LatencySnapshot;
-use crate::types::{Px, Qty};
+use crate::types::{Px, Qty, TradingState};
 use crate::ui::app::{App, EngineState, Mode, SymbolState};
 use crate::ui::theme;
 
@@ -79,8 +80,9 @@ fn render_top_status(frame: &mut Frame, area: Rect, app: &App) {
     );
     // Mid: in Synthetic mode, the simulator's OU mid; in Live mode, the
     // most recent observed mid_history sample.
-    let mid_cents = match &app.mode {
-        Mode::Synthetic => app.selected_state().sim.mid_cents(),
+    let spec = app.selected_state().instrument;
+    let mid_raw = match &app.mode {
+        Mode::Synthetic => app.selected_state().sim.mid(),
         Mode::Live { .. } => app
             .selected_state()
             .mid_history
@@ -88,10 +90,10 @@ fn render_top_status(frame: &mut Frame, area: Rect, app: &App) {
             .copied()
             .unwrap_or(0),
     };
-    let mid = if mid_cents == 0 {
+    let mid = if mid_raw == 0 {
         "—".to_string()
     } else {
-        format_price_cents(mid_cents)
+        format_price(&spec, mid_raw)
     };
     let mid_span = Span::styled(format!("mid {mid}"), theme::fg(theme::ACCENT));
     let live_status = match &app.mode {
@@ -215,23 +217,24 @@ fn render_depth_of_book(frame: &mut Frame, area: Rect, app: &App) {
         .max(1) as f64;
 
     let bar_width = (inner.width as usize).saturating_sub(28);
+    let spec = app.selected_state().instrument;
     let mut lines: Vec<Line> = Vec::new();
 
     for (px, qty) in asks.iter().rev() {
-        lines.push(level_line(*px, *qty, max_qty, bar_width, theme::ASK));
+        lines.push(level_line(&spec, *px, *qty, max_qty, bar_width, theme::ASK));
     }
 
     let spread_row = match (book.best_bid(), book.best_ask()) {
         (Some((b, _)), Some((a, _))) => {
-            let spread_cents = a.cents().saturating_sub(b.cents());
-            let bps = if a.cents() > 0 {
-                spread_cents as f64 / a.cents() as f64 * 10_000.0
+            let spread = a.raw().saturating_sub(b.raw());
+            let bps = if a.raw() > 0 {
+                spread as f64 / a.raw() as f64 * 10_000.0
             } else {
                 0.0
             };
             format!(
                 "── spread {} / {:.1} bp ──",
-                format_price_cents(spread_cents),
+                format_price(&spec, spread),
                 bps
             )
         }
@@ -243,7 +246,7 @@ fn render_depth_of_book(frame: &mut Frame, area: Rect, app: &App) {
     )]));
 
     for (px, qty) in bids.iter() {
-        lines.push(level_line(*px, *qty, max_qty, bar_width, theme::BID));
+        lines.push(level_line(&spec, *px, *qty, max_qty, bar_width, theme::BID));
     }
 
     if asks.is_empty() && bids.is_empty() {
@@ -258,7 +261,7 @@ fn render_depth_of_book(frame: &mut Frame, area: Rect, app: &App) {
         let (depth_bid, depth_ask) = book.depth(10);
         let pressure_width = (inner.width as usize).saturating_sub(28);
         let (l_bar, r_bar, left_frac) =
-            theme::pressure_bar(depth_bid.value() as u64, depth_ask.value() as u64, pressure_width);
+            theme::pressure_bar(depth_bid.value(), depth_ask.value(), pressure_width);
         let pct_left = (left_frac * 100.0).round() as u32;
         let pct_right = 100 - pct_left;
         let ratio_str = if depth_ask.value() == 0 {
@@ -284,12 +287,19 @@ fn render_depth_of_book(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(Paragraph::new(lines), inner);
 }
 
-fn level_line(price: Px, qty: Qty, max_qty: f64, bar_width: usize, color: Color) -> Line<'static> {
+fn level_line(
+    spec: &InstrumentSpec,
+    price: Px,
+    qty: Qty,
+    max_qty: f64,
+    bar_width: usize,
+    color: Color,
+) -> Line<'static> {
     let ratio = qty.value() as f64 / max_qty;
     let bar = theme::block_bar(ratio, bar_width);
     Line::from(vec![
-        Span::styled(format!("  {:>8} ", format_price_cents(price.cents())), theme::neutral()),
-        Span::styled(format!("{:>5}  ", qty.value()), theme::fg(color)),
+        Span::styled(format!("  {:>8} ", format_price(spec, price.raw())), theme::neutral()),
+        Span::styled(format!("{:>5}  ", spec.format_qty(qty)), theme::fg(color)),
         Span::styled(bar, theme::fg(color)),
     ])
 }
@@ -304,6 +314,7 @@ fn render_trade_tape(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(block, area);
 
     let tape = &app.selected_state().tape;
+    let spec = app.selected_state().instrument;
     let cap = inner.height as usize;
 
     // Visible-window max for size-bar normalisation. Re-computed per
@@ -323,19 +334,20 @@ fn render_trade_tape(frame: &mut Frame, area: Rect, app: &App) {
     let mut lines: Vec<Line> = Vec::with_capacity(cap);
     for print in visible {
         let (color, glyph) = match print.aggressor {
-            crate::types::Side::Buy => (theme::BID, "▲"),
-            crate::types::Side::Sell => (theme::ASK, "▼"),
+            Some(crate::types::Side::Buy) => (theme::BID, "▲"),
+            Some(crate::types::Side::Sell) => (theme::ASK, "▼"),
+            None => (theme::ACCENT, "◆"),
         };
         let ts = format_clock_ns(print.at.nanos());
         let bar = theme::block_bar(print.quantity.value() as f64 / max_qty, bar_width);
         lines.push(Line::from(vec![
             Span::styled(format!(" {ts}  "), theme::fg_dim(theme::CHROME)),
             Span::styled(
-                format!("{:>9}", format_price_cents(print.price.cents())),
+                format!("{:>9}", format_price(&spec, print.price.raw())),
                 theme::fg(color),
             ),
             Span::styled(format!("  {glyph} "), theme::fg(color)),
-            Span::styled(format!("{:>5} ", print.quantity.value()), theme::neutral()),
+            Span::styled(format!("{:>5} ", spec.format_qty(print.quantity)), theme::neutral()),
             Span::styled(bar, theme::fg(color)),
         ]));
     }
@@ -642,38 +654,43 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
 
     let book = app.selected_book();
     let state: &SymbolState = app.selected_state();
+    let spec = state.instrument;
 
     let bid = book
         .and_then(|b| b.best_bid())
-        .map(|(p, _)| format_price_cents(p.cents()))
+        .map(|(p, _)| format_price(&spec, p.raw()))
         .unwrap_or_else(|| "—".into());
     let ask = book
         .and_then(|b| b.best_ask())
-        .map(|(p, _)| format_price_cents(p.cents()))
+        .map(|(p, _)| format_price(&spec, p.raw()))
         .unwrap_or_else(|| "—".into());
     let bid_qty = book
         .and_then(|b| b.best_bid())
-        .map(|(_, q)| q.value().to_string())
+        .map(|(_, q)| spec.format_qty(q).to_string())
         .unwrap_or_else(|| "—".into());
     let ask_qty = book
         .and_then(|b| b.best_ask())
-        .map(|(_, q)| q.value().to_string())
+        .map(|(_, q)| spec.format_qty(q).to_string())
         .unwrap_or_else(|| "—".into());
 
     let spread = book
-        .and_then(|b| b.spread_cents())
+        .and_then(|b| b.spread())
         .map(|c| {
             let bps = book
                 .and_then(|b| b.best_ask())
-                .map(|(a, _)| c as f64 / a.cents() as f64 * 10_000.0)
+                .map(|(a, _)| c as f64 / a.raw() as f64 * 10_000.0)
                 .unwrap_or(0.0);
-            format!("{} ({:.1} bp)", format_price_cents(c), bps)
+            format!("{} ({:.1} bp)", format_price(&spec, c), bps)
         })
         .unwrap_or_else(|| "—".into());
 
     let microprice = book
         .and_then(|b| b.microprice())
-        .map(|cents| format!("${:.4}", cents / 100.0))
+        .map(|raw| {
+            // Two places finer than the price grid.
+            let places = usize::from(spec.price_scale.decimals()) + 2;
+            format!("${:.*}", places, raw / spec.price_scale.factor() as f64)
+        })
         .unwrap_or_else(|| "—".into());
 
     let ofi = book.map(|b| b.ofi(10)).unwrap_or(0.0);
@@ -691,6 +708,14 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
         .map(|b| b.depth(10))
         .unwrap_or((Qty::ZERO, Qty::ZERO));
 
+    let trading = book.map(|b| b.state()).unwrap_or_default();
+    let trading_color = match trading {
+        TradingState::Continuous => theme::GOOD,
+        TradingState::PreOpen => theme::WARN,
+        TradingState::Halted => theme::ALERT,
+        TradingState::Closed => theme::CHROME,
+    };
+
     let kv_styled = |k: &str, v: String, c: Color| -> Line<'static> {
         Line::from(vec![
             Span::styled(format!("  {k:<13}"), theme::fg_dim(theme::CHROME)),
@@ -711,8 +736,8 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
     let axis_width = (inner.width as usize).saturating_sub(8).max(20);
     let microprice_axis_str = match (book.and_then(|b| b.best_bid()), book.and_then(|b| b.best_ask())) {
         (Some((b, _)), Some((a, _))) => {
-            let mp = book.and_then(|b| b.microprice()).map(|f| f.round() as u64).unwrap_or((a.cents() + b.cents()) / 2);
-            theme::microprice_axis(b.cents(), a.cents(), mp, axis_width)
+            let mp = book.and_then(|b| b.microprice()).map(|f| f.round() as u64).unwrap_or((a.raw() + b.raw()) / 2);
+            theme::microprice_axis(b.raw(), a.raw(), mp, axis_width)
         }
         _ => "─".repeat(axis_width),
     };
@@ -721,10 +746,10 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
     // spreads are 0.1bp (one cell or less) which renders as a tiny
     // sliver = "tight"; meme coins or thin books show much more fill.
     let spread_bps = book
-        .and_then(|b| b.spread_cents())
+        .and_then(|b| b.spread())
         .and_then(|c| {
             book.and_then(|b| b.best_ask())
-                .map(|(a, _)| c as f64 / a.cents() as f64 * 10_000.0)
+                .map(|(a, _)| c as f64 / a.raw() as f64 * 10_000.0)
         })
         .unwrap_or(0.0);
     let spread_gauge_width = 20usize;
@@ -748,8 +773,8 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
     // total proportionally. Width fills the pane.
     let depth_width = (inner.width as usize).saturating_sub(8).max(16);
     let (l_bar, r_bar, _) = theme::pressure_bar(
-        depth_bid.value() as u64,
-        depth_ask.value() as u64,
+        depth_bid.value(),
+        depth_ask.value(),
         depth_width,
     );
 
@@ -763,6 +788,7 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
 
     let lines = vec![
         Line::from(Span::styled(" microstructure", theme::bold())),
+        kv_styled("state", trading.to_string(), trading_color),
         kv_styled("best bid", format!("{bid} × {bid_qty}"), theme::BID),
         kv_styled("best ask", format!("{ask} × {ask_qty}"), theme::ASK),
         Line::from(vec![
@@ -798,7 +824,10 @@ fn render_engine_summary(frame: &mut Frame, area: Rect, app: &App) {
             Span::styled("    bid  ", theme::fg(theme::BID)),
             Span::styled(l_bar, theme::fg(theme::BID)),
             Span::styled(r_bar, theme::fg(theme::ASK)),
-            Span::styled(format!("  {} / {}", depth_bid.value(), depth_ask.value()), theme::neutral()),
+            Span::styled(
+                format!("  {} / {}", spec.format_qty(depth_bid), spec.format_qty(depth_ask)),
+                theme::neutral(),
+            ),
         ]),
         Line::from(""),
         Line::from(vec![
@@ -855,10 +884,9 @@ fn pane_block(title: &str) -> Block<'_> {
         .title(Span::styled(format!(" {title} "), theme::bold()))
 }
 
-fn format_price_cents(cents: u64) -> String {
-    let dollars = cents / 100;
-    let frac = cents % 100;
-    format!("${dollars}.{frac:02}")
+/// A raw price (or price difference) at the instrument's price scale.
+fn format_price(spec: &InstrumentSpec, raw: u64) -> String {
+    format!("${}", spec.price_scale.display(raw))
 }
 
 fn format_latency_ns(ns: u64) -> String {
@@ -136,16 +136,16 @@ pub fn pressure_bar(left: u64, right: u64, width: usize) -> (String, String, f64
 /// `╋` marker placed at the position corresponding to the microprice.
 /// Returns a string of length `width + 1` (the `╋` adds one cell).
 pub fn microprice_axis(
-    bid_cents: u64,
-    ask_cents: u64,
-    microprice_cents: u64,
+    bid: u64,
+    ask: u64,
+    microprice: u64,
     width: usize,
 ) -> String {
-    if ask_cents <= bid_cents || width < 3 {
+    if ask <= bid || width < 3 {
         return "─".repeat(width);
     }
-    let span = (ask_cents - bid_cents) as f64;
-    let offset = (microprice_cents.saturating_sub(bid_cents)) as f64;
+    let span = (ask - bid) as f64;
+    let offset = (microprice.saturating_sub(bid)) as f64;
     let frac = (offset / span).clamp(0.0, 1.0);
     let pos = (frac * (width - 1) as f64).round() as usize;
     let mut s = String::with_capacity(width + 1);
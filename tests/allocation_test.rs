@@ -0,0 +1,241 @@
+//! Integration tests for the per-book matching algorithms.
+//!
+//! Each rule gets its own section. Every test builds one level of resting
+//! asks, sends a single aggressor, and pins which resting orders traded
+//! and for how much, in emission order.
+
+use nyquestro::book::{
+    BookConfig, Market, MatchingAlgorithm, OrderBook, SelfTradePrevention, SubmitResult,
+};
+use nyquestro::events::{OrderEvent, OrderRejectionReason};
+use nyquestro::order::Order;
+use nyquestro::types::{OrderID, OwnerID, Px, Qty, Side, Symbol, TimeInForce, Ts};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+fn book(matching: MatchingAlgorithm) -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            matching,
+            ..BookConfig::default()
+        },
+    )
+}
+
+/// Rest asks of the given sizes at 10000, ids 1.. in arrival order.
+fn level(book: &mut OrderBook, sizes: &[u64]) {
+    for (i, &qty) in sizes.iter().enumerate() {
+        let id = i as u64 + 1;
+        book.submit_limit(order(id, Side::Sell, 10000, qty, id)).unwrap();
+    }
+}
+
+/// `(resting seller id, quantity)` for each fill, in emission order.
+fn fills(res: &SubmitResult) -> Vec<(u64, u64)> {
+    res.fills
+        .iter()
+        .map(|f| (f.seller_order_id.value(), f.quantity.value()))
+        .collect()
+}
+
+fn remaining(book: &OrderBook, id: u64) -> Option<u64> {
+    book.order(OrderID::new(id).unwrap()).map(|o| o.remaining().value())
+}
+
+// ─── FIFO ─────────────────────────────────────────────────────────────────
+
+#[test]
+fn fifo_is_the_default() {
+    assert_eq!(BookConfig::default().matching, MatchingAlgorithm::Fifo);
+    assert_eq!(OrderBook::new(SYM).config().matching, MatchingAlgorithm::Fifo);
+}
+
+#[test]
+fn fifo_fills_front_to_back() {
+    let mut book = book(MatchingAlgorithm::Fifo);
+    level(&mut book, &[10, 30, 60]);
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 25, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 10), (2, 15)]);
+    assert_eq!(remaining(&book, 3), Some(60));
+}
+
+// ─── Pro-rata ─────────────────────────────────────────────────────────────
+
+#[test]
+fn pro_rata_splits_by_size() {
+    let mut book = book(MatchingAlgorithm::ProRata);
+    level(&mut book, &[10, 30, 60]);
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 50, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 5), (2, 15), (3, 30)]);
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10000).unwrap(), Qty::new(50))));
+}
+
+#[test]
+fn pro_rata_leftover_goes_to_the_largest() {
+    let mut book = book(MatchingAlgorithm::ProRata);
+    level(&mut book, &[10, 30, 60]);
+    // 0.7 / 2.1 / 4.2 round down to 0 / 2 / 4; the spare unit goes to #3.
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 7, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(2, 2), (3, 5)]);
+}
+
+#[test]
+fn pro_rata_equal_sizes_break_on_queue_position() {
+    let mut book = book(MatchingAlgorithm::ProRata);
+    level(&mut book, &[5, 5, 5]);
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 4, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 2), (2, 1), (3, 1)]);
+}
+
+#[test]
+fn pro_rata_clears_a_level_before_the_next() {
+    let mut book = book(MatchingAlgorithm::ProRata);
+    level(&mut book, &[10, 30]);
+    book.submit_limit(order(4, Side::Sell, 10010, 20, 4)).unwrap();
+    let res = book.submit_limit(order(9, Side::Buy, 10010, 45, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 10), (2, 30), (4, 5)]);
+    assert_eq!(res.fills[2].price.raw(), 10010);
+}
+
+#[test]
+fn pro_rata_shares_only_displayed_iceberg_quantity() {
+    let mut book = book(MatchingAlgorithm::ProRata);
+    let ice = order(1, Side::Sell, 10000, 100, 1).with_peak(Qty::new(10)).unwrap();
+    book.submit_limit(ice).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 10, 2)).unwrap();
+
+    let res = book.submit_limit(order(8, Side::Buy, 10000, 10, 8)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 5), (2, 5)]);
+
+    // Both displayed remainders go first; the refreshed peak then takes
+    // the rest one peak at a time.
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 30, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 5), (2, 5), (1, 10), (1, 10)]);
+    assert_eq!(remaining(&book, 1), Some(70));
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10000).unwrap(), Qty::new(10))));
+}
+
+// ─── Pro-rata with top-order priority ─────────────────────────────────────
+
+#[test]
+fn top_order_fills_first_then_pro_rata() {
+    let mut book = book(MatchingAlgorithm::ProRataTopOrder);
+    level(&mut book, &[10, 30, 60]);
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 25, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 10), (2, 5), (3, 10)]);
+}
+
+#[test]
+fn top_order_rounding_residual_is_fifo() {
+    let mut book = book(MatchingAlgorithm::ProRataTopOrder);
+    level(&mut book, &[10, 30, 60]);
+    // 2 left after the top order: 0.67 / 1.33 round to 0 / 1, and the
+    // spare unit goes to the earlier order, not the larger one.
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 12, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 10), (2, 1), (3, 1)]);
+}
+
+#[test]
+fn top_order_smaller_than_the_top_fills_only_the_top() {
+    let mut book = book(MatchingAlgorithm::ProRataTopOrder);
+    level(&mut book, &[10, 30, 60]);
+    let res = book.submit_limit(order(9, Side::Buy, 10000, 4, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 4)]);
+}
+
+// ─── Self-trade prevention under pro-rata ─────────────────────────────────
+
+fn owned(id: u64, side: Side, qty: u64, owner: u64, ts: u64) -> Order {
+    order(id, side, 10000, qty, ts).with_owner(OwnerID::new(owner).unwrap())
+}
+
+fn stp_book(stp: SelfTradePrevention) -> OrderBook {
+    let mut book = OrderBook::with_config(
+        SYM,
+        BookConfig {
+            matching: MatchingAlgorithm::ProRata,
+            self_trade_prevention: stp,
+            ..BookConfig::default()
+        },
+    );
+    book.submit_limit(owned(1, Side::Sell, 10, 1, 1)).unwrap();
+    book.submit_limit(owned(2, Side::Sell, 30, 2, 2)).unwrap();
+    book
+}
+
+#[test]
+fn pro_rata_cancel_newest_sees_conflicts_behind_the_front() {
+    let mut book = stp_book(SelfTradePrevention::CancelNewest);
+    let res = book.submit_limit(owned(9, Side::Buy, 20, 2, 9)).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(matches!(
+        res.lifecycle[0],
+        OrderEvent::Rejected {
+            reason: OrderRejectionReason::SelfMatch,
+            ..
+        }
+    ));
+    assert_eq!(remaining(&book, 1), Some(10));
+    assert_eq!(remaining(&book, 2), Some(30));
+}
+
+#[test]
+fn pro_rata_cancel_oldest_removes_conflict_then_shares() {
+    let mut book = stp_book(SelfTradePrevention::CancelOldest);
+    let res = book.submit_limit(owned(9, Side::Buy, 8, 2, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 8)]);
+    assert_eq!(remaining(&book, 2), None);
+}
+
+#[test]
+fn pro_rata_fok_does_not_count_a_level_it_cannot_trade() {
+    let mut book = stp_book(SelfTradePrevention::CancelNewest);
+    let fok = owned(9, Side::Buy, 5, 2, 9).with_time_in_force(TimeInForce::Fok);
+    let res = book.submit_limit(fok).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(matches!(
+        res.lifecycle[0],
+        OrderEvent::Rejected {
+            reason: OrderRejectionReason::FillOrKillUnfilled,
+            ..
+        }
+    ));
+}
+
+// ─── Per-symbol choice ────────────────────────────────────────────────────
+
+#[test]
+fn market_books_keep_their_own_algorithm() {
+    let other = Symbol::from_const("OTHER");
+    let mut market = Market::new();
+    market.register_with(
+        SYM,
+        BookConfig {
+            matching: MatchingAlgorithm::ProRata,
+            ..BookConfig::default()
+        },
+    );
+    market.register(other);
+    assert_eq!(market.book(SYM).unwrap().config().matching, MatchingAlgorithm::ProRata);
+    assert_eq!(market.book(other).unwrap().config().matching, MatchingAlgorithm::Fifo);
+
+    for (i, qty) in [10, 30].into_iter().enumerate() {
+        let id = i as u64 + 1;
+        market.submit_limit(order(id, Side::Sell, 10000, qty, id)).unwrap();
+    }
+    let res = market.submit_limit(order(9, Side::Buy, 10000, 20, 9)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 5), (2, 15)]);
+}
@@ -0,0 +1,301 @@
+//! Integration tests for opening and closing call auctions.
+//!
+//! Each test drives a book through `start_auction` → submissions →
+//! `uncross` and pins the auction events, the single-price fills and the
+//! state the book is left in.
+
+use nyquestro::book::{BookConfig, Market, OrderBook, SelfTradePrevention, SubmitResult};
+use nyquestro::errors::NyquestroError;
+use nyquestro::events::{AuctionEvent, OrderEvent, OrderRejectionReason, Sequenced};
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, PostOnly, Px, Qty, Side, Symbol,
+    TimeInForce, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+fn auction_book(kind: AuctionKind) -> OrderBook {
+    let mut book = OrderBook::new(SYM);
+    book.start_auction(kind, Ts::from_nanos(1)).unwrap();
+    book
+}
+
+/// `(price, volume)` of every `Indicative` event in `res`.
+fn indicatives(res: &SubmitResult) -> Vec<Option<(u64, u64)>> {
+    res.auction
+        .iter()
+        .filter_map(|e| match e {
+            AuctionEvent::Indicative { price, volume, .. } => {
+                Some(price.map(|p| (p.raw(), volume.value())))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// `(buyer, seller, price, quantity)` for each fill.
+fn fills(res: &SubmitResult) -> Vec<(u64, u64, u64, u64)> {
+    res.fills
+        .iter()
+        .map(|f| {
+            (
+                f.buyer_order_id.value(),
+                f.seller_order_id.value(),
+                f.price.raw(),
+                f.quantity.value(),
+            )
+        })
+        .collect()
+}
+
+// ─── Phase transitions ────────────────────────────────────────────────────
+
+#[test]
+fn start_and_uncross_are_explicit_transitions() {
+    let mut book = OrderBook::new(SYM);
+    let res = book.start_auction(AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    assert_eq!(
+        res.auction,
+        // Sequenced after the state change the auction caused.
+        vec![AuctionEvent::started(SYM, AuctionKind::Opening, Ts::from_nanos(1)).with_seq(2)]
+    );
+    assert_eq!(book.auction(), Some(AuctionKind::Opening));
+    assert!(matches!(
+        book.start_auction(AuctionKind::Closing, Ts::from_nanos(2)),
+        Err(NyquestroError::InvalidPhaseTransition { .. })
+    ));
+
+    let res = book.uncross(Ts::from_nanos(3)).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(matches!(
+        res.auction[..],
+        [AuctionEvent::Uncrossed {
+            kind: AuctionKind::Opening,
+            price: None,
+            ..
+        }]
+    ));
+    assert_eq!(book.auction(), None);
+    assert!(matches!(
+        book.uncross(Ts::from_nanos(4)),
+        Err(NyquestroError::InvalidPhaseTransition { .. })
+    ));
+}
+
+// ─── Accumulation ─────────────────────────────────────────────────────────
+
+#[test]
+fn crossing_orders_rest_and_publish_indicatives() {
+    let mut book = auction_book(AuctionKind::Opening);
+    let res = book.submit_limit(order(1, Side::Buy, 10010, 5, 2)).unwrap();
+    assert!(indicatives(&res).is_empty());
+
+    let res = book.submit_limit(order(2, Side::Sell, 9990, 3, 3)).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(matches!(res.lifecycle[0], OrderEvent::Placed { .. }));
+    assert_eq!(indicatives(&res), vec![Some((9990, 3))]);
+    assert!(book.best_bid().unwrap().0 > book.best_ask().unwrap().0);
+
+    let res = book.submit_limit(order(3, Side::Buy, 10000, 4, 4)).unwrap();
+    assert_eq!(indicatives(&res), vec![Some((10010, 3))]);
+    let res = book.submit_limit(order(4, Side::Sell, 10000, 6, 5)).unwrap();
+    assert_eq!(indicatives(&res), vec![Some((10000, 9))]);
+    assert_eq!(book.indicative(), Some((Px::from_raw(10000).unwrap(), Qty::new(9))));
+
+    // An order that leaves the uncross where it was publishes nothing.
+    let res = book.submit_limit(order(5, Side::Buy, 9000, 1, 6)).unwrap();
+    assert!(indicatives(&res).is_empty());
+}
+
+#[test]
+fn orders_that_cannot_wait_are_rejected() {
+    let mut book = auction_book(AuctionKind::Closing);
+    book.submit_limit(order(1, Side::Sell, 10000, 5, 2)).unwrap();
+    let peg = Peg {
+        reference: PegReference::Primary,
+        offset: 0,
+        cap: None,
+    };
+    let refused = [
+        Order::market(OrderID::new(2).unwrap(), SYM, Side::Buy, Qty::new(1), Ts::from_nanos(3))
+            .unwrap(),
+        order(3, Side::Buy, 10000, 1, 3).with_time_in_force(TimeInForce::Ioc),
+        order(4, Side::Buy, 10000, 1, 3).with_time_in_force(TimeInForce::Fok),
+        Order::pegged(
+            OrderID::new(5).unwrap(),
+            SYM,
+            Side::Sell,
+            peg,
+            Qty::new(1),
+            Ts::from_nanos(3),
+        )
+        .unwrap(),
+        order(6, Side::Buy, 9000, 1, 3).with_post_only(PostOnly::Reject),
+        order(7, Side::Buy, 10000, 2, 3).with_min_quantity(Qty::new(2)).unwrap(),
+    ];
+    for o in refused {
+        let res = book.submit_limit(o).unwrap();
+        assert!(
+            matches!(
+                res.lifecycle[..],
+                [OrderEvent::Rejected {
+                    reason: OrderRejectionReason::NotAcceptedInAuction,
+                    ..
+                }]
+            ),
+            "{o:?}"
+        );
+    }
+    assert_eq!(book.len(), 1);
+}
+
+#[test]
+fn cancel_then_reprice_republishes_the_indicative() {
+    let mut book = auction_book(AuctionKind::Opening);
+    book.submit_limit(order(1, Side::Buy, 10010, 5, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 9990, 3, 3)).unwrap();
+    book.submit_limit(order(3, Side::Buy, 10000, 4, 4)).unwrap();
+    book.submit_limit(order(4, Side::Sell, 10000, 6, 5)).unwrap();
+
+    book.cancel(OrderID::new(4).unwrap(), Ts::from_nanos(6)).unwrap();
+    let res = book.reprice(Ts::from_nanos(6)).unwrap();
+    assert_eq!(indicatives(&res), vec![Some((10010, 3))]);
+}
+
+// ─── Uncross ──────────────────────────────────────────────────────────────
+
+#[test]
+fn uncross_prints_every_fill_at_one_price() {
+    let mut book = auction_book(AuctionKind::Opening);
+    book.submit_limit(order(1, Side::Buy, 10010, 5, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 9990, 3, 3)).unwrap();
+    book.submit_limit(order(3, Side::Buy, 10000, 4, 4)).unwrap();
+    book.submit_limit(order(4, Side::Sell, 10000, 6, 5)).unwrap();
+
+    let res = book.uncross(Ts::from_nanos(10)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 2, 10000, 3), (1, 4, 10000, 2), (3, 4, 10000, 4)]);
+    assert!(res.fills.iter().all(|f| f.timestamp == Ts::from_nanos(10)));
+    // Nobody aggressed in an uncross; trade ids still count up.
+    assert!(res.fills.iter().all(|f| f.aggressor.is_none()));
+    let trade_ids: Vec<_> = res.fills.iter().map(|f| f.trade_id).collect();
+    assert_eq!(trade_ids, vec![1, 2, 3]);
+    // Every order traded is reported, partial or not.
+    let filled = res
+        .lifecycle
+        .iter()
+        .filter(|e| matches!(e, OrderEvent::Filled { .. }))
+        .count();
+    assert_eq!(filled, 6);
+    assert!(matches!(
+        res.auction[..],
+        [AuctionEvent::Uncrossed {
+            price: Some(p),
+            volume,
+            ..
+        }] if p.raw() == 10000 && volume == Qty::new(9)
+    ));
+    assert!(book.is_empty());
+    assert_eq!(book.last_trade(), Some(Px::from_raw(10000).unwrap()));
+}
+
+#[test]
+fn last_trade_breaks_the_price_tie() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(order(10, Side::Sell, 10005, 1, 1)).unwrap();
+    book.submit_limit(order(11, Side::Buy, 10005, 1, 2)).unwrap();
+    book.start_auction(AuctionKind::Closing, Ts::from_nanos(3)).unwrap();
+
+    book.submit_limit(order(1, Side::Buy, 10010, 5, 4)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 5, 5)).unwrap();
+    assert_eq!(book.indicative(), Some((Px::from_raw(10005).unwrap(), Qty::new(5))));
+    let res = book.uncross(Ts::from_nanos(6)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 2, 10005, 5)]);
+}
+
+#[test]
+fn self_trade_prevention_does_not_apply_to_the_uncross() {
+    let config = BookConfig {
+        self_trade_prevention: SelfTradePrevention::CancelBoth,
+        ..BookConfig::default()
+    };
+    let mut book = OrderBook::with_config(SYM, config);
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    let owner = OwnerID::new(7).unwrap();
+    book.submit_limit(order(1, Side::Buy, 10000, 5, 2).with_owner(owner)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 5, 3).with_owner(owner)).unwrap();
+    assert_eq!(book.indicative(), Some((Px::from_raw(10000).unwrap(), Qty::new(5))));
+
+    let res = book.uncross(Ts::from_nanos(4)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 2, 10000, 5)]);
+    assert!(res.lifecycle.iter().all(|e| !matches!(e, OrderEvent::Cancelled { .. })));
+    assert!(book.is_empty());
+}
+
+#[test]
+fn hidden_iceberg_quantity_takes_part() {
+    let mut book = auction_book(AuctionKind::Opening);
+    let ice = order(1, Side::Sell, 10000, 20, 2).with_peak(Qty::new(5)).unwrap();
+    book.submit_limit(ice).unwrap();
+    let res = book.submit_limit(order(2, Side::Buy, 10000, 12, 3)).unwrap();
+    assert_eq!(indicatives(&res), vec![Some((10000, 12))]);
+
+    let res = book.uncross(Ts::from_nanos(4)).unwrap();
+    assert_eq!(fills(&res), vec![(2, 1, 10000, 5), (2, 1, 10000, 5), (2, 1, 10000, 2)]);
+    assert_eq!(book.order(OrderID::new(1).unwrap()).unwrap().remaining(), Qty::new(8));
+}
+
+#[test]
+fn book_resumes_continuous_matching_and_fires_stops() {
+    let mut book = auction_book(AuctionKind::Opening);
+    book.submit_limit(order(3, Side::Buy, 9990, 5, 2)).unwrap();
+    let stop =
+        Order::market(OrderID::new(9).unwrap(), SYM, Side::Sell, Qty::new(1), Ts::from_nanos(2))
+            .unwrap()
+            .with_stop(Px::from_raw(10000).unwrap());
+    book.submit_limit(stop).unwrap();
+    book.submit_limit(order(1, Side::Buy, 10000, 2, 3)).unwrap();
+    let res = book.submit_limit(order(2, Side::Sell, 10000, 2, 4)).unwrap();
+    assert!(res.fills.is_empty());
+    assert_eq!(book.stops().len(), 1);
+
+    // The clearing price reaches the stop, which sells into the bid.
+    let res = book.uncross(Ts::from_nanos(5)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 2, 10000, 2), (3, 9, 9990, 1)]);
+    assert!(res.lifecycle.iter().any(|e| matches!(e, OrderEvent::Triggered { .. })));
+    assert!(book.stops().is_empty());
+
+    let res = book.submit_limit(order(4, Side::Sell, 9990, 1, 6)).unwrap();
+    assert_eq!(fills(&res), vec![(3, 4, 9990, 1)]);
+}
+
+// ─── Market routing ───────────────────────────────────────────────────────
+
+#[test]
+fn market_runs_auctions_per_symbol() {
+    let other = Symbol::from_const("OTHER");
+    let mut market = Market::new();
+    market.register(SYM);
+    market.register(other);
+    market.start_auction(SYM, AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    assert_eq!(market.book(SYM).unwrap().auction(), Some(AuctionKind::Opening));
+    assert_eq!(market.book(other).unwrap().auction(), None);
+
+    market.submit_limit(order(1, Side::Buy, 10000, 1, 2)).unwrap();
+    let res = market.submit_limit(order(2, Side::Sell, 10000, 1, 3)).unwrap();
+    assert!(res.fills.is_empty());
+    let res = market.uncross(SYM, Ts::from_nanos(4)).unwrap();
+    assert_eq!(fills(&res), vec![(1, 2, 10000, 1)]);
+}
@@ -0,0 +1,142 @@
+//! Integration tests for the book auditor and checked mode.
+//!
+//! Nothing outside the crate can corrupt a book, so these pin the other
+//! half of the contract: every operation leaves a sound book behind, and
+//! running checked changes nothing but the cost. Drift itself is covered
+//! by the unit tests in `book/audit.rs`.
+
+use nyquestro::book::{BookConfig, MassCancel, OrderBook};
+use nyquestro::errors::NyquestroError;
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, Px, Qty, Side, Symbol, TimeInForce, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+fn checked() -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            checked: true,
+            ..BookConfig::default()
+        },
+    )
+}
+
+#[test]
+fn an_empty_book_is_sound() {
+    assert!(OrderBook::new(SYM).audit().is_empty());
+}
+
+#[test]
+fn a_crossed_auction_is_sound_until_continuous_trading() {
+    let mut book = checked();
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    book.submit_limit(order(1, Side::Buy, 10010, 10, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 9990, 4, 3)).unwrap();
+    assert!(book.audit().is_empty());
+
+    book.uncross(Ts::from_nanos(4)).unwrap();
+    assert!(book.audit().is_empty());
+    assert_eq!(book.best_ask(), None);
+}
+
+#[test]
+fn a_crossed_book_refuses_to_resume() {
+    let mut book = checked();
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    book.submit_limit(order(1, Side::Buy, 10010, 10, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 9990, 4, 3)).unwrap();
+    // Halting abandons the auction with both sides still through each
+    // other; that is allowed while nothing trades.
+    book.halt(Ts::from_nanos(4)).unwrap();
+    assert!(book.audit().is_empty());
+
+    // Resuming would rest the overlap in continuous trading; the book
+    // refuses instead of failing the audit.
+    let err = book.resume(Ts::from_nanos(5)).unwrap_err();
+    assert!(!err.is_fatal());
+    assert!(matches!(err, NyquestroError::InvalidPhaseTransition { .. }));
+    assert!(book.audit().is_empty());
+}
+
+#[test]
+fn checked_mode_changes_nothing_over_a_long_random_session() {
+    // Small LCG so the session is the same on every run.
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = |n: u64| {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (state >> 33) % n
+    };
+    let mut plain = OrderBook::new(SYM);
+    let mut checked = checked();
+    let mut traded = 0;
+    for step in 1..=5_000u64 {
+        let ts = Ts::from_nanos(step);
+        let side = if next(2) == 0 { Side::Buy } else { Side::Sell };
+        let price = match side {
+            Side::Buy => 9_975 + next(30),
+            Side::Sell => 9_995 + next(30),
+        };
+        let qty = 1 + next(20);
+        let pick = OrderID::new(1 + next(step)).unwrap();
+        let op = next(100);
+        let run = |book: &mut OrderBook| match op {
+            0..=49 => {
+                let mut o = order(step, side, price, qty, step)
+                    .with_owner(OwnerID::new(1 + step % 7).unwrap());
+                match step % 11 {
+                    0 => o = o.with_peak(Qty::new(1 + step % 3)).unwrap(),
+                    1 => o = o.with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(step + 40))),
+                    2 => o = o.with_stop(Px::from_raw(price - 15).unwrap()),
+                    3 => {
+                        let peg = Peg {
+                            reference: PegReference::Primary,
+                            offset: 0,
+                            cap: None,
+                        };
+                        o = Order::pegged(o.id(), SYM, side, peg, o.quantity(), ts).unwrap();
+                    }
+                    _ => {}
+                }
+                format!("{:?}", book.submit_limit(o))
+            }
+            50..=69 => format!("{:?}", book.cancel(pick, ts)),
+            70..=84 => {
+                let px = Px::from_raw(price).unwrap();
+                format!("{:?}", book.replace(pick, px, Qty::new(1 + step % 9), ts))
+            }
+            85..=89 => format!("{:?}", book.expire(ts)),
+            90 | 91 => {
+                let filter = MassCancel {
+                    side: Some(side),
+                    min_price: Some(Px::from_raw(price).unwrap()),
+                    ..MassCancel::default()
+                };
+                format!("{:?}", book.mass_cancel(&filter, ts))
+            }
+            92 => format!("{:?}", book.start_auction(AuctionKind::Closing, ts)),
+            93 | 94 => format!("{:?}", book.uncross(ts)),
+            95..=97 => format!("{:?}", book.resume(ts)),
+            _ => format!("{:?}", book.reprice(ts)),
+        };
+        let expected = run(&mut plain);
+        assert_eq!(run(&mut checked), expected, "step {step}");
+        assert!(plain.audit().is_empty(), "step {step}: {:?}", plain.audit());
+        traded += usize::from(expected.contains("FillEvent"));
+    }
+    assert!(traded > 100 && plain.len() > 50);
+}
@@ -10,7 +10,7 @@ fn id(n: u64) -> OrderID {
     OrderID::new(n).unwrap()
 }
 fn px(c: u64) -> Px {
-    Px::from_cents(c).unwrap()
+    Px::from_raw(c).unwrap()
 }
 fn ts(n: u64) -> Ts {
     Ts::from_nanos(n)
@@ -0,0 +1,91 @@
+//! Integration tests for the live-feed bridge driving the dashboard's
+//! engine.
+//!
+//! The bridge keys one virtual order per `(symbol, side, price)` cell and
+//! never sees fills, so these run its output through `App::dispatch` and
+//! check the book against what the feed last said. No socket is opened;
+//! `FeedEvent`s are built by hand.
+
+use std::sync::mpsc;
+
+use nyquestro::feed::{Bridge, FeedAction, FeedEvent};
+use nyquestro::order::Order;
+use nyquestro::simulator::SimAction;
+use nyquestro::telemetry::TelemetryHandle;
+use nyquestro::types::{OrderID, Px, Qty, Side, Symbol, Ts};
+use nyquestro::ui::App;
+
+fn px(c: u64) -> Px {
+    Px::from_raw(c).unwrap()
+}
+
+fn qty(n: u64) -> Qty {
+    Qty::new(n)
+}
+
+fn feed(app: &mut App, bridge: &mut Bridge, event: FeedEvent) {
+    for action in bridge.translate(event) {
+        if let FeedAction::Action { symbol_idx, action } = action {
+            app.dispatch(symbol_idx, action);
+        }
+    }
+}
+
+#[test]
+fn a_consumed_level_returns_when_the_feed_updates_it() {
+    let btc = Symbol::from_const("BTC-USD");
+    let (_tx, rx) = mpsc::channel();
+    let mut app = App::new_live(vec![(btc, 7_000_000)], rx, TelemetryHandle::noop());
+    let mut bridge = Bridge::new(vec![btc]);
+
+    feed(
+        &mut app,
+        &mut bridge,
+        FeedEvent::Snapshot {
+            symbol: btc,
+            bids: vec![(px(7_000_000), qty(100))],
+            asks: vec![],
+        },
+    );
+    assert_eq!(app.market.book(btc).unwrap().best_bid(), Some((px(7_000_000), qty(100))));
+
+    // A sell takes the whole level: the engine forgets the virtual order,
+    // the bridge still holds its id.
+    let sell = Order::new(
+        OrderID::new(1).unwrap(),
+        btc,
+        Side::Sell,
+        px(7_000_000),
+        qty(100),
+        Ts::from_nanos(1),
+    )
+    .unwrap();
+    app.dispatch(0, SimAction::Submit(sell));
+    assert_eq!(app.market.book(btc).unwrap().best_bid(), None);
+
+    feed(
+        &mut app,
+        &mut bridge,
+        FeedEvent::Update {
+            symbol: btc,
+            side: Side::Buy,
+            price: px(7_000_000),
+            new_quantity: qty(150),
+        },
+    );
+    assert_eq!(app.market.book(btc).unwrap().best_bid(), Some((px(7_000_000), qty(150))));
+
+    // The level is back under its old id, so the next update resizes it.
+    feed(
+        &mut app,
+        &mut bridge,
+        FeedEvent::Update {
+            symbol: btc,
+            side: Side::Buy,
+            price: px(7_000_000),
+            new_quantity: qty(40),
+        },
+    );
+    assert_eq!(app.market.book(btc).unwrap().best_bid(), Some((px(7_000_000), qty(40))));
+    assert_eq!(app.market.book(btc).unwrap().len(), 1);
+}
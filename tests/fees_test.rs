@@ -0,0 +1,185 @@
+//! Integration tests for maker / taker fees charged by `Market`.
+//!
+//! Each test routes a short sequence through a market with a fee schedule
+//! and pins the fee on each side of every fill and the per-account totals
+//! left behind.
+
+use nyquestro::book::{FeeRate, FeeSchedule, FeeTier, FeeTotals, Market};
+use nyquestro::instrument::InstrumentSpec;
+use nyquestro::order::Order;
+use nyquestro::types::{AuctionKind, OrderID, OwnerID, Px, Qty, Scale, Side, Symbol, Ts};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn firm(n: u64) -> OwnerID {
+    OwnerID::new(n).unwrap()
+}
+
+fn order(id: u64, owner: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+    .with_owner(firm(owner))
+}
+
+/// Makers earn 1bp, takers pay 3bp.
+fn market() -> Market {
+    let mut market = Market::new();
+    market.set_fee_schedule(FeeSchedule::flat(FeeRate::Bps(-1), FeeRate::Bps(3)));
+    market
+}
+
+#[test]
+fn maker_earns_the_rebate_and_taker_pays() {
+    let mut market = market();
+    market.submit_limit(order(1, 1, Side::Sell, 10000, 50, 1)).unwrap();
+    let res = market.submit_limit(order(2, 2, Side::Buy, 10000, 50, 2)).unwrap();
+
+    // Notional 500_000 cents: 3bp = 150, 1bp rebate = -50.
+    let fill = res.fills[0];
+    assert_eq!((fill.buyer_owner, fill.seller_owner), (Some(firm(2)), Some(firm(1))));
+    assert_eq!((fill.buyer_fee, fill.seller_fee), (150, -50));
+    assert_eq!(
+        market.fee_totals(firm(1), SYM),
+        FeeTotals {
+            maker: -50,
+            taker: 0,
+            volume: 50,
+        }
+    );
+    assert_eq!(market.fee_totals(firm(2), SYM).net(), 150);
+    assert_eq!(market.fee_totals(firm(3), SYM), FeeTotals::default());
+}
+
+#[test]
+fn odd_amounts_round_toward_the_venue() {
+    let mut market = market();
+    market.submit_limit(order(1, 1, Side::Sell, 10001, 3, 1)).unwrap();
+    let res = market.submit_limit(order(2, 2, Side::Buy, 10001, 3, 2)).unwrap();
+    // Notional 30_003: 9.0009 charged as 10, a 3.0003 rebate paid as 3.
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (10, -3));
+}
+
+#[test]
+fn tier_changes_after_the_fill_that_reaches_it() {
+    let mut market = Market::new();
+    market.set_fee_schedule(
+        FeeSchedule::new(vec![
+            FeeTier::flat(FeeRate::PerUnit(0), FeeRate::PerUnit(2)),
+            FeeTier {
+                from_volume: 10,
+                maker: FeeRate::PerUnit(0),
+                taker: FeeRate::PerUnit(1),
+            },
+        ])
+        .unwrap(),
+    );
+    market.submit_limit(order(1, 1, Side::Sell, 10000, 30, 1)).unwrap();
+    let fees: Vec<i64> = (0..3)
+        .map(|i| {
+            let res = market.submit_limit(order(2 + i, 2, Side::Buy, 10000, 10, 2 + i)).unwrap();
+            res.fills[0].buyer_fee
+        })
+        .collect();
+    assert_eq!(fees, vec![20, 10, 10]);
+    assert_eq!(market.fee_totals(firm(2), SYM).taker, 40);
+    assert_eq!(market.fee_totals(firm(2), SYM).volume, 30);
+}
+
+#[test]
+fn account_and_symbol_overrides_take_precedence() {
+    let other = Symbol::from_const("OTHR");
+    let mut market = market();
+    market.set_fee_schedule(
+        FeeSchedule::flat(FeeRate::Bps(-1), FeeRate::Bps(3))
+            .with_owner(firm(2), vec![FeeTier::flat(FeeRate::Bps(-2), FeeRate::Bps(1))])
+            .unwrap()
+            .with_owner_symbol(firm(2), other, vec![FeeTier::flat(FeeRate::FREE, FeeRate::FREE)])
+            .unwrap(),
+    );
+    market.submit_limit(order(1, 1, Side::Sell, 10000, 50, 1)).unwrap();
+    let res = market.submit_limit(order(2, 2, Side::Buy, 10000, 50, 2)).unwrap();
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (50, -50));
+
+    let in_other = |id, owner, side| {
+        Order::new(
+            OrderID::new(id).unwrap(),
+            other,
+            side,
+            Px::from_raw(10000).unwrap(),
+            Qty::new(50),
+            Ts::from_nanos(3),
+        )
+        .unwrap()
+        .with_owner(firm(owner))
+    };
+    market.submit_limit(in_other(1, 1, Side::Buy)).unwrap();
+    let res = market.submit_limit(in_other(2, 2, Side::Sell)).unwrap();
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (-50, 0));
+
+    let firm2: Vec<_> = market.account_fees(firm(2)).map(|(s, t)| (s, t.net())).collect();
+    assert_eq!(firm2, vec![(other, 0), (SYM, 50)]);
+}
+
+#[test]
+fn fees_use_the_instrument_quantity_scale() {
+    let mut market = market();
+    market
+        .register_instrument(
+            SYM,
+            InstrumentSpec {
+                qty_scale: Scale::new(8).unwrap(),
+                ..InstrumentSpec::default()
+            },
+        )
+        .unwrap();
+    // Half a unit at $60,000.00: notional 3_000_000 cents.
+    market.submit_limit(order(1, 1, Side::Sell, 6_000_000, 50_000_000, 1)).unwrap();
+    let res = market.submit_limit(order(2, 2, Side::Buy, 6_000_000, 50_000_000, 2)).unwrap();
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (900, -300));
+}
+
+#[test]
+fn uncross_charges_both_sides_as_takers() {
+    let mut market = market();
+    market.register(SYM);
+    market.start_auction(SYM, AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    market.submit_limit(order(1, 1, Side::Buy, 10000, 50, 2)).unwrap();
+    market.submit_limit(order(2, 2, Side::Sell, 10000, 50, 3)).unwrap();
+    let res = market.uncross(SYM, Ts::from_nanos(4)).unwrap();
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (150, 150));
+}
+
+#[test]
+fn anonymous_orders_are_charged_but_not_totalled() {
+    let mut market = market();
+    let anonymous = |id, side, ts| {
+        Order::new(
+            OrderID::new(id).unwrap(),
+            SYM,
+            side,
+            Px::from_raw(10000).unwrap(),
+            Qty::new(50),
+            Ts::from_nanos(ts),
+        )
+        .unwrap()
+    };
+    market.submit_limit(anonymous(1, Side::Sell, 1)).unwrap();
+    let res = market.submit_limit(anonymous(2, Side::Buy, 2)).unwrap();
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (150, -50));
+    assert_eq!(market.account_fees(firm(1)).count(), 0);
+}
+
+#[test]
+fn a_bare_book_charges_nothing() {
+    let mut book = nyquestro::book::OrderBook::new(SYM);
+    book.submit_limit(order(1, 1, Side::Sell, 10000, 50, 1)).unwrap();
+    let res = book.submit_limit(order(2, 2, Side::Buy, 10000, 50, 2)).unwrap();
+    assert_eq!((res.fills[0].buyer_fee, res.fills[0].seller_fee), (0, 0));
+}
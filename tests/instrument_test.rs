@@ -0,0 +1,325 @@
+//! Integration tests for instrument specs and reference data.
+//!
+//! Each test builds a book with an [`InstrumentSpec`] and pins which
+//! orders and amendments it accepts, the reason it gives for those it
+//! refuses, and how the tick size feeds the price bands.
+
+use nyquestro::book::{BookConfig, Market, OrderBook, ProtectionBand, SubmitResult};
+use nyquestro::errors::NyquestroError;
+use nyquestro::events::{OrderEvent, OrderRejectionReason};
+use nyquestro::instrument::{InstrumentSpec, ReferenceData};
+use nyquestro::order::Order;
+use nyquestro::simulator::{MarketSimulator, SimAction, SimConfig};
+use nyquestro::types::{
+    OrderID, Peg, PegReference, PostOnly, Px, Qty, Scale, Side, Symbol, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+/// Five-cent tick, lots of 10, 10–1000 per order, $1–$1000.
+fn spec() -> InstrumentSpec {
+    InstrumentSpec {
+        tick_size: 5,
+        lot_size: 10,
+        min_qty: Qty::new(10),
+        max_qty: Qty::new(1_000),
+        min_price: Px::from_raw(100).unwrap(),
+        max_price: Px::from_raw(100_000).unwrap(),
+        ..InstrumentSpec::default()
+    }
+}
+
+fn spec_book() -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            instrument: Some(spec()),
+            ..BookConfig::default()
+        },
+    )
+}
+
+fn rejection(res: &SubmitResult) -> Option<OrderRejectionReason> {
+    res.lifecycle.iter().find_map(|e| match e {
+        OrderEvent::Rejected { reason, .. } => Some(*reason),
+        _ => None,
+    })
+}
+
+// ─── Order entry ──────────────────────────────────────────────────────────
+
+#[test]
+fn each_rule_has_its_own_reason() {
+    let mut book = spec_book();
+    let cases = [
+        (order(1, Side::Buy, 10_003, 10, 1), OrderRejectionReason::PriceOffTick),
+        (order(2, Side::Buy, 95, 10, 1), OrderRejectionReason::PriceOutOfRange),
+        (order(3, Side::Buy, 100_005, 10, 1), OrderRejectionReason::PriceOutOfRange),
+        (order(4, Side::Buy, 10_000, 15, 1), OrderRejectionReason::QuantityOffLot),
+        (order(5, Side::Buy, 10_000, 1_010, 1), OrderRejectionReason::QuantityAboveMaximum),
+    ];
+    for (o, reason) in cases {
+        let res = book.submit_limit(o).unwrap();
+        assert_eq!(rejection(&res), Some(reason), "{o:?}");
+    }
+    assert!(book.is_empty());
+
+    let res = book.submit_limit(order(6, Side::Buy, 10_005, 20, 1)).unwrap();
+    assert!(matches!(res.lifecycle[..], [OrderEvent::Placed { .. }]));
+}
+
+#[test]
+fn minimum_size_applies_with_a_lot_of_one() {
+    let mut book = OrderBook::with_config(
+        SYM,
+        BookConfig {
+            instrument: Some(InstrumentSpec {
+                lot_size: 1,
+                ..spec()
+            }),
+            ..BookConfig::default()
+        },
+    );
+    let res = book.submit_limit(order(1, Side::Buy, 10_000, 9, 1)).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::QuantityBelowMinimum));
+}
+
+#[test]
+fn stop_triggers_and_iceberg_peaks_are_checked() {
+    let mut book = spec_book();
+    let stop = order(1, Side::Buy, 10_100, 10, 1).with_stop(Px::from_raw(10_002).unwrap());
+    let res = book.submit_limit(stop).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::PriceOffTick));
+
+    let ice = order(2, Side::Sell, 10_100, 100, 1).with_peak(Qty::new(25)).unwrap();
+    let res = book.submit_limit(ice).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::QuantityOffLot));
+}
+
+#[test]
+fn market_and_pegged_orders_skip_the_price_check() {
+    let mut book = spec_book();
+    book.submit_limit(order(1, Side::Sell, 10_000, 10, 1)).unwrap();
+    book.submit_limit(order(2, Side::Buy, 9_990, 10, 1)).unwrap();
+
+    let peg = Peg {
+        reference: PegReference::Primary,
+        offset: 0,
+        cap: None,
+    };
+    let pegged = Order::pegged(
+        OrderID::new(3).unwrap(),
+        SYM,
+        Side::Buy,
+        peg,
+        Qty::new(10),
+        Ts::from_nanos(2),
+    )
+    .unwrap();
+    let res = book.submit_limit(pegged).unwrap();
+    assert_eq!(rejection(&res), None);
+
+    let market =
+        Order::market(OrderID::new(4).unwrap(), SYM, Side::Buy, Qty::new(10), Ts::from_nanos(3))
+            .unwrap();
+    let res = book.submit_limit(market).unwrap();
+    assert_eq!(res.fills.len(), 1);
+
+    // Quantity is still checked.
+    let market =
+        Order::market(OrderID::new(5).unwrap(), SYM, Side::Buy, Qty::new(7), Ts::from_nanos(4))
+            .unwrap();
+    let res = book.submit_limit(market).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::QuantityOffLot));
+}
+
+// ─── Amendments ───────────────────────────────────────────────────────────
+
+#[test]
+fn replace_off_spec_is_rejected_and_leaves_the_order() {
+    let mut book = spec_book();
+    book.submit_limit(order(1, Side::Buy, 10_000, 50, 1)).unwrap();
+    let id = OrderID::new(1).unwrap();
+    let px = |c| Px::from_raw(c).unwrap();
+
+    let res = book.replace(id, px(10_001), Qty::new(50), Ts::from_nanos(2)).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::PriceOffTick));
+    let res = book.replace(id, px(10_000), Qty::new(45), Ts::from_nanos(3)).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::QuantityOffLot));
+    assert_eq!(book.best_bid(), Some((px(10_000), Qty::new(50))));
+
+    // A valid amendment goes through.
+    let res = book.replace(id, px(10_005), Qty::new(40), Ts::from_nanos(4)).unwrap();
+    assert_eq!(rejection(&res), None);
+    assert_eq!(book.best_bid(), Some((px(10_005), Qty::new(40))));
+}
+
+// ─── Tick size ────────────────────────────────────────────────────────────
+
+#[test]
+fn market_protection_counts_instrument_ticks() {
+    let mut book = OrderBook::with_config(
+        SYM,
+        BookConfig {
+            instrument: Some(spec()),
+            market_protection: ProtectionBand::Ticks(2),
+            ..BookConfig::default()
+        },
+    );
+    book.submit_limit(order(1, Side::Sell, 10_000, 10, 1)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10_010, 10, 1)).unwrap();
+    book.submit_limit(order(3, Side::Sell, 10_015, 10, 1)).unwrap();
+
+    // Two five-cent ticks reach 10_010 but not 10_015.
+    let market =
+        Order::market(OrderID::new(4).unwrap(), SYM, Side::Buy, Qty::new(30), Ts::from_nanos(2))
+            .unwrap();
+    let res = book.submit_limit(market).unwrap();
+    assert_eq!(res.fills.len(), 2);
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10_015).unwrap(), Qty::new(10))));
+}
+
+#[test]
+fn post_only_slides_one_instrument_tick() {
+    let mut book = spec_book();
+    book.submit_limit(order(1, Side::Sell, 10_000, 10, 1)).unwrap();
+    let post = order(2, Side::Buy, 10_010, 10, 2).with_post_only(PostOnly::Slide);
+    let res = book.submit_limit(post).unwrap();
+    assert!(res.fills.is_empty());
+    assert_eq!(book.best_bid(), Some((Px::from_raw(9_995).unwrap(), Qty::new(10))));
+}
+
+// ─── Reference data ───────────────────────────────────────────────────────
+
+#[test]
+fn market_registers_books_from_reference_data() {
+    let mut data = ReferenceData::new();
+    data.insert(SYM, spec()).unwrap();
+    let mut market = Market::new();
+    market.register_reference_data(&data).unwrap();
+    assert_eq!(market.book(SYM).unwrap().config().instrument, Some(spec()));
+
+    let res = market.submit_limit(order(1, Side::Buy, 10_001, 10, 1)).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::PriceOffTick));
+
+    let bad = InstrumentSpec {
+        lot_size: 0,
+        ..spec()
+    };
+    assert!(matches!(
+        market.register_instrument(Symbol::from_const("BAD"), bad),
+        Err(NyquestroError::InvalidInstrument(_))
+    ));
+    assert!(market.book(Symbol::from_const("BAD")).is_none());
+}
+
+#[test]
+fn reference_data_loads_from_a_file() {
+    let path = std::env::temp_dir().join(format!("nyquestro-ref-{}.json", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"{"instruments": {"TEST": {"tick_size": 5, "lot_size": 10, "min_qty": 10}}}"#,
+    )
+    .unwrap();
+    let data = ReferenceData::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let loaded = data.get(SYM).unwrap();
+    assert_eq!((loaded.tick_size, loaded.lot_size, loaded.min_qty), (5, 10, Qty::new(10)));
+    assert_eq!(loaded.max_price, Px::MAX);
+
+    assert!(matches!(ReferenceData::load(&path), Err(NyquestroError::ReferenceData(_))));
+}
+
+#[test]
+fn simulator_only_generates_orders_the_spec_accepts() {
+    let cfg = SimConfig {
+        symbol: SYM,
+        fair_value: 10_000,
+        instrument: spec(),
+        ..SimConfig::default()
+    };
+    let mut sim = MarketSimulator::new(cfg, 7);
+    let mut book = spec_book();
+    let mut submitted = 0;
+    for _ in 0..200 {
+        for action in sim.step(0.05) {
+            if let SimAction::Submit(o) = action {
+                submitted += 1;
+                let res = book.submit_limit(o).unwrap();
+                assert!(
+                    !matches!(
+                        rejection(&res),
+                        Some(
+                            OrderRejectionReason::PriceOffTick
+                                | OrderRejectionReason::PriceOutOfRange
+                                | OrderRejectionReason::QuantityOffLot
+                                | OrderRejectionReason::QuantityBelowMinimum
+                                | OrderRejectionReason::QuantityAboveMaximum
+                        )
+                    ),
+                    "{o:?}"
+                );
+            }
+        }
+    }
+    assert!(submitted > 0);
+}
+
+// ─── Scales ───────────────────────────────────────────────────────────────
+
+#[test]
+fn scaled_instrument_trades_sub_cent_prices_and_large_sizes() {
+    // Four price places, satoshi sizes: far past what a u32 of 1e-8 units
+    // could hold.
+    let coin = InstrumentSpec {
+        price_scale: Scale::new(4).unwrap(),
+        qty_scale: Scale::new(8).unwrap(),
+        ..InstrumentSpec::default()
+    };
+    let mut market = Market::new();
+    market.register_instrument(SYM, coin).unwrap();
+    let px = coin.parse_price("0.1234").unwrap();
+    let size = coin.parse_qty("12500.5").unwrap();
+    assert_eq!((px.raw(), size.value()), (1_234, 1_250_050_000_000));
+
+    let entry = |id, side| {
+        Order::new(OrderID::new(id).unwrap(), SYM, side, px, size, Ts::from_nanos(id)).unwrap()
+    };
+    market.submit_limit(entry(1, Side::Sell)).unwrap();
+    let res = market.submit_limit(entry(2, Side::Buy)).unwrap();
+    let fill = &res.fills[0];
+    assert_eq!(coin.format_price(fill.price).to_string(), "0.1234");
+    assert_eq!(coin.format_qty(fill.quantity).to_string(), "12500.50000000");
+}
+
+#[test]
+fn builtin_scales_read_venue_strings_exactly() {
+    let btc = ReferenceData::builtin().spec_or_default(Symbol::from_const("BTC-USD"));
+    assert_eq!(btc.parse_price("67234.56").unwrap().raw(), 6_723_456);
+    assert_eq!(btc.parse_qty("0.00012345").unwrap().value(), 12_345);
+    assert_eq!(btc.parse_qty("0").unwrap(), Qty::ZERO);
+    // Finer than the grid is refused rather than rounded onto a neighbour.
+    assert!(matches!(
+        btc.parse_price("67234.565"),
+        Err(NyquestroError::InvalidDecimal { decimals: 2, .. })
+    ));
+    assert!(btc.parse_price("0.00").is_err());
+
+    let bad_scale = r#"{"instruments": {"X": {"qty_scale": 19}}}"#;
+    assert!(matches!(
+        ReferenceData::from_json(bad_scale),
+        Err(NyquestroError::InvalidScale { decimals: 19 })
+    ));
+}
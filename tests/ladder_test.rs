@@ -0,0 +1,144 @@
+//! Integration tests for the tick-indexed ladder.
+//!
+//! The ladder kind is a storage choice only, so the contract is that a
+//! book behaves identically on either: same results, same events, same
+//! depth. The band here is kept narrow so sessions keep spilling past it,
+//! re-centring it and walking the overflow. The ladder's own bookkeeping
+//! is covered by the unit tests in `book/ladder.rs`.
+
+use nyquestro::book::{BookConfig, LadderKind, MarketData, MassCancel, OrderBook};
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, Px, Qty, Side, Symbol, TimeInForce, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+fn book(ladder: LadderKind) -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            market_data: MarketData::FULL,
+            checked: true,
+            ladder,
+            ..BookConfig::default()
+        },
+    )
+}
+
+const NARROW: LadderKind = LadderKind::Ticks { slots: 16 };
+
+#[test]
+fn best_prices_outside_the_band_come_from_the_overflow() {
+    let mut book = book(NARROW);
+    book.submit_limit(order(1, Side::Buy, 10_000, 5, 1)).unwrap();
+    book.submit_limit(order(2, Side::Buy, 10_500, 3, 2)).unwrap();
+    book.submit_limit(order(3, Side::Sell, 20_000, 4, 3)).unwrap();
+    book.submit_limit(order(4, Side::Sell, 10_501, 2, 4)).unwrap();
+    assert_eq!(book.best_bid(), Some((Px::from_raw(10_500).unwrap(), Qty::new(3))));
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10_501).unwrap(), Qty::new(2))));
+
+    // Sweeping every ask walks off the band and into the overflow, and the
+    // residual becomes a best bid far above the band.
+    let result = book.submit_limit(order(5, Side::Buy, 20_000, 7, 5)).unwrap();
+    let prices: Vec<u64> = result.fills.iter().map(|f| f.price.raw()).collect();
+    assert_eq!(prices, [10_501, 20_000]);
+    assert_eq!(book.best_ask(), None);
+    assert_eq!(book.best_bid(), Some((Px::from_raw(20_000).unwrap(), Qty::new(1))));
+    assert_eq!(book.level_counts(), (3, 0));
+}
+
+#[test]
+fn snapshots_keep_the_ladder_kind() {
+    let mut book = book(NARROW);
+    book.submit_limit(order(1, Side::Buy, 9_990, 5, 1)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 12_000, 5, 2)).unwrap();
+    let restored = OrderBook::from_snapshot(book.snapshot()).unwrap();
+    assert_eq!(restored.config().ladder, NARROW);
+    assert_eq!(restored.top_n_asks(5), book.top_n_asks(5));
+    assert_eq!(restored.top_n_bids(5), book.top_n_bids(5));
+}
+
+#[test]
+fn both_ladders_run_a_long_random_session_identically() {
+    // Small LCG so the session is the same on every run.
+    let mut state: u64 = 0x853c_49e6_748f_ea9b;
+    let mut next = |n: u64| {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (state >> 33) % n
+    };
+    let mut tree = book(LadderKind::Tree);
+    let mut ticks = book(NARROW);
+    let mut traded = 0;
+    for step in 1..=5_000u64 {
+        let ts = Ts::from_nanos(step);
+        let side = if next(2) == 0 { Side::Buy } else { Side::Sell };
+        // Mostly near the touch, now and then far enough away to land
+        // outside any band the book has placed.
+        let far = if next(10) == 0 { next(400) } else { 0 };
+        let price = match side {
+            Side::Buy => 9_975 + next(30) - far.min(9_000),
+            Side::Sell => 9_995 + next(30) + far,
+        };
+        let qty = 1 + next(20);
+        let pick = OrderID::new(1 + next(step)).unwrap();
+        let op = next(100);
+        let run = |book: &mut OrderBook| match op {
+            0..=54 => {
+                let mut o = order(step, side, price, qty, step)
+                    .with_owner(OwnerID::new(1 + step % 7).unwrap());
+                match step % 11 {
+                    0 => o = o.with_peak(Qty::new(1 + step % 3)).unwrap(),
+                    1 => o = o.with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(step + 40))),
+                    2 => o = o.with_stop(Px::from_raw(price - 15).unwrap()),
+                    3 => {
+                        let peg = Peg {
+                            reference: PegReference::Primary,
+                            offset: -1,
+                            cap: None,
+                        };
+                        o = Order::pegged(o.id(), SYM, side, peg, o.quantity(), ts).unwrap();
+                    }
+                    _ => {}
+                }
+                format!("{:?}", book.submit_limit(o))
+            }
+            55..=74 => format!("{:?}", book.cancel(pick, ts)),
+            75..=86 => {
+                let px = Px::from_raw(price).unwrap();
+                format!("{:?}", book.replace(pick, px, Qty::new(1 + step % 9), ts))
+            }
+            87..=90 => format!("{:?}", book.expire(ts)),
+            91 | 92 => {
+                let filter = MassCancel {
+                    side: Some(side),
+                    min_price: Some(Px::from_raw(price).unwrap()),
+                    ..MassCancel::default()
+                };
+                format!("{:?}", book.mass_cancel(&filter, ts))
+            }
+            93 => format!("{:?}", book.start_auction(AuctionKind::Opening, ts)),
+            94 | 95 => format!("{:?}", book.uncross(ts)),
+            _ => format!("{:?}", book.reprice(ts)),
+        };
+        let expected = run(&mut tree);
+        assert_eq!(run(&mut ticks), expected, "step {step}");
+        assert_eq!(ticks.top_n_bids(50), tree.top_n_bids(50), "step {step}");
+        assert_eq!(ticks.top_n_asks(50), tree.top_n_asks(50), "step {step}");
+        traded += usize::from(expected.contains("FillEvent"));
+    }
+    assert!(traded > 100 && tree.len() > 50);
+    assert_eq!(ticks.depth_snapshot(Ts::from_nanos(0)), tree.depth_snapshot(Ts::from_nanos(0)));
+}
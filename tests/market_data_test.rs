@@ -0,0 +1,413 @@
+//! Integration tests for the full-depth market-data feeds.
+//!
+//! A `Replica` rebuilds the book from nothing but `LevelEvent`s and
+//! `MboEvent`s (plus, optionally, a depth snapshot) and is compared against
+//! the real ladders after every operation.
+
+use std::collections::{BTreeSet, HashMap};
+
+use nyquestro::book::{
+    BookConfig, MarketData, MassCancel, OrderBook, PriceLevel, SelfTradePrevention, SubmitResult,
+};
+use nyquestro::events::{LevelAction, LevelEvent, MboAction, MboEvent, QuoteSide, Sequenced};
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, Px, Qty, Side, Symbol, TimeInForce, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+fn book() -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            market_data: MarketData::FULL,
+            ..BookConfig::default()
+        },
+    )
+}
+
+type Queue = Vec<(OrderID, Qty)>;
+
+/// A consumer's copy of the book, kept from the feeds alone.
+#[derive(Default)]
+struct Replica {
+    /// Market-by-order: every queue, keyed by side and price.
+    queues: HashMap<(Side, Px), Queue>,
+    /// Market-by-price ladders, best first.
+    bids: Vec<(Px, Qty)>,
+    asks: Vec<(Px, Qty)>,
+    /// `seq` of the last event applied.
+    seq: u64,
+}
+
+impl Replica {
+    fn from_snapshot(book: &OrderBook) -> Self {
+        let mut replica = Replica {
+            seq: book.last_seq(),
+            ..Replica::default()
+        };
+        for add in book.depth_snapshot(Ts::from_nanos(0)) {
+            assert_eq!(add.seq, book.last_seq());
+            replica.apply_order(add);
+        }
+        replica.bids = book.top_n_bids(usize::MAX);
+        replica.asks = book.top_n_asks(usize::MAX);
+        replica
+    }
+
+    /// Apply a result's depth events in `seq` order; none may be older than
+    /// what has been applied already.
+    fn apply(&mut self, res: &SubmitResult) {
+        let mut depth: Vec<(u64, Option<LevelEvent>, Option<MboEvent>)> = res
+            .levels
+            .iter()
+            .map(|e| (e.seq, Some(*e), None))
+            .chain(res.orders.iter().map(|e| (e.seq, None, Some(*e))))
+            .collect();
+        depth.sort_by_key(|(seq, ..)| *seq);
+        for (seq, level, order) in depth {
+            assert!(seq > self.seq, "depth event {seq} replayed after {}", self.seq);
+            self.seq = seq;
+            if let Some(e) = level {
+                self.apply_level(e);
+            }
+            if let Some(e) = order {
+                self.apply_order(e);
+            }
+        }
+    }
+
+    fn apply_level(&mut self, e: LevelEvent) {
+        let ladder = match e.side {
+            QuoteSide::Bid => &mut self.bids,
+            QuoteSide::Ask => &mut self.asks,
+        };
+        match e.action {
+            LevelAction::New => ladder.insert(e.index, (e.price, e.quantity)),
+            LevelAction::Change => {
+                assert_eq!(ladder[e.index].0, e.price);
+                ladder[e.index].1 = e.quantity;
+            }
+            LevelAction::Delete => {
+                assert_eq!(ladder.remove(e.index).0, e.price);
+            }
+        }
+    }
+
+    fn apply_order(&mut self, e: MboEvent) {
+        let key = (e.side, e.price);
+        match e.action {
+            MboAction::Add => self.queues.entry(key).or_default().push((e.order_id, e.quantity)),
+            MboAction::Modify => {
+                let queue = self.queues.get_mut(&key).unwrap();
+                queue.iter_mut().find(|(id, _)| *id == e.order_id).unwrap().1 = e.quantity;
+            }
+            MboAction::Delete => {
+                let queue = self.queues.get_mut(&key).unwrap();
+                queue.retain(|(id, _)| *id != e.order_id);
+                if queue.is_empty() {
+                    self.queues.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn assert_matches(&self, book: &OrderBook) {
+        assert_eq!(self.bids, book.top_n_bids(usize::MAX), "bid ladder");
+        assert_eq!(self.asks, book.top_n_asks(usize::MAX), "ask ladder");
+        let queue = |side: Side, px: &Px, level: &PriceLevel| {
+            let orders: Queue = level.iter().map(|o| (o.id(), o.displayed())).collect();
+            ((side, *px), orders)
+        };
+        let actual: HashMap<(Side, Px), Queue> = book
+            .bid_levels()
+            .map(|(px, l)| queue(Side::Buy, px, l))
+            .chain(book.ask_levels().map(|(px, l)| queue(Side::Sell, px, l)))
+            .collect();
+        assert_eq!(self.queues, actual, "order queues");
+    }
+}
+
+/// Drive `book` and `replica` together and compare after each result.
+struct Feed {
+    book: OrderBook,
+    replica: Replica,
+}
+
+impl Feed {
+    fn new() -> Self {
+        Feed {
+            book: book(),
+            replica: Replica::default(),
+        }
+    }
+
+    fn check(&mut self, res: SubmitResult) -> SubmitResult {
+        self.replica.apply(&res);
+        self.replica.assert_matches(&self.book);
+        res
+    }
+
+    fn submit(&mut self, order: Order) -> SubmitResult {
+        let res = self.book.submit_limit(order).unwrap();
+        self.check(res)
+    }
+
+    fn cancel(&mut self, id: u64, ts: u64) -> SubmitResult {
+        let res = self.book.cancel_collect(OrderID::new(id).unwrap(), Ts::from_nanos(ts)).unwrap();
+        self.check(res)
+    }
+
+    fn replace(&mut self, id: u64, price: u64, qty: u64, ts: u64) -> SubmitResult {
+        let res = self
+            .book
+            .replace(
+                OrderID::new(id).unwrap(),
+                Px::from_raw(price).unwrap(),
+                Qty::new(qty),
+                Ts::from_nanos(ts),
+            )
+            .unwrap();
+        self.check(res)
+    }
+}
+
+fn actions(res: &SubmitResult) -> Vec<(MboAction, u64, u64)> {
+    res.orders
+        .iter()
+        .map(|e| (e.action, e.order_id.value(), e.quantity.value()))
+        .collect()
+}
+
+fn level_actions(res: &SubmitResult) -> Vec<(QuoteSide, LevelAction, usize, u64, u64)> {
+    res.levels
+        .iter()
+        .map(|e| (e.side, e.action, e.index, e.price.raw(), e.quantity.value()))
+        .collect()
+}
+
+#[test]
+fn nothing_is_published_by_default() {
+    let mut book = OrderBook::new(SYM);
+    let res = book.submit_limit(order(1, Side::Sell, 10010, 5, 1)).unwrap();
+    let res2 = book.submit_limit(order(2, Side::Buy, 10010, 2, 2)).unwrap();
+    assert!(res.levels.is_empty() && res.orders.is_empty());
+    assert!(res2.levels.is_empty() && res2.orders.is_empty());
+}
+
+#[test]
+fn levels_carry_their_index_from_the_best() {
+    let mut feed = Feed::new();
+    feed.submit(order(1, Side::Buy, 9990, 5, 1));
+    let res = feed.submit(order(2, Side::Buy, 9980, 4, 2));
+    assert_eq!(level_actions(&res), vec![(QuoteSide::Bid, LevelAction::New, 1, 9980, 4)]);
+    let res = feed.submit(order(3, Side::Buy, 9995, 1, 3));
+    assert_eq!(level_actions(&res), vec![(QuoteSide::Bid, LevelAction::New, 0, 9995, 1)]);
+    let res = feed.submit(order(4, Side::Buy, 9990, 2, 4));
+    assert_eq!(level_actions(&res), vec![(QuoteSide::Bid, LevelAction::Change, 1, 9990, 7)]);
+
+    // A sell sweeping the top two levels deletes the first and leaves the
+    // second, now the best, with one lot.
+    let res = feed.submit(order(5, Side::Sell, 9990, 7, 5));
+    assert_eq!(
+        actions(&res),
+        vec![
+            (MboAction::Delete, 3, 0),
+            (MboAction::Delete, 1, 0),
+            (MboAction::Modify, 4, 1),
+        ]
+    );
+    assert_eq!(
+        level_actions(&res),
+        vec![
+            (QuoteSide::Bid, LevelAction::Delete, 0, 9995, 0),
+            (QuoteSide::Bid, LevelAction::Change, 0, 9990, 2),
+            (QuoteSide::Bid, LevelAction::Change, 0, 9990, 1),
+        ]
+    );
+}
+
+#[test]
+fn a_refreshed_peak_leaves_and_rejoins_the_queue() {
+    let mut feed = Feed::new();
+    feed.submit(order(1, Side::Sell, 10010, 10, 1).with_peak(Qty::new(4)).unwrap());
+    feed.submit(order(2, Side::Sell, 10010, 3, 2));
+    let res = feed.submit(order(3, Side::Buy, 10010, 4, 3));
+    assert_eq!(actions(&res), vec![(MboAction::Delete, 1, 0), (MboAction::Add, 1, 4)]);
+    // The level showed 7 and shows 7 again: nothing to publish by price.
+    assert!(res.levels.is_empty());
+    assert_eq!(feed.replica.queues[&(Side::Sell, Px::from_raw(10010).unwrap())][0].0.value(), 2);
+}
+
+#[test]
+fn amendments_in_place_modify_and_moves_delete_then_add() {
+    let mut feed = Feed::new();
+    feed.submit(order(1, Side::Buy, 9990, 5, 1));
+    feed.submit(order(2, Side::Buy, 9990, 5, 2));
+    let res = feed.replace(1, 9990, 3, 3);
+    assert_eq!(actions(&res), vec![(MboAction::Modify, 1, 3)]);
+    let res = feed.replace(1, 9985, 3, 4);
+    assert_eq!(actions(&res), vec![(MboAction::Delete, 1, 0), (MboAction::Add, 1, 3)]);
+    let res = feed.cancel(2, 5);
+    assert_eq!(actions(&res), vec![(MboAction::Delete, 2, 0)]);
+    assert_eq!(level_actions(&res), vec![(QuoteSide::Bid, LevelAction::Delete, 0, 9990, 0)]);
+}
+
+#[test]
+fn depth_events_share_the_book_sequence() {
+    let mut book = book();
+    book.submit_limit(order(1, Side::Sell, 10010, 5, 1)).unwrap();
+    let res = book.submit_limit(order(2, Side::Buy, 10010, 2, 2)).unwrap();
+    let mut seqs: Vec<u64> = res
+        .fills
+        .iter()
+        .map(Sequenced::seq)
+        .chain(res.quotes.iter().map(Sequenced::seq))
+        .chain(res.levels.iter().map(Sequenced::seq))
+        .chain(res.orders.iter().map(Sequenced::seq))
+        .chain(res.lifecycle.iter().map(Sequenced::seq))
+        .collect();
+    seqs.sort_unstable();
+    let first = seqs[0];
+    assert_eq!(seqs, (first..first + seqs.len() as u64).collect::<Vec<_>>());
+    assert_eq!(seqs.last(), Some(&book.last_seq()));
+}
+
+#[test]
+fn every_kind_of_change_replays_exactly() {
+    let mut feed = Feed::new();
+    let firm = OwnerID::new(7).unwrap();
+    feed.submit(order(1, Side::Buy, 9990, 5, 1));
+    feed.submit(order(2, Side::Sell, 10010, 5, 2));
+    let peg = Peg {
+        reference: PegReference::Primary,
+        offset: 0,
+        cap: None,
+    };
+    let pegged = Order::pegged(
+        OrderID::new(3).unwrap(),
+        SYM,
+        Side::Buy,
+        peg,
+        Qty::new(2),
+        Ts::from_nanos(3),
+    )
+    .unwrap();
+    feed.submit(pegged);
+    // A better bid drags the peg up with it.
+    feed.submit(order(4, Side::Buy, 9995, 1, 4));
+    assert_eq!(feed.book.order(OrderID::new(3).unwrap()).unwrap().price().raw(), 9995);
+
+    let gtd = order(5, Side::Sell, 10020, 3, 5)
+        .with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(50)));
+    feed.submit(gtd);
+    let res = feed.book.expire(Ts::from_nanos(60)).unwrap();
+    feed.check(res);
+
+    feed.submit(order(6, Side::Sell, 10030, 2, 6));
+    feed.submit(order(7, Side::Sell, 10040, 2, 7));
+    let range = MassCancel {
+        min_price: Some(Px::from_raw(10030).unwrap()),
+        ..MassCancel::all()
+    };
+    let res = feed.book.mass_cancel(&range, Ts::from_nanos(8)).unwrap();
+    feed.check(res);
+
+    feed.submit(order(8, Side::Buy, 9990, 4, 9).with_owner(firm));
+    feed.submit(order(9, Side::Buy, 9980, 1, 10));
+    let res = feed.book.start_auction(AuctionKind::Opening, Ts::from_nanos(11)).unwrap();
+    feed.check(res);
+    feed.submit(order(10, Side::Sell, 9980, 14, 12));
+    let res = feed.book.uncross(Ts::from_nanos(13)).unwrap();
+    feed.check(res);
+    assert_eq!(feed.book.last_trade(), Some(Px::from_raw(9980).unwrap()));
+    assert!(!feed.replica.queues.is_empty());
+}
+
+#[test]
+fn decrement_and_cancel_modifies_the_resting_order() {
+    let mut feed = Feed {
+        book: OrderBook::with_config(
+            SYM,
+            BookConfig {
+                self_trade_prevention: SelfTradePrevention::DecrementAndCancel,
+                market_data: MarketData::FULL,
+                ..BookConfig::default()
+            },
+        ),
+        replica: Replica::default(),
+    };
+    let firm = OwnerID::new(1).unwrap();
+    feed.submit(order(1, Side::Sell, 10010, 5, 1).with_owner(firm));
+    let res = feed.submit(order(2, Side::Buy, 10010, 2, 2).with_owner(firm));
+    assert_eq!(actions(&res), vec![(MboAction::Modify, 1, 3)]);
+}
+
+#[test]
+fn a_snapshot_and_the_feeds_rebuild_a_long_random_session() {
+    // Small LCG so the session is the same on every run.
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |n: u64| {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (state >> 33) % n
+    };
+    let mut feed = Feed::new();
+    let mut late: Option<Replica> = None;
+    let mut live = BTreeSet::new();
+    let mut traded = 0;
+    for step in 1..=5_000u64 {
+        let side = if next(2) == 0 { Side::Buy } else { Side::Sell };
+        // The sides overlap by ten ticks: most orders rest, some trade.
+        let price = match side {
+            Side::Buy => 9_975 + next(30),
+            Side::Sell => 9_995 + next(30),
+        };
+        live.retain(|id| feed.book.contains(OrderID::new(*id).unwrap()));
+        let pick = live.iter().copied().nth(next(live.len().max(1) as u64) as usize);
+        let res = match next(10) {
+            0..=5 => {
+                let mut o = order(step, side, price, 1 + next(20), step);
+                if next(4) == 0 {
+                    o = o.with_peak(Qty::new(1 + next(3))).unwrap();
+                }
+                live.insert(step);
+                feed.submit(o)
+            }
+            6 | 7 => match pick {
+                Some(id) => feed.cancel(id, step),
+                None => continue,
+            },
+            _ => {
+                let Some(id) = pick else {
+                    continue;
+                };
+                let o = *feed.book.order(OrderID::new(id).unwrap()).unwrap();
+                let price = if next(2) == 0 { o.price().raw() } else { price };
+                let qty = 1 + next(o.remaining().value() + 5);
+                feed.replace(id, price, qty, step)
+            }
+        };
+        traded += res.fills.len();
+        if let Some(replica) = late.as_mut() {
+            replica.apply(&res);
+            replica.assert_matches(&feed.book);
+        }
+        if step == 2_500 {
+            late = Some(Replica::from_snapshot(&feed.book));
+        }
+    }
+    assert!(traded > 100 && feed.book.len() > 100);
+}
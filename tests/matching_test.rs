@@ -3,36 +3,56 @@
 //! Every test pins a fixed input sequence and asserts the exact `FillEvent`
 //! / `QuoteEvent` / `OrderEvent` outputs. Determinism is the contract.
 
-use nyquestro::book::OrderBook;
-use nyquestro::events::{OrderEvent, OrderRejectionReason, QuoteSide};
+use nyquestro::book::{
+    BookConfig, Market, MassCancel, OrderBook, ProtectionBand, SelfTradePrevention, SubmitResult,
+};
+use nyquestro::errors::NyquestroResult;
+use nyquestro::events::{CancelReason, OrderEvent, OrderRejectionReason, QuoteSide, Sequenced};
 use nyquestro::order::Order;
-use nyquestro::types::{OrderID, Px, Qty, Side, Status, Symbol, Ts};
+use nyquestro::types::{
+    OrderID, OwnerID, Peg, PegReference, PostOnly, Px, Qty, Side, Status, Symbol, TimeInForce, Ts,
+};
 
 const SYM: Symbol = Symbol::from_const("TEST");
 
-fn buy(id: u64, price: u64, qty: u32, ts: u64) -> Order {
+fn buy(id: u64, price: u64, qty: u64, ts: u64) -> Order {
     Order::new(
         OrderID::new(id).unwrap(),
         SYM,
         Side::Buy,
-        Px::from_cents(price).unwrap(),
+        Px::from_raw(price).unwrap(),
         Qty::new(qty),
         Ts::from_nanos(ts),
     )
     .unwrap()
 }
-fn sell(id: u64, price: u64, qty: u32, ts: u64) -> Order {
+fn sell(id: u64, price: u64, qty: u64, ts: u64) -> Order {
     Order::new(
         OrderID::new(id).unwrap(),
         SYM,
         Side::Sell,
-        Px::from_cents(price).unwrap(),
+        Px::from_raw(price).unwrap(),
         Qty::new(qty),
         Ts::from_nanos(ts),
     )
     .unwrap()
 }
 
+fn replace(
+    book: &mut OrderBook,
+    id: u64,
+    price: u64,
+    qty: u64,
+    ts: u64,
+) -> NyquestroResult<SubmitResult> {
+    book.replace(
+        OrderID::new(id).unwrap(),
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+}
+
 // ─── Resting & inspection ──────────────────────────────────────────────────
 
 #[test]
@@ -48,8 +68,8 @@ fn resting_orders_define_top_of_book() {
     book.submit_limit(buy(2, 9985, 3, 2)).unwrap();
     book.submit_limit(sell(3, 10005, 4, 3)).unwrap();
 
-    assert_eq!(book.best_bid(), Some((Px::from_cents(9990).unwrap(), Qty::new(5))));
-    assert_eq!(book.best_ask(), Some((Px::from_cents(10005).unwrap(), Qty::new(4))));
+    assert_eq!(book.best_bid(), Some((Px::from_raw(9990).unwrap(), Qty::new(5))));
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10005).unwrap(), Qty::new(4))));
     assert_eq!(book.len(), 3);
 }
 
@@ -65,7 +85,7 @@ fn simple_cross_one_resting_one_aggressor() {
     let f = res.fills[0];
     assert_eq!(f.buyer_order_id.value(), 2);
     assert_eq!(f.seller_order_id.value(), 1);
-    assert_eq!(f.price.cents(), 10000);
+    assert_eq!(f.price.raw(), 10000);
     assert_eq!(f.quantity, Qty::new(5));
     // Book is now empty.
     assert!(book.is_empty());
@@ -86,16 +106,16 @@ fn aggressor_sweeps_three_levels() {
     let res = book.submit_limit(buy(99, 10025, 8, 4)).unwrap();
     assert_eq!(res.fills.len(), 3);
     assert_eq!(res.fills[0].quantity, Qty::new(3));
-    assert_eq!(res.fills[0].price.cents(), 10000);
+    assert_eq!(res.fills[0].price.raw(), 10000);
     assert_eq!(res.fills[1].quantity, Qty::new(4));
-    assert_eq!(res.fills[1].price.cents(), 10010);
+    assert_eq!(res.fills[1].price.raw(), 10010);
     assert_eq!(res.fills[2].quantity, Qty::new(1));
-    assert_eq!(res.fills[2].price.cents(), 10020);
+    assert_eq!(res.fills[2].price.raw(), 10020);
 
     // Level 3 still has 1 unit remaining.
     assert_eq!(
         book.best_ask(),
-        Some((Px::from_cents(10020).unwrap(), Qty::new(1)))
+        Some((Px::from_raw(10020).unwrap(), Qty::new(1)))
     );
     assert!(book.best_bid().is_none()); // aggressor was fully filled
 }
@@ -115,7 +135,7 @@ fn partial_match_then_rest_remainder() {
     // Bid side now has 5 resting at 10000.
     assert_eq!(
         book.best_bid(),
-        Some((Px::from_cents(10000).unwrap(), Qty::new(5)))
+        Some((Px::from_raw(10000).unwrap(), Qty::new(5)))
     );
     assert!(book.best_ask().is_none());
 
@@ -128,6 +148,11 @@ fn partial_match_then_rest_remainder() {
             OrderEvent::Filled { .. } => "F",
             OrderEvent::Cancelled { .. } => "C",
             OrderEvent::Rejected { .. } => "R",
+            OrderEvent::Replaced { .. } => "M",
+            OrderEvent::Decremented { .. } => "D",
+            OrderEvent::Expired { .. } => "E",
+            OrderEvent::Refreshed { .. } => "I",
+            OrderEvent::Triggered { .. } => "T",
         })
         .collect();
     assert!(kinds.contains(&"P"));
@@ -154,89 +179,1399 @@ fn fifo_within_a_price_level() {
     // Order 12 still has 1 remaining at 10000.
     assert_eq!(
         book.best_ask(),
-        Some((Px::from_cents(10000).unwrap(), Qty::new(1)))
+        Some((Px::from_raw(10000).unwrap(), Qty::new(1)))
     );
 }
 
-// ─── Self-match rejection ──────────────────────────────────────────────────
+// ─── Duplicate ids ─────────────────────────────────────────────────────────
 
 #[test]
-fn self_match_rejects_aggressor_leaves_resting() {
+fn duplicate_id_rejects_aggressor_leaves_resting() {
     let mut book = OrderBook::new(SYM);
-    // Place a sell from id=42, then have id=42 try to buy across — match-time
-    // rejection: the aggressor is rejected, the resting order is untouched.
+    // Place a sell from id=42, then submit a buy reusing id=42. The id is
+    // already resting, so the new order is rejected before matching and
+    // the resting order is untouched.
     book.submit_limit(sell(42, 10000, 5, 1)).unwrap();
     let res = book.submit_limit(buy(42, 10000, 5, 2)).unwrap();
     assert!(res.fills.is_empty());
+    assert!(res.quotes.is_empty());
     assert!(matches!(
         res.lifecycle.last().unwrap(),
         OrderEvent::Rejected {
-            reason: OrderRejectionReason::SelfMatch,
+            reason: OrderRejectionReason::DuplicateOrderId,
             ..
         }
     ));
     // Resting sell still in the book.
     assert_eq!(
         book.best_ask(),
-        Some((Px::from_cents(10000).unwrap(), Qty::new(5)))
+        Some((Px::from_raw(10000).unwrap(), Qty::new(5)))
     );
+    assert_eq!(book.len(), 1);
 }
 
-// ─── Cancellation ─────────────────────────────────────────────────────────
-
 #[test]
-fn cancel_removes_order_from_book() {
+fn id_is_reusable_once_no_longer_resting() {
     let mut book = OrderBook::new(SYM);
     book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
-    book.submit_limit(buy(2, 9990, 3, 2)).unwrap();
+    book.cancel(OrderID::new(1).unwrap(), Ts::from_nanos(2)).unwrap();
+    let res = book.submit_limit(buy(1, 9990, 5, 3)).unwrap();
+    assert!(matches!(res.lifecycle[0], OrderEvent::Placed { .. }));
 
-    let evt = book.cancel(OrderID::new(1).unwrap(), Ts::from_nanos(3)).unwrap();
-    assert!(matches!(evt, OrderEvent::Cancelled { .. }));
-    // FIFO remainder: only id=2 (3 units) is left at 9990.
+    // Fully filled resting orders leave the index too.
+    book.submit_limit(sell(2, 9990, 5, 4)).unwrap();
+    assert!(!book.contains(OrderID::new(1).unwrap()));
+    let res = book.submit_limit(sell(1, 10010, 5, 5)).unwrap();
+    assert!(matches!(res.lifecycle[0], OrderEvent::Placed { .. }));
+}
+
+// ─── Self-trade prevention ────────────────────────────────────────────────
+
+fn firm(n: u64) -> OwnerID {
+    OwnerID::new(n).unwrap()
+}
+
+fn stp_book(mode: SelfTradePrevention) -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            self_trade_prevention: mode,
+            ..BookConfig::default()
+        },
+    )
+}
+
+fn stp_cancelled(e: &OrderEvent) -> Option<(u64, u64)> {
+    match e {
+        OrderEvent::Cancelled {
+            order_id,
+            remaining,
+            reason: CancelReason::SelfTradePrevention,
+            ..
+        } => Some((order_id.value(), remaining.value())),
+        _ => None,
+    }
+}
+
+#[test]
+fn anonymous_orders_never_self_trade() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 5, 1)).unwrap();
+    let res = book.submit_limit(buy(2, 10000, 5, 2)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+}
+
+#[test]
+fn stp_cancel_newest_rejects_untraded_aggressor() {
+    let mut book = stp_book(SelfTradePrevention::CancelNewest);
+    book.submit_limit(sell(1, 10000, 5, 1).with_owner(firm(7))).unwrap();
+    let res = book.submit_limit(buy(2, 10000, 5, 2).with_owner(firm(7))).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(res.quotes.is_empty());
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::SelfMatch,
+            ..
+        }]
+    ));
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(5)))
+    );
+}
+
+#[test]
+fn stp_cancel_newest_cancels_residual_after_trading() {
+    let mut book = stp_book(SelfTradePrevention::CancelNewest);
+    book.submit_limit(sell(1, 10000, 2, 1).with_owner(firm(8))).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 2).with_owner(firm(7))).unwrap();
+    book.submit_limit(sell(3, 10000, 5, 3).with_owner(firm(8))).unwrap();
+
+    let res = book.submit_limit(buy(9, 10000, 6, 4).with_owner(firm(7))).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].seller_order_id.value(), 1);
+    assert_eq!(stp_cancelled(res.lifecycle.last().unwrap()), Some((9, 4)));
+    // Rest of the level untouched: 2 then 3.
+    let ids: Vec<_> = book
+        .ask_levels()
+        .flat_map(|(_, l)| l.iter().map(|o| o.id().value()))
+        .collect();
+    assert_eq!(ids, vec![2, 3]);
+    assert!(book.best_bid().is_none());
+    assert_eq!(res.quotes.len(), 1);
+    assert_eq!(res.quotes[0].quantity, Qty::new(10));
+}
+
+#[test]
+fn stp_cancel_oldest_removes_resting_and_keeps_matching() {
+    let mut book = stp_book(SelfTradePrevention::CancelOldest);
+    book.submit_limit(sell(1, 10000, 3, 1).with_owner(firm(7))).unwrap();
+    book.submit_limit(sell(2, 10000, 4, 2).with_owner(firm(8))).unwrap();
+
+    let res = book.submit_limit(buy(9, 10000, 5, 3).with_owner(firm(7))).unwrap();
+    assert_eq!(stp_cancelled(&res.lifecycle[0]), Some((1, 3)));
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].seller_order_id.value(), 2);
+    assert_eq!(res.fills[0].quantity, Qty::new(4));
+    // 1 left on the aggressor rests as a bid.
     assert_eq!(
         book.best_bid(),
-        Some((Px::from_cents(9990).unwrap(), Qty::new(3)))
+        Some((Px::from_raw(10000).unwrap(), Qty::new(1)))
     );
+    assert!(book.best_ask().is_none());
+    assert!(!book.contains(OrderID::new(1).unwrap()));
 }
 
 #[test]
-fn cancel_unknown_id_errors() {
+fn stp_cancel_both_cancels_resting_and_aggressor() {
+    let mut book = stp_book(SelfTradePrevention::CancelBoth);
+    book.submit_limit(sell(1, 10000, 3, 1).with_owner(firm(7))).unwrap();
+    book.submit_limit(sell(2, 10000, 4, 2).with_owner(firm(8))).unwrap();
+
+    let res = book.submit_limit(buy(9, 10000, 5, 3).with_owner(firm(7))).unwrap();
+    assert!(res.fills.is_empty());
+    let cancels: Vec<_> = res.lifecycle.iter().filter_map(stp_cancelled).collect();
+    assert_eq!(cancels, vec![(1, 3), (9, 5)]);
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(4)))
+    );
+    assert!(book.best_bid().is_none());
+    assert!(res.quotes.iter().any(|q| q.side == QuoteSide::Ask && q.quantity == Qty::new(4)));
+}
+
+#[test]
+fn stp_decrement_shrinks_larger_resting_order_in_place() {
+    let mut book = stp_book(SelfTradePrevention::DecrementAndCancel);
+    book.submit_limit(sell(1, 10000, 8, 1).with_owner(firm(7))).unwrap();
+    book.submit_limit(sell(2, 10000, 4, 2)).unwrap();
+
+    let res = book.submit_limit(buy(9, 10000, 3, 3).with_owner(firm(7))).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(matches!(
+        res.lifecycle[0],
+        OrderEvent::Decremented { order_id, decremented, remaining, .. }
+            if order_id.value() == 1 && decremented == Qty::new(3) && remaining == Qty::new(5)
+    ));
+    assert_eq!(stp_cancelled(&res.lifecycle[1]), Some((9, 3)));
+    // Order 1 keeps its place at the front.
+    let front = book.ask_levels().next().unwrap().1.front().unwrap().id();
+    assert_eq!(front.value(), 1);
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(9)))
+    );
+}
+
+#[test]
+fn stp_decrement_cancels_smaller_resting_and_continues() {
+    let mut book = stp_book(SelfTradePrevention::DecrementAndCancel);
+    book.submit_limit(sell(1, 10000, 2, 1).with_owner(firm(7))).unwrap();
+    book.submit_limit(sell(2, 10000, 4, 2)).unwrap();
+
+    let res = book.submit_limit(buy(9, 10000, 5, 3).with_owner(firm(7))).unwrap();
+    assert_eq!(stp_cancelled(&res.lifecycle[0]), Some((1, 2)));
+    // Aggressor decremented 5 → 3, then trades 3 against order 2.
+    assert!(matches!(
+        res.lifecycle[1],
+        OrderEvent::Decremented { order_id, side: Side::Buy, decremented, remaining, .. }
+            if order_id.value() == 9 && decremented == Qty::new(2) && remaining == Qty::new(3)
+    ));
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].quantity, Qty::new(3));
+    assert!(book.best_bid().is_none());
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(1)))
+    );
+}
+
+#[test]
+fn stp_decrement_equal_sizes_cancels_both() {
+    let mut book = stp_book(SelfTradePrevention::DecrementAndCancel);
+    book.submit_limit(sell(1, 10000, 4, 1).with_owner(firm(7))).unwrap();
+    let res = book.submit_limit(buy(9, 10000, 4, 2).with_owner(firm(7))).unwrap();
+    let cancels: Vec<_> = res.lifecycle.iter().filter_map(stp_cancelled).collect();
+    assert_eq!(cancels, vec![(1, 4), (9, 4)]);
+    assert!(book.is_empty());
+    assert!(res.quotes.iter().any(|q| q.side == QuoteSide::Ask && q.quantity.is_zero()));
+}
+
+// ─── Time in force ────────────────────────────────────────────────────────
+
+#[test]
+fn ioc_trades_what_it_can_and_cancels_the_rest() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 3, 2)).unwrap();
+
+    let res = book
+        .submit_limit(buy(9, 10000, 5, 3).with_time_in_force(TimeInForce::Ioc))
+        .unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert!(matches!(
+        res.lifecycle.last().unwrap(),
+        OrderEvent::Cancelled {
+            reason: CancelReason::ImmediateOrCancel,
+            remaining,
+            ..
+        } if *remaining == Qty::new(2)
+    ));
+    assert!(book.best_bid().is_none());
+    assert!(!book.contains(OrderID::new(9).unwrap()));
+}
+
+#[test]
+fn ioc_with_nothing_to_hit_is_cancelled_in_full() {
+    let mut book = OrderBook::new(SYM);
+    let res = book
+        .submit_limit(buy(9, 10000, 5, 1).with_time_in_force(TimeInForce::Ioc))
+        .unwrap();
+    assert!(res.fills.is_empty());
+    assert!(res.quotes.is_empty());
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Cancelled {
+            reason: CancelReason::ImmediateOrCancel,
+            ..
+        }]
+    ));
+    assert!(book.is_empty());
+}
+
+#[test]
+fn fok_fills_in_full_across_levels() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 3, 2)).unwrap();
+
+    let res = book
+        .submit_limit(buy(9, 10010, 5, 3).with_time_in_force(TimeInForce::Fok))
+        .unwrap();
+    let filled: u64 = res.fills.iter().map(|f| f.quantity.value()).sum();
+    assert_eq!(filled, 5);
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10010).unwrap(), Qty::new(1)))
+    );
+}
+
+#[test]
+fn fok_short_of_size_is_rejected_without_touching_the_book() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    // Enough size exists, but only beyond the FOK's limit price.
+    book.submit_limit(sell(2, 10020, 3, 2)).unwrap();
+
+    let res = book
+        .submit_limit(buy(9, 10010, 5, 3).with_time_in_force(TimeInForce::Fok))
+        .unwrap();
+    assert!(res.fills.is_empty());
+    assert!(res.quotes.is_empty());
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::FillOrKillUnfilled,
+            ..
+        }]
+    ));
+    assert_eq!(book.len(), 2);
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(3)))
+    );
+}
+
+#[test]
+fn fok_probe_does_not_count_own_liquidity() {
+    let owner = OwnerID::new(7).unwrap();
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 5, 1).with_owner(owner)).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 2)).unwrap();
+    let res = book
+        .submit_limit(
+            buy(9, 10000, 5, 3)
+                .with_owner(owner)
+                .with_time_in_force(TimeInForce::Fok),
+        )
+        .unwrap();
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::FillOrKillUnfilled,
+            ..
+        }]
+    ));
+    assert_eq!(book.len(), 2);
+}
+
+#[test]
+fn gtd_and_day_orders_expire_on_sweep() {
+    const DAY: u64 = 86_400 * 1_000_000_000;
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1).with_time_in_force(TimeInForce::Day))
+        .unwrap();
+    book.submit_limit(buy(2, 9995, 5, 2).with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(100))))
+        .unwrap();
+    book.submit_limit(buy(3, 9980, 5, 3)).unwrap();
+
+    // Nothing due yet.
+    assert!(book.expire(Ts::from_nanos(99)).unwrap().lifecycle.is_empty());
+
+    let res = book.expire(Ts::from_nanos(100)).unwrap();
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Expired { order_id, remaining, .. }]
+            if order_id.value() == 2 && *remaining == Qty::new(5)
+    ));
+    assert_eq!(res.quotes.len(), 1);
+    assert_eq!(res.quotes[0].price, Px::from_raw(9990).unwrap());
+
+    let res = book.expire(Ts::from_nanos(DAY)).unwrap();
+    assert_eq!(res.lifecycle.len(), 1);
+    assert_eq!(res.lifecycle[0].order_id().value(), 1);
+    // GTC order survives every sweep.
+    assert_eq!(book.len(), 1);
+    assert!(book.contains(OrderID::new(3).unwrap()));
+}
+
+#[test]
+fn expiry_schedule_forgets_filled_and_cancelled_orders() {
+    let gtd = TimeInForce::Gtd(Ts::from_nanos(50));
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 5, 1).with_time_in_force(gtd)).unwrap();
+    book.submit_limit(sell(2, 10010, 5, 2).with_time_in_force(gtd)).unwrap();
+    book.submit_limit(buy(9, 10000, 5, 3)).unwrap();
+    book.cancel(OrderID::new(2).unwrap(), Ts::from_nanos(4)).unwrap();
+
+    let res = book.expire(Ts::from_nanos(50)).unwrap();
+    assert!(res.lifecycle.is_empty());
+    assert!(book.is_empty());
+}
+
+#[test]
+fn gtd_in_the_past_is_rejected() {
+    let mut book = OrderBook::new(SYM);
+    let res = book
+        .submit_limit(buy(1, 9990, 5, 10).with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(10))))
+        .unwrap();
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::AlreadyExpired,
+            ..
+        }]
+    ));
+    assert!(book.is_empty());
+}
+
+#[test]
+fn replace_keeps_original_expiry() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1).with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(50))))
+        .unwrap();
+    replace(&mut book, 1, 9995, 8, 10).unwrap();
+    let res = book.expire(Ts::from_nanos(50)).unwrap();
+    assert_eq!(res.lifecycle.len(), 1);
+    assert!(book.is_empty());
+}
+
+// ─── Market orders ────────────────────────────────────────────────────────
+
+fn market(id: u64, side: Side, qty: u64, ts: u64) -> Order {
+    Order::market(OrderID::new(id).unwrap(), SYM, side, Qty::new(qty), Ts::from_nanos(ts)).unwrap()
+}
+
+fn banded_book(band: ProtectionBand) -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            market_protection: band,
+            ..BookConfig::default()
+        },
+    )
+}
+
+fn cancel_reason(e: &OrderEvent) -> Option<CancelReason> {
+    match e {
+        OrderEvent::Cancelled { reason, .. } => Some(*reason),
+        _ => None,
+    }
+}
+
+#[test]
+fn market_order_sweeps_at_resting_prices() {
+    let mut book = banded_book(ProtectionBand::Ticks(20));
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 3, 2)).unwrap();
+
+    let res = book.submit_limit(market(9, Side::Buy, 5, 3)).unwrap();
+    let prices: Vec<_> = res.fills.iter().map(|f| f.price.raw()).collect();
+    assert_eq!(prices, vec![10000, 10010]);
+    assert!(!res.lifecycle.iter().any(|e| matches!(e, OrderEvent::Placed { .. })));
+    assert!(book.best_bid().is_none());
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10010).unwrap(), Qty::new(1)))
+    );
+}
+
+#[test]
+fn market_order_stops_at_protection_band() {
+    // Band of 10 ticks from the best ask at arrival (10000) → limit 10010.
+    let mut book = banded_book(ProtectionBand::Ticks(10));
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 3, 2)).unwrap();
+    book.submit_limit(sell(3, 10011, 3, 3)).unwrap();
+
+    let res = book.submit_limit(market(9, Side::Buy, 10, 4)).unwrap();
+    let filled: u64 = res.fills.iter().map(|f| f.quantity.value()).sum();
+    assert_eq!(filled, 6);
+    let last = res.lifecycle.last().unwrap();
+    assert_eq!(cancel_reason(last), Some(CancelReason::ProtectionBand));
+    assert!(matches!(last, OrderEvent::Cancelled { remaining, .. } if *remaining == Qty::new(4)));
+    // The level beyond the band is untouched and nothing rests on the bid.
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10011).unwrap(), Qty::new(3)))
+    );
+    assert!(book.best_bid().is_none());
+}
+
+#[test]
+fn market_sell_band_in_bps() {
+    // 100 bps of 10000 = 100 ticks → sells may go down to 9900.
+    let mut book = banded_book(ProtectionBand::Bps(100));
+    book.submit_limit(buy(1, 10000, 2, 1)).unwrap();
+    book.submit_limit(buy(2, 9900, 2, 2)).unwrap();
+    book.submit_limit(buy(3, 9899, 2, 3)).unwrap();
+
+    let res = book.submit_limit(market(9, Side::Sell, 6, 4)).unwrap();
+    assert_eq!(res.fills.len(), 2);
+    assert_eq!(
+        cancel_reason(res.lifecycle.last().unwrap()),
+        Some(CancelReason::ProtectionBand)
+    );
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9899).unwrap(), Qty::new(2)))
+    );
+}
+
+#[test]
+fn market_order_exhausting_the_book_cancels_residual() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    let res = book.submit_limit(market(9, Side::Buy, 5, 2)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(
+        cancel_reason(res.lifecycle.last().unwrap()),
+        Some(CancelReason::LiquidityExhausted)
+    );
+    assert!(book.is_empty());
+}
+
+#[test]
+fn market_order_into_empty_side_is_rejected() {
     let mut book = OrderBook::new(SYM);
     book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
-    let err = book.cancel(OrderID::new(99).unwrap(), Ts::from_nanos(2));
-    assert!(err.is_err());
+    let res = book.submit_limit(market(9, Side::Buy, 5, 2)).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(res.quotes.is_empty());
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::NoLiquidity,
+            ..
+        }]
+    ));
+    assert_eq!(book.len(), 1);
 }
 
-// ─── Determinism ──────────────────────────────────────────────────────────
+// ─── Iceberg orders ───────────────────────────────────────────────────────
+
+fn iceberg(id: u64, side: Side, price: u64, qty: u64, peak: u64, ts: u64) -> Order {
+    let o = match side {
+        Side::Buy => buy(id, price, qty, ts),
+        Side::Sell => sell(id, price, qty, ts),
+    };
+    o.with_peak(Qty::new(peak)).unwrap()
+}
 
 #[test]
-fn run_twice_identical_sequence_identical_output() {
-    let sequence: Vec<Order> = vec![
-        sell(1, 10010, 5, 1),
-        sell(2, 10000, 3, 2),
-        buy(3, 9995, 4, 3),
-        buy(4, 10005, 7, 4),
-        sell(5, 10005, 2, 5),
-        buy(6, 10010, 10, 6),
-    ];
+fn iceberg_shows_only_its_peak() {
+    let mut book = OrderBook::new(SYM);
+    let res = book.submit_limit(iceberg(1, Side::Sell, 10000, 100, 10, 1)).unwrap();
+    assert_eq!(res.quotes.len(), 1);
+    assert_eq!(res.quotes[0].quantity, Qty::new(10));
+    book.submit_limit(sell(2, 10000, 5, 2)).unwrap();
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(15)))
+    );
+    assert_eq!(
+        book.top_n_asks(1),
+        vec![(Px::from_raw(10000).unwrap(), Qty::new(15))]
+    );
+    assert_eq!(book.order(OrderID::new(1).unwrap()).unwrap().hidden(), Qty::new(90));
+}
 
-    let mut a = OrderBook::new(SYM);
-    let mut b = OrderBook::new(SYM);
-    let ra: Vec<_> = sequence
+#[test]
+fn refreshed_peak_queues_behind_displayed_orders() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(iceberg(1, Side::Sell, 10000, 25, 10, 1)).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 2)).unwrap();
+
+    let res = book.submit_limit(buy(9, 10000, 18, 3)).unwrap();
+    let fills: Vec<_> = res
+        .fills
         .iter()
-        .copied()
-        .map(|o| a.submit_limit(o).unwrap())
+        .map(|f| (f.seller_order_id.value(), f.quantity.value()))
         .collect();
-    let rb: Vec<_> = sequence
+    assert_eq!(fills, vec![(1, 10), (2, 5), (1, 3)]);
+    assert!(res.lifecycle.iter().any(|e| matches!(
+        e,
+        OrderEvent::Refreshed { order_id, displayed, hidden, timestamp, .. }
+            if order_id.value() == 1
+                && *displayed == Qty::new(10)
+                && *hidden == Qty::new(5)
+                && *timestamp == Ts::from_nanos(3)
+    )));
+    // 7 of the second peak left showing, 5 still hidden.
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(7)))
+    );
+    assert_eq!(res.quotes.last().unwrap().quantity, Qty::new(7));
+    let o = book.order(OrderID::new(1).unwrap()).unwrap();
+    assert_eq!((o.remaining(), o.hidden()), (Qty::new(12), Qty::new(5)));
+}
+
+#[test]
+fn aggressor_works_through_hidden_reserve() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(iceberg(1, Side::Buy, 10000, 30, 10, 1)).unwrap();
+    let res = book.submit_limit(sell(9, 9990, 30, 2)).unwrap();
+    assert_eq!(res.fills.len(), 3);
+    let refreshes = res
+        .lifecycle
         .iter()
-        .copied()
-        .map(|o| b.submit_limit(o).unwrap())
-        .collect();
+        .filter(|e| matches!(e, OrderEvent::Refreshed { .. }))
+        .count();
+    assert_eq!(refreshes, 2);
+    assert!(book.is_empty());
+}
+
+#[test]
+fn fok_counts_hidden_quantity() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(iceberg(1, Side::Sell, 10000, 20, 5, 1)).unwrap();
+    let fok = buy(9, 10000, 20, 2).with_time_in_force(TimeInForce::Fok);
+    let res = book.submit_limit(fok).unwrap();
+    let filled: u64 = res.fills.iter().map(|f| f.quantity.value()).sum();
+    assert_eq!(filled, 20);
+    assert!(book.is_empty());
+}
+
+#[test]
+fn resting_iceberg_aggressor_shows_a_full_peak() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(iceberg(9, Side::Buy, 10000, 50, 10, 2)).unwrap();
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(10)))
+    );
+    let o = book.order(OrderID::new(9).unwrap()).unwrap();
+    assert_eq!((o.remaining(), o.hidden()), (Qty::new(47), Qty::new(37)));
+}
+
+// ─── Pegged orders ────────────────────────────────────────────────────────
+
+fn pegged(id: u64, side: Side, reference: PegReference, offset: i64, qty: u64, ts: u64) -> Order {
+    let peg = Peg { reference, offset, cap: None };
+    Order::pegged(OrderID::new(id).unwrap(), SYM, side, peg, Qty::new(qty), Ts::from_nanos(ts))
+        .unwrap()
+}
+
+fn price_of(book: &OrderBook, id: u64) -> u64 {
+    book.order(OrderID::new(id).unwrap()).unwrap().price().raw()
+}
+
+#[test]
+fn primary_peg_follows_best_bid() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10020, 5, 2)).unwrap();
+    book.submit_limit(pegged(5, Side::Buy, PegReference::Primary, 0, 3, 3)).unwrap();
+    assert_eq!(price_of(&book, 5), 10000);
+
+    let res = book.submit_limit(buy(3, 10010, 2, 4)).unwrap();
+    assert!(matches!(
+        res.lifecycle.last().unwrap(),
+        OrderEvent::Replaced { order_id, price, .. }
+            if order_id.value() == 5 && price.raw() == 10010
+    ));
+    // Repriced peg queues behind the order that moved the reference.
+    let (_, level) = book.bid_levels().next().unwrap();
+    let queue: Vec<_> = level.iter().map(|o| o.id().value()).collect();
+    assert_eq!(queue, vec![3, 5]);
+    assert_eq!(
+        res.quotes,
+        vec![nyquestro::events::QuoteEvent::live(
+            SYM,
+            QuoteSide::Bid,
+            Px::from_raw(10010).unwrap(),
+            Qty::new(5),
+            Ts::from_nanos(4),
+        )
+        .unwrap()
+        .with_seq(9)]
+    );
+}
+
+#[test]
+fn midpoint_peg_rounds_passively_and_respects_cap() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10011, 5, 2)).unwrap();
+    book.submit_limit(pegged(5, Side::Buy, PegReference::Midpoint, 0, 3, 3)).unwrap();
+    book.submit_limit(pegged(6, Side::Sell, PegReference::Midpoint, 0, 3, 4)).unwrap();
+    assert_eq!((price_of(&book, 5), price_of(&book, 6)), (10005, 10006));
+
+    let capped = Peg {
+        reference: PegReference::Midpoint,
+        offset: 0,
+        cap: Some(Px::from_raw(10002).unwrap()),
+    };
+    let id = OrderID::new(7).unwrap();
+    let o = Order::pegged(id, SYM, Side::Buy, capped, Qty::new(1), Ts::from_nanos(5)).unwrap();
+    book.submit_limit(o).unwrap();
+    assert_eq!(price_of(&book, 7), 10002);
+}
+
+#[test]
+fn pegs_never_chase_each_other() {
+    // Each peg bids one tick above the best bid. Read from pegged
+    // liquidity too, they would ratchet upwards forever.
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10050, 5, 2)).unwrap();
+    book.submit_limit(pegged(5, Side::Buy, PegReference::Primary, 1, 3, 3)).unwrap();
+    book.submit_limit(pegged(6, Side::Buy, PegReference::Primary, 1, 3, 4)).unwrap();
+    assert_eq!((price_of(&book, 5), price_of(&book, 6)), (10001, 10001));
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(10001).unwrap(), Qty::new(6)))
+    );
+}
+
+#[test]
+fn repriced_peg_that_crosses_trades() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9985, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 5, 2)).unwrap();
+    book.submit_limit(pegged(5, Side::Sell, PegReference::Primary, -20, 3, 3)).unwrap();
+    assert_eq!(price_of(&book, 5), 9990);
+
+    // A better ask drags the peg down to 9984, through the 9985 bid.
+    let res = book.submit_limit(sell(3, 10004, 5, 4)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    let f = res.fills[0];
+    assert_eq!((f.buyer_order_id.value(), f.seller_order_id.value()), (1, 5));
+    assert_eq!((f.price.raw(), f.quantity.value()), (9985, 3));
+    assert!(!book.contains(OrderID::new(5).unwrap()));
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9985).unwrap(), Qty::new(2)))
+    );
+}
+
+#[test]
+fn peg_without_reference_is_rejected() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 10000, 5, 1)).unwrap();
+    let res = book
+        .submit_limit(pegged(5, Side::Buy, PegReference::Midpoint, 0, 3, 2))
+        .unwrap();
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::NoReferencePrice,
+            ..
+        }]
+    ));
+    assert_eq!(book.len(), 1);
+}
+
+#[test]
+fn reprice_after_cancel_moves_pegs() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(buy(2, 9990, 5, 2)).unwrap();
+    book.submit_limit(pegged(5, Side::Buy, PegReference::Primary, 0, 3, 3)).unwrap();
+    book.cancel(OrderID::new(1).unwrap(), Ts::from_nanos(4)).unwrap();
+    assert_eq!(price_of(&book, 5), 10000);
+
+    let res = book.reprice(Ts::from_nanos(5)).unwrap();
+    assert_eq!(res.lifecycle.len(), 1);
+    assert_eq!(price_of(&book, 5), 9990);
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9990).unwrap(), Qty::new(8)))
+    );
+    // Nothing left to move: a second pass is silent.
+    assert_eq!(book.reprice(Ts::from_nanos(6)).unwrap(), SubmitResult::default());
+}
+
+// ─── Stop orders ──────────────────────────────────────────────────────────
+
+fn stop_market(id: u64, side: Side, trigger: u64, qty: u64, ts: u64) -> Order {
+    market(id, side, qty, ts).with_stop(Px::from_raw(trigger).unwrap())
+}
+
+fn triggered_ids(res: &SubmitResult) -> Vec<u64> {
+    res.lifecycle
+        .iter()
+        .filter_map(|e| match e {
+            OrderEvent::Triggered { order_id, .. } => Some(order_id.value()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn stop_waits_out_of_sight_until_last_trade_reaches_it() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 2, 2)).unwrap();
+    book.submit_limit(sell(3, 10020, 5, 3)).unwrap();
+
+    let res = book.submit_limit(stop_market(9, Side::Buy, 10010, 3, 4)).unwrap();
+    assert!(matches!(res.lifecycle.as_slice(), [OrderEvent::Placed { .. }]));
+    assert!(res.quotes.is_empty());
+    assert_eq!(book.stops().len(), 1);
+    assert_eq!(book.len(), 3);
+
+    // A print at 10010 fires the stop, which lifts what is left.
+    let res = book.submit_limit(buy(4, 10010, 1, 5)).unwrap();
+    assert_eq!(triggered_ids(&res), vec![9]);
+    let fills: Vec<_> = res
+        .fills
+        .iter()
+        .map(|f| (f.buyer_order_id.value(), f.price.raw(), f.quantity.value()))
+        .collect();
+    assert_eq!(fills, vec![(4, 10010, 1), (9, 10010, 1), (9, 10020, 2)]);
+    assert!(book.stops().is_empty());
+    assert_eq!(book.last_trade(), Some(Px::from_raw(10020).unwrap()));
+}
+
+#[test]
+fn stop_cascade_resolves_in_one_result() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 1, 1)).unwrap();
+    book.submit_limit(buy(2, 9980, 1, 2)).unwrap();
+    book.submit_limit(buy(3, 9970, 5, 3)).unwrap();
+    book.submit_limit(stop_market(21, Side::Sell, 9980, 1, 4)).unwrap();
+    book.submit_limit(stop_market(20, Side::Sell, 9990, 1, 5)).unwrap();
+
+    // 9990 print → stop 20 sells into 9980 → that print fires stop 21.
+    let res = book.submit_limit(sell(9, 9990, 1, 6)).unwrap();
+    assert_eq!(triggered_ids(&res), vec![20, 21]);
+    let prices: Vec<_> = res.fills.iter().map(|f| f.price.raw()).collect();
+    assert_eq!(prices, vec![9990, 9980, 9970]);
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9970).unwrap(), Qty::new(4)))
+    );
+    assert_eq!(
+        res.quotes.last().unwrap().price,
+        Px::from_raw(9970).unwrap()
+    );
+}
+
+#[test]
+fn triggered_stop_limit_rests_at_its_limit() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10010, 1, 1)).unwrap();
+    book.submit_limit(sell(2, 10030, 5, 2)).unwrap();
+    let stop_limit = buy(9, 10020, 4, 3).with_stop(Px::from_raw(10010).unwrap());
+    book.submit_limit(stop_limit).unwrap();
+
+    let res = book.submit_limit(buy(3, 10010, 1, 4)).unwrap();
+    assert_eq!(triggered_ids(&res), vec![9]);
+    assert_eq!(res.fills.len(), 1);
+    let o = book.order(OrderID::new(9).unwrap()).unwrap();
+    assert_eq!((o.price().raw(), o.timestamp()), (10020, Ts::from_nanos(4)));
+    assert!(!o.is_stop());
+}
+
+#[test]
+fn stop_already_through_fires_on_entry() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10010, 1, 1)).unwrap();
+    book.submit_limit(buy(2, 10010, 1, 2)).unwrap();
+    book.submit_limit(sell(3, 10020, 5, 3)).unwrap();
+
+    let res = book.submit_limit(stop_market(9, Side::Buy, 10000, 2, 4)).unwrap();
+    assert!(matches!(res.lifecycle[0], OrderEvent::Placed { .. }));
+    assert_eq!(triggered_ids(&res), vec![9]);
+    assert_eq!(res.fills.len(), 1);
+}
+
+#[test]
+fn waiting_stops_cancel_expire_and_block_duplicate_ids() {
+    const DAY: u64 = 86_400 * 1_000_000_000;
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(stop_market(1, Side::Buy, 10100, 1, 1)).unwrap();
+    let day_stop = stop_market(2, Side::Sell, 9900, 1, 2).with_time_in_force(TimeInForce::Day);
+    book.submit_limit(day_stop).unwrap();
+
+    let dup = book.submit_limit(buy(1, 9000, 1, 3)).unwrap();
+    assert!(matches!(
+        dup.lifecycle.as_slice(),
+        [OrderEvent::Rejected {
+            reason: OrderRejectionReason::DuplicateOrderId,
+            ..
+        }]
+    ));
+    let evt = book.cancel(OrderID::new(1).unwrap(), Ts::from_nanos(4)).unwrap();
+    assert!(matches!(evt, OrderEvent::Cancelled { .. }));
+
+    let res = book.expire(Ts::from_nanos(DAY)).unwrap();
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Expired { order_id, .. }] if order_id.value() == 2
+    ));
+    assert!(book.stops().is_empty());
+    assert!(book.is_empty());
+}
+
+// ─── Post-only & minimum quantity ─────────────────────────────────────────
+
+fn rejection(res: &SubmitResult) -> Option<OrderRejectionReason> {
+    match res.lifecycle.as_slice() {
+        [OrderEvent::Rejected { reason, .. }] => Some(*reason),
+        _ => None,
+    }
+}
+
+#[test]
+fn post_only_that_would_take_is_rejected() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10010, 5, 1)).unwrap();
+    let res = book
+        .submit_limit(buy(9, 10010, 2, 2).with_post_only(PostOnly::Reject))
+        .unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::PostOnlyWouldTake));
+    assert!(res.fills.is_empty() && res.quotes.is_empty());
+    assert_eq!(book.len(), 1);
+
+    // Passive post-only orders rest as usual.
+    let res = book
+        .submit_limit(buy(9, 10009, 2, 3).with_post_only(PostOnly::Reject))
+        .unwrap();
+    assert!(matches!(res.lifecycle.as_slice(), [OrderEvent::Placed { .. }]));
+}
+
+#[test]
+fn post_only_slide_rests_one_tick_inside() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10010, 5, 1)).unwrap();
+    book.submit_limit(buy(2, 10000, 5, 2)).unwrap();
+    let res = book
+        .submit_limit(buy(9, 10050, 2, 3).with_post_only(PostOnly::Slide))
+        .unwrap();
+    assert!(res.fills.is_empty());
+    assert!(matches!(
+        res.lifecycle.as_slice(),
+        [OrderEvent::Placed { price, .. }] if price.raw() == 10009
+    ));
+    let res = book
+        .submit_limit(sell(8, 9000, 2, 4).with_post_only(PostOnly::Slide))
+        .unwrap();
+    assert!(res.fills.is_empty());
+    assert_eq!(price_of(&book, 8), 10010);
+}
+
+#[test]
+fn post_only_replace_cannot_cross() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10010, 5, 1)).unwrap();
+    book.submit_limit(buy(9, 10000, 2, 2).with_post_only(PostOnly::Reject)).unwrap();
+    let res = replace(&mut book, 9, 10010, 2, 3).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::PostOnlyWouldTake));
+    assert_eq!(price_of(&book, 9), 10000);
+}
+
+#[test]
+fn min_quantity_needs_enough_on_arrival() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10010, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10030, 5, 2)).unwrap();
+
+    let block = buy(9, 10020, 10, 3).with_min_quantity(Qty::new(4)).unwrap();
+    let res = book.submit_limit(block).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::MinQuantityUnfilled));
+    assert_eq!(book.len(), 2);
+
+    // At the minimum it trades, and the rest rests without one.
+    let block = buy(9, 10020, 10, 4).with_min_quantity(Qty::new(3)).unwrap();
+    let res = book.submit_limit(block).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(10020).unwrap(), Qty::new(7)))
+    );
+}
+
+// ─── Cancellation ─────────────────────────────────────────────────────────
+
+#[test]
+fn cancel_removes_order_from_book() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
+    book.submit_limit(buy(2, 9990, 3, 2)).unwrap();
+
+    let evt = book.cancel(OrderID::new(1).unwrap(), Ts::from_nanos(3)).unwrap();
+    assert!(matches!(evt, OrderEvent::Cancelled { .. }));
+    // FIFO remainder: only id=2 (3 units) is left at 9990.
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9990).unwrap(), Qty::new(3)))
+    );
+}
+
+#[test]
+fn cancel_unknown_id_errors() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
+    let err = book.cancel(OrderID::new(99).unwrap(), Ts::from_nanos(2));
+    assert!(err.is_err());
+}
+
+// ─── Mass cancel ──────────────────────────────────────────────────────────
+
+fn mass_cancelled(res: &SubmitResult) -> Vec<(u64, u64)> {
+    res.lifecycle
+        .iter()
+        .map(|e| match e {
+            OrderEvent::Cancelled {
+                order_id,
+                remaining,
+                reason: CancelReason::MassCancel,
+                ..
+            } => (order_id.value(), remaining.value()),
+            other => panic!("unexpected event {other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn mass_cancel_filters_by_side_owner_and_price() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1).with_owner(firm(1))).unwrap();
+    book.submit_limit(buy(2, 9980, 4, 2).with_owner(firm(2))).unwrap();
+    book.submit_limit(buy(3, 9980, 3, 3).with_owner(firm(1))).unwrap();
+    book.submit_limit(buy(4, 9970, 2, 4).with_owner(firm(1))).unwrap();
+    book.submit_limit(sell(5, 10010, 6, 5).with_owner(firm(1))).unwrap();
+
+    let filter = MassCancel {
+        side: Some(Side::Buy),
+        owner: Some(firm(1)),
+        min_price: Some(Px::from_raw(9975).unwrap()),
+        ..MassCancel::default()
+    };
+    let res = book.mass_cancel(&filter, Ts::from_nanos(6)).unwrap();
+    // Best bid first; firm 2's order and the bid below the range survive.
+    assert_eq!(mass_cancelled(&res), vec![(1, 5), (3, 3)]);
+    assert!(res.fills.is_empty());
+    assert_eq!(res.quotes.len(), 1);
+    assert_eq!(res.quotes[0].side, QuoteSide::Bid);
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9980).unwrap(), Qty::new(4)))
+    );
+    assert_eq!(book.len(), 3);
+    assert!(!book.contains(OrderID::new(1).unwrap()));
+    assert!(book.contains(OrderID::new(4).unwrap()));
+    assert!(book.contains(OrderID::new(5).unwrap()));
+}
+
+#[test]
+fn mass_cancel_clears_both_sides_and_waiting_stops() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 5, 2)).unwrap();
+    book.submit_limit(sell(3, 10020, 5, 3)).unwrap();
+    book.submit_limit(stop_market(4, Side::Buy, 10050, 2, 4)).unwrap();
+
+    let res = book.mass_cancel(&MassCancel::all(), Ts::from_nanos(5)).unwrap();
+    assert_eq!(mass_cancelled(&res), vec![(1, 5), (2, 5), (3, 5), (4, 2)]);
+    assert_eq!(res.quotes.len(), 2);
+    assert!(book.is_empty());
+    assert!(book.stops().is_empty());
+
+    // Ids are free again and an expiry sweep finds nothing left behind.
+    book.submit_limit(buy(1, 9990, 5, 6)).unwrap();
+    assert!(book.expire(Ts::from_nanos(u64::MAX)).unwrap().lifecycle.is_empty());
+}
+
+#[test]
+fn mass_cancel_with_an_empty_price_range_cancels_nothing() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
+    let filter = MassCancel {
+        min_price: Some(Px::from_raw(10000).unwrap()),
+        max_price: Some(Px::from_raw(9000).unwrap()),
+        ..MassCancel::default()
+    };
+    let res = book.mass_cancel(&filter, Ts::from_nanos(2)).unwrap();
+    assert_eq!(res, SubmitResult::default());
+    assert_eq!(book.len(), 1);
+}
+
+#[test]
+fn mass_cancel_clears_a_deep_book_in_one_call() {
+    let mut book = OrderBook::new(SYM);
+    for id in 1..=100_000u64 {
+        let order = if id % 2 == 0 {
+            buy(id, 9_000 - id % 500, 1, id)
+        } else {
+            sell(id, 11_000 + id % 500, 1, id)
+        };
+        book.submit_limit(order).unwrap();
+    }
+    let res = book.mass_cancel(&MassCancel::all(), Ts::from_nanos(200_000)).unwrap();
+    assert_eq!(res.lifecycle.len(), 100_000);
+    assert_eq!(res.quotes.len(), 2);
+    assert!(book.is_empty());
+    assert_eq!(book.len(), 0);
+}
+
+// ─── Cancel/replace ───────────────────────────────────────────────────────
+
+#[test]
+fn replace_size_decrease_keeps_priority() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 2)).unwrap();
+
+    let res = replace(&mut book, 1, 10000, 2, 3).unwrap();
+    assert!(matches!(
+        res.lifecycle[0],
+        OrderEvent::Replaced { remaining, .. } if remaining == Qty::new(2)
+    ));
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(7)))
+    );
+
+    // Id 1 is still first in line.
+    let res = book.submit_limit(buy(9, 10000, 2, 4)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].seller_order_id.value(), 1);
+}
+
+#[test]
+fn replace_size_increase_loses_priority() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 5, 1)).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 2)).unwrap();
+
+    replace(&mut book, 1, 10000, 6, 3).unwrap();
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(11)))
+    );
+
+    let res = book.submit_limit(buy(9, 10000, 5, 4)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].seller_order_id.value(), 2);
+}
+
+#[test]
+fn a_growing_replace_loses_priority_even_at_the_orders_own_time() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(iceberg(1, Side::Sell, 10000, 20, 4, 1)).unwrap();
+    book.submit_limit(buy(9, 10000, 3, 2)).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 3)).unwrap();
+
+    // Same timestamp as the order's own, but bigger: it goes to the back
+    // of the level with a fresh peak instead of its one-lot remainder.
+    replace(&mut book, 1, 10000, 20, 1).unwrap();
+    assert_eq!(
+        book.best_ask(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(9)))
+    );
+    let res = book.submit_limit(buy(10, 10000, 5, 4)).unwrap();
+    assert_eq!(res.fills[0].seller_order_id.value(), 2);
+}
+
+#[test]
+fn replace_price_change_moves_level() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
+    book.submit_limit(buy(2, 9995, 5, 2)).unwrap();
+
+    let res = replace(&mut book, 1, 9995, 5, 3).unwrap();
+    assert!(res.fills.is_empty());
+    assert!(book.top_n_bids(5).iter().all(|(p, _)| p.raw() == 9995));
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9995).unwrap(), Qty::new(10)))
+    );
+
+    // Id 2 arrived at 9995 first, so it fills first.
+    let res = book.submit_limit(sell(9, 9995, 5, 4)).unwrap();
+    assert_eq!(res.fills[0].buyer_order_id.value(), 2);
+}
+
+#[test]
+fn replace_to_crossing_price_trades() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(buy(2, 9990, 5, 2)).unwrap();
+
+    let res = replace(&mut book, 2, 10000, 5, 3).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].buyer_order_id.value(), 2);
+    assert_eq!(res.fills[0].quantity, Qty::new(3));
+    assert!(book.best_ask().is_none());
+    // The residual rests at the new price.
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(10000).unwrap(), Qty::new(2)))
+    );
+    assert!(res.quotes.iter().any(|q| q.side == QuoteSide::Ask && q.quantity.is_zero()));
+}
+
+#[test]
+fn replace_unknown_id_errors() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(buy(1, 9990, 5, 1)).unwrap();
+    let err = replace(&mut book, 99, 9990, 1, 2);
+    assert!(err.is_err());
+    assert_eq!(
+        book.best_bid(),
+        Some((Px::from_raw(9990).unwrap(), Qty::new(5)))
+    );
+}
+
+// ─── Id index ─────────────────────────────────────────────────────────────
+
+/// Cross-check the id index (`len`, `contains`, `order`) against a walk of
+/// both ladders.
+fn assert_index_matches_ladders(book: &OrderBook, ids: std::ops::RangeInclusive<u64>) {
+    let mut walked = 0;
+    for (side, levels) in [
+        (Side::Buy, book.bid_levels().collect::<Vec<_>>()),
+        (Side::Sell, book.ask_levels().collect::<Vec<_>>()),
+    ] {
+        for (px, level) in levels {
+            for o in level.iter() {
+                walked += 1;
+                let found = book.order(o.id()).expect("resting order is indexed");
+                assert_eq!(found.side(), side);
+                assert_eq!(found.price(), *px);
+            }
+        }
+    }
+    assert_eq!(book.len(), walked);
+    for raw in ids {
+        let id = OrderID::new(raw).unwrap();
+        let on_ladder = book
+            .bid_levels()
+            .chain(book.ask_levels())
+            .any(|(_, l)| l.iter().any(|o| o.id() == id));
+        assert_eq!(book.contains(id), on_ladder, "index drift on id {raw}");
+    }
+}
+
+#[test]
+fn index_never_drifts_from_ladders() {
+    let mut book = OrderBook::new(SYM);
+    // Small xorshift so the flow is deterministic without a dev-dependency.
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut next = move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state
+    };
+    const MAX_ID: u64 = 64;
+    for ts in 1..=2_000u64 {
+        let r = next();
+        let id = 1 + r % MAX_ID;
+        let price = 9_990 + (r >> 8) % 21;
+        let qty = 1 + (r >> 16) % 9;
+        match (r >> 24) % 4 {
+            0 | 1 => {
+                let order = if (r >> 32) & 1 == 0 {
+                    buy(id, price, qty, ts)
+                } else {
+                    sell(id, price, qty, ts)
+                };
+                book.submit_limit(order).unwrap();
+            }
+            2 => {
+                let _ = book.cancel(OrderID::new(id).unwrap(), Ts::from_nanos(ts));
+            }
+            _ => {
+                let _ = replace(&mut book, id, price, qty, ts);
+            }
+        }
+        assert_index_matches_ladders(&book, 1..=MAX_ID);
+    }
+}
+
+// ─── Determinism ──────────────────────────────────────────────────────────
+
+#[test]
+fn run_twice_identical_sequence_identical_output() {
+    let sequence: Vec<Order> = vec![
+        sell(1, 10010, 5, 1),
+        sell(2, 10000, 3, 2),
+        buy(3, 9995, 4, 3),
+        buy(4, 10005, 7, 4),
+        sell(5, 10005, 2, 5),
+        buy(6, 10010, 10, 6),
+    ];
+
+    let mut a = OrderBook::new(SYM);
+    let mut b = OrderBook::new(SYM);
+    let ra: Vec<_> = sequence
+        .iter()
+        .copied()
+        .map(|o| a.submit_limit(o).unwrap())
+        .collect();
+    let rb: Vec<_> = sequence
+        .iter()
+        .copied()
+        .map(|o| b.submit_limit(o).unwrap())
+        .collect();
     assert_eq!(ra, rb);
     assert_eq!(a.best_bid(), b.best_bid());
     assert_eq!(a.best_ask(), b.best_ask());
 }
 
+// ─── Sequencing ───────────────────────────────────────────────────────────
+
+/// Every event in `res`, whatever its kind, by `seq`.
+fn merged_seqs(res: &SubmitResult) -> Vec<u64> {
+    let mut seqs: Vec<u64> = res
+        .fills
+        .iter()
+        .map(Sequenced::seq)
+        .chain(res.quotes.iter().map(Sequenced::seq))
+        .chain(res.levels.iter().map(Sequenced::seq))
+        .chain(res.orders.iter().map(Sequenced::seq))
+        .chain(res.lifecycle.iter().map(Sequenced::seq))
+        .chain(res.auction.iter().map(Sequenced::seq))
+        .chain(res.state.iter().map(Sequenced::seq))
+        .collect();
+    seqs.sort_unstable();
+    seqs
+}
+
+#[test]
+fn every_event_is_numbered_once_without_gaps() {
+    let mut book = OrderBook::new(SYM);
+    let mut expected = 1;
+    let mut check = |res: &SubmitResult| {
+        let seqs = merged_seqs(res);
+        let want: Vec<u64> = (expected..expected + seqs.len() as u64).collect();
+        assert_eq!(seqs, want);
+        expected += seqs.len() as u64;
+    };
+    check(&book.submit_limit(sell(1, 10010, 5, 1)).unwrap());
+    check(&book.submit_limit(sell(2, 10020, 5, 2)).unwrap());
+    check(&book.submit_limit(buy(3, 10020, 8, 3)).unwrap());
+    check(&replace(&mut book, 2, 10030, 4, 4).unwrap());
+    check(&book.halt(Ts::from_nanos(5)).unwrap());
+    check(&book.resume(Ts::from_nanos(6)).unwrap());
+    let cancelled = book.cancel(OrderID::new(2).unwrap(), Ts::from_nanos(7)).unwrap();
+    assert_eq!(cancelled.seq(), expected);
+    assert_eq!(book.last_seq(), expected);
+}
+
+#[test]
+fn fills_carry_trade_ids_and_the_aggressor() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10010, 3, 2)).unwrap();
+    let res = book.submit_limit(buy(3, 10010, 8, 3)).unwrap();
+
+    let trades: Vec<_> = res
+        .fills
+        .iter()
+        .map(|f| {
+            (
+                f.trade_id,
+                f.aggressor,
+                f.aggressor_order_id().unwrap().value(),
+                f.resting_order_id().unwrap().value(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        trades,
+        vec![(1, Some(Side::Buy), 3, 1), (2, Some(Side::Buy), 3, 2)]
+    );
+    // A fill is numbered before the lifecycle events it causes.
+    assert!(res.fills[0].seq < res.lifecycle[0].seq());
+
+    let res = book.submit_limit(sell(4, 9990, 1, 4)).unwrap();
+    assert_eq!(res.fills[0].trade_id, 3);
+    assert_eq!(res.fills[0].aggressor, Some(Side::Sell));
+    assert_eq!(res.fills[0].resting_order_id().unwrap().value(), 3);
+    assert_eq!(book.last_trade_id(), 3);
+}
+
+#[test]
+fn executions_carry_the_time_of_the_order_that_caused_them() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(sell(1, 10000, 3, 1)).unwrap();
+    book.submit_limit(sell(2, 10000, 5, 2)).unwrap();
+    let res = book.submit_limit(buy(3, 10000, 4, 9)).unwrap();
+
+    // Resting since 1 and 2, traded at 9: the fills and every `Filled`
+    // event, aggressor's and resting orders' alike, say 9.
+    assert_eq!(res.fills.len(), 2);
+    assert!(res.fills.iter().all(|f| f.timestamp == Ts::from_nanos(9)));
+    let filled: Vec<_> = res
+        .lifecycle
+        .iter()
+        .filter(|e| matches!(e, OrderEvent::Filled { .. }))
+        .map(|e| e.timestamp())
+        .collect();
+    assert_eq!(filled, vec![Ts::from_nanos(9); 3]);
+}
+
+#[test]
+fn each_symbol_counts_on_its_own() {
+    let other = Symbol::from_const("OTHR");
+    let mut market = Market::new();
+    market.submit_limit(sell(1, 10000, 5, 1)).unwrap();
+    let res = market.submit_limit(buy(2, 10000, 5, 2)).unwrap();
+    assert_eq!(res.fills[0].trade_id, 1);
+
+    let other_sell = Order::new(
+        OrderID::new(1).unwrap(),
+        other,
+        Side::Sell,
+        Px::from_raw(10000).unwrap(),
+        Qty::new(5),
+        Ts::from_nanos(3),
+    )
+    .unwrap();
+    let res = market.submit_limit(other_sell).unwrap();
+    assert_eq!(merged_seqs(&res), vec![1, 2]);
+    assert_eq!(market.book(SYM).unwrap().last_trade_id(), 1);
+    assert_eq!(market.book(other).unwrap().last_trade_id(), 0);
+}
+
 // ─── Top-of-book quote semantics ──────────────────────────────────────────
 
 #[test]
@@ -7,12 +7,12 @@ use nyquestro::types::{OrderID, Px, Qty, Side, Status, Symbol, Ts};
 
 const SYM: Symbol = Symbol::from_const("TEST");
 
-fn order(id: u64, qty: u32) -> Order {
+fn order(id: u64, qty: u64) -> Order {
     Order::new(
         OrderID::new(id).unwrap(),
         SYM,
         Side::Buy,
-        Px::from_cents(100).unwrap(),
+        Px::from_raw(100).unwrap(),
         Qty::new(qty),
         Ts::from_nanos(1),
     )
@@ -82,7 +82,7 @@ fn zero_quantity_construction_rejected() {
         OrderID::new(1).unwrap(),
         SYM,
         Side::Buy,
-        Px::from_cents(100).unwrap(),
+        Px::from_raw(100).unwrap(),
         Qty::ZERO,
         Ts::from_nanos(1),
     );
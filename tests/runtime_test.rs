@@ -0,0 +1,286 @@
+//! Integration tests for the sharded runtime.
+//!
+//! The contract is per-symbol determinism: a symbol's outputs are the same
+//! whatever the shard count, and the same as a lone `BookShard` applying
+//! every command on one thread. Streams are compared as `Debug` strings,
+//! so any difference in any field shows up. Routing itself is covered by
+//! the unit tests in `concurrent/runtime.rs`.
+
+use std::collections::BTreeMap;
+use std::thread;
+
+use nyquestro::book::{
+    BookConfig, EventSink, FeeRate, FeeSchedule, Market, MarketData, MassCancel,
+};
+use nyquestro::command::Command;
+use nyquestro::concurrent::{BookShard, EngineEvent, Output, Runtime, RuntimeConfig, shard_of};
+use nyquestro::errors::NyquestroResult;
+use nyquestro::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboEvent, OrderEvent, QuoteEvent, StateEvent,
+};
+use nyquestro::order::Order;
+use nyquestro::sequencer::{ManualClock, Sequencer, Stamped};
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, Px, Qty, Side, Symbol, TimeInForce, Ts,
+};
+
+fn symbols() -> Vec<Symbol> {
+    (0..8).map(|n| format!("SYM{n}").parse().unwrap()).collect()
+}
+
+fn fees() -> FeeSchedule {
+    FeeSchedule::flat(FeeRate::Bps(-1), FeeRate::Bps(3))
+}
+
+/// A seeded session over every symbol, registrations first, stamped by a
+/// sequencer on a manual clock that reads each step's number.
+fn session(steps: u64) -> Vec<Stamped> {
+    // Small LCG so the session is the same on every run.
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    let mut next = |n: u64| {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (state >> 33) % n
+    };
+    let symbols = symbols();
+    let config = BookConfig {
+        market_data: MarketData::FULL,
+        checked: true,
+        ..BookConfig::default()
+    };
+    let clock = ManualClock::default();
+    let mut sequencer = Sequencer::new(clock.clone());
+    let mut commands: Vec<Stamped> = symbols
+        .iter()
+        .map(|&symbol| sequencer.stamp(Command::Register { symbol, config }))
+        .collect();
+    for step in 1..=steps {
+        let symbol = symbols[next(symbols.len() as u64) as usize];
+        let ts = Ts::from_nanos(step);
+        let side = if next(2) == 0 { Side::Buy } else { Side::Sell };
+        let price = match side {
+            Side::Buy => 9_975 + next(30),
+            Side::Sell => 9_995 + next(30),
+        };
+        let px = Px::from_raw(price).unwrap();
+        let qty = Qty::new(1 + next(20));
+        let id = OrderID::new(1 + next(step)).unwrap();
+        let command = match next(100) {
+            0..=54 => {
+                let mut o = Order::new(OrderID::new(step).unwrap(), symbol, side, px, qty, ts)
+                    .unwrap()
+                    .with_owner(OwnerID::new(1 + step % 5).unwrap());
+                match step % 11 {
+                    0 => o = o.with_peak(Qty::new(1 + step % 3)).unwrap(),
+                    1 => o = o.with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(step + 60))),
+                    2 => o = o.with_stop(Px::from_raw(price - 15).unwrap()),
+                    3 => {
+                        let peg = Peg {
+                            reference: PegReference::Primary,
+                            offset: -1,
+                            cap: None,
+                        };
+                        o = Order::pegged(o.id(), symbol, side, peg, qty, ts).unwrap();
+                    }
+                    _ => {}
+                }
+                Command::Submit(o)
+            }
+            55..=69 => Command::Cancel { symbol, id, ts },
+            70..=79 => Command::Replace {
+                symbol,
+                id,
+                px,
+                qty,
+                ts,
+            },
+            80..=83 => Command::Expire { ts },
+            84 | 85 => Command::MassCancel {
+                filter: MassCancel {
+                    symbol: Some(symbol),
+                    side: Some(side),
+                    ..MassCancel::default()
+                },
+                ts,
+            },
+            86 => Command::MassCancel {
+                filter: MassCancel {
+                    min_price: Some(px),
+                    ..MassCancel::default()
+                },
+                ts,
+            },
+            87 => Command::Halt { symbol, ts },
+            88 | 89 => Command::Resume { symbol, ts },
+            90 => Command::StartAuction {
+                symbol,
+                kind: AuctionKind::Opening,
+                ts,
+            },
+            91 | 92 => Command::Uncross { symbol, ts },
+            _ => Command::Reprice { symbol, ts },
+        };
+        clock.set(ts);
+        commands.push(sequencer.stamp(command));
+    }
+    commands
+}
+
+/// Each symbol's outputs as text, in the order they were published.
+#[derive(Debug, Default, PartialEq)]
+struct Streams(BTreeMap<Symbol, Vec<String>>);
+
+impl Streams {
+    fn event(&mut self, event: EngineEvent) {
+        self.0.entry(event.symbol()).or_default().push(format!("{event:?}"));
+    }
+
+    /// A routed command's result joins its symbol's stream; a broadcast
+    /// one has no single symbol and is only checked.
+    fn done(&mut self, command: &Command, seq: u64, result: &NyquestroResult<()>) {
+        match command.symbol() {
+            Some(symbol) => {
+                self.0.entry(symbol).or_default().push(format!("#{seq} {result:?}"));
+            }
+            None => assert!(result.is_ok(), "#{seq} {result:?}"),
+        }
+    }
+}
+
+impl EventSink for Streams {
+    fn fill(&mut self, event: FillEvent) {
+        self.event(EngineEvent::Fill(event));
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.event(EngineEvent::Quote(event));
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.event(EngineEvent::Level(event));
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.event(EngineEvent::Order(event));
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.event(EngineEvent::Lifecycle(event));
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.event(EngineEvent::Auction(event));
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.event(EngineEvent::State(event));
+    }
+}
+
+/// Run `commands` through a runtime, draining every outbound queue on its
+/// own thread, and return what each shard published and its market.
+fn run_sharded(config: RuntimeConfig, commands: &[Stamped]) -> (Vec<Vec<Output>>, Vec<Market>) {
+    let mut runtime = Runtime::spawn(config).unwrap();
+    thread::scope(|scope| {
+        // Consumers start before the first send: nothing drains the queues
+        // otherwise, and a full one would stall its shard.
+        let mut consumers = Vec::new();
+        for shard in 0..runtime.shards() {
+            let outbound = runtime.outbound(shard).unwrap();
+            consumers.push(scope.spawn(move || outbound.iter().collect::<Vec<_>>()));
+        }
+        for &stamped in commands {
+            runtime.send(stamped).unwrap();
+        }
+        let markets = runtime.shutdown().unwrap();
+        let outputs = consumers.into_iter().map(|c| c.join().unwrap()).collect();
+        (outputs, markets)
+    })
+}
+
+#[test]
+fn each_symbol_sees_the_same_output_whatever_the_shard_count() {
+    let commands = session(6_000);
+
+    let mut market = Market::new();
+    market.set_fee_schedule(fees());
+    let mut lone = BookShard::new(market);
+    let mut expected = Streams::default();
+    for &Stamped { stamp, command } in &commands {
+        let result = lone.apply(command, &mut expected);
+        expected.done(&command, stamp.seq, &result);
+    }
+    let fills = expected.0.values().flatten().filter(|e| e.starts_with("Fill")).count();
+    assert!(fills > 150, "{fills}");
+
+    for shards in [1, 2, 4] {
+        let config = RuntimeConfig {
+            shards,
+            fees: fees(),
+            ..RuntimeConfig::default()
+        };
+        let (outputs, markets) = run_sharded(config, &commands);
+        let mut streams = Streams::default();
+        for output in outputs.into_iter().flatten() {
+            match output {
+                Output::Event(event) => streams.event(event),
+                Output::Done { stamp, result } => {
+                    let command = commands[stamp.seq as usize - 1].command;
+                    streams.done(&command, stamp.seq, &result)
+                }
+            }
+        }
+        assert_eq!(streams, expected, "{shards} shards");
+
+        // Every book lives on the shard its symbol routes to, and only there.
+        for symbol in symbols() {
+            let home = shard_of(symbol, shards);
+            for (shard, market) in markets.iter().enumerate() {
+                assert_eq!(market.book(symbol).is_some(), shard == home, "{symbol}");
+            }
+            let book = markets[home].book(symbol).unwrap();
+            let reference = lone.market().book(symbol).unwrap();
+            let depth = |ts| (book.depth_snapshot(ts), reference.depth_snapshot(ts));
+            let (got, want) = depth(Ts::from_nanos(0));
+            assert_eq!(got, want, "{symbol}");
+            for owner in 1..=5 {
+                let owner = OwnerID::new(owner).unwrap();
+                assert_eq!(
+                    markets[home].fee_totals(owner, symbol),
+                    lone.market().fee_totals(owner, symbol)
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn single_slot_queues_lose_nothing_and_keep_each_shards_order() {
+    let commands = session(1_500);
+    let config = RuntimeConfig {
+        shards: 3,
+        ingress_capacity: 1,
+        outbound_capacity: 1,
+        ..RuntimeConfig::default()
+    };
+    let (outputs, _) = run_sharded(config, &commands);
+
+    let mut answered = vec![0; commands.len()];
+    for queue in &outputs {
+        let seqs: Vec<u64> = queue
+            .iter()
+            .filter_map(|output| match output {
+                Output::Done { stamp, .. } => Some(stamp.seq),
+                Output::Event(_) => None,
+            })
+            .collect();
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+        for seq in seqs {
+            answered[seq as usize - 1] += 1;
+        }
+    }
+    // A routed command is answered by its shard, a broadcast by all three.
+    for (stamped, count) in commands.iter().zip(answered) {
+        let expected = if stamped.command.symbol().is_some() { 1 } else { 3 };
+        assert_eq!(count, expected, "{stamped:?}");
+    }
+}
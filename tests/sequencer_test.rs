@@ -0,0 +1,214 @@
+//! Integration tests for the sequencer.
+//!
+//! Commands come from sources whose clocks disagree; the sequencer stamps
+//! them from one real monotonic clock as they are applied. The log of
+//! stamped commands is then replayed, without a sequencer, into a fresh
+//! `BookShard` and into a sharded `Runtime`, and every symbol's output has
+//! to come out exactly as it did live.
+
+use std::collections::BTreeMap;
+use std::thread;
+
+use nyquestro::book::{BookConfig, EventSink, MarketData, MassCancel};
+use nyquestro::command::Command;
+use nyquestro::concurrent::{BookShard, EngineEvent, Output, Runtime, RuntimeConfig};
+use nyquestro::errors::NyquestroResult;
+use nyquestro::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboEvent, OrderEvent, QuoteEvent, StateEvent,
+};
+use nyquestro::order::Order;
+use nyquestro::sequencer::{MonotonicClock, Sequencer, Stamped};
+use nyquestro::types::{OrderID, Px, Qty, Side, Symbol, TimeInForce, Ts};
+
+const SYMBOLS: [Symbol; 3] = [
+    Symbol::from_const("AAA"),
+    Symbol::from_const("BBB"),
+    Symbol::from_const("CCC"),
+];
+
+/// Each symbol's outputs as text, in the order they were produced.
+#[derive(Debug, Default, PartialEq)]
+struct Tape(BTreeMap<Symbol, Vec<String>>);
+
+impl Tape {
+    fn event(&mut self, event: EngineEvent) {
+        self.0.entry(event.symbol()).or_default().push(format!("{event:?}"));
+    }
+
+    fn done(&mut self, stamped: &Stamped, result: &NyquestroResult<()>) {
+        let line = format!("{:?} {result:?}", stamped.stamp);
+        match stamped.command.symbol() {
+            Some(symbol) => self.0.entry(symbol).or_default().push(line),
+            None => assert!(result.is_ok(), "{line}"),
+        }
+    }
+}
+
+impl EventSink for Tape {
+    fn fill(&mut self, event: FillEvent) {
+        self.event(EngineEvent::Fill(event));
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.event(EngineEvent::Quote(event));
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.event(EngineEvent::Level(event));
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.event(EngineEvent::Order(event));
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.event(EngineEvent::Lifecycle(event));
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.event(EngineEvent::Auction(event));
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.event(EngineEvent::State(event));
+    }
+}
+
+/// A seeded run of commands whose own timestamps are noise: each source
+/// reads a different clock, some of them behind, none of them the engine's.
+fn sources(steps: u64) -> Vec<Command> {
+    // Small LCG so the commands are the same on every run.
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |n: u64| {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (state >> 33) % n
+    };
+    let config = BookConfig {
+        market_data: MarketData::FULL,
+        checked: true,
+        ..BookConfig::default()
+    };
+    let mut commands: Vec<Command> = SYMBOLS
+        .iter()
+        .map(|&symbol| Command::Register { symbol, config })
+        .collect();
+    for step in 1..=steps {
+        let symbol = SYMBOLS[next(SYMBOLS.len() as u64) as usize];
+        let ts = Ts::from_nanos(next(1_000_000));
+        let side = if next(2) == 0 { Side::Buy } else { Side::Sell };
+        let price = match side {
+            Side::Buy => 990 + next(15),
+            Side::Sell => 998 + next(15),
+        };
+        let px = Px::from_raw(price).unwrap();
+        let qty = Qty::new(1 + next(10));
+        let id = OrderID::new(1 + next(step)).unwrap();
+        let command = match next(10) {
+            0..=5 => {
+                let tif = if step % 2 == 0 { TimeInForce::Day } else { TimeInForce::Gtc };
+                let order = Order::new(OrderID::new(step).unwrap(), symbol, side, px, qty, ts)
+                    .unwrap()
+                    .with_time_in_force(tif);
+                Command::Submit(order)
+            }
+            6 | 7 => Command::Cancel { symbol, id, ts },
+            8 => Command::Replace {
+                symbol,
+                id,
+                px,
+                qty,
+                ts,
+            },
+            _ if step % 3 == 0 => Command::Expire { ts },
+            _ => Command::MassCancel {
+                filter: MassCancel {
+                    symbol: Some(symbol),
+                    side: Some(side),
+                    ..MassCancel::default()
+                },
+                ts,
+            },
+        };
+        commands.push(command);
+    }
+    commands
+}
+
+/// Stamp each command as it arrives and apply it straight away, as a live
+/// engine would; return the log and what the engine produced.
+fn live(commands: Vec<Command>) -> (Vec<Stamped>, Tape) {
+    let mut sequencer = Sequencer::new(MonotonicClock::new());
+    let mut shard = BookShard::default();
+    let mut tape = Tape::default();
+    let mut log = Vec::with_capacity(commands.len());
+    for command in commands {
+        let stamped = sequencer.stamp(command);
+        let result = shard.apply(stamped.command, &mut tape);
+        tape.done(&stamped, &result);
+        log.push(stamped);
+    }
+    (log, tape)
+}
+
+#[test]
+fn engine_time_comes_from_the_sequencer_not_the_sources() {
+    let started = Ts::now();
+    let (log, _) = live(sources(2_000));
+
+    for (n, stamped) in log.iter().enumerate() {
+        assert_eq!(stamped.stamp.seq, n as u64 + 1);
+        assert!(stamped.stamp.ts >= started);
+        if let Command::Submit(order) = stamped.command {
+            assert_eq!(order.timestamp(), stamped.stamp.ts);
+            if order.time_in_force() == TimeInForce::Day {
+                assert!(order.expires_at().unwrap() > stamped.stamp.ts);
+            }
+        }
+    }
+    assert!(log.windows(2).all(|w| w[0].stamp.ts <= w[1].stamp.ts));
+}
+
+#[test]
+fn a_stamped_log_replays_exactly_on_one_thread_or_several() {
+    let (log, recorded) = live(sources(3_000));
+    let fills = recorded.0.values().flatten().filter(|e| e.starts_with("Fill")).count();
+    assert!(fills > 100, "{fills}");
+
+    let mut shard = BookShard::default();
+    let mut replayed = Tape::default();
+    for stamped in &log {
+        let result = shard.apply(stamped.command, &mut replayed);
+        replayed.done(stamped, &result);
+    }
+    assert_eq!(replayed, recorded);
+
+    let config = RuntimeConfig {
+        shards: 2,
+        ..RuntimeConfig::default()
+    };
+    let mut runtime = Runtime::spawn(config).unwrap();
+    let outputs: Vec<Vec<Output>> = thread::scope(|scope| {
+        let mut consumers = Vec::new();
+        for shard in 0..runtime.shards() {
+            let outbound = runtime.outbound(shard).unwrap();
+            consumers.push(scope.spawn(move || outbound.iter().collect::<Vec<_>>()));
+        }
+        for &stamped in &log {
+            runtime.send(stamped).unwrap();
+        }
+        runtime.shutdown().unwrap();
+        consumers.into_iter().map(|c| c.join().unwrap()).collect()
+    });
+    let mut sharded = Tape::default();
+    for output in outputs.into_iter().flatten() {
+        match output {
+            Output::Event(event) => sharded.event(event),
+            Output::Done { stamp, result } => {
+                let stamped = log[stamp.seq as usize - 1];
+                assert_eq!(stamped.stamp, stamp);
+                sharded.done(&stamped, &result);
+            }
+        }
+    }
+    assert_eq!(sharded, recorded);
+}
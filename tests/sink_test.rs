@@ -0,0 +1,252 @@
+//! Integration tests for event sinks.
+//!
+//! The `_into` operations are the engine's output path and the
+//! `SubmitResult`-returning ones are adapters over it, so the contract is
+//! that a sink sees exactly what a result would hold, one event at a time
+//! and in `seq` order. That the path does not allocate is covered by
+//! `slab_test.rs`, which owns the counting allocator.
+
+use nyquestro::book::{
+    BookConfig, EventSink, FeeRate, FeeSchedule, MarketData, MassCancel, Market, OrderBook,
+    SubmitResult,
+};
+use nyquestro::events::{
+    AuctionEvent, FillEvent, LevelEvent, MboAction, MboEvent, OrderEvent, QuoteEvent, Sequenced,
+    StateEvent,
+};
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, Px, Qty, Side, Symbol, TimeInForce, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+fn book() -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            market_data: MarketData::FULL,
+            checked: true,
+            ..BookConfig::default()
+        },
+    )
+}
+
+/// Every event in arrival order, whatever its kind.
+#[derive(Default)]
+struct Tape(Vec<String>);
+
+impl EventSink for Tape {
+    fn fill(&mut self, event: FillEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.0.push(format!("{event:?}"));
+    }
+}
+
+/// Fails on any event that is not numbered one past the last.
+#[derive(Default)]
+struct Gapless {
+    last: u64,
+}
+
+impl Gapless {
+    fn see(&mut self, event: &impl Sequenced) {
+        assert_eq!(event.seq(), self.last + 1);
+        self.last = event.seq();
+    }
+}
+
+impl EventSink for Gapless {
+    fn fill(&mut self, event: FillEvent) {
+        self.see(&event);
+    }
+
+    fn quote(&mut self, event: QuoteEvent) {
+        self.see(&event);
+    }
+
+    fn level(&mut self, event: LevelEvent) {
+        self.see(&event);
+    }
+
+    fn order(&mut self, event: MboEvent) {
+        self.see(&event);
+    }
+
+    fn lifecycle(&mut self, event: OrderEvent) {
+        self.see(&event);
+    }
+
+    fn auction(&mut self, event: AuctionEvent) {
+        self.see(&event);
+    }
+
+    fn state(&mut self, event: StateEvent) {
+        self.see(&event);
+    }
+}
+
+#[test]
+fn a_cancel_carries_its_own_depth_events() {
+    let mut book = book();
+    book.submit_limit(order(1, Side::Buy, 9_990, 5, 1)).unwrap();
+    book.submit_limit(order(2, Side::Buy, 9_980, 5, 2)).unwrap();
+    let res = book.cancel_collect(OrderID::new(1).unwrap(), Ts::from_nanos(3)).unwrap();
+    let [MboEvent { seq, action: MboAction::Delete, .. }] = res.orders.as_slice() else {
+        panic!("{:?}", res.orders);
+    };
+    let [cancelled @ OrderEvent::Cancelled { .. }] = res.lifecycle.as_slice() else {
+        panic!("{:?}", res.lifecycle);
+    };
+    // The delete was numbered before the cancel event it belongs to, and
+    // nothing is left over for the next operation.
+    assert_eq!((*seq, res.levels.len()), (cancelled.seq() - 2, 1));
+    assert_eq!(book.last_seq(), cancelled.seq());
+    let mut tape = Tape::default();
+    book.reprice_into(Ts::from_nanos(4), &mut tape).unwrap();
+    assert!(tape.0.is_empty(), "{:?}", tape.0);
+
+    // A cancel into a sink delivers the same events in the same order.
+    book.cancel_into(OrderID::new(2).unwrap(), Ts::from_nanos(5), &mut tape).unwrap();
+    assert_eq!(tape.0.len(), 3);
+    assert!(tape.0[2].starts_with("Cancelled"));
+}
+
+#[test]
+fn the_market_charges_fills_before_they_reach_the_sink() {
+    #[derive(Default)]
+    struct Fees(Vec<(i64, i64)>);
+    impl EventSink for Fees {
+        fn fill(&mut self, event: FillEvent) {
+            self.0.push((event.buyer_fee, event.seller_fee));
+        }
+    }
+
+    let mut market = Market::new();
+    market.set_fee_schedule(FeeSchedule::flat(FeeRate::Bps(-1), FeeRate::Bps(3)));
+    let firm = |n| OwnerID::new(n).unwrap();
+    market.submit_limit(order(1, Side::Sell, 10_000, 50, 1).with_owner(firm(1))).unwrap();
+    let mut sinks = (Fees::default(), Gapless::default());
+    sinks.1.last = market.book(SYM).unwrap().last_seq();
+    let buy = order(2, Side::Buy, 10_000, 50, 2).with_owner(firm(2));
+    market.submit_limit_into(buy, &mut sinks).unwrap();
+    assert_eq!(sinks.0.0, [(150, -50)]);
+    assert_eq!(market.fee_totals(firm(2), SYM).net(), 150);
+    assert_eq!(sinks.1.last, market.book(SYM).unwrap().last_seq());
+}
+
+#[test]
+fn a_sink_sees_what_a_result_holds_over_a_long_random_session() {
+    // Small LCG so the session is the same on every run.
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = |n: u64| {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+        (state >> 33) % n
+    };
+    let mut collected = book();
+    let mut streamed = book();
+    let mut gapless = Gapless::default();
+    let mut traded = 0;
+    for step in 1..=5_000u64 {
+        let ts = Ts::from_nanos(step);
+        let side = if next(2) == 0 { Side::Buy } else { Side::Sell };
+        let price = match side {
+            Side::Buy => 9_975 + next(30),
+            Side::Sell => 9_995 + next(30),
+        };
+        let qty = 1 + next(20);
+        let pick = OrderID::new(1 + next(step)).unwrap();
+        let op = next(100);
+        let mut o = order(step, side, price, qty, step)
+            .with_owner(OwnerID::new(1 + step % 7).unwrap());
+        match step % 11 {
+            0 => o = o.with_peak(Qty::new(1 + step % 3)).unwrap(),
+            1 => o = o.with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(step + 40))),
+            2 => o = o.with_stop(Px::from_raw(price - 15).unwrap()),
+            3 => {
+                let peg = Peg {
+                    reference: PegReference::Primary,
+                    offset: -1,
+                    cap: None,
+                };
+                o = Order::pegged(o.id(), SYM, side, peg, o.quantity(), ts).unwrap();
+            }
+            _ => {}
+        }
+        let filter = MassCancel {
+            side: Some(side),
+            min_price: Some(Px::from_raw(price).unwrap()),
+            ..MassCancel::default()
+        };
+        let px = Px::from_raw(price).unwrap();
+        let replace_qty = Qty::new(1 + step % 9);
+
+        let result = match op {
+            0..=54 => collected.submit_limit(o),
+            55..=74 => SubmitResult::collect(|out| collected.cancel_into(pick, ts, out)),
+            75..=86 => collected.replace(pick, px, replace_qty, ts),
+            87..=90 => collected.expire(ts),
+            91 | 92 => collected.mass_cancel(&filter, ts),
+            93 => collected.start_auction(AuctionKind::Opening, ts),
+            94 | 95 => collected.uncross(ts),
+            _ => collected.reprice(ts),
+        };
+        let mut tape = Tape::default();
+        let mut out = (&mut tape, &mut gapless);
+        let streamed_ok = match op {
+            0..=54 => streamed.submit_limit_into(o, &mut out),
+            55..=74 => streamed.cancel_into(pick, ts, &mut out),
+            75..=86 => streamed.replace_into(pick, px, replace_qty, ts, &mut out),
+            87..=90 => streamed.expire_into(ts, &mut out),
+            91 | 92 => streamed.mass_cancel_into(&filter, ts, &mut out),
+            93 => streamed.start_auction_into(AuctionKind::Opening, ts, &mut out),
+            94 | 95 => streamed.uncross_into(ts, &mut out),
+            _ => streamed.reprice_into(ts, &mut out),
+        };
+
+        let mut replayed = Tape::default();
+        assert_eq!(result.is_ok(), streamed_ok.is_ok(), "step {step}");
+        if let Ok(result) = result {
+            traded += usize::from(!result.fills.is_empty());
+            result.replay_into(&mut replayed);
+        }
+        assert_eq!(tape.0, replayed.0, "step {step}");
+    }
+    assert!(traded > 100 && streamed.len() > 50);
+    assert_eq!(gapless.last, streamed.last_seq());
+}
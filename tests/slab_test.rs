@@ -0,0 +1,264 @@
+//! Allocation counts for the order-node slab.
+//!
+//! A counting global allocator proves that once a level's slab and a
+//! ladder's spare levels have grown to the session's needs, queueing,
+//! cancelling from anywhere, filling and refreshing allocate nothing. The
+//! count is per thread, so tests running alongside do not disturb it.
+//! The pro-rata rules share a level out in scratch space the book keeps,
+//! so they trade without allocating too.
+//! Submissions go through the `_into` forms: a `SubmitResult` allocates
+//! its vectors on every call, an [`EventSink`] need not.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use nyquestro::book::{
+    BookConfig, EventSink, LadderKind, MarketData, MatchingAlgorithm, OrderBook, PriceLevel,
+};
+use nyquestro::events::FillEvent;
+use nyquestro::order::Order;
+use nyquestro::types::{OrderID, Px, Qty, Side, Symbol, Ts};
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// The crate's only `unsafe`, confined to this test binary: see
+// `context/notes/safe-rust-philosophy.md`.
+//
+// # Safety
+//
+// Every call is forwarded unchanged to `System`, so `Counting` upholds
+// `GlobalAlloc`'s contract exactly as `System` does. The counter is a
+// thread-local `Cell` and `try_with` never allocates or panics, so counting
+// cannot re-enter the allocator.
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+/// Heap allocations `f` makes on this thread.
+fn allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(id),
+    )
+    .unwrap()
+}
+
+#[test]
+fn a_warm_level_queues_cancels_fills_and_refreshes_without_allocating() {
+    let mut level = PriceLevel::new(Px::from_raw(100).unwrap());
+    let mut slots: Vec<_> =
+        (1..=64).map(|n| level.insert(order(n, Side::Buy, 100, 10)).unwrap()).collect();
+    // One node more than the session keeps resting, for the iceberg.
+    let spare = level.insert(order(65, Side::Buy, 100, 10)).unwrap();
+    level.remove_at(spare).unwrap();
+    let iceberg = order(1_000, Side::Buy, 100, 50).with_peak(Qty::new(5)).unwrap();
+    let mut next_id = 2_000;
+
+    let made = allocations(|| {
+        for round in 0..10_000 {
+            // Cancel from the middle, fill the front, queue a replacement.
+            let at = slots.swap_remove(round % slots.len());
+            assert!(level.remove_at(at).unwrap().is_some());
+            let front = level.entries().next().map(|(at, _)| at).unwrap();
+            let filled = level.fill_at(front, Qty::new(10)).unwrap();
+            assert!(filled.status().is_terminal());
+            level.remove_at(front).unwrap();
+            slots.retain(|&s| s != front);
+            for _ in 0..2 {
+                next_id += 1;
+                slots.push(level.insert(order(next_id, Side::Buy, 100, 10)).unwrap());
+            }
+        }
+        // A spent iceberg peak goes to the back in its own node.
+        let at = level.insert(iceberg).unwrap();
+        level.fill_at(at, Qty::new(5)).unwrap();
+        assert_eq!(level.refresh_at(at, Ts::from_nanos(9)).unwrap().displayed(), Qty::new(5));
+        assert_eq!(level.get(at).map(Order::id), Some(iceberg.id()));
+    });
+    assert_eq!(made, 0);
+    assert_eq!(level.len(), 65);
+}
+
+#[test]
+fn cancelling_from_a_warm_book_does_not_allocate() {
+    for ladder in [LadderKind::Tree, LadderKind::Ticks { slots: 256 }] {
+        let config = BookConfig {
+            ladder,
+            ..BookConfig::default()
+        };
+        let mut book = OrderBook::with_config(SYM, config);
+        let mut id = 0;
+        let mut rest_all = |book: &mut OrderBook| {
+            for price in 9_990..10_000 {
+                for _ in 0..20 {
+                    id += 1;
+                    book.submit_limit(order(id, Side::Buy, price, 5)).unwrap();
+                }
+            }
+            id
+        };
+        // Rest, empty and rest again at every level once so each slab and
+        // the ladder's spares are already grown.
+        let last = rest_all(&mut book);
+        for n in 1..=last {
+            book.cancel(OrderID::new(n).unwrap(), Ts::from_nanos(n)).unwrap();
+        }
+        let last = rest_all(&mut book);
+
+        // Middle of each queue first, then everything else, emptying
+        // every level.
+        let made = allocations(|| {
+            for n in (last - 199..=last).filter(|n| n % 20 == 10) {
+                book.cancel(OrderID::new(n).unwrap(), Ts::from_nanos(n)).unwrap();
+            }
+            for n in (last - 199..=last).filter(|n| n % 20 != 10) {
+                book.cancel(OrderID::new(n).unwrap(), Ts::from_nanos(n)).unwrap();
+            }
+        });
+        assert_eq!(made, 0, "{ladder:?}");
+        assert!(book.is_empty());
+    }
+}
+
+#[test]
+fn trading_into_a_sink_does_not_allocate() {
+    /// Counts fills and drops everything else.
+    #[derive(Default)]
+    struct Tally {
+        fills: usize,
+    }
+    impl EventSink for Tally {
+        fn fill(&mut self, _: FillEvent) {
+            self.fills += 1;
+        }
+    }
+
+    for ladder in [LadderKind::Tree, LadderKind::Ticks { slots: 256 }] {
+        let config = BookConfig {
+            ladder,
+            market_data: MarketData::FULL,
+            ..BookConfig::default()
+        };
+        let mut book = OrderBook::with_config(SYM, config);
+        let mut tally = Tally::default();
+        let mut id = 0;
+        for price in 10_000..10_010 {
+            for _ in 0..20 {
+                id += 1;
+                book.submit_limit_into(order(id, Side::Sell, price, 5), &mut tally).unwrap();
+            }
+        }
+        // Each round rests a sell inside the others, shrinks it in place and
+        // cancels it, then opens a new best ask and trades it away, so the
+        // book is the same shape after every round.
+        let mut round = |book: &mut OrderBook, tally: &mut Tally, n: u64| {
+            let first = OrderID::new(id + 1).unwrap();
+            let price = 10_000 + n % 10;
+            book.submit_limit_into(order(id + 1, Side::Sell, price, 5), &mut *tally).unwrap();
+            let (px, ts) = (Px::from_raw(price).unwrap(), Ts::from_nanos(id + 1));
+            book.replace_into(first, px, Qty::new(3), ts, &mut *tally).unwrap();
+            book.cancel_into(first, ts, &mut *tally).unwrap();
+            book.submit_limit_into(order(id + 2, Side::Sell, 9_999, 5), &mut *tally).unwrap();
+            book.submit_limit_into(order(id + 3, Side::Buy, 9_999, 5), &mut *tally).unwrap();
+            id += 3;
+        };
+        for n in 0..1_000 {
+            round(&mut book, &mut tally, n);
+        }
+
+        let fills = tally.fills;
+        let made = allocations(|| {
+            for n in 0..1_000 {
+                round(&mut book, &mut tally, n);
+            }
+        });
+        assert_eq!(made, 0, "{ladder:?}");
+        assert_eq!(tally.fills - fills, 1_000);
+        assert_eq!(book.len(), 200);
+    }
+}
+
+#[test]
+fn pro_rata_matching_does_not_allocate() {
+    /// Counts fills and drops everything else.
+    #[derive(Default)]
+    struct Tally {
+        fills: usize,
+    }
+    impl EventSink for Tally {
+        fn fill(&mut self, _: FillEvent) {
+            self.fills += 1;
+        }
+    }
+
+    for matching in [MatchingAlgorithm::ProRata, MatchingAlgorithm::ProRataTopOrder] {
+        let config = BookConfig {
+            matching,
+            ..BookConfig::default()
+        };
+        let mut book = OrderBook::with_config(SYM, config);
+        let mut tally = Tally::default();
+        let mut id = 0;
+        // Each round rests ten sells of 3 to 12 at one price, buys a third
+        // of the level so every order takes a partial fill, and cancels
+        // what is left: the book is empty again after every round.
+        let mut round = |book: &mut OrderBook, tally: &mut Tally| {
+            for size in 3..13 {
+                id += 1;
+                book.submit_limit_into(order(id, Side::Sell, 10_000, size), &mut *tally).unwrap();
+            }
+            book.submit_limit_into(order(id + 1, Side::Buy, 10_000, 25), &mut *tally).unwrap();
+            // The top-order rule fills the front order outright.
+            for n in (id - 9..=id).map(|n| OrderID::new(n).unwrap()) {
+                if book.contains(n) {
+                    book.cancel_into(n, Ts::from_nanos(id), &mut ()).unwrap();
+                }
+            }
+            id += 1;
+        };
+        for _ in 0..100 {
+            round(&mut book, &mut tally);
+        }
+
+        let fills = tally.fills;
+        let made = allocations(|| {
+            for _ in 0..1_000 {
+                round(&mut book, &mut tally);
+            }
+        });
+        assert_eq!(made, 0, "{matching:?}");
+        assert_eq!(tally.fills - fills, 10_000, "{matching:?}");
+        assert!(book.is_empty());
+    }
+}
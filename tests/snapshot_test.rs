@@ -0,0 +1,300 @@
+//! Integration tests for market snapshots.
+//!
+//! A market with something of everything — partial fills, a refreshed
+//! iceberg, a peg, a stop, a GTD order, a running auction, fees — is
+//! snapshotted, encoded, decoded and restored, and the copy must hold the
+//! same state and go on behaving exactly like the original. Tampered
+//! snapshots must be refused.
+
+use nyquestro::book::{
+    BookConfig, FeeRate, FeeSchedule, FeeTier, Market, MarketData, MarketSnapshot, ProtectionBand,
+    SNAPSHOT_VERSION, VolatilityGuard,
+};
+use nyquestro::errors::NyquestroError;
+use nyquestro::instrument::InstrumentSpec;
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, OwnerID, Peg, PegReference, Px, Qty, Side, Status, Symbol, TimeInForce,
+    Ts,
+};
+use serde_json::Value;
+
+const SYM: Symbol = Symbol::from_const("TEST");
+const OPEN: Symbol = Symbol::from_const("OPEN");
+
+fn id(n: u64) -> OrderID {
+    OrderID::new(n).unwrap()
+}
+
+fn order(symbol: Symbol, n: u64, owner: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        id(n),
+        symbol,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+    .with_owner(OwnerID::new(owner).unwrap())
+}
+
+fn market() -> Market {
+    let mut market = Market::new();
+    market.set_fee_schedule(
+        FeeSchedule::flat(FeeRate::Bps(-1), FeeRate::Bps(3))
+            .with_owner(
+                OwnerID::new(3).unwrap(),
+                vec![FeeTier::flat(FeeRate::Bps(0), FeeRate::Bps(2))],
+            )
+            .unwrap(),
+    );
+    market.register_with(
+        SYM,
+        BookConfig {
+            volatility_guard: Some(VolatilityGuard {
+                band: ProtectionBand::Bps(500),
+                window: 1_000_000,
+            }),
+            instrument: Some(InstrumentSpec {
+                tick_size: 5,
+                ..InstrumentSpec::default()
+            }),
+            market_data: MarketData::FULL,
+            ..BookConfig::default()
+        },
+    );
+    let submit = |m: &mut Market, o: Order| {
+        m.submit_limit(o).unwrap();
+    };
+    submit(&mut market, order(SYM, 1, 1, Side::Sell, 10010, 100, 1));
+    submit(&mut market, order(SYM, 2, 2, Side::Sell, 10010, 40, 2));
+    // Takes 30 off the front of the queue.
+    submit(&mut market, order(SYM, 3, 3, Side::Buy, 10010, 30, 3));
+    submit(
+        &mut market,
+        order(SYM, 4, 1, Side::Buy, 9990, 100, 4).with_peak(Qty::new(20)).unwrap(),
+    );
+    // Uses up the peak and 5 of the next one: 75 left, 15 showing.
+    submit(&mut market, order(SYM, 5, 2, Side::Sell, 9990, 25, 5));
+    submit(&mut market, order(SYM, 6, 3, Side::Buy, 9990, 10, 6));
+    let peg = Peg {
+        reference: PegReference::Primary,
+        offset: -5,
+        cap: Some(Px::from_raw(10000).unwrap()),
+    };
+    let pegged = Order::pegged(id(7), SYM, Side::Buy, peg, Qty::new(10), Ts::from_nanos(7))
+        .unwrap()
+        .with_owner(OwnerID::new(2).unwrap());
+    submit(&mut market, pegged);
+    submit(
+        &mut market,
+        order(SYM, 8, 3, Side::Sell, 9850, 10, 8).with_stop(Px::from_raw(9900).unwrap()),
+    );
+    submit(
+        &mut market,
+        order(SYM, 9, 1, Side::Buy, 9980, 10, 9)
+            .with_time_in_force(TimeInForce::Gtd(Ts::from_nanos(1_000_000))),
+    );
+
+    market.register(OPEN);
+    market.start_auction(OPEN, AuctionKind::Opening, Ts::from_nanos(10)).unwrap();
+    submit(&mut market, order(OPEN, 1, 1, Side::Buy, 505, 20, 11));
+    submit(&mut market, order(OPEN, 2, 2, Side::Sell, 500, 15, 12));
+    market
+}
+
+/// Run the same operations on `market`, returning everything they produce.
+fn drive(market: &mut Market) -> Vec<String> {
+    let mut out = Vec::new();
+    // Sweeps the iceberg, the order behind it and the peg.
+    let res = market.submit_limit(order(SYM, 20, 4, Side::Sell, 9985, 95, 100));
+    out.push(format!("{res:?}"));
+    out.push(format!("{:?}", market.expire(Ts::from_nanos(2_000_000))));
+    // A print at 9900 trips the stop.
+    let res = market.submit_limit(order(SYM, 21, 4, Side::Sell, 9900, 10, 2_000_001));
+    out.push(format!("{res:?}"));
+    let res = market.submit_limit(order(SYM, 22, 5, Side::Buy, 9900, 10, 2_000_002));
+    out.push(format!("{res:?}"));
+    out.push(format!("{:?}", market.uncross(OPEN, Ts::from_nanos(2_000_003))));
+    for owner in 1..=5 {
+        let totals: Vec<_> = market.account_fees(OwnerID::new(owner).unwrap()).collect();
+        out.push(format!("{totals:?}"));
+    }
+    out
+}
+
+/// The `TEST` book in a JSON snapshot.
+fn test_book(snapshot: &mut Value) -> &mut Value {
+    snapshot["books"]
+        .as_array_mut()
+        .unwrap()
+        .iter_mut()
+        .find(|b| b["symbol"] == "TEST")
+        .unwrap()
+}
+
+/// Load the market's JSON snapshot after `edit`; the error it fails with.
+fn load_tampered(edit: impl FnOnce(&mut Value)) -> String {
+    let mut json: Value = serde_json::from_str(&market().snapshot().to_json().unwrap()).unwrap();
+    edit(&mut json);
+    let err = MarketSnapshot::from_json(&json.to_string())
+        .and_then(Market::from_snapshot)
+        .unwrap_err();
+    match err {
+        NyquestroError::Snapshot(why) => why,
+        other => panic!("expected a snapshot error, got {other:?}"),
+    }
+}
+
+#[test]
+fn json_round_trip_restores_the_same_state() {
+    let market = market();
+    let snapshot = market.snapshot();
+    let json = snapshot.to_json().unwrap();
+    assert!(json.contains(&format!("\"version\": {SNAPSHOT_VERSION}")));
+
+    let restored = Market::from_snapshot(MarketSnapshot::from_json(&json).unwrap()).unwrap();
+    assert_eq!(restored.snapshot(), snapshot);
+    assert_eq!(restored.fee_schedule(), market.fee_schedule());
+}
+
+#[test]
+fn binary_round_trip_restores_the_same_state() {
+    let snapshot = market().snapshot();
+    let bytes = snapshot.to_bytes().unwrap();
+    assert!(bytes.starts_with(b"NYQS"));
+    assert!(bytes.len() < snapshot.to_json().unwrap().len() / 4);
+
+    let restored = Market::from_snapshot(MarketSnapshot::from_bytes(&bytes).unwrap()).unwrap();
+    assert_eq!(restored.snapshot(), snapshot);
+}
+
+#[test]
+fn restored_book_keeps_queues_and_fills() {
+    let original = market();
+    let restored = Market::from_snapshot(original.snapshot()).unwrap();
+    let book = restored.book(SYM).unwrap();
+
+    let front = book.order(id(1)).unwrap();
+    assert_eq!((front.remaining(), front.status()), (Qty::new(70), Status::PartiallyFilled));
+    let asks: Vec<_> = book.ask_levels().flat_map(|(_, l)| l.iter().map(|o| o.id())).collect();
+    assert_eq!(asks, vec![id(1), id(2)]);
+
+    // The refreshed iceberg went to the back, then order 6 joined.
+    let iceberg = book.order(id(4)).unwrap();
+    assert_eq!((iceberg.displayed(), iceberg.hidden()), (Qty::new(15), Qty::new(60)));
+    let (_, level) = book.bid_levels().next().unwrap();
+    assert_eq!(level.iter().map(|o| o.id()).collect::<Vec<_>>(), vec![id(4), id(6)]);
+    assert_eq!(level.total_quantity(), Qty::new(25));
+
+    assert_eq!(book.order(id(7)).unwrap().price(), Px::from_raw(9985).unwrap());
+    assert_eq!(book.stops().len(), 1);
+    assert_eq!(book.last_seq(), original.book(SYM).unwrap().last_seq());
+    assert_eq!(book.last_trade_id(), 3);
+    let now = Ts::from_nanos(10);
+    assert_eq!(book.price_limits(now), original.book(SYM).unwrap().price_limits(now));
+
+    let auction = restored.book(OPEN).unwrap();
+    assert_eq!(auction.auction(), Some(AuctionKind::Opening));
+    assert_eq!(auction.indicative(), original.book(OPEN).unwrap().indicative());
+}
+
+#[test]
+fn restored_market_behaves_like_the_original() {
+    let mut original = market();
+    let json = original.snapshot().to_json().unwrap();
+    let mut from_json = Market::from_snapshot(MarketSnapshot::from_json(&json).unwrap()).unwrap();
+    let bytes = original.snapshot().to_bytes().unwrap();
+    let mut from_bytes =
+        Market::from_snapshot(MarketSnapshot::from_bytes(&bytes).unwrap()).unwrap();
+
+    let expected = drive(&mut original);
+    assert!(expected[1].contains("Expired") && expected[3].contains("Triggered"));
+    assert_eq!(drive(&mut from_json), expected);
+    assert_eq!(drive(&mut from_bytes), expected);
+    assert_eq!(from_json.snapshot(), original.snapshot());
+}
+
+#[test]
+fn refuses_a_level_total_that_disagrees_with_its_orders() {
+    let why = load_tampered(|s| test_book(s)["bids"][0]["total"] = 26.into());
+    assert!(why.contains("total 26 but its orders show 25"), "{why}");
+}
+
+#[test]
+fn refuses_an_order_priced_off_its_level() {
+    let why = load_tampered(|s| test_book(s)["asks"][0]["orders"][1]["price"] = 10015.into());
+    assert!(why.contains("order 2 is priced at 10015"), "{why}");
+}
+
+#[test]
+fn refuses_an_order_on_the_wrong_side() {
+    let why = load_tampered(|s| test_book(s)["asks"][0]["orders"][1]["side"] = "Buy".into());
+    assert!(why.contains("order 2 is on the other side"), "{why}");
+}
+
+#[test]
+fn refuses_a_duplicate_id() {
+    let why = load_tampered(|s| test_book(s)["asks"][0]["orders"][1]["id"] = 4.into());
+    assert!(why.contains("order 4 appears twice"), "{why}");
+    let why = load_tampered(|s| test_book(s)["stops"][0]["id"] = 9.into());
+    assert!(why.contains("order 9 appears twice"), "{why}");
+}
+
+#[test]
+fn refuses_levels_out_of_price_order() {
+    let why = load_tampered(|s| test_book(s)["bids"].as_array_mut().unwrap().reverse());
+    assert!(why.contains("out of order"), "{why}");
+}
+
+#[test]
+fn refuses_a_crossed_book() {
+    // The best bid moved through the best ask at 10010, consistently.
+    let why = load_tampered(|s| {
+        let level = &mut test_book(s)["bids"][0];
+        level["price"] = 10015.into();
+        for order in level["orders"].as_array_mut().unwrap() {
+            order["price"] = 10015.into();
+        }
+    });
+    assert_eq!(why, "TEST: book crossed at rest: bid 10015 >= ask 10010");
+}
+
+#[test]
+fn refuses_fills_that_disagree_with_the_status() {
+    let why = load_tampered(|s| test_book(s)["asks"][0]["orders"][1]["remaining"] = 39.into());
+    assert!(why.starts_with("order 2:"), "{why}");
+    let why = load_tampered(|s| test_book(s)["bids"][0]["orders"][0]["displayed"] = 21.into());
+    assert!(why.starts_with("order 4:"), "{why}");
+}
+
+#[test]
+fn refuses_a_tick_ladder_too_large_to_allocate() {
+    let why = load_tampered(|s| {
+        let ladder = serde_json::json!({ "Ticks": { "slots": 4_000_000_000u32 } });
+        test_book(s)["config"]["ladder"] = ladder;
+    });
+    assert_eq!(why, "Tick ladder must have 1..=65536 slots, got 4000000000");
+}
+
+#[test]
+fn refuses_other_versions_and_formats() {
+    let why = load_tampered(|s| s["version"] = 2.into());
+    assert_eq!(why, "unsupported version 2");
+    let why = load_tampered(|s| test_book(s)["colour"] = "blue".into());
+    assert!(why.contains("unknown field"), "{why}");
+
+    let mut bytes = market().snapshot().to_bytes().unwrap();
+    bytes[4] = 2; // the version is the first field after the magic
+    assert!(matches!(
+        MarketSnapshot::from_bytes(&bytes),
+        Err(NyquestroError::Snapshot(why)) if why == "unsupported version 2"
+    ));
+    assert!(matches!(
+        MarketSnapshot::from_bytes(b"{\"version\": 1}"),
+        Err(NyquestroError::Snapshot(why)) if why == "not a binary snapshot"
+    ));
+    let bytes = market().snapshot().to_bytes().unwrap();
+    assert!(MarketSnapshot::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+}
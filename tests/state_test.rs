@@ -0,0 +1,342 @@
+//! Integration tests for per-book trading states and the volatility guard.
+//!
+//! Each test drives a book through explicit transitions or a trade that
+//! breaks the band, and pins the `StateEvent`s, the rejections orders meet
+//! in each state and what is left resting afterwards.
+
+use nyquestro::book::{
+    BookConfig, Market, OrderBook, ProtectionBand, SubmitResult, VolatilityGuard,
+};
+use nyquestro::errors::NyquestroError;
+use nyquestro::events::{
+    CancelReason, OrderEvent, OrderRejectionReason, Sequenced, StateEvent, StateReason,
+};
+use nyquestro::order::Order;
+use nyquestro::types::{
+    AuctionKind, OrderID, Px, Qty, Side, Symbol, TimeInForce, TradingState, Ts,
+};
+
+const SYM: Symbol = Symbol::from_const("TEST");
+
+fn order(id: u64, side: Side, price: u64, qty: u64, ts: u64) -> Order {
+    Order::new(
+        OrderID::new(id).unwrap(),
+        SYM,
+        side,
+        Px::from_raw(price).unwrap(),
+        Qty::new(qty),
+        Ts::from_nanos(ts),
+    )
+    .unwrap()
+}
+
+/// A book halting any trade more than 1% away from the mean of the last
+/// 100ns of trades.
+fn guarded_book() -> OrderBook {
+    OrderBook::with_config(
+        SYM,
+        BookConfig {
+            volatility_guard: Some(VolatilityGuard {
+                band: ProtectionBand::Bps(100),
+                window: 100,
+            }),
+            ..BookConfig::default()
+        },
+    )
+}
+
+/// `(from, to, reason)` of every state change in `res`.
+fn changes(res: &SubmitResult) -> Vec<(TradingState, TradingState, StateReason)> {
+    res.state.iter().map(|e| (e.from, e.to, e.reason)).collect()
+}
+
+fn rejection(res: &SubmitResult) -> Option<OrderRejectionReason> {
+    res.lifecycle.iter().find_map(|e| match e {
+        OrderEvent::Rejected { reason, .. } => Some(*reason),
+        _ => None,
+    })
+}
+
+fn is_transition_error<T>(res: Result<T, NyquestroError>) -> bool {
+    matches!(res, Err(NyquestroError::InvalidPhaseTransition { .. }))
+}
+
+// ─── Transitions ──────────────────────────────────────────────────────────
+
+#[test]
+fn books_start_continuous() {
+    assert_eq!(OrderBook::new(SYM).state(), TradingState::Continuous);
+}
+
+#[test]
+fn halt_resume_and_close_report_each_change() {
+    let mut book = OrderBook::new(SYM);
+    let res = book.halt(Ts::from_nanos(1)).unwrap();
+    assert_eq!(
+        res.state,
+        vec![StateEvent::new(
+            SYM,
+            TradingState::Continuous,
+            TradingState::Halted,
+            StateReason::Requested,
+            Ts::from_nanos(1),
+        )
+        .unwrap()
+        .with_seq(1)]
+    );
+    assert!(is_transition_error(book.halt(Ts::from_nanos(2))));
+
+    let res = book.resume(Ts::from_nanos(3)).unwrap();
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::Halted, TradingState::Continuous, StateReason::Requested)]
+    );
+    assert!(is_transition_error(book.resume(Ts::from_nanos(4))));
+
+    let res = book.close(Ts::from_nanos(5)).unwrap();
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::Continuous, TradingState::Closed, StateReason::Requested)]
+    );
+    assert!(is_transition_error(book.close(Ts::from_nanos(6))));
+    assert!(is_transition_error(book.halt(Ts::from_nanos(6))));
+    assert!(is_transition_error(book.start_auction(AuctionKind::Closing, Ts::from_nanos(6))));
+}
+
+#[test]
+fn opening_auction_runs_in_pre_open() {
+    let mut book = OrderBook::new(SYM);
+    book.close(Ts::from_nanos(1)).unwrap();
+    let res = book.start_auction(AuctionKind::Opening, Ts::from_nanos(2)).unwrap();
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::Closed, TradingState::PreOpen, StateReason::Requested)]
+    );
+    book.submit_limit(order(1, Side::Buy, 10000, 2, 3)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 2, 4)).unwrap();
+
+    let res = book.uncross(Ts::from_nanos(5)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::PreOpen, TradingState::Continuous, StateReason::Requested)]
+    );
+}
+
+#[test]
+fn closing_auction_leaves_the_book_closed() {
+    let mut book = OrderBook::new(SYM);
+    let res = book.start_auction(AuctionKind::Closing, Ts::from_nanos(1)).unwrap();
+    assert!(res.state.is_empty());
+    assert_eq!(book.state(), TradingState::Continuous);
+    book.submit_limit(order(1, Side::Buy, 10000, 2, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 1, 3)).unwrap();
+
+    let res = book.uncross(Ts::from_nanos(4)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::Continuous, TradingState::Closed, StateReason::Requested)]
+    );
+    // The unfilled bid stays for the next session.
+    assert_eq!(book.best_bid(), Some((Px::from_raw(10000).unwrap(), Qty::new(1))));
+}
+
+#[test]
+fn halting_abandons_a_running_auction() {
+    let mut book = OrderBook::new(SYM);
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    book.submit_limit(order(1, Side::Buy, 10000, 2, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 2, 3)).unwrap();
+
+    let res = book.halt(Ts::from_nanos(4)).unwrap();
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::PreOpen, TradingState::Halted, StateReason::Requested)]
+    );
+    assert_eq!(book.auction(), None);
+    assert!(is_transition_error(book.uncross(Ts::from_nanos(5))));
+
+    // Reopening through a fresh auction uncrosses what was left.
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(6)).unwrap();
+    let res = book.uncross(Ts::from_nanos(7)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert!(book.is_empty());
+}
+
+#[test]
+fn a_book_left_crossed_by_an_abandoned_auction_only_reopens_through_one() {
+    let mut book = OrderBook::new(SYM);
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(1)).unwrap();
+    book.submit_limit(order(1, Side::Buy, 10000, 2, 2)).unwrap();
+    book.submit_limit(order(2, Side::Sell, 10000, 2, 3)).unwrap();
+    book.halt(Ts::from_nanos(4)).unwrap();
+
+    // Continuous trading on a crossed book would never match the overlap.
+    assert!(is_transition_error(book.resume(Ts::from_nanos(5))));
+    assert_eq!(book.state(), TradingState::Halted);
+    assert_eq!(book.len(), 2);
+
+    book.start_auction(AuctionKind::Opening, Ts::from_nanos(6)).unwrap();
+    let res = book.uncross(Ts::from_nanos(7)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(book.state(), TradingState::Continuous);
+    assert!(book.is_empty());
+
+    // The same holds after a close.
+    book.start_auction(AuctionKind::Closing, Ts::from_nanos(8)).unwrap();
+    book.submit_limit(order(3, Side::Buy, 10000, 2, 9)).unwrap();
+    book.submit_limit(order(4, Side::Sell, 10000, 2, 10)).unwrap();
+    book.close(Ts::from_nanos(11)).unwrap();
+    assert!(is_transition_error(book.resume(Ts::from_nanos(12))));
+    assert_eq!(book.state(), TradingState::Closed);
+}
+
+// ─── Order entry by state ─────────────────────────────────────────────────
+
+#[test]
+fn halted_and_closed_books_refuse_orders_and_amendments() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(order(1, Side::Buy, 10000, 5, 1)).unwrap();
+
+    book.halt(Ts::from_nanos(2)).unwrap();
+    let res = book.submit_limit(order(2, Side::Sell, 10000, 5, 3)).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::TradingHalted));
+    let id = OrderID::new(1).unwrap();
+    let res = book
+        .replace(id, Px::from_raw(10010).unwrap(), Qty::new(5), Ts::from_nanos(3))
+        .unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::TradingHalted));
+    assert_eq!(book.order(id).unwrap().price().raw(), 10000);
+
+    book.close(Ts::from_nanos(4)).unwrap();
+    let res = book.submit_limit(order(3, Side::Sell, 10000, 5, 5)).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::MarketClosed));
+
+    // Cancels still go through.
+    assert!(matches!(
+        book.cancel(id, Ts::from_nanos(6)).unwrap(),
+        OrderEvent::Cancelled { .. }
+    ));
+    assert!(book.is_empty());
+}
+
+#[test]
+fn resume_matches_again() {
+    let mut book = OrderBook::new(SYM);
+    book.submit_limit(order(1, Side::Buy, 10000, 5, 1)).unwrap();
+    book.halt(Ts::from_nanos(2)).unwrap();
+    book.resume(Ts::from_nanos(3)).unwrap();
+    let res = book.submit_limit(order(2, Side::Sell, 10000, 5, 4)).unwrap();
+    assert_eq!(res.fills.len(), 1);
+}
+
+// ─── Volatility guard ─────────────────────────────────────────────────────
+
+#[test]
+fn no_band_before_the_first_trade() {
+    let mut book = guarded_book();
+    assert_eq!(book.price_limits(Ts::from_nanos(0)), None);
+    book.submit_limit(order(1, Side::Sell, 10000, 1, 1)).unwrap();
+    book.submit_limit(order(2, Side::Buy, 10000, 1, 2)).unwrap();
+    assert_eq!(
+        book.price_limits(Ts::from_nanos(3)),
+        Some((Px::from_raw(9900).unwrap(), Px::from_raw(10100).unwrap()))
+    );
+}
+
+#[test]
+fn sweep_through_the_band_halts_and_cancels_the_residual() {
+    let mut book = guarded_book();
+    book.submit_limit(order(1, Side::Sell, 10000, 1, 1)).unwrap();
+    book.submit_limit(order(2, Side::Buy, 10000, 1, 2)).unwrap();
+    book.submit_limit(order(3, Side::Sell, 10050, 2, 3)).unwrap();
+    book.submit_limit(order(4, Side::Sell, 10200, 2, 4)).unwrap();
+
+    let res = book.submit_limit(order(5, Side::Buy, 10300, 6, 5)).unwrap();
+    // 10050 is inside the 9900–10100 band, 10200 is not.
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(res.fills[0].price.raw(), 10050);
+    assert_eq!(
+        changes(&res),
+        vec![(TradingState::Continuous, TradingState::Halted, StateReason::VolatilityHalt)]
+    );
+    assert!(res.lifecycle.iter().any(|e| matches!(
+        e,
+        OrderEvent::Cancelled {
+            reason: CancelReason::TradingHalted,
+            ..
+        }
+    )));
+    assert!(book.order(OrderID::new(5).unwrap()).is_none());
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10200).unwrap(), Qty::new(2))));
+    assert_eq!(book.state(), TradingState::Halted);
+}
+
+#[test]
+fn all_or_nothing_orders_only_count_liquidity_inside_the_band() {
+    let mut book = guarded_book();
+    book.submit_limit(order(1, Side::Sell, 10000, 1, 1)).unwrap();
+    book.submit_limit(order(2, Side::Buy, 10000, 1, 2)).unwrap();
+    book.submit_limit(order(3, Side::Sell, 10050, 5, 3)).unwrap();
+    book.submit_limit(order(4, Side::Sell, 10200, 5, 4)).unwrap();
+
+    // Size is there only by counting 10200, which would halt the book.
+    let fok = order(5, Side::Buy, 10200, 10, 5).with_time_in_force(TimeInForce::Fok);
+    let res = book.submit_limit(fok).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::FillOrKillUnfilled));
+    assert!(res.fills.is_empty());
+    assert!(res.state.is_empty());
+
+    let min_qty = order(6, Side::Buy, 10200, 10, 6).with_min_quantity(Qty::new(8)).unwrap();
+    let res = book.submit_limit(min_qty).unwrap();
+    assert_eq!(rejection(&res), Some(OrderRejectionReason::MinQuantityUnfilled));
+    assert!(res.fills.is_empty());
+
+    assert_eq!(book.state(), TradingState::Continuous);
+    assert_eq!(book.best_ask(), Some((Px::from_raw(10050).unwrap(), Qty::new(5))));
+}
+
+#[test]
+fn reference_rolls_with_recent_trades() {
+    let mut book = guarded_book();
+    let mut id = 0;
+    let mut trade = |book: &mut OrderBook, px: u64, ts: u64| {
+        id += 2;
+        book.submit_limit(order(id, Side::Sell, px, 1, ts)).unwrap();
+        book.submit_limit(order(id + 1, Side::Buy, px, 1, ts)).unwrap()
+    };
+    trade(&mut book, 10000, 1);
+    // Each step stays inside the band around the trades still in the
+    // window, so the price can walk further than 1% in total.
+    trade(&mut book, 10090, 200);
+    trade(&mut book, 10180, 400);
+    let res = trade(&mut book, 10270, 600);
+    assert_eq!(res.fills.len(), 1);
+    assert_eq!(book.state(), TradingState::Continuous);
+
+    // Two quick jumps inside the same window cannot.
+    let res = trade(&mut book, 10370, 601);
+    assert_eq!(res.fills.len(), 1);
+    let res = trade(&mut book, 10480, 602);
+    assert!(res.fills.is_empty());
+    assert_eq!(book.state(), TradingState::Halted);
+}
+
+// ─── Market routing ───────────────────────────────────────────────────────
+
+#[test]
+fn market_routes_state_changes_per_symbol() {
+    let other = Symbol::from_const("OTHER");
+    let mut market = Market::new();
+    market.register(SYM);
+    market.register(other);
+    market.halt(SYM, Ts::from_nanos(1)).unwrap();
+    assert_eq!(market.book(SYM).unwrap().state(), TradingState::Halted);
+    assert_eq!(market.book(other).unwrap().state(), TradingState::Continuous);
+    market.resume(SYM, Ts::from_nanos(2)).unwrap();
+    market.close(other, Ts::from_nanos(3)).unwrap();
+    assert_eq!(market.book(SYM).unwrap().state(), TradingState::Continuous);
+    assert_eq!(market.book(other).unwrap().state(), TradingState::Closed);
+    assert!(market.halt(Symbol::from_const("NONE"), Ts::from_nanos(4)).is_err());
+}
@@ -12,15 +12,15 @@ fn order_id_round_trip() {
 
 #[test]
 fn px_canonical_paths() {
-    let from_cents = Px::from_cents(12_345).unwrap();
-    assert_eq!(from_cents.cents(), 12_345);
+    let from_cents = Px::from_raw(12_345).unwrap();
+    assert_eq!(from_cents.raw(), 12_345);
     assert_eq!(from_cents.to_dollars(), 123.45);
 
     let from_dollars = Px::from_dollars(50.25).unwrap();
-    assert_eq!(from_dollars.cents(), 5025);
+    assert_eq!(from_dollars.raw(), 5025);
 
     // Round-half-to-even / nearest cents — historical truncation bug fixed.
-    assert_eq!(Px::from_dollars(10.999).unwrap().cents(), 1100);
+    assert_eq!(Px::from_dollars(10.999).unwrap().raw(), 1100);
 }
 
 #[test]